@@ -4,7 +4,13 @@
 
 use std::any::Any;
 use std::fmt;
+use std::ops::Add;
 
+use common_arrow::arrow::datatypes::ArrowPrimitiveType;
+use common_arrow::arrow::datatypes::Float32Type;
+use common_arrow::arrow::datatypes::Float64Type;
+use common_arrow::arrow::datatypes::Int64Type;
+use common_arrow::arrow::datatypes::UInt64Type;
 use common_datavalues::DataColumnarValue;
 use common_datavalues::DataField;
 use common_datavalues::DataSchema;
@@ -14,9 +20,13 @@ use common_datavalues::DataValueArithmetic;
 use common_datavalues::DataValueArithmeticOperator;
 use common_exception::Result;
 
+use crate::aggregate_sum::SumStates;
 use crate::aggregator_common::assert_unary_arguments;
+use crate::AggregateCountFunction;
 use crate::AggregateFunction;
+use crate::AggregateStates;
 use crate::AggregateSumFunction;
+use crate::FunctionStates;
 
 #[derive(Clone)]
 pub struct AggregateAvgFunction {
@@ -67,7 +77,9 @@ impl AggregateFunction for AggregateAvgFunction {
             let count = DataValueArithmetic::data_value_arithmetic_op(
                 DataValueArithmeticOperator::Plus,
                 values[1].clone(),
-                DataValue::UInt64(Some(input_rows as u64)),
+                DataValue::UInt64(Some(AggregateCountFunction::count_batch(
+                    columns, input_rows,
+                )?)),
             )?;
 
             self.state = DataValue::Struct(vec![sum, count]);
@@ -76,6 +88,10 @@ impl AggregateFunction for AggregateAvgFunction {
     }
 
     fn accumulate_scalar(&mut self, scalar_values: &[DataValue]) -> Result<()> {
+        if scalar_values[0].is_null() || matches!(scalar_values[0], DataValue::Null) {
+            return Ok(());
+        }
+
         if let DataValue::Struct(values) = self.state.clone() {
             let sum = DataValueArithmetic::data_value_arithmetic_op(
                 DataValueArithmeticOperator::Plus,
@@ -94,6 +110,16 @@ impl AggregateFunction for AggregateAvgFunction {
         Ok(())
     }
 
+    fn create_states(&self) -> Box<dyn AggregateStates> {
+        match AggregateSumFunction::sum_return_type(self.arguments[0].data_type()) {
+            Ok(DataType::Int64) => Box::new(AvgStates::<Int64Type>::create()),
+            Ok(DataType::UInt64) => Box::new(AvgStates::<UInt64Type>::create()),
+            Ok(DataType::Float32) => Box::new(AvgStates::<Float32Type>::create()),
+            Ok(DataType::Float64) => Box::new(AvgStates::<Float64Type>::create()),
+            _ => Box::new(FunctionStates::create(Box::new(self.clone()))),
+        }
+    }
+
     fn accumulate_result(&self) -> Result<Vec<DataValue>> {
         Ok(vec![self.state.clone()])
     }
@@ -136,3 +162,71 @@ impl fmt::Display for AggregateAvgFunction {
         write!(f, "{}", self.display_name)
     }
 }
+
+/// The sums and the counts of the groups.
+struct AvgStates<T: ArrowPrimitiveType> {
+    sums: SumStates<T>,
+    counts: Vec<u64>,
+}
+
+impl<T> AvgStates<T>
+where
+    T: ArrowPrimitiveType,
+    T::Native: Add<Output = T::Native>,
+{
+    fn create() -> Self {
+        AvgStates {
+            sums: SumStates::create(),
+            counts: vec![],
+        }
+    }
+
+    fn state(&self, place: usize) -> Result<DataValue> {
+        Ok(DataValue::Struct(vec![
+            self.sums.sum(place)?,
+            DataValue::UInt64(Some(self.counts[place])),
+        ]))
+    }
+}
+
+impl<T> AggregateStates for AvgStates<T>
+where
+    T: ArrowPrimitiveType,
+    T::Native: Add<Output = T::Native>,
+{
+    fn add_state(&mut self) -> usize {
+        self.counts.push(0);
+        self.sums.add_sum()
+    }
+
+    fn accumulate(&mut self, columns: &[DataColumnarValue], places: &[usize]) -> Result<()> {
+        self.sums.accumulate_sums(&columns[0], places)?;
+        AggregateCountFunction::count_into_places(columns, places, &mut self.counts)
+    }
+
+    fn merge(&mut self, place: usize, states: &[DataValue]) -> Result<()> {
+        if let DataValue::Struct(states) = &states[0] {
+            self.sums.merge_sum(place, &states[0])?;
+            if let DataValue::UInt64(Some(count)) = states[1] {
+                self.counts[place] += count;
+            }
+        }
+        Ok(())
+    }
+
+    fn serialize(&self, place: usize, writer: &mut Vec<u8>) -> Result<()> {
+        DataValue::serialize_values(&[self.state(place)?], writer)
+    }
+
+    fn merge_result(&self, place: usize) -> Result<DataValue> {
+        DataValueArithmetic::data_value_arithmetic_op(
+            DataValueArithmeticOperator::Div,
+            self.sums.sum(place)?,
+            DataValue::UInt64(Some(self.counts[place])),
+        )
+    }
+
+    fn memory_size(&self) -> usize {
+        self.sums.sums_memory_size() + self.counts.len() * std::mem::size_of::<u64>()
+    }
+}
@@ -149,6 +149,14 @@ impl AggregateFunction for AggregateDistinctCombinator {
         })
     }
 
+    fn memory_size(&self) -> usize {
+        let values_size = std::mem::size_of::<DataGroupValues>()
+            + self.arguments.len() * std::mem::size_of::<DataGroupValue>();
+        std::mem::size_of_val(self)
+            + self.nested.memory_size()
+            + self.state.capacity() * values_size
+    }
+
     fn merge_result(&self) -> Result<DataValue> {
         // faster path for count
         if self
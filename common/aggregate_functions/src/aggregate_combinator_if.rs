@@ -100,6 +100,10 @@ impl AggregateFunction for AggregateIfCombinator {
     fn merge_result(&self) -> Result<DataValue> {
         self.nested.merge_result()
     }
+
+    fn memory_size(&self) -> usize {
+        std::mem::size_of_val(self) + self.nested.memory_size()
+    }
 }
 
 impl fmt::Display for AggregateIfCombinator {
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0.
 
 use std::any::Any;
+use std::convert::TryInto;
 use std::fmt;
 
 use common_datavalues::DataColumnarValue;
@@ -12,10 +13,12 @@ use common_datavalues::DataType;
 use common_datavalues::DataValue;
 use common_datavalues::DataValueArithmetic;
 use common_datavalues::DataValueArithmeticOperator;
+use common_exception::ErrorCode;
 use common_exception::Result;
 
 use crate::aggregator_common::assert_variadic_arguments;
 use crate::AggregateFunction;
+use crate::AggregateStates;
 
 #[derive(Clone)]
 pub struct AggregateCountFunction {
@@ -55,16 +58,23 @@ impl AggregateFunction for AggregateCountFunction {
         self
     }
 
-    fn accumulate(&mut self, _columns: &[DataColumnarValue], input_rows: usize) -> Result<()> {
+    fn accumulate(&mut self, columns: &[DataColumnarValue], input_rows: usize) -> Result<()> {
         self.state = DataValueArithmetic::data_value_arithmetic_op(
             DataValueArithmeticOperator::Plus,
             self.state.clone(),
-            DataValue::UInt64(Some(input_rows as u64)),
+            DataValue::UInt64(Some(Self::count_batch(columns, input_rows)?)),
         )?;
         Ok(())
     }
 
-    fn accumulate_scalar(&mut self, _values: &[DataValue]) -> Result<()> {
+    fn accumulate_scalar(&mut self, values: &[DataValue]) -> Result<()> {
+        if values
+            .first()
+            .map_or(false, |v| v.is_null() || matches!(v, DataValue::Null))
+        {
+            return Ok(());
+        }
+
         self.state = DataValueArithmetic::data_value_arithmetic_op(
             DataValueArithmeticOperator::Plus,
             self.state.clone(),
@@ -73,10 +83,32 @@ impl AggregateFunction for AggregateCountFunction {
         Ok(())
     }
 
+    fn create_states(&self) -> Box<dyn AggregateStates> {
+        Box::new(CountStates { counts: vec![] })
+    }
+
     fn accumulate_result(&self) -> Result<Vec<DataValue>> {
         Ok(vec![self.state.clone()])
     }
 
+    fn serialize(&self, writer: &mut Vec<u8>) -> Result<()> {
+        match self.state {
+            DataValue::UInt64(Some(count)) => writer.extend_from_slice(&count.to_le_bytes()),
+            _ => writer.extend_from_slice(&0u64.to_le_bytes()),
+        }
+        Ok(())
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<Vec<DataValue>> {
+        let count = bytes.try_into().map(u64::from_le_bytes).map_err(|_| {
+            ErrorCode::BadBytes(format!(
+                "Cannot deserialize the count state from {} bytes",
+                bytes.len()
+            ))
+        })?;
+        Ok(vec![DataValue::UInt64(Some(count))])
+    }
+
     fn merge(&mut self, states: &[DataValue]) -> Result<()> {
         let val = states[0].clone();
         self.state = DataValueArithmetic::data_value_arithmetic_op(
@@ -97,3 +129,81 @@ impl fmt::Display for AggregateCountFunction {
         write!(f, "{}", self.display_name)
     }
 }
+
+impl AggregateCountFunction {
+    /// The number of the rows whose argument is not null, all the rows without argument.
+    pub fn count_batch(columns: &[DataColumnarValue], input_rows: usize) -> Result<u64> {
+        Ok(match columns.first() {
+            None => input_rows as u64,
+            Some(DataColumnarValue::Constant(value, size)) => {
+                if value.is_null() || matches!(value, DataValue::Null) {
+                    0
+                } else {
+                    *size as u64
+                }
+            }
+            Some(DataColumnarValue::Array(array)) => (array.len() - array.null_count()) as u64,
+        })
+    }
+
+    /// Counts the rows into the counts of their places, places[row] is the place of the row.
+    pub fn count_into_places(
+        columns: &[DataColumnarValue],
+        places: &[usize],
+        counts: &mut [u64],
+    ) -> Result<()> {
+        match columns.first() {
+            Some(DataColumnarValue::Array(array)) if array.null_count() > 0 => {
+                for (row, place) in places.iter().enumerate() {
+                    if array.is_valid(row) {
+                        counts[*place] += 1;
+                    }
+                }
+            }
+            Some(DataColumnarValue::Constant(value, _))
+                if value.is_null() || matches!(value, DataValue::Null) => {}
+            _ => {
+                for place in places {
+                    counts[*place] += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The counts of the groups.
+struct CountStates {
+    counts: Vec<u64>,
+}
+
+impl AggregateStates for CountStates {
+    fn add_state(&mut self) -> usize {
+        self.counts.push(0);
+        self.counts.len() - 1
+    }
+
+    fn accumulate(&mut self, columns: &[DataColumnarValue], places: &[usize]) -> Result<()> {
+        AggregateCountFunction::count_into_places(columns, places, &mut self.counts)
+    }
+
+    fn merge(&mut self, place: usize, states: &[DataValue]) -> Result<()> {
+        if let DataValue::UInt64(Some(count)) = states[0] {
+            self.counts[place] += count;
+        }
+        Ok(())
+    }
+
+    fn serialize(&self, place: usize, writer: &mut Vec<u8>) -> Result<()> {
+        writer.extend_from_slice(&self.counts[place].to_le_bytes());
+        Ok(())
+    }
+
+    fn merge_result(&self, place: usize) -> Result<DataValue> {
+        Ok(DataValue::UInt64(Some(self.counts[place])))
+    }
+
+    fn memory_size(&self) -> usize {
+        self.counts.len() * std::mem::size_of::<u64>()
+    }
+}
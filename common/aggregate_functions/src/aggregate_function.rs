@@ -12,7 +12,10 @@ use common_datavalues::DataValue;
 use common_exception::Result;
 use dyn_clone::DynClone;
 
-pub trait AggregateFunction: fmt::Display + Sync + Send + DynClone {
+use crate::AggregateStates;
+use crate::FunctionStates;
+
+pub trait AggregateFunction: fmt::Display + Sync + Send + DynClone + 'static {
     fn name(&self) -> &str;
     fn return_type(&self) -> Result<DataType>;
     fn nullable(&self, _input_schema: &DataSchema) -> Result<bool>;
@@ -38,9 +41,32 @@ pub trait AggregateFunction: fmt::Display + Sync + Send + DynClone {
     // must be implemented even we implement `accumulate`, because the combinator need this function
     fn accumulate_scalar(&mut self, _values: &[DataValue]) -> Result<()>;
 
+    // create_states is to create the arena of the states of the groups, which accumulates
+    // the columns of the groups in place. The states are copies of self by default.
+    fn create_states(&self) -> Box<dyn AggregateStates> {
+        Box::new(FunctionStates::create(dyn_clone::clone_box(self)))
+    }
+
     fn accumulate_result(&self) -> Result<Vec<DataValue>>;
+
+    // serialize is to write the states of accumulate_result in binary, to be sent to the final stage.
+    fn serialize(&self, writer: &mut Vec<u8>) -> Result<()> {
+        DataValue::serialize_values(&self.accumulate_result()?, writer)
+    }
+
+    // deserialize is to read the states written by serialize, to be merged by merge.
+    fn deserialize(&self, bytes: &[u8]) -> Result<Vec<DataValue>> {
+        DataValue::deserialize_values(bytes)
+    }
+
     fn merge(&mut self, _states: &[DataValue]) -> Result<()>;
     fn merge_result(&self) -> Result<DataValue>;
+
+    // memory_size is the approximate memory size of the function with its state,
+    // the functions whose states grow with the rows must count them.
+    fn memory_size(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
 }
 
 dyn_clone::clone_trait_object!(AggregateFunction);
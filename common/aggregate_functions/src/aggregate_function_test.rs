@@ -159,3 +159,169 @@ fn test_aggregate_function() -> Result<()> {
     }
     Ok(())
 }
+
+#[test]
+fn test_aggregate_function_by_groups() -> Result<()> {
+    let columns: Vec<DataColumnarValue> = vec![
+        Arc::new(Int64Array::from(vec![4, 3, 2, 1, 6])).into(),
+        Arc::new(Int64Array::from(vec![1, 2, 3, 4, 5])).into(),
+    ];
+    let args = vec![
+        DataField::new("a", DataType::Int64, false),
+        DataField::new("b", DataType::Int64, false),
+    ];
+    // The rows of the groups: 0 => [0, 3], 1 => [1, 4], 2 => [2].
+    let places = vec![0, 1, 2, 0, 1];
+
+    for name in &["count", "sum", "avg", "min", "max", "argMin", "argMax"] {
+        let arguments = match *name {
+            "argMin" | "argMax" => args.clone(),
+            _ => vec![args[0].clone()],
+        };
+        let arg_columns = &columns[0..arguments.len()];
+        let func = AggregateFunctionFactory::get(name, arguments)?;
+        let mut states = func.create_states();
+        for group in 0..3 {
+            assert_eq!(states.add_state(), group, "{}", name);
+        }
+        states.accumulate(arg_columns, &places)?;
+
+        // The same as accumulating the rows of every group one by one.
+        for group in 0..3 {
+            let mut expect = func.clone();
+            for (row, place) in places.iter().enumerate() {
+                if *place == group {
+                    let values = arg_columns
+                        .iter()
+                        .map(|c| DataValue::try_from_column(c, row))
+                        .collect::<Result<Vec<_>>>()?;
+                    expect.accumulate_scalar(&values)?;
+                }
+            }
+            assert_eq!(
+                expect.merge_result()?,
+                states.merge_result(group)?,
+                "{} of group {}",
+                name,
+                group
+            );
+
+            // The serialized state is merged by the function.
+            let mut bytes = vec![];
+            states.serialize(group, &mut bytes)?;
+            let mut merged = func.clone();
+            merged.merge(&func.deserialize(&bytes)?)?;
+            assert_eq!(
+                expect.merge_result()?,
+                merged.merge_result()?,
+                "{} of group {}",
+                name,
+                group
+            );
+        }
+
+        // The states of a group are merged in place.
+        let mut merged = func.create_states();
+        merged.add_state();
+        for group in 0..3 {
+            let mut bytes = vec![];
+            states.serialize(group, &mut bytes)?;
+            merged.merge(0, &func.deserialize(&bytes)?)?;
+        }
+        let mut expect = func.clone();
+        expect.accumulate(arg_columns, places.len())?;
+        assert_eq!(expect.merge_result()?, merged.merge_result(0)?, "{}", name);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_aggregate_function_nullable() -> Result<()> {
+    let column: DataColumnarValue = Arc::new(Int64Array::from(vec![
+        Some(4),
+        None,
+        Some(2),
+        None,
+        Some(6),
+    ]))
+    .into();
+    let arguments = vec![DataField::new("a", DataType::Int64, true)];
+    // The rows of the groups: 0 => [0, 2], 1 => [1, 3], 2 => [4].
+    let places = vec![0, 1, 0, 1, 2];
+
+    let expects = vec![
+        ("count", DataValue::UInt64(Some(3))),
+        ("sum", DataValue::Int64(Some(12))),
+        ("avg", DataValue::Float64(Some(4.0))),
+        ("min", DataValue::Int64(Some(2))),
+        ("max", DataValue::Int64(Some(6))),
+    ];
+
+    for (name, expect) in expects {
+        let func = AggregateFunctionFactory::get(name, arguments.clone())?;
+
+        // The nulls are skipped in batch.
+        let mut batch = func.clone();
+        batch.accumulate(&[column.clone()], 5)?;
+        assert_eq!(batch.merge_result()?, expect, "{}", name);
+
+        // The same as accumulating the rows one by one.
+        let mut scalar = func.clone();
+        for row in 0..5 {
+            scalar.accumulate_scalar(&[DataValue::try_from_column(&column, row)?])?;
+        }
+        assert_eq!(scalar.merge_result()?, expect, "{}", name);
+
+        // The states of the groups merged.
+        let mut states = func.create_states();
+        (0..3).for_each(|_| {
+            states.add_state();
+        });
+        states.accumulate(&[column.clone()], &places)?;
+        let mut merged = func.clone();
+        for group in 0..3 {
+            let mut bytes = vec![];
+            states.serialize(group, &mut bytes)?;
+            merged.merge(&func.deserialize(&bytes)?)?;
+        }
+        assert_eq!(merged.merge_result()?, expect, "{}", name);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_aggregate_function_serialize() -> Result<()> {
+    let columns: Vec<DataColumnarValue> = vec![
+        Arc::new(Float64Array::from(vec![0.1, 0.2, 0.3, 0.7])).into(),
+        Arc::new(Int64Array::from(vec![1, 2, 3, 4])).into(),
+    ];
+    let args = vec![
+        DataField::new("a", DataType::Float64, false),
+        DataField::new("b", DataType::Int64, false),
+    ];
+
+    for name in &[
+        "count", "sum", "avg", "min", "max", "argMin", "argMax", "uniq",
+    ] {
+        let arguments = match *name {
+            "argMin" | "argMax" => args.clone(),
+            _ => vec![args[0].clone()],
+        };
+        let arg_columns = &columns[0..arguments.len()];
+        let func = AggregateFunctionFactory::get(name, arguments)?;
+
+        let mut partial = func.clone();
+        partial.accumulate(arg_columns, 4)?;
+        let mut bytes = vec![];
+        partial.serialize(&mut bytes)?;
+
+        // The states are the same after the round trip.
+        let states = func.deserialize(&bytes)?;
+        assert_eq!(states, partial.accumulate_result()?, "{}", name);
+
+        let mut merged = func.clone();
+        merged.merge(&states)?;
+        assert_eq!(merged.merge_result()?, partial.merge_result()?, "{}", name);
+    }
+    Ok(())
+}
@@ -5,12 +5,16 @@
 use std::any::Any;
 use std::fmt;
 
+use common_arrow::arrow::array::Array;
 use common_datavalues::*;
 use common_exception::ErrorCode;
 use common_exception::Result;
 
+use crate::aggregate_states::try_create_min_max_states;
 use crate::aggregator_common::assert_unary_arguments;
 use crate::AggregateFunction;
+use crate::AggregateStates;
+use crate::FunctionStates;
 
 #[derive(Clone)]
 pub struct AggregateMaxFunction {
@@ -72,6 +76,11 @@ impl AggregateFunction for AggregateMaxFunction {
         Ok(())
     }
 
+    fn create_states(&self) -> Box<dyn AggregateStates> {
+        try_create_min_max_states(self.arguments[0].data_type(), false)
+            .unwrap_or_else(|| Box::new(FunctionStates::create(Box::new(self.clone()))))
+    }
+
     fn accumulate_result(&self) -> Result<Vec<DataValue>> {
         Ok(vec![self.state.clone()])
     }
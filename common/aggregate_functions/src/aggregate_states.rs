@@ -0,0 +1,264 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::sync::Arc;
+
+use common_arrow::arrow::array::Array;
+use common_arrow::arrow::array::PrimitiveArray;
+use common_arrow::arrow::datatypes::ArrowPrimitiveType;
+use common_arrow::arrow::datatypes::Float32Type;
+use common_arrow::arrow::datatypes::Float64Type;
+use common_arrow::arrow::datatypes::Int16Type;
+use common_arrow::arrow::datatypes::Int32Type;
+use common_arrow::arrow::datatypes::Int64Type;
+use common_arrow::arrow::datatypes::Int8Type;
+use common_arrow::arrow::datatypes::UInt16Type;
+use common_arrow::arrow::datatypes::UInt32Type;
+use common_arrow::arrow::datatypes::UInt64Type;
+use common_arrow::arrow::datatypes::UInt8Type;
+use common_datavalues::data_array_cast;
+use common_datavalues::DataArrayRef;
+use common_datavalues::DataColumnarValue;
+use common_datavalues::DataType;
+use common_datavalues::DataValue;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::AggregateFunction;
+
+/// The states of a function for all the groups of an aggregation. The states are allocated
+/// together in an arena and indexed by their place, the rows of a block are accumulated
+/// into them in place.
+pub trait AggregateStates: Send + Sync {
+    /// Allocates the initial state of a new group, returns the place of the state.
+    fn add_state(&mut self) -> usize;
+
+    /// Accumulates the rows into the states, places[row] is the place of the state of the row.
+    fn accumulate(&mut self, columns: &[DataColumnarValue], places: &[usize]) -> Result<()>;
+
+    /// Merges the partial states, in the format of accumulate_result, into the state of the place.
+    fn merge(&mut self, place: usize, states: &[DataValue]) -> Result<()>;
+
+    /// Writes the state of the place in the format of AggregateFunction::serialize.
+    fn serialize(&self, place: usize, writer: &mut Vec<u8>) -> Result<()>;
+
+    fn merge_result(&self, place: usize) -> Result<DataValue>;
+
+    /// The approximate memory size of the states.
+    fn memory_size(&self) -> usize;
+}
+
+/// The states of the functions without an arena of their own, a copy of the function per place.
+pub struct FunctionStates<F: AggregateFunction + ?Sized> {
+    func: Box<F>,
+    states: Vec<Box<F>>,
+}
+
+impl<F: AggregateFunction + ?Sized> FunctionStates<F> {
+    pub fn create(func: Box<F>) -> Self {
+        FunctionStates {
+            func,
+            states: vec![],
+        }
+    }
+}
+
+impl<F: AggregateFunction + ?Sized> AggregateStates for FunctionStates<F> {
+    fn add_state(&mut self) -> usize {
+        self.states.push(dyn_clone::clone_box(&*self.func));
+        self.states.len() - 1
+    }
+
+    fn accumulate(&mut self, columns: &[DataColumnarValue], places: &[usize]) -> Result<()> {
+        for (row, place) in places.iter().enumerate() {
+            let values = columns
+                .iter()
+                .map(|column| DataValue::try_from_column(column, row))
+                .collect::<Result<Vec<_>>>()?;
+            self.states[*place].accumulate_scalar(&values)?;
+        }
+        Ok(())
+    }
+
+    fn merge(&mut self, place: usize, states: &[DataValue]) -> Result<()> {
+        self.states[place].merge(states)
+    }
+
+    fn serialize(&self, place: usize, writer: &mut Vec<u8>) -> Result<()> {
+        self.states[place].serialize(writer)
+    }
+
+    fn merge_result(&self, place: usize) -> Result<DataValue> {
+        self.states[place].merge_result()
+    }
+
+    fn memory_size(&self) -> usize {
+        self.states
+            .iter()
+            .map(|state| std::mem::size_of::<Box<F>>() + state.memory_size())
+            .sum()
+    }
+}
+
+/// The native values of the states of the groups, None until the first value is folded.
+pub(crate) struct PrimitiveValues<T: ArrowPrimitiveType> {
+    values: Vec<Option<T::Native>>,
+}
+
+impl<T: ArrowPrimitiveType> PrimitiveValues<T> {
+    pub fn create() -> Self {
+        PrimitiveValues { values: vec![] }
+    }
+
+    pub fn add_value(&mut self) -> usize {
+        self.values.push(None);
+        self.values.len() - 1
+    }
+
+    /// Folds the values of the column into the values of their places, the nulls are skipped.
+    pub fn fold<Op>(&mut self, column: &DataColumnarValue, places: &[usize], op: Op) -> Result<()>
+    where Op: Fn(T::Native, T::Native) -> T::Native {
+        let array = column.to_array()?;
+        let array = if array.data_type() == &T::DATA_TYPE {
+            array
+        } else {
+            data_array_cast(&array, &T::DATA_TYPE)?
+        };
+        let array = Self::downcast(&array)?;
+
+        if array.null_count() == 0 {
+            for (value, place) in array.values().iter().zip(places.iter()) {
+                self.fold_value(*place, *value, &op);
+            }
+        } else {
+            for (row, place) in places.iter().enumerate() {
+                if array.is_valid(row) {
+                    self.fold_value(*place, array.value(row), &op);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Folds the value of a partial state into the value of the place.
+    pub fn fold_data_value<Op>(&mut self, place: usize, value: &DataValue, op: Op) -> Result<()>
+    where Op: Fn(T::Native, T::Native) -> T::Native {
+        if value.is_null() || matches!(value, DataValue::Null) {
+            return Ok(());
+        }
+
+        let array = value.cast(&T::DATA_TYPE)?.to_array()?;
+        let array = Self::downcast(&array)?;
+        if array.is_valid(0) {
+            self.fold_value(place, array.value(0), &op);
+        }
+        Ok(())
+    }
+
+    pub fn data_value(&self, place: usize) -> Result<DataValue> {
+        let array: DataArrayRef = Arc::new(PrimitiveArray::<T>::from(vec![self.values[place]]));
+        DataValue::try_from_array(&array, 0)
+    }
+
+    pub fn memory_size(&self) -> usize {
+        self.values.len() * std::mem::size_of::<Option<T::Native>>()
+    }
+
+    fn fold_value<Op>(&mut self, place: usize, value: T::Native, op: &Op)
+    where Op: Fn(T::Native, T::Native) -> T::Native {
+        self.values[place] = Some(match self.values[place] {
+            Some(state) => op(state, value),
+            None => value,
+        });
+    }
+
+    fn downcast(array: &DataArrayRef) -> Result<&PrimitiveArray<T>> {
+        array
+            .as_any()
+            .downcast_ref::<PrimitiveArray<T>>()
+            .ok_or_else(|| {
+                ErrorCode::BadDataValueType(format!(
+                    "Cannot downcast the array of {:?} to {:?}",
+                    array.data_type(),
+                    T::DATA_TYPE
+                ))
+            })
+    }
+}
+
+/// The min or the max values of the groups.
+pub(crate) struct MinMaxStates<T: ArrowPrimitiveType> {
+    values: PrimitiveValues<T>,
+    is_min: bool,
+}
+
+impl<T: ArrowPrimitiveType> MinMaxStates<T> {
+    fn min_max(is_min: bool) -> impl Fn(T::Native, T::Native) -> T::Native {
+        move |state, value| {
+            if (value < state) == is_min {
+                value
+            } else {
+                state
+            }
+        }
+    }
+}
+
+/// The min or the max states of the numeric types, None for the other types.
+pub(crate) fn try_create_min_max_states(
+    data_type: &DataType,
+    is_min: bool,
+) -> Option<Box<dyn AggregateStates>> {
+    macro_rules! min_max_states {
+        ($TYPE:ident) => {{
+            let states: Box<dyn AggregateStates> = Box::new(MinMaxStates::<$TYPE> {
+                values: PrimitiveValues::create(),
+                is_min,
+            });
+            Some(states)
+        }};
+    }
+
+    match data_type {
+        DataType::Int8 => min_max_states!(Int8Type),
+        DataType::Int16 => min_max_states!(Int16Type),
+        DataType::Int32 => min_max_states!(Int32Type),
+        DataType::Int64 => min_max_states!(Int64Type),
+        DataType::UInt8 => min_max_states!(UInt8Type),
+        DataType::UInt16 => min_max_states!(UInt16Type),
+        DataType::UInt32 => min_max_states!(UInt32Type),
+        DataType::UInt64 => min_max_states!(UInt64Type),
+        DataType::Float32 => min_max_states!(Float32Type),
+        DataType::Float64 => min_max_states!(Float64Type),
+        _ => None,
+    }
+}
+
+impl<T: ArrowPrimitiveType> AggregateStates for MinMaxStates<T> {
+    fn add_state(&mut self) -> usize {
+        self.values.add_value()
+    }
+
+    fn accumulate(&mut self, columns: &[DataColumnarValue], places: &[usize]) -> Result<()> {
+        self.values
+            .fold(&columns[0], places, Self::min_max(self.is_min))
+    }
+
+    fn merge(&mut self, place: usize, states: &[DataValue]) -> Result<()> {
+        self.values
+            .fold_data_value(place, &states[0], Self::min_max(self.is_min))
+    }
+
+    fn serialize(&self, place: usize, writer: &mut Vec<u8>) -> Result<()> {
+        DataValue::serialize_values(&[self.values.data_value(place)?], writer)
+    }
+
+    fn merge_result(&self, place: usize) -> Result<DataValue> {
+        self.values.data_value(place)
+    }
+
+    fn memory_size(&self) -> usize {
+        self.values.memory_size()
+    }
+}
@@ -5,13 +5,21 @@
 use std::any::Any;
 use std::convert::TryFrom;
 use std::fmt;
+use std::ops::Add;
 
+use common_arrow::arrow::datatypes::ArrowPrimitiveType;
+use common_arrow::arrow::datatypes::Float32Type;
+use common_arrow::arrow::datatypes::Float64Type;
+use common_arrow::arrow::datatypes::Int64Type;
+use common_arrow::arrow::datatypes::UInt64Type;
 use common_datavalues::*;
 use common_exception::ErrorCode;
 use common_exception::Result;
 
+use crate::aggregate_states::PrimitiveValues;
 use crate::aggregator_common::assert_unary_arguments;
 use crate::AggregateFunction;
+use crate::AggregateStates;
 
 #[derive(Clone)]
 pub struct AggregateSumFunction {
@@ -75,6 +83,15 @@ impl AggregateFunction for AggregateSumFunction {
         Ok(())
     }
 
+    fn create_states(&self) -> Box<dyn AggregateStates> {
+        match self.state.data_type() {
+            DataType::Int64 => Box::new(SumStates::<Int64Type>::create()),
+            DataType::UInt64 => Box::new(SumStates::<UInt64Type>::create()),
+            DataType::Float32 => Box::new(SumStates::<Float32Type>::create()),
+            _ => Box::new(SumStates::<Float64Type>::create()),
+        }
+    }
+
     fn accumulate_result(&self) -> Result<Vec<DataValue>> {
         Ok(vec![self.state.clone()])
     }
@@ -101,7 +118,7 @@ impl fmt::Display for AggregateSumFunction {
 }
 
 impl AggregateSumFunction {
-    fn sum_return_type(arg_type: &DataType) -> Result<DataType> {
+    pub(crate) fn sum_return_type(arg_type: &DataType) -> Result<DataType> {
         match arg_type {
             DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64 => {
                 Ok(DataType::Int64)
@@ -119,6 +136,7 @@ impl AggregateSumFunction {
         }
     }
 
+    /// The sum of the non-null values by the arrow kernel, which is vectorized under the `simd` feature.
     pub fn sum_batch(column: DataColumnarValue) -> Result<DataValue> {
         match column {
             DataColumnarValue::Constant(value, size) => {
@@ -134,3 +152,71 @@ impl AggregateSumFunction {
         }
     }
 }
+
+/// The sums of the groups in the native type of the sum.
+pub(crate) struct SumStates<T: ArrowPrimitiveType> {
+    sums: PrimitiveValues<T>,
+}
+
+impl<T> SumStates<T>
+where
+    T: ArrowPrimitiveType,
+    T::Native: Add<Output = T::Native>,
+{
+    pub fn create() -> Self {
+        SumStates {
+            sums: PrimitiveValues::create(),
+        }
+    }
+
+    pub fn add_sum(&mut self) -> usize {
+        self.sums.add_value()
+    }
+
+    pub fn accumulate_sums(&mut self, column: &DataColumnarValue, places: &[usize]) -> Result<()> {
+        self.sums.fold(column, places, |sum, value| sum + value)
+    }
+
+    pub fn merge_sum(&mut self, place: usize, sum: &DataValue) -> Result<()> {
+        self.sums
+            .fold_data_value(place, sum, |sum, value| sum + value)
+    }
+
+    pub fn sum(&self, place: usize) -> Result<DataValue> {
+        self.sums.data_value(place)
+    }
+
+    pub fn sums_memory_size(&self) -> usize {
+        self.sums.memory_size()
+    }
+}
+
+impl<T> AggregateStates for SumStates<T>
+where
+    T: ArrowPrimitiveType,
+    T::Native: Add<Output = T::Native>,
+{
+    fn add_state(&mut self) -> usize {
+        self.add_sum()
+    }
+
+    fn accumulate(&mut self, columns: &[DataColumnarValue], places: &[usize]) -> Result<()> {
+        self.accumulate_sums(&columns[0], places)
+    }
+
+    fn merge(&mut self, place: usize, states: &[DataValue]) -> Result<()> {
+        self.merge_sum(place, &states[0])
+    }
+
+    fn serialize(&self, place: usize, writer: &mut Vec<u8>) -> Result<()> {
+        DataValue::serialize_values(&[self.sum(place)?], writer)
+    }
+
+    fn merge_result(&self, place: usize) -> Result<DataValue> {
+        self.sum(place)
+    }
+
+    fn memory_size(&self) -> usize {
+        self.sums_memory_size()
+    }
+}
@@ -22,6 +22,7 @@ mod aggregate_function;
 mod aggregate_function_factory;
 mod aggregate_max;
 mod aggregate_min;
+mod aggregate_states;
 mod aggregate_sum;
 mod aggregator;
 mod aggregator_common;
@@ -36,5 +37,7 @@ pub use aggregate_function::AggregateFunction;
 pub use aggregate_function_factory::AggregateFunctionFactory;
 pub use aggregate_max::AggregateMaxFunction;
 pub use aggregate_min::AggregateMinFunction;
+pub use aggregate_states::AggregateStates;
+pub use aggregate_states::FunctionStates;
 pub use aggregate_sum::AggregateSumFunction;
 pub use aggregator::AggregatorFunction;
@@ -0,0 +1,230 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use common_arrow::arrow::array::Array;
+use common_arrow::arrow::array::Int16Array;
+use common_arrow::arrow::array::Int32Array;
+use common_arrow::arrow::array::Int64Array;
+use common_arrow::arrow::array::Int8Array;
+use common_arrow::arrow::array::UInt16Array;
+use common_arrow::arrow::array::UInt32Array;
+use common_arrow::arrow::array::UInt64Array;
+use common_arrow::arrow::array::UInt8Array;
+use common_datavalues::downcast_array;
+use common_datavalues::DataColumnarValue;
+use common_datavalues::DataSchema;
+use common_datavalues::DataType;
+use common_datavalues::DataValue;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+use crate::DataBlock;
+
+/// The way the group by columns of a row are turned into a hash key.
+pub trait HashMethod {
+    type HashKey: Eq + Hash + Clone + Debug + Send + Sync;
+
+    fn name(&self) -> String;
+
+    /// The keys of all the rows.
+    fn build_keys(
+        &self,
+        group_columns: &[&DataColumnarValue],
+        rows: usize,
+    ) -> Result<Vec<Self::HashKey>>;
+
+    /// The bytes of the key, the same as `DataValue::concat_row_to_one_key` of the columns.
+    fn key_bytes(&self, key: &Self::HashKey) -> Vec<u8>;
+}
+
+/// Serializes the columns of the row into bytes, for any type of the columns.
+#[derive(Clone, Debug, Default)]
+pub struct HashMethodSerializer;
+
+impl HashMethod for HashMethodSerializer {
+    type HashKey = Vec<u8>;
+
+    fn name(&self) -> String {
+        "Serializer".to_string()
+    }
+
+    fn build_keys(
+        &self,
+        group_columns: &[&DataColumnarValue],
+        rows: usize,
+    ) -> Result<Vec<Self::HashKey>> {
+        let mut keys = Vec::with_capacity(rows);
+        for row in 0..rows {
+            let mut key = vec![];
+            for col in group_columns {
+                DataValue::concat_row_to_one_key(col, row, &mut key)?;
+            }
+            keys.push(key);
+        }
+        Ok(keys)
+    }
+
+    fn key_bytes(&self, key: &Self::HashKey) -> Vec<u8> {
+        key.clone()
+    }
+}
+
+/// The unsigned integers holding the packed fixed-width keys.
+pub trait FixedKey: Copy + Default + Eq + Hash + Debug + Send + Sync + 'static {
+    fn from_u128(value: u128) -> Self;
+    fn to_bytes(&self) -> Vec<u8>;
+}
+
+macro_rules! impl_fixed_key {
+    ($TYPE:ty) => {
+        impl FixedKey for $TYPE {
+            fn from_u128(value: u128) -> Self {
+                value as $TYPE
+            }
+
+            fn to_bytes(&self) -> Vec<u8> {
+                self.to_le_bytes().to_vec()
+            }
+        }
+    };
+}
+
+impl_fixed_key!(u8);
+impl_fixed_key!(u16);
+impl_fixed_key!(u32);
+impl_fixed_key!(u64);
+impl_fixed_key!(u128);
+
+/// Packs the integer columns of the row into one unsigned integer,
+/// the first column in the lowest bytes.
+#[derive(Clone, Debug)]
+pub struct HashMethodFixedKeys<T> {
+    key_size: usize,
+    t: PhantomData<T>,
+}
+
+impl<T: FixedKey> HashMethodFixedKeys<T> {
+    pub fn create(key_size: usize) -> Self {
+        HashMethodFixedKeys {
+            key_size,
+            t: PhantomData,
+        }
+    }
+}
+
+macro_rules! pack_column {
+    ($KEYS:expr, $ARRAY:expr, $ARRAY_TYPE:ident, $UNSIGNED:ty, $SHIFT:expr) => {{
+        let array = downcast_array!($ARRAY, $ARRAY_TYPE)?;
+        for (row, key) in $KEYS.iter_mut().enumerate() {
+            *key |= (array.value(row) as $UNSIGNED as u128) << $SHIFT;
+        }
+    }};
+}
+
+impl<T: FixedKey> HashMethod for HashMethodFixedKeys<T> {
+    type HashKey = T;
+
+    fn name(&self) -> String {
+        format!("FixedKeys{}", std::mem::size_of::<T>() * 8)
+    }
+
+    fn build_keys(
+        &self,
+        group_columns: &[&DataColumnarValue],
+        rows: usize,
+    ) -> Result<Vec<Self::HashKey>> {
+        let mut keys = vec![0u128; rows];
+        let mut offset = 0;
+        for col in group_columns {
+            let array = col.to_array()?;
+            let shift = offset * 8;
+            match array.data_type() {
+                DataType::Int8 => pack_column!(keys, array, Int8Array, u8, shift),
+                DataType::Int16 => pack_column!(keys, array, Int16Array, u16, shift),
+                DataType::Int32 => pack_column!(keys, array, Int32Array, u32, shift),
+                DataType::Int64 => pack_column!(keys, array, Int64Array, u64, shift),
+                DataType::UInt8 => pack_column!(keys, array, UInt8Array, u8, shift),
+                DataType::UInt16 => pack_column!(keys, array, UInt16Array, u16, shift),
+                DataType::UInt32 => pack_column!(keys, array, UInt32Array, u32, shift),
+                DataType::UInt64 => pack_column!(keys, array, UInt64Array, u64, shift),
+                other => {
+                    return Err(ErrorCode::BadDataValueType(format!(
+                        "Cannot pack the group key of the type {:?}",
+                        other
+                    )))
+                }
+            }
+            offset += common_datavalues::numeric_byte_size(array.data_type())?;
+        }
+        Ok(keys.into_iter().map(T::from_u128).collect())
+    }
+
+    fn key_bytes(&self, key: &Self::HashKey) -> Vec<u8> {
+        let mut bytes = key.to_bytes();
+        bytes.truncate(self.key_size);
+        bytes
+    }
+}
+
+pub type HashMethodKeysU8 = HashMethodFixedKeys<u8>;
+pub type HashMethodKeysU16 = HashMethodFixedKeys<u16>;
+pub type HashMethodKeysU32 = HashMethodFixedKeys<u32>;
+pub type HashMethodKeysU64 = HashMethodFixedKeys<u64>;
+pub type HashMethodKeysU128 = HashMethodFixedKeys<u128>;
+
+/// The hash method chosen for the group by columns.
+#[derive(Clone, Debug)]
+pub enum HashMethodKind {
+    Serializer(HashMethodSerializer),
+    KeysU8(HashMethodKeysU8),
+    KeysU16(HashMethodKeysU16),
+    KeysU32(HashMethodKeysU32),
+    KeysU64(HashMethodKeysU64),
+    KeysU128(HashMethodKeysU128),
+}
+
+impl HashMethodKind {
+    pub fn name(&self) -> String {
+        match self {
+            HashMethodKind::Serializer(method) => method.name(),
+            HashMethodKind::KeysU8(method) => method.name(),
+            HashMethodKind::KeysU16(method) => method.name(),
+            HashMethodKind::KeysU32(method) => method.name(),
+            HashMethodKind::KeysU64(method) => method.name(),
+            HashMethodKind::KeysU128(method) => method.name(),
+        }
+    }
+}
+
+impl DataBlock {
+    /// Chooses the hash method of the group by columns: the non-nullable integer columns
+    /// fitting in 16 bytes are packed into a fixed-width key, the others are serialized.
+    pub fn choose_hash_method(
+        schema: &DataSchema,
+        column_names: &[String],
+    ) -> Result<HashMethodKind> {
+        let mut key_size = 0;
+        for name in column_names {
+            let field = schema.field_with_name(name)?;
+            let typ = field.data_type();
+            if field.is_nullable() || !common_datavalues::is_integer(typ) {
+                return Ok(HashMethodKind::Serializer(HashMethodSerializer));
+            }
+            key_size += common_datavalues::numeric_byte_size(typ)?;
+        }
+
+        Ok(match key_size {
+            1 => HashMethodKind::KeysU8(HashMethodKeysU8::create(key_size)),
+            2 => HashMethodKind::KeysU16(HashMethodKeysU16::create(key_size)),
+            3..=4 => HashMethodKind::KeysU32(HashMethodKeysU32::create(key_size)),
+            5..=8 => HashMethodKind::KeysU64(HashMethodKeysU64::create(key_size)),
+            9..=16 => HashMethodKind::KeysU128(HashMethodKeysU128::create(key_size)),
+            _ => HashMethodKind::Serializer(HashMethodSerializer),
+        })
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::sync::Arc;
+
+use common_datavalues::*;
+
+use crate::*;
+
+#[test]
+fn test_data_block_choose_hash_method() -> anyhow::Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int8, false),
+        DataField::new("b", DataType::UInt16, false),
+        DataField::new("c", DataType::Int64, false),
+        DataField::new("d", DataType::UInt64, false),
+        DataField::new("e", DataType::Int32, true),
+        DataField::new("f", DataType::Utf8, false),
+        DataField::new("g", DataType::Float64, false),
+    ]);
+
+    let tests = vec![
+        (vec!["a"], "FixedKeys8"),
+        (vec!["b"], "FixedKeys16"),
+        (vec!["a", "b"], "FixedKeys32"),
+        (vec!["c"], "FixedKeys64"),
+        (vec!["a", "c"], "FixedKeys128"),
+        (vec!["c", "d"], "FixedKeys128"),
+        (vec!["a", "c", "d"], "Serializer"),
+        (vec!["e"], "Serializer"),
+        (vec!["f"], "Serializer"),
+        (vec!["g"], "Serializer"),
+    ];
+    for (columns, expect) in tests {
+        let columns = columns.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        let method = DataBlock::choose_hash_method(&schema, &columns)?;
+        assert_eq!(expect, method.name(), "{:?}", columns);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_data_block_fixed_keys() -> anyhow::Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int8, false),
+        DataField::new("b", DataType::UInt16, false),
+    ]);
+    let block = DataBlock::create_by_array(schema.clone(), vec![
+        Arc::new(Int8Array::from(vec![1, -1, 1, 1])),
+        Arc::new(UInt16Array::from(vec![2, 2, 2, 258])),
+    ]);
+
+    let columns = &["a".to_string(), "b".to_string()];
+    let method = HashMethodKeysU32::create(3);
+    let group_columns = columns
+        .iter()
+        .map(|c| block.try_column_by_name(c))
+        .collect::<common_exception::Result<Vec<_>>>()?;
+    let keys = method.build_keys(&group_columns, block.num_rows())?;
+    assert_eq!(keys, vec![0x0201, 0x02ff, 0x0201, 0x010201]);
+
+    // The key bytes are the same as the serialized keys.
+    let serializer = HashMethodSerializer;
+    let serialized = serializer.build_keys(&group_columns, block.num_rows())?;
+    for (key, bytes) in keys.iter().zip(serialized.iter()) {
+        assert_eq!(&method.key_bytes(key), bytes);
+    }
+    Ok(())
+}
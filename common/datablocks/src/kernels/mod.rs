@@ -5,6 +5,8 @@
 #[cfg(test)]
 mod data_block_concat_test;
 #[cfg(test)]
+mod data_block_group_by_hash_test;
+#[cfg(test)]
 mod data_block_group_by_test;
 #[cfg(test)]
 mod data_block_scatter_test;
@@ -15,8 +17,19 @@ mod data_block_take_test;
 
 mod data_block_concat;
 mod data_block_group_by;
+mod data_block_group_by_hash;
 mod data_block_scatter;
 mod data_block_sort;
 mod data_block_take;
 
+pub use data_block_group_by_hash::FixedKey;
+pub use data_block_group_by_hash::HashMethod;
+pub use data_block_group_by_hash::HashMethodFixedKeys;
+pub use data_block_group_by_hash::HashMethodKeysU128;
+pub use data_block_group_by_hash::HashMethodKeysU16;
+pub use data_block_group_by_hash::HashMethodKeysU32;
+pub use data_block_group_by_hash::HashMethodKeysU64;
+pub use data_block_group_by_hash::HashMethodKeysU8;
+pub use data_block_group_by_hash::HashMethodKind;
+pub use data_block_group_by_hash::HashMethodSerializer;
 pub use data_block_sort::SortColumnDescription;
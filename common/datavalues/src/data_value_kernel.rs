@@ -332,4 +332,16 @@ impl DataValue {
             Err(_) => Ok(DataValue::Float64(Some(literal.parse::<f64>()?))),
         }
     }
+
+    /// Writes the values in the compact binary format, the floats are kept exactly.
+    pub fn serialize_values(values: &[DataValue], writer: &mut Vec<u8>) -> Result<()> {
+        bincode::serialize_into(writer, values)
+            .map_err(|e| ErrorCode::BadBytes(format!("Cannot serialize the values: {}", e)))
+    }
+
+    /// Reads the values written by `serialize_values`.
+    pub fn deserialize_values(bytes: &[u8]) -> Result<Vec<DataValue>> {
+        bincode::deserialize(bytes)
+            .map_err(|e| ErrorCode::BadBytes(format!("Cannot deserialize the values: {}", e)))
+    }
 }
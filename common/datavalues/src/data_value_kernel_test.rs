@@ -55,3 +55,32 @@ fn test_data_value_kernel_concat_row_key() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_data_value_kernel_serialize_values() -> anyhow::Result<()> {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    let values = vec![
+        DataValue::Null,
+        DataValue::UInt64(Some(19999900000)),
+        DataValue::Float64(Some(0.1 + 0.2)),
+        DataValue::Float32(None),
+        DataValue::Utf8(Some("x1".to_string())),
+        DataValue::Struct(vec![DataValue::Int64(Some(-1)), DataValue::UInt64(Some(2))]),
+        DataValue::List(
+            Some(vec![DataValue::Int8(Some(1)), DataValue::Int8(Some(2))]),
+            DataType::Int8,
+        ),
+    ];
+
+    let mut bytes = vec![];
+    DataValue::serialize_values(&values, &mut bytes)?;
+    assert_eq!(DataValue::deserialize_values(&bytes)?, values);
+
+    // Truncated bytes.
+    let result = DataValue::deserialize_values(&bytes[..bytes.len() - 1]);
+    assert!(result.is_err());
+    Ok(())
+}
@@ -128,6 +128,12 @@ build_exceptions! {
     DnsParseError(37),
     CannotConnectNode(38),
     DuplicateGetStream(39),
+    UnknownFormat(40),
+    BadBytes(41),
+    MemoryLimitExceeded(42),
+    AbortedQuery(43),
+    Timeout(44),
+    SessionIsLocked(45),
 
     UnknownException(1000),
     TokioError(1001)
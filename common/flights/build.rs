@@ -17,6 +17,7 @@ fn build_proto() {
     let protos = [
         &Path::new(&proto_dir).join(Path::new("queryflight.proto")),
         &Path::new(&proto_dir).join(Path::new("storeflight.proto")),
+        &Path::new(&proto_dir).join(Path::new("flightsql.proto")),
     ];
 
     for proto in protos.iter() {
@@ -8,6 +8,7 @@ pub use flight_token::FlightClaim;
 pub use flight_token::FlightToken;
 pub use store_client::BlockStream;
 pub use store_client::StoreClient;
+pub use store_do_action::ColumnMinMax;
 pub use store_do_action::CreateDatabaseAction;
 pub use store_do_action::CreateDatabaseActionResult;
 pub use store_do_action::CreateTableAction;
@@ -19,15 +20,21 @@ pub use store_do_action::DropTableAction;
 pub use store_do_action::DropTableActionResult;
 pub use store_do_action::GetTableAction;
 pub use store_do_action::GetTableActionResult;
+pub use store_do_action::GetTableStatisticsAction;
+pub use store_do_action::GetTableStatisticsActionResult;
 pub use store_do_action::ReadPlanAction;
 pub use store_do_action::ReadPlanActionResult;
 pub use store_do_action::ScanPartitionAction;
 pub use store_do_action::ScanPartitionResult;
+pub use store_do_action::SetTableStatisticsAction;
+pub use store_do_action::SetTableStatisticsActionResult;
 pub use store_do_action::StoreDoAction;
 pub use store_do_action::StoreDoActionResult;
 pub use store_do_get::ReadAction;
 pub use store_do_get::StoreDoGet;
+pub use store_do_put::do_put_commit_marker;
 pub use store_do_put::get_do_put_meta;
+pub use store_do_put::is_do_put_commit_marker;
 pub use store_do_put::set_do_put_meta;
 pub use store_do_put::AppendResult;
 
@@ -44,6 +51,7 @@ mod store_do_put;
 pub mod protobuf {
     tonic::include_proto!("queryflight");
     tonic::include_proto!("storeflight");
+    tonic::include_proto!("arrow.flight.protocol.sql");
 }
 
 #[cfg(test)]
@@ -24,12 +24,14 @@ use common_planners::CreateTablePlan;
 use common_planners::DropDatabasePlan;
 use common_planners::DropTablePlan;
 use common_planners::ScanPlan;
+use common_planners::TableStatistics;
 use common_streams::SendableDataBlockStream;
 use futures::stream;
 use futures::SinkExt;
 use futures::StreamExt;
 use log::info;
 use prost::Message;
+use tokio::sync::oneshot;
 use tonic::metadata::MetadataValue;
 use tonic::transport::Channel;
 use tonic::Request;
@@ -52,12 +54,16 @@ use crate::DropTableAction;
 use crate::DropTableActionResult;
 use crate::GetTableAction;
 use crate::GetTableActionResult;
+use crate::GetTableStatisticsAction;
 use crate::ScanPartitionAction;
 use crate::ScanPartitionResult;
+use crate::SetTableStatisticsAction;
+use crate::SetTableStatisticsActionResult;
 use crate::StoreDoGet;
 
-pub type BlockStream =
-    std::pin::Pin<Box<dyn futures::stream::Stream<Item = DataBlock> + Sync + Send + 'static>>;
+pub type BlockStream = std::pin::Pin<
+    Box<dyn futures::stream::Stream<Item = common_exception::Result<DataBlock>> + Send + 'static>,
+>;
 
 #[derive(Clone)]
 pub struct StoreClient {
@@ -168,6 +174,41 @@ impl StoreClient {
         anyhow::bail!("invalid response")
     }
 
+    /// Get the statistics stored by ANALYZE TABLE.
+    pub async fn get_table_statistics(
+        &mut self,
+        db: String,
+        table: String,
+    ) -> anyhow::Result<Option<TableStatistics>> {
+        let action = StoreDoAction::GetTableStatistics(GetTableStatisticsAction { db, table });
+        let rst = self.do_action(&action).await?;
+
+        if let StoreDoActionResult::GetTableStatistics(rst) = rst {
+            return Ok(rst.statistics);
+        }
+        anyhow::bail!("invalid response")
+    }
+
+    /// Store the statistics with the table meta, None drops them.
+    pub async fn set_table_statistics(
+        &mut self,
+        db: String,
+        table: String,
+        statistics: Option<TableStatistics>,
+    ) -> anyhow::Result<SetTableStatisticsActionResult> {
+        let action = StoreDoAction::SetTableStatistics(SetTableStatisticsAction {
+            db,
+            table,
+            statistics,
+        });
+        let rst = self.do_action(&action).await?;
+
+        if let StoreDoActionResult::SetTableStatistics(rst) = rst {
+            return Ok(rst);
+        }
+        anyhow::bail!("invalid response")
+    }
+
     pub async fn scan_partition(
         &mut self,
         db_name: String,
@@ -263,6 +304,8 @@ impl StoreClient {
     }
 
     /// Appends data partitions to specified table
+    /// The parts are committed only once the whole stream is sent, an error of the stream
+    /// fails the append and the parts sent before it are not committed.
     pub async fn append_data(
         &mut self,
         db_name: String,
@@ -276,38 +319,49 @@ impl StoreClient {
 
         tx.send(flight_schema).await?;
 
+        let (error_tx, mut error_rx) = oneshot::channel();
         tokio::spawn(async move {
             while let Some(block) = block_stream.next().await {
                 info!("next data block");
-                match RecordBatch::try_from(block) {
+                let batch =
+                    block.and_then(|block| RecordBatch::try_from(block).map_err(ErrorCode::from));
+                match batch {
                     Ok(batch) => {
                         if let Err(_e) = tx
                             .send(flight_data_from_arrow_batch(&batch, &ipc_write_opt).1)
                             .await
                         {
                             log::error!("failed to send flight-data to downstream, breaking out");
-                            break;
+                            return;
                         }
                     }
                     Err(e) => {
-                        log::error!(
-                            "failed to convert DataBlock to RecordBatch , breaking out, {:?}",
-                            e
-                        );
-                        break;
+                        // The stream ends without the commit marker, the store drops the put.
+                        log::error!("failed to read the block to append, breaking out, {:?}", e);
+                        let _ = error_tx.send(e);
+                        return;
                     }
                 }
             }
+            if let Err(_e) = tx.send(store_do_put::do_put_commit_marker()).await {
+                log::error!("failed to send the commit marker to downstream");
+            }
         });
 
         let mut req = Request::new(flight_stream);
         let meta = req.metadata_mut();
         store_do_put::set_do_put_meta(meta, &db_name, &tbl_name);
 
-        let res = self.client.do_put(req).await?;
+        let res = self.client.do_put(req).await;
+
+        // The error is sent before the request stream ends, it's already here if the put failed
+        // because of it.
+        if let Ok(error) = error_rx.try_recv() {
+            return Err(anyhow::anyhow!(error.to_string()));
+        }
 
         use anyhow::Context;
-        let put_result = res.into_inner().next().await.context("empty response")??;
+        let put_result = res?.into_inner().next().await.context("empty response")??;
         let vec = serde_json::from_slice(&put_result.app_metadata)?;
         Ok(vec)
     }
@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0.
 
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::io::Cursor;
@@ -9,6 +10,7 @@ use std::io::Cursor;
 use common_arrow::arrow_flight;
 use common_arrow::arrow_flight::Action;
 use common_datavalues::DataSchemaRef;
+use common_datavalues::DataValue;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
 use common_planners::DropDatabasePlan;
@@ -16,6 +18,7 @@ use common_planners::DropTablePlan;
 use common_planners::Partition;
 use common_planners::ScanPlan;
 use common_planners::Statistics;
+use common_planners::TableStatistics;
 use prost::Message;
 use tonic::Request;
 
@@ -68,20 +71,52 @@ pub struct GetTableAction {
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
 pub struct GetTableActionResult {
     pub table_id: i64,
+    // The version of the table, changed by every modification, e.g. appending data.
+    pub ver: i64,
     pub db: String,
     pub name: String,
     pub schema: DataSchemaRef,
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct GetTableStatisticsAction {
+    pub db: String,
+    pub table: String,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct GetTableStatisticsActionResult {
+    // None if the table is not analyzed or it's modified since.
+    pub statistics: Option<TableStatistics>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct SetTableStatisticsAction {
+    pub db: String,
+    pub table: String,
+    // None drops the stored statistics.
+    pub statistics: Option<TableStatistics>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct SetTableStatisticsActionResult {}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct ScanPartitionAction {
     pub scan_plan: ScanPlan,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+/// The min and max non-null values of one column in a data part.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct ColumnMinMax {
+    pub min: DataValue,
+    pub max: DataValue,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
 pub struct DataPartInfo {
     pub partition: Partition,
     pub stats: Statistics,
+    // Keyed by the column name, the columns without non-null values or not comparable are absent.
+    pub column_stats: HashMap<String, ColumnMinMax>,
 }
 
 pub type ScanPartitionResult = Option<Vec<DataPartInfo>>;
@@ -96,9 +131,11 @@ pub enum StoreDoAction {
     DropTable(DropTableAction),
     ScanPartition(ScanPartitionAction),
     GetTable(GetTableAction),
+    GetTableStatistics(GetTableStatisticsAction),
+    SetTableStatistics(SetTableStatisticsAction),
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
 pub enum StoreDoActionResult {
     ReadPlan(ReadPlanActionResult),
     CreateDatabase(CreateDatabaseActionResult),
@@ -107,6 +144,8 @@ pub enum StoreDoActionResult {
     DropTable(DropTableActionResult),
     ScanPartition(ScanPartitionResult),
     GetTable(GetTableActionResult),
+    GetTableStatistics(GetTableStatisticsActionResult),
+    SetTableStatistics(SetTableStatisticsActionResult),
 }
 
 /// Try convert tonic::Request<Action> to DoActionAction.
@@ -3,9 +3,14 @@
 // SPDX-License-Identifier: Apache-2.0.
 //
 
+use std::collections::HashMap;
+
+use common_arrow::arrow_flight::FlightData;
 use tonic::metadata::MetadataMap;
 use tonic::metadata::MetadataValue;
 
+use crate::ColumnMinMax;
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
 pub struct AppendResult {
     pub summary: Summary,
@@ -35,6 +40,7 @@ pub struct PartitionInfo {
     pub wire_bytes: usize,
     pub disk_bytes: usize,
     pub location: String,
+    pub column_stats: HashMap<String, ColumnMinMax>,
 }
 
 impl AppendResult {
@@ -45,6 +51,7 @@ impl AppendResult {
         cols: usize,
         wire_bytes: usize,
         disk_bytes: usize,
+        column_stats: HashMap<String, ColumnMinMax>,
     ) {
         let part = PartitionInfo {
             rows,
@@ -52,6 +59,7 @@ impl AppendResult {
             wire_bytes,
             disk_bytes,
             location: location.to_string(),
+            column_stats,
         };
         self.parts.push(part);
         self.summary.increase(rows, wire_bytes, disk_bytes);
@@ -61,6 +69,21 @@ impl AppendResult {
 pub const META_KEY_DB_NAME: &str = "fq-db-name-bin";
 pub const META_KEY_TBL_NAME: &str = "fq-tbl-name-bin";
 
+// The put ends with this message once every block is sent, the store only commits the
+// parts of a put that ends with it.
+const COMMIT_MARKER: &[u8] = b"fq-do-put-commit";
+
+pub fn do_put_commit_marker() -> FlightData {
+    FlightData {
+        app_metadata: COMMIT_MARKER.to_vec(),
+        ..Default::default()
+    }
+}
+
+pub fn is_do_put_commit_marker(flight_data: &FlightData) -> bool {
+    flight_data.data_header.is_empty() && flight_data.app_metadata == COMMIT_MARKER
+}
+
 pub fn set_do_put_meta(meta: &mut MetadataMap, db_name: &str, tbl_name: &str) {
     meta.insert_bin(
         META_KEY_DB_NAME,
@@ -7,7 +7,9 @@
 mod test {
     use tonic::metadata::MetadataMap;
 
+    use crate::do_put_commit_marker;
     use crate::get_do_put_meta;
+    use crate::is_do_put_commit_marker;
     use crate::set_do_put_meta;
 
     #[test]
@@ -20,4 +22,10 @@ mod test {
         assert_eq!(test_db, db);
         assert_eq!(test_tbl, tbl);
     }
+
+    #[test]
+    fn test_commit_marker() {
+        assert!(is_do_put_commit_marker(&do_put_commit_marker()));
+        assert!(!is_do_put_commit_marker(&Default::default()));
+    }
 }
@@ -23,6 +23,12 @@ pub trait Function: fmt::Display + Sync + Send + DynClone {
         None
     }
 
+    // The same arguments always give the same result, the optimizer can fold it
+    // into a constant when the arguments are constants.
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+
     fn return_type(&self, args: &[DataType]) -> Result<DataType>;
     fn nullable(&self, _input_schema: &DataSchema) -> Result<bool>;
     fn eval(&self, columns: &[DataColumnarValue], _input_rows: usize) -> Result<DataColumnarValue>;
@@ -37,6 +37,10 @@ impl Function for SleepFunction {
         1
     }
 
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+
     fn return_type(&self, args: &[DataType]) -> Result<DataType> {
         if !is_numeric(&args[0]) {
             return Err(ErrorCode::BadArguments(format!(
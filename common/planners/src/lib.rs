@@ -7,6 +7,8 @@ mod plan_aggregator_test;
 #[cfg(test)]
 mod plan_builder_test;
 #[cfg(test)]
+mod plan_column_range_test;
+#[cfg(test)]
 mod plan_display_test;
 #[cfg(test)]
 mod plan_explain_test;
@@ -34,6 +36,7 @@ mod test;
 mod plan_aggregator_final;
 mod plan_aggregator_partial;
 mod plan_builder;
+mod plan_column_range;
 mod plan_database_create;
 mod plan_database_drop;
 mod plan_display;
@@ -66,6 +69,7 @@ mod plan_setting;
 mod plan_sort;
 mod plan_stage;
 mod plan_statistics;
+mod plan_table_analyze;
 mod plan_table_create;
 mod plan_table_drop;
 mod plan_use_database;
@@ -75,6 +79,8 @@ mod plan_walker;
 pub use plan_aggregator_final::AggregatorFinalPlan;
 pub use plan_aggregator_partial::AggregatorPartialPlan;
 pub use plan_builder::PlanBuilder;
+pub use plan_column_range::compare_values;
+pub use plan_column_range::ColumnRange;
 pub use plan_database_create::CreateDatabasePlan;
 pub use plan_database_create::DatabaseEngineType;
 pub use plan_database_create::DatabaseOptions;
@@ -118,7 +124,10 @@ pub use plan_setting::VarValue;
 pub use plan_sort::SortPlan;
 pub use plan_stage::StageKind;
 pub use plan_stage::StagePlan;
+pub use plan_statistics::ColumnStatistics;
 pub use plan_statistics::Statistics;
+pub use plan_statistics::TableStatistics;
+pub use plan_table_analyze::AnalyzeTablePlan;
 pub use plan_table_create::CreateTablePlan;
 pub use plan_table_create::TableEngineType;
 pub use plan_table_create::TableOptions;
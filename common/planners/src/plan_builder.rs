@@ -118,15 +118,15 @@ impl PlanBuilder {
                 let fields = RewriteHelper::exprs_to_fields(aggr_expr, &schema_before_groupby)?;
                 let mut partial_fields = fields
                     .iter()
-                    .map(|f| DataField::new(f.name(), DataType::Utf8, false))
+                    .map(|f| DataField::new(f.name(), DataType::Binary, false))
                     .collect::<Vec<_>>();
 
                 if !group_expr.is_empty() {
                     // Fields. [aggrs,  [keys],  key ]
-                    // aggrs: aggr_len aggregate states
-                    // keys:  Vec<Key>, DataTypeStruct
+                    // aggrs: aggr_len aggregate states, DataTypeBinary
+                    // keys:  Vec<Key>, DataTypeBinary
                     // key:  group id, DataTypeBinary
-                    partial_fields.push(DataField::new("_group_keys", DataType::Utf8, false));
+                    partial_fields.push(DataField::new("_group_keys", DataType::Binary, false));
                     partial_fields.push(DataField::new("_group_by_key", DataType::Binary, false));
                 }
 
@@ -0,0 +1,203 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::cmp::Ordering;
+
+use common_datavalues::DataType;
+use common_datavalues::DataValue;
+
+use crate::Expression;
+
+const NANOS_PER_DAY: i128 = 86_400_000_000_000;
+
+/// The value range of one column derived from the push down filters, both bounds are inclusive.
+/// The range may be wider than the filters, it's only used to skip the data which can't match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnRange {
+    pub min: Option<DataValue>,
+    pub max: Option<DataValue>,
+}
+
+impl ColumnRange {
+    pub fn unbounded() -> Self {
+        ColumnRange {
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Collects the range of the column from the comparisons with literals, such as:
+    /// a > 1 and 10 >= a
+    /// The string literals are cast to the type of the column, such as: d >= '2021-01-01'
+    pub fn from_filters(filters: &[Expression], column: &str, data_type: &DataType) -> Self {
+        let mut range = Self::unbounded();
+        for filter in filters {
+            range.apply(filter, column, data_type);
+        }
+        range
+    }
+
+    /// The filters can't match any value.
+    pub fn is_empty(&self) -> bool {
+        match (&self.min, &self.max) {
+            (Some(min), Some(max)) => compare_values(min, max) == Some(Ordering::Greater),
+            _ => false,
+        }
+    }
+
+    /// Whether the values between [min, max] may match the range.
+    pub fn may_overlap(&self, min: &DataValue, max: &DataValue) -> bool {
+        if let Some(range_max) = &self.max {
+            if compare_values(range_max, min) == Some(Ordering::Less) {
+                return false;
+            }
+        }
+        if let Some(range_min) = &self.min {
+            if compare_values(range_min, max) == Some(Ordering::Greater) {
+                return false;
+            }
+        }
+        !self.is_empty()
+    }
+
+    fn apply(&mut self, filter: &Expression, column: &str, data_type: &DataType) {
+        if let Expression::BinaryExpression { left, op, right } = filter {
+            match (left.as_ref(), op.to_lowercase().as_str(), right.as_ref()) {
+                (_, "and", _) => {
+                    self.apply(left, column, data_type);
+                    self.apply(right, column, data_type);
+                }
+                (Expression::Column(name), op, Expression::Literal(value)) if name == column => {
+                    self.apply_comparison(op, value, data_type)
+                }
+                (Expression::Literal(value), op, Expression::Column(name)) if name == column => {
+                    // 1 < a => a > 1
+                    let op = match op {
+                        "<" => ">",
+                        "<=" => ">=",
+                        ">" => "<",
+                        ">=" => "<=",
+                        other => other,
+                    };
+                    self.apply_comparison(op, value, data_type)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn apply_comparison(&mut self, op: &str, value: &DataValue, data_type: &DataType) {
+        // The strings which can't be cast are skipped, the range is only wider.
+        let value = match (value, data_type) {
+            (
+                DataValue::Utf8(Some(_)),
+                DataType::Utf8 | DataType::LargeUtf8 | DataType::Binary | DataType::LargeBinary,
+            ) => value.clone(),
+            (DataValue::Utf8(Some(_)), data_type) => match value.cast(data_type) {
+                Ok(value) => value,
+                Err(_) => return,
+            },
+            _ => value.clone(),
+        };
+        if value.is_null() {
+            return;
+        }
+        match op {
+            "=" => {
+                self.narrow_min(&value);
+                self.narrow_max(&value);
+            }
+            ">" | ">=" => self.narrow_min(&value),
+            "<" | "<=" => self.narrow_max(&value),
+            _ => {}
+        }
+    }
+
+    fn narrow_min(&mut self, value: &DataValue) {
+        let narrower = match &self.min {
+            None => true,
+            Some(min) => compare_values(value, min) == Some(Ordering::Greater),
+        };
+        if narrower {
+            self.min = Some(value.clone());
+        }
+    }
+
+    fn narrow_max(&mut self, value: &DataValue) {
+        let narrower = match &self.max {
+            None => true,
+            Some(max) => compare_values(value, max) == Some(Ordering::Less),
+        };
+        if narrower {
+            self.max = Some(value.clone());
+        }
+    }
+}
+
+/// Compares the integers, floats, dates, timestamps and strings, None if they are not comparable.
+pub fn compare_values(left: &DataValue, right: &DataValue) -> Option<Ordering> {
+    // The dates and timestamps are only comparable with each other.
+    if is_temporal(left) != is_temporal(right) {
+        return None;
+    }
+    if let (Some(l), Some(r)) = (as_i128(left), as_i128(right)) {
+        return Some(l.cmp(&r));
+    }
+    if let (Some(l), Some(r)) = (as_f64(left), as_f64(right)) {
+        return l.partial_cmp(&r);
+    }
+    match (as_bytes(left), as_bytes(right)) {
+        (Some(l), Some(r)) => Some(l.cmp(r)),
+        _ => None,
+    }
+}
+
+fn as_i128(value: &DataValue) -> Option<i128> {
+    match value {
+        DataValue::Int8(Some(v)) => Some(*v as i128),
+        DataValue::Int16(Some(v)) => Some(*v as i128),
+        DataValue::Int32(Some(v)) => Some(*v as i128),
+        DataValue::Int64(Some(v)) => Some(*v as i128),
+        DataValue::UInt8(Some(v)) => Some(*v as i128),
+        DataValue::UInt16(Some(v)) => Some(*v as i128),
+        DataValue::UInt32(Some(v)) => Some(*v as i128),
+        DataValue::UInt64(Some(v)) => Some(*v as i128),
+        // The dates and timestamps are in nanoseconds since the epoch.
+        DataValue::Date32(Some(v)) => Some(*v as i128 * NANOS_PER_DAY),
+        DataValue::Date64(Some(v)) => Some(*v as i128 * 1_000_000),
+        DataValue::TimestampSecond(Some(v)) => Some(*v as i128 * 1_000_000_000),
+        DataValue::TimestampMillisecond(Some(v)) => Some(*v as i128 * 1_000_000),
+        DataValue::TimestampMicrosecond(Some(v)) => Some(*v as i128 * 1_000),
+        DataValue::TimestampNanosecond(Some(v)) => Some(*v as i128),
+        _ => None,
+    }
+}
+
+fn is_temporal(value: &DataValue) -> bool {
+    matches!(
+        value,
+        DataValue::Date32(_)
+            | DataValue::Date64(_)
+            | DataValue::TimestampSecond(_)
+            | DataValue::TimestampMillisecond(_)
+            | DataValue::TimestampMicrosecond(_)
+            | DataValue::TimestampNanosecond(_)
+    )
+}
+
+fn as_f64(value: &DataValue) -> Option<f64> {
+    match value {
+        DataValue::Float32(Some(v)) => Some(*v as f64),
+        DataValue::Float64(Some(v)) => Some(*v),
+        other => as_i128(other).map(|v| v as f64),
+    }
+}
+
+fn as_bytes(value: &DataValue) -> Option<&[u8]> {
+    match value {
+        DataValue::Utf8(Some(v)) => Some(v.as_bytes()),
+        DataValue::Binary(Some(v)) => Some(v.as_slice()),
+        _ => None,
+    }
+}
@@ -0,0 +1,172 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use common_datavalues::DataType;
+use common_datavalues::DataValue;
+use common_exception::Result;
+use pretty_assertions::assert_eq;
+
+use crate::*;
+
+#[test]
+fn test_column_range_from_filters() -> Result<()> {
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        filters: Vec<Expression>,
+        data_type: DataType,
+        expect: ColumnRange,
+    }
+
+    let tests = vec![
+        Test {
+            name: "no-filters",
+            filters: vec![],
+            data_type: DataType::Int64,
+            expect: ColumnRange::unbounded(),
+        },
+        Test {
+            name: "gt-and-mirrored-gt",
+            filters: vec![col("a").gt(lit(1u64)), lit(10u64).gt(col("a"))],
+            data_type: DataType::UInt64,
+            expect: ColumnRange {
+                min: Some(DataValue::UInt64(Some(1))),
+                max: Some(DataValue::UInt64(Some(10))),
+            },
+        },
+        Test {
+            name: "narrowest-bounds",
+            filters: vec![
+                col("a").gt_eq(lit(1i64)).and(col("a").gt_eq(lit(5i64))),
+                col("a").lt(lit(9i64)),
+                col("a").lt_eq(lit(20i64)),
+            ],
+            data_type: DataType::Int64,
+            expect: ColumnRange {
+                min: Some(DataValue::Int64(Some(5))),
+                max: Some(DataValue::Int64(Some(9))),
+            },
+        },
+        Test {
+            name: "eq",
+            filters: vec![col("a").eq(lit("x"))],
+            data_type: DataType::Utf8,
+            expect: ColumnRange {
+                min: Some(DataValue::Utf8(Some("x".to_string()))),
+                max: Some(DataValue::Utf8(Some("x".to_string()))),
+            },
+        },
+        Test {
+            name: "other-columns-and-expressions",
+            filters: vec![
+                col("b").gt(lit(1i64)),
+                add(col("a"), lit(1i64)).gt(lit(1i64)),
+                col("a").gt(col("b")),
+                col("a").gt(lit(1i64)).or(col("a").lt(lit(0i64))),
+            ],
+            data_type: DataType::Int64,
+            expect: ColumnRange::unbounded(),
+        },
+        Test {
+            name: "date-strings",
+            filters: vec![
+                col("a").gt_eq(lit("2021-01-01")),
+                col("a").lt(lit("2021-02-01")),
+            ],
+            data_type: DataType::Date32,
+            expect: ColumnRange {
+                min: Some(DataValue::Date32(Some(18628))),
+                max: Some(DataValue::Date32(Some(18659))),
+            },
+        },
+        Test {
+            name: "invalid-date-strings",
+            filters: vec![col("a").gt_eq(lit("x"))],
+            data_type: DataType::Date32,
+            expect: ColumnRange::unbounded(),
+        },
+    ];
+
+    for test in tests {
+        let actual = ColumnRange::from_filters(&test.filters, "a", &test.data_type);
+        assert_eq!(test.expect, actual, "{:#?}", test.name);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_column_range_overlap() -> Result<()> {
+    let range = ColumnRange::from_filters(
+        &[col("a").gt(lit(10u64)), col("a").lt(lit(20u64))],
+        "a",
+        &DataType::UInt64,
+    );
+    assert!(!range.is_empty());
+    assert!(range.may_overlap(&DataValue::Int32(Some(0)), &DataValue::Int32(Some(10))));
+    assert!(range.may_overlap(&DataValue::Int64(Some(15)), &DataValue::Int64(Some(100))));
+    assert!(range.may_overlap(
+        &DataValue::Float64(Some(19.5)),
+        &DataValue::Float64(Some(30.0))
+    ));
+    assert!(!range.may_overlap(&DataValue::UInt64(Some(0)), &DataValue::UInt64(Some(9))));
+    assert!(!range.may_overlap(
+        &DataValue::Float32(Some(20.5)),
+        &DataValue::Float32(Some(30.0))
+    ));
+    // Not comparable, may overlap.
+    assert!(range.may_overlap(
+        &DataValue::Utf8(Some("a".to_string())),
+        &DataValue::Utf8(Some("b".to_string()))
+    ));
+
+    let range = ColumnRange::from_filters(
+        &[col("a").gt(lit(10u64)), col("a").lt(lit(5u64))],
+        "a",
+        &DataType::UInt64,
+    );
+    assert!(range.is_empty());
+    assert!(!range.may_overlap(&DataValue::UInt64(Some(0)), &DataValue::UInt64(Some(100))));
+
+    let range = ColumnRange::from_filters(&[col("a").gt_eq(lit("b"))], "a", &DataType::Utf8);
+    assert!(!range.may_overlap(
+        &DataValue::Utf8(Some("a".to_string())),
+        &DataValue::Utf8(Some("aa".to_string()))
+    ));
+    assert!(range.may_overlap(
+        &DataValue::Utf8(Some("a".to_string())),
+        &DataValue::Utf8(Some("c".to_string()))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_column_range_overlap_dates() -> Result<()> {
+    let range =
+        ColumnRange::from_filters(&[col("a").gt_eq(lit("2021-01-01"))], "a", &DataType::Date32);
+    assert!(!range.may_overlap(
+        &DataValue::Date32(Some(18600)),
+        &DataValue::Date32(Some(18627))
+    ));
+    assert!(range.may_overlap(
+        &DataValue::Date32(Some(18600)),
+        &DataValue::Date32(Some(18628))
+    ));
+
+    // The dates and timestamps of the other units are compared at the same scale.
+    assert!(!range.may_overlap(
+        &DataValue::Date64(Some(0)),
+        &DataValue::Date64(Some(18627 * 86_400_000))
+    ));
+    assert!(range.may_overlap(
+        &DataValue::TimestampSecond(Some(0)),
+        &DataValue::TimestampSecond(Some(18628 * 86_400))
+    ));
+
+    // Not comparable with the integers, may overlap.
+    assert!(range.may_overlap(&DataValue::Int32(Some(0)), &DataValue::Int32(Some(1))));
+
+    Ok(())
+}
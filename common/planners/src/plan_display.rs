@@ -131,6 +131,12 @@ impl PlanNode {
                                 plan.statistics.read_rows,
                                 plan.statistics.read_bytes,
                             )?;
+                            if !plan.scan_plan.filters.is_empty() {
+                                write!(f, ", push down filters: {:?}", plan.scan_plan.filters)?;
+                            }
+                            if let Some(limit) = plan.scan_plan.limit {
+                                write!(f, ", push down limit: {}", limit)?;
+                            }
                             Ok(false)
                         }
                         PlanNode::Explain(plan) => {
@@ -167,6 +173,10 @@ impl PlanNode {
                             write!(f, " if_exists:{:}", plan.if_exists)?;
                             Ok(false)
                         }
+                        PlanNode::AnalyzeTable(plan) => {
+                            write!(f, "Analyze table {:}.{:}", plan.db, plan.table)?;
+                            Ok(false)
+                        }
                         _ => Ok(false),
                     }
                 })
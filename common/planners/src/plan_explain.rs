@@ -16,6 +16,7 @@ pub enum ExplainType {
     Syntax,
     Graph,
     Pipeline,
+    Optimizer,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
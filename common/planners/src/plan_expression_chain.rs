@@ -35,6 +35,16 @@ impl ExpressionChain {
     }
 
     fn add_expr(&mut self, expr: &Expression) -> Result<()> {
+        // The same subexpression is added once, all its uses read the same column.
+        let column_name = expr.column_name();
+        if self
+            .actions
+            .iter()
+            .any(|action| action.column_name() == column_name)
+        {
+            return Ok(());
+        }
+
         match expr {
             Expression::Alias(name, sub_expr) => {
                 self.add_expr(sub_expr)?;
@@ -7,10 +7,12 @@ use std::sync::Mutex;
 
 use common_datablocks::DataBlock;
 use common_datavalues::DataSchemaRef;
+use common_exception::Result;
 
 /// please do not keep this, this code is just for test purpose
+/// An error of the input stream fails the insert, the rows before it are not committed.
 type BlockStream =
-    std::pin::Pin<Box<dyn futures::stream::Stream<Item = DataBlock> + Sync + Send + 'static>>;
+    std::pin::Pin<Box<dyn futures::stream::Stream<Item = Result<DataBlock>> + Send + 'static>>;
 
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub struct InsertIntoPlan {
@@ -40,4 +42,15 @@ impl InsertIntoPlan {
     pub fn schema(&self) -> DataSchemaRef {
         self.schema.clone()
     }
+
+    /// Rows of a streaming insert (e.g. `INSERT INTO t FORMAT Native`) are sent by the client
+    /// after the query, so the handler attaches them once the plan is built.
+    pub fn set_input_stream(&self, input_stream: BlockStream) {
+        let mut inner = self.input_stream.lock().unwrap();
+        *inner = Some(input_stream);
+    }
+
+    pub fn has_input_stream(&self) -> bool {
+        self.input_stream.lock().unwrap().is_some()
+    }
 }
@@ -10,6 +10,7 @@ use common_exception::Result;
 
 use crate::AggregatorFinalPlan;
 use crate::AggregatorPartialPlan;
+use crate::AnalyzeTablePlan;
 use crate::CreateDatabasePlan;
 use crate::CreateTablePlan;
 use crate::DropDatabasePlan;
@@ -54,6 +55,7 @@ pub enum PlanNode {
     DropDatabase(DropDatabasePlan),
     CreateTable(CreateTablePlan),
     DropTable(DropTablePlan),
+    AnalyzeTable(AnalyzeTablePlan),
     UseDatabase(UseDatabasePlan),
     SetVariable(SettingPlan),
     InsertInto(InsertIntoPlan),
@@ -82,6 +84,7 @@ impl PlanNode {
             PlanNode::DropDatabase(v) => v.schema(),
             PlanNode::CreateTable(v) => v.schema(),
             PlanNode::DropTable(v) => v.schema(),
+            PlanNode::AnalyzeTable(v) => v.schema(),
             PlanNode::SetVariable(v) => v.schema(),
             PlanNode::Sort(v) => v.schema(),
             PlanNode::UseDatabase(v) => v.schema(),
@@ -110,6 +113,7 @@ impl PlanNode {
             PlanNode::DropDatabase(_) => "DropDatabasePlan",
             PlanNode::CreateTable(_) => "CreateTablePlan",
             PlanNode::DropTable(_) => "DropTablePlan",
+            PlanNode::AnalyzeTable(_) => "AnalyzeTablePlan",
             PlanNode::SetVariable(_) => "SetVariablePlan",
             PlanNode::Sort(_) => "SortPlan",
             PlanNode::UseDatabase(_) => "UseDatabasePlan",
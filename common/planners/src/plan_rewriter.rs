@@ -13,6 +13,7 @@ use common_exception::Result;
 
 use crate::AggregatorFinalPlan;
 use crate::AggregatorPartialPlan;
+use crate::AnalyzeTablePlan;
 use crate::CreateDatabasePlan;
 use crate::CreateTablePlan;
 use crate::DropDatabasePlan;
@@ -80,6 +81,7 @@ pub trait PlanRewriter<'plan> {
             PlanNode::Having(plan) => self.rewrite_having(plan),
             PlanNode::Expression(plan) => self.rewrite_expression(plan),
             PlanNode::DropTable(plan) => self.rewrite_drop_table(plan),
+            PlanNode::AnalyzeTable(plan) => self.rewrite_analyze_table(plan),
             PlanNode::DropDatabase(plan) => self.rewrite_drop_database(plan),
             PlanNode::InsertInto(plan) => self.rewrite_insert_into(plan),
         }
@@ -218,6 +220,10 @@ pub trait PlanRewriter<'plan> {
         Ok(PlanNode::DropTable(plan.clone()))
     }
 
+    fn rewrite_analyze_table(&mut self, plan: &'plan AnalyzeTablePlan) -> Result<PlanNode> {
+        Ok(PlanNode::AnalyzeTable(plan.clone()))
+    }
+
     fn rewrite_drop_database(&mut self, plan: &'plan DropDatabasePlan) -> Result<PlanNode> {
         Ok(PlanNode::DropDatabase(plan.clone()))
     }
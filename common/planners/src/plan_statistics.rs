@@ -2,6 +2,10 @@
 //
 // SPDX-License-Identifier: Apache-2.0.
 
+use std::collections::HashMap;
+
+use common_datavalues::DataValue;
+
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Debug, Default)]
 pub struct Statistics {
     /// Total rows of the query read.
@@ -15,3 +19,24 @@ impl Statistics {
         *self = Self::default();
     }
 }
+
+/// The statistics of a table collected by ANALYZE TABLE.
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Clone, Debug, Default)]
+pub struct TableStatistics {
+    pub rows: usize,
+    pub bytes: usize,
+    /// The statistics of each column, keyed by the column name.
+    pub columns: HashMap<String, ColumnStatistics>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Clone, Debug, Default)]
+pub struct ColumnStatistics {
+    /// None if the column has no values or the values are not comparable.
+    pub min: Option<DataValue>,
+    pub max: Option<DataValue>,
+    pub null_count: usize,
+    pub distinct_count: usize,
+    /// The upper bounds of the equi-depth histogram buckets in ascending order,
+    /// each bucket holds about the same number of the non-null values.
+    pub histogram: Vec<DataValue>,
+}
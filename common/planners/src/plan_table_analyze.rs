@@ -0,0 +1,28 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use common_datavalues::DataField;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataSchemaRefExt;
+use common_datavalues::DataType;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct AnalyzeTablePlan {
+    pub db: String,
+    /// The table name
+    pub table: String,
+}
+
+impl AnalyzeTablePlan {
+    /// One row of the collected statistics for each column.
+    pub fn schema(&self) -> DataSchemaRef {
+        DataSchemaRefExt::create(vec![
+            DataField::new("column", DataType::Utf8, false),
+            DataField::new("min", DataType::Utf8, false),
+            DataField::new("max", DataType::Utf8, false),
+            DataField::new("null_count", DataType::UInt64, false),
+            DataField::new("distinct_count", DataType::UInt64, false),
+        ])
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::AggregatorFinalPlan;
 use crate::AggregatorPartialPlan;
+use crate::AnalyzeTablePlan;
 use crate::CreateDatabasePlan;
 use crate::CreateTablePlan;
 use crate::DropDatabasePlan;
@@ -87,6 +88,7 @@ pub trait PlanVisitor<'plan> {
             PlanNode::DropDatabase(plan) => self.visit_drop_database(plan),
             PlanNode::CreateTable(plan) => self.visit_create_table(plan),
             PlanNode::DropTable(plan) => self.visit_drop_table(plan),
+            PlanNode::AnalyzeTable(plan) => self.visit_analyze_table(plan),
             PlanNode::UseDatabase(plan) => self.visit_use_database(plan),
             PlanNode::SetVariable(plan) => self.visit_set_variable(plan),
             PlanNode::Stage(plan) => self.visit_stage(plan),
@@ -161,6 +163,8 @@ pub trait PlanVisitor<'plan> {
 
     fn visit_drop_table(&mut self, _: &'plan DropTablePlan) {}
 
+    fn visit_analyze_table(&mut self, _: &'plan AnalyzeTablePlan) {}
+
     fn visit_use_database(&mut self, _: &'plan UseDatabasePlan) {}
 
     fn visit_set_variable(&mut self, _: &'plan SettingPlan) {}
@@ -2,26 +2,42 @@
 //
 // SPDX-License-Identifier: Apache-2.0.
 
+#[cfg(test)]
+mod stream_abort_test;
 #[cfg(test)]
 mod stream_datablock_test;
 
 #[cfg(test)]
 mod stream_progress_test;
+#[cfg(test)]
+mod stream_sort_merge_test;
+#[cfg(test)]
+mod stream_spill_test;
+#[cfg(test)]
+mod stream_take_test;
 
 mod stream;
+mod stream_abort;
 mod stream_datablock;
 mod stream_limit_by;
 mod stream_parquet;
 mod stream_progress;
 mod stream_skip;
 mod stream_sort;
+mod stream_sort_merge;
+mod stream_spill;
 mod stream_take;
 
 pub use stream::SendableDataBlockStream;
+pub use stream_abort::AbortCallback;
+pub use stream_abort::AbortStream;
 pub use stream_datablock::DataBlockStream;
 pub use stream_limit_by::LimitByStream;
 pub use stream_parquet::ParquetStream;
 pub use stream_progress::ProgressStream;
 pub use stream_skip::SkipStream;
 pub use stream_sort::SortStream;
+pub use stream_sort_merge::SortMergeStream;
+pub use stream_spill::SpillFile;
+pub use stream_spill::SpillStream;
 pub use stream_take::TakeStream;
@@ -0,0 +1,83 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::future::Future;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Instant;
+
+use common_datablocks::DataBlock;
+use common_exception::Result;
+use futures::Stream;
+use pin_project_lite::pin_project;
+use tokio::time::Sleep;
+
+use crate::SendableDataBlockStream;
+
+/// Checked before every block, an error stops the stream.
+pub type AbortCallback = Box<dyn Fn() -> Result<()> + Send + Sync + 'static>;
+
+pin_project! {
+    /// Ends the input with the error of the callback, it is how a cancelled
+    /// or timed out query stops reading its sources.
+    pub struct AbortStream {
+        #[pin]
+        input: SendableDataBlockStream,
+        callback: AbortCallback,
+        // Wakes the stream at the deadline, when the input is stalled and never yields again.
+        #[pin]
+        deadline: Option<Sleep>,
+        aborted: bool,
+    }
+}
+
+impl AbortStream {
+    pub fn try_create(input: SendableDataBlockStream, callback: AbortCallback) -> Result<Self> {
+        Ok(Self {
+            input,
+            callback,
+            deadline: None,
+            aborted: false,
+        })
+    }
+
+    /// The callback is checked again at the deadline even if the input has no block.
+    pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline.map(|deadline| tokio::time::sleep_until(deadline.into()));
+        self
+    }
+}
+
+impl Stream for AbortStream {
+    type Item = Result<DataBlock>;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        if *this.aborted {
+            return Poll::Ready(None);
+        }
+
+        if let Err(cause) = (this.callback)() {
+            *this.aborted = true;
+            return Poll::Ready(Some(Err(cause)));
+        }
+
+        match this.input.poll_next(ctx) {
+            Poll::Pending => match this.deadline.as_pin_mut() {
+                Some(deadline) if deadline.poll(ctx).is_ready() => match (this.callback)() {
+                    Err(cause) => {
+                        *this.aborted = true;
+                        Poll::Ready(Some(Err(cause)))
+                    }
+                    Ok(_) => Poll::Pending,
+                },
+                _ => Poll::Pending,
+            },
+            poll => poll,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[tokio::test]
+async fn test_abort_stream() -> anyhow::Result<()> {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
+    use common_datablocks::*;
+    use common_datavalues::*;
+    use common_exception::ErrorCode;
+    use futures::StreamExt;
+
+    use crate::*;
+
+    let schema = DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int64, false)]);
+
+    let block = DataBlock::create_by_array(schema.clone(), vec![Arc::new(Int64Array::from(vec![
+        1, 2, 3,
+    ]))]);
+
+    let input = DataBlockStream::create(Arc::new(DataSchema::empty()), None, vec![
+        block.clone(),
+        block.clone(),
+        block,
+    ]);
+
+    let aborted = Arc::new(AtomicBool::new(false));
+    let aborted_clone = aborted.clone();
+    let callback = Box::new(move || match aborted_clone.load(Ordering::Relaxed) {
+        true => Err(ErrorCode::AbortedQuery("Query was aborted")),
+        false => Ok(()),
+    });
+
+    let mut stream = AbortStream::try_create(Box::pin(input), callback)?;
+    assert_eq!(stream.next().await.unwrap()?.num_rows(), 3);
+
+    // The stream ends with the error after the abort.
+    aborted.store(true, Ordering::Relaxed);
+    let error = stream.next().await.unwrap().unwrap_err();
+    assert_eq!(error.code(), 43);
+    assert!(stream.next().await.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_abort_stream_with_deadline() -> anyhow::Result<()> {
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use common_datablocks::DataBlock;
+    use common_exception::ErrorCode;
+    use common_exception::Result;
+    use futures::StreamExt;
+
+    use crate::*;
+
+    let deadline = Instant::now() + Duration::from_millis(50);
+    let callback = Box::new(move || match Instant::now() >= deadline {
+        true => Err(ErrorCode::Timeout("Timeout exceeded")),
+        false => Ok(()),
+    });
+
+    // The input is stalled, the stream ends with the error at the deadline.
+    let input: SendableDataBlockStream = Box::pin(futures::stream::pending::<Result<DataBlock>>());
+    let mut stream = AbortStream::try_create(input, callback)?.with_deadline(Some(deadline));
+    let error = stream.next().await.unwrap().unwrap_err();
+    assert_eq!(error.code(), 44);
+    assert!(stream.next().await.is_none());
+
+    Ok(())
+}
@@ -7,8 +7,8 @@ use std::task::Poll;
 
 use common_datablocks::DataBlock;
 use common_exception::Result;
-use crossbeam::channel::Receiver;
 use futures::Stream;
+use tokio::sync::mpsc::Receiver;
 
 pub struct ParquetStream {
     response_rx: Receiver<Option<Result<DataBlock>>>,
@@ -23,11 +23,16 @@ impl ParquetStream {
 impl Stream for ParquetStream {
     type Item = Result<DataBlock>;
 
-    fn poll_next(self: std::pin::Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        match self.response_rx.recv() {
-            Ok(block) => Poll::Ready(block),
-            // RecvError means receiver has exited and closed the channel
-            Err(_) => Poll::Ready(None),
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        // The reader runs on a blocking thread, the worker is not held while it reads.
+        match self.response_rx.poll_recv(cx) {
+            Poll::Ready(Some(block)) => Poll::Ready(block),
+            // None means the reader has exited and closed the channel
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
         }
     }
 }
@@ -0,0 +1,363 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::task::Context;
+use std::task::Poll;
+
+use common_arrow::arrow::array::build_compare;
+use common_arrow::arrow::array::make_array;
+use common_arrow::arrow::array::ArrayRef;
+use common_arrow::arrow::array::DynComparator;
+use common_arrow::arrow::array::MutableArrayData;
+use common_datablocks::DataBlock;
+use common_datablocks::SortColumnDescription;
+use common_datavalues::DataSchemaRef;
+use common_exception::Result;
+use futures::Stream;
+use futures::StreamExt;
+
+use crate::SendableDataBlockStream;
+
+// The current block of an input.
+struct MergeCursor {
+    id: usize,
+    block: DataBlock,
+    sort_arrays: Vec<ArrayRef>,
+}
+
+// The comparators between the current blocks of the inputs, built once for a run of the merge.
+struct MergeComparators<'a> {
+    cursors: &'a [Option<MergeCursor>],
+    descriptions: &'a [SortColumnDescription],
+    // The comparators of the sort columns for the inputs (left, right), left < right.
+    comparators: HashMap<(usize, usize), Vec<DynComparator<'a>>>,
+}
+
+impl<'a> MergeComparators<'a> {
+    fn try_create(
+        cursors: &'a [Option<MergeCursor>],
+        descriptions: &'a [SortColumnDescription],
+    ) -> Result<Self> {
+        let mut comparators = HashMap::new();
+        for (left, l_cursor) in cursors.iter().enumerate() {
+            for (right, r_cursor) in cursors.iter().enumerate().skip(left + 1) {
+                if let (Some(l_cursor), Some(r_cursor)) = (l_cursor, r_cursor) {
+                    let columns = l_cursor
+                        .sort_arrays
+                        .iter()
+                        .zip(r_cursor.sort_arrays.iter())
+                        .map(|(l, r)| build_compare(l.as_ref(), r.as_ref()))
+                        .collect::<common_arrow::arrow::error::Result<Vec<_>>>()?;
+                    comparators.insert((left, right), columns);
+                }
+            }
+        }
+
+        Ok(MergeComparators {
+            cursors,
+            descriptions,
+            comparators,
+        })
+    }
+
+    // Compares the rows of the two inputs, taking the nulls and the sort options into account.
+    fn compare(&self, lhs: usize, l_row: usize, rhs: usize, r_row: usize) -> Ordering {
+        let (left, right) = match (&self.cursors[lhs], &self.cursors[rhs]) {
+            (Some(left), Some(right)) => (left, right),
+            _ => return Ordering::Equal,
+        };
+
+        for (c, description) in self.descriptions.iter().enumerate() {
+            let l_valid = left.sort_arrays[c].is_valid(l_row);
+            let r_valid = right.sort_arrays[c].is_valid(r_row);
+            // The nulls are placed by nulls_first whatever the direction, the same as the sort kernel.
+            let result = match (l_valid, r_valid) {
+                (true, true) => {
+                    let ordering = if lhs < rhs {
+                        (self.comparators[&(lhs, rhs)][c])(l_row, r_row)
+                    } else {
+                        (self.comparators[&(rhs, lhs)][c])(r_row, l_row).reverse()
+                    };
+                    if description.asc {
+                        ordering
+                    } else {
+                        ordering.reverse()
+                    }
+                }
+                (false, true) if description.nulls_first => Ordering::Less,
+                (false, true) => Ordering::Greater,
+                (true, false) if description.nulls_first => Ordering::Greater,
+                (true, false) => Ordering::Less,
+                (false, false) => Ordering::Equal,
+            };
+            if result != Ordering::Equal {
+                return result;
+            }
+        }
+        Ordering::Equal
+    }
+}
+
+// The current row of an input in the heap of the merge.
+struct MergeRow<'a> {
+    input: usize,
+    row: usize,
+    comparators: &'a MergeComparators<'a>,
+}
+
+impl<'a> Ord for MergeRow<'a> {
+    // The heap is a max-heap, the smallest row is the greatest and the first input wins the
+    // ties to keep the merge stable.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.comparators
+            .compare(self.input, self.row, other.input, other.row)
+            .then(self.input.cmp(&other.input))
+            .reverse()
+    }
+}
+
+impl<'a> PartialOrd for MergeRow<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> PartialEq for MergeRow<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'a> Eq for MergeRow<'a> {}
+
+// The rows of the output block, as the slices of the input blocks.
+struct MergeOutput {
+    blocks: Vec<(usize, DataBlock)>,
+    // The index of the block in blocks, the start and the end rows.
+    slices: Vec<(usize, usize, usize)>,
+    rows: usize,
+}
+
+impl MergeOutput {
+    fn empty() -> Self {
+        MergeOutput {
+            blocks: vec![],
+            slices: vec![],
+            rows: 0,
+        }
+    }
+
+    // Appends the row of the current block of the input.
+    fn push(&mut self, cursor: &MergeCursor, row: usize) {
+        match self.slices.last_mut() {
+            // The next row of the last slice.
+            Some((block, _, end)) if self.blocks[*block].0 == cursor.id && *end == row => {
+                *end += 1;
+            }
+            _ => {
+                let block = match self.blocks.iter().position(|(id, _)| *id == cursor.id) {
+                    Some(block) => block,
+                    None => {
+                        self.blocks.push((cursor.id, cursor.block.clone()));
+                        self.blocks.len() - 1
+                    }
+                };
+                self.slices.push((block, row, row + 1));
+            }
+        }
+        self.rows += 1;
+    }
+}
+
+/// Merges the sorted input streams into one sorted stream, the rows are compared one by one
+/// so only the current block of every input is kept in memory.
+/// The output blocks have at most block_size rows, the inputs are dropped once the limit is reached.
+pub struct SortMergeStream {
+    schema: DataSchemaRef,
+    inputs: Vec<SendableDataBlockStream>,
+    sort_columns_descriptions: Vec<SortColumnDescription>,
+    limit: Option<usize>,
+    block_size: usize,
+    cursors: Vec<Option<MergeCursor>>,
+    // The position of the next row to merge in the current block of every input.
+    rows: Vec<usize>,
+    finished: Vec<bool>,
+    output: MergeOutput,
+    merged_rows: usize,
+    next_block_id: usize,
+}
+
+impl SortMergeStream {
+    pub fn try_create(
+        schema: DataSchemaRef,
+        inputs: Vec<SendableDataBlockStream>,
+        sort_columns_descriptions: Vec<SortColumnDescription>,
+        limit: Option<usize>,
+        block_size: usize,
+    ) -> Result<Self> {
+        let size = inputs.len();
+        Ok(SortMergeStream {
+            schema,
+            inputs,
+            sort_columns_descriptions,
+            limit,
+            block_size: std::cmp::max(block_size, 1),
+            cursors: (0..size).map(|_| None).collect(),
+            rows: vec![0; size],
+            finished: vec![false; size],
+            output: MergeOutput::empty(),
+            merged_rows: 0,
+            next_block_id: 0,
+        })
+    }
+
+    fn set_cursor(&mut self, index: usize, block: DataBlock) -> Result<()> {
+        let sort_arrays = self
+            .sort_columns_descriptions
+            .iter()
+            .map(|f| block.try_array_by_name(&f.column_name))
+            .collect::<Result<Vec<_>>>()?;
+        self.next_block_id += 1;
+        self.cursors[index] = Some(MergeCursor {
+            id: self.next_block_id,
+            block,
+            sort_arrays,
+        });
+        self.rows[index] = 0;
+        Ok(())
+    }
+
+    // Merges the rows of the current blocks until a block is exhausted, the output is full
+    // or the limit is reached.
+    fn merge_current_blocks(&mut self) -> Result<()> {
+        let comparators =
+            MergeComparators::try_create(&self.cursors, &self.sort_columns_descriptions)?;
+        let mut heap = self
+            .cursors
+            .iter()
+            .enumerate()
+            .filter(|(_, cursor)| cursor.is_some())
+            .map(|(input, _)| MergeRow {
+                input,
+                row: self.rows[input],
+                comparators: &comparators,
+            })
+            .collect::<BinaryHeap<_>>();
+
+        let mut exhausted = None;
+        while let Some(mut next) = heap.peek_mut() {
+            let cursor = self.cursors[next.input].as_ref().unwrap();
+            self.output.push(cursor, next.row);
+            self.merged_rows += 1;
+            next.row += 1;
+
+            if next.row >= cursor.block.num_rows() {
+                exhausted = Some(next.input);
+                break;
+            }
+            if self.output.rows >= self.block_size || self.is_limited() {
+                break;
+            }
+        }
+
+        for merge_row in heap.iter() {
+            self.rows[merge_row.input] = merge_row.row;
+        }
+        drop(heap);
+        drop(comparators);
+
+        if let Some(input) = exhausted {
+            self.cursors[input] = None;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<Option<DataBlock>> {
+        if self.output.rows == 0 {
+            return Ok(None);
+        }
+
+        let output = std::mem::replace(&mut self.output, MergeOutput::empty());
+        let mut arrays = Vec::with_capacity(self.schema.fields().len());
+        for index in 0..self.schema.fields().len() {
+            let columns = output
+                .blocks
+                .iter()
+                .map(|(_, block)| block.column(index).to_array())
+                .collect::<Result<Vec<_>>>()?;
+            let data = columns.iter().map(|c| c.data_ref()).collect::<Vec<_>>();
+            let mut mutable = MutableArrayData::new(data, false, output.rows);
+            for (block, start, end) in &output.slices {
+                mutable.extend(*block, *start, *end);
+            }
+            arrays.push(make_array(mutable.freeze()));
+        }
+        Ok(Some(DataBlock::create_by_array(
+            self.schema.clone(),
+            arrays,
+        )))
+    }
+
+    fn is_limited(&self) -> bool {
+        matches!(self.limit, Some(limit) if self.merged_rows >= limit)
+    }
+}
+
+impl Stream for SortMergeStream {
+    type Item = Result<DataBlock>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        loop {
+            if self.is_limited() {
+                // No more rows are needed, release the inputs and their current blocks.
+                self.inputs.clear();
+                self.cursors.clear();
+                return Poll::Ready(self.flush().transpose());
+            }
+
+            // Every input needs its current block to choose the next row.
+            let mut pending = false;
+            for index in 0..self.inputs.len() {
+                while self.cursors[index].is_none() && !self.finished[index] {
+                    match self.inputs[index].poll_next_unpin(ctx) {
+                        Poll::Ready(Some(Ok(block))) if block.num_rows() == 0 => {}
+                        Poll::Ready(Some(Ok(block))) => {
+                            if let Err(e) = self.set_cursor(index, block) {
+                                return Poll::Ready(Some(Err(e)));
+                            }
+                        }
+                        Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                        Poll::Ready(None) => self.finished[index] = true,
+                        Poll::Pending => {
+                            pending = true;
+                            break;
+                        }
+                    }
+                }
+            }
+            if pending {
+                return match self.output.rows {
+                    0 => Poll::Pending,
+                    _ => Poll::Ready(self.flush().transpose()),
+                };
+            }
+
+            if self.cursors.iter().all(|cursor| cursor.is_none()) {
+                return Poll::Ready(self.flush().transpose());
+            }
+            if let Err(e) = self.merge_current_blocks() {
+                return Poll::Ready(Some(Err(e)));
+            }
+            if self.output.rows >= self.block_size {
+                return Poll::Ready(self.flush().transpose());
+            }
+        }
+    }
+}
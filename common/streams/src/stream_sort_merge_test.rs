@@ -0,0 +1,233 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use common_datablocks::*;
+use common_datavalues::*;
+use futures::stream::StreamExt;
+use futures::stream::TryStreamExt;
+
+use crate::*;
+
+fn create_block(schema: &DataSchemaRef, a: Vec<Option<i64>>, b: Vec<&str>) -> DataBlock {
+    DataBlock::create_by_array(schema.clone(), vec![
+        Arc::new(Int64Array::from(a)),
+        Arc::new(StringArray::from(b)),
+    ])
+}
+
+fn create_run(schema: &DataSchemaRef, blocks: Vec<DataBlock>) -> SendableDataBlockStream {
+    Box::pin(DataBlockStream::create(schema.clone(), None, blocks))
+}
+
+#[tokio::test]
+async fn test_sort_merge_stream() -> anyhow::Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int64, true),
+        DataField::new("b", DataType::Utf8, false),
+    ]);
+
+    let runs = vec![
+        create_run(&schema, vec![
+            create_block(&schema, vec![Some(1), Some(4)], vec!["x1", "x4"]),
+            create_block(&schema, vec![Some(7)], vec!["x7"]),
+        ]),
+        create_run(&schema, vec![create_block(
+            &schema,
+            vec![Some(2), Some(4), Some(5)],
+            vec!["y2", "y4", "y5"],
+        )]),
+        create_run(&schema, vec![]),
+        create_run(&schema, vec![create_block(
+            &schema,
+            vec![None, Some(3)],
+            vec!["z0", "z3"],
+        )]),
+    ];
+
+    let descriptions = vec![SortColumnDescription {
+        column_name: "a".to_string(),
+        asc: true,
+        nulls_first: true,
+    }];
+    let stream = SortMergeStream::try_create(schema.clone(), runs, descriptions, None, 3)?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+
+    // The blocks have at most 3 rows, the ties keep the order of the runs.
+    assert_eq!(
+        vec![3, 3, 2],
+        result.iter().map(|b| b.num_rows()).collect::<Vec<_>>()
+    );
+    let expected = vec![
+        "+---+----+",
+        "| a | b  |",
+        "+---+----+",
+        "|   | z0 |",
+        "| 1 | x1 |",
+        "| 2 | y2 |",
+        "| 3 | z3 |",
+        "| 4 | x4 |",
+        "| 4 | y4 |",
+        "| 5 | y5 |",
+        "| 7 | x7 |",
+        "+---+----+",
+    ];
+    assert_blocks_eq(expected, result.as_slice());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sort_merge_stream_desc_with_limit() -> anyhow::Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int64, true),
+        DataField::new("b", DataType::Utf8, false),
+    ]);
+
+    let runs = vec![
+        create_run(&schema, vec![create_block(
+            &schema,
+            vec![Some(9), Some(3), None],
+            vec!["x9", "x3", "x0"],
+        )]),
+        create_run(&schema, vec![create_block(
+            &schema,
+            vec![Some(8), Some(6), Some(1)],
+            vec!["y8", "y6", "y1"],
+        )]),
+    ];
+
+    let descriptions = vec![SortColumnDescription {
+        column_name: "a".to_string(),
+        asc: false,
+        nulls_first: false,
+    }];
+    let stream = SortMergeStream::try_create(schema.clone(), runs, descriptions, Some(4), 10)?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+
+    let expected = vec![
+        "+---+----+",
+        "| a | b  |",
+        "+---+----+",
+        "| 9 | x9 |",
+        "| 8 | y8 |",
+        "| 6 | y6 |",
+        "| 3 | x3 |",
+        "+---+----+",
+    ];
+    assert_blocks_eq(expected, result.as_slice());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sort_merge_stream_nulls_in_many_inputs() -> anyhow::Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int64, true),
+        DataField::new("b", DataType::Utf8, false),
+    ]);
+
+    let runs = vec![
+        create_run(&schema, vec![
+            create_block(&schema, vec![Some(5), Some(2)], vec!["x5", "x2"]),
+            create_block(&schema, vec![None, None], vec!["x0", "x1"]),
+        ]),
+        create_run(&schema, vec![create_block(
+            &schema,
+            vec![Some(5), Some(3), None],
+            vec!["y5", "y3", "y0"],
+        )]),
+        create_run(&schema, vec![
+            create_block(&schema, vec![Some(6)], vec!["z6"]),
+            create_block(&schema, vec![Some(2), None], vec!["z2", "z0"]),
+        ]),
+        create_run(&schema, vec![create_block(
+            &schema,
+            vec![Some(4), Some(1)],
+            vec!["w4", "w1"],
+        )]),
+    ];
+
+    let descriptions = vec![SortColumnDescription {
+        column_name: "a".to_string(),
+        asc: false,
+        nulls_first: false,
+    }];
+    let stream = SortMergeStream::try_create(schema.clone(), runs, descriptions, None, 4)?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+
+    // The nulls are the last, the ties keep the order of the runs.
+    let expected = vec![
+        "+---+----+",
+        "| a | b  |",
+        "+---+----+",
+        "| 6 | z6 |",
+        "| 5 | x5 |",
+        "| 5 | y5 |",
+        "| 4 | w4 |",
+        "| 3 | y3 |",
+        "| 2 | x2 |",
+        "| 2 | z2 |",
+        "| 1 | w1 |",
+        "|   | x0 |",
+        "|   | x1 |",
+        "|   | y0 |",
+        "|   | z0 |",
+        "+---+----+",
+    ];
+    assert_blocks_eq(expected, result.as_slice());
+    Ok(())
+}
+
+struct DropGuard(Arc<AtomicBool>);
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+#[tokio::test]
+async fn test_sort_merge_stream_drops_inputs_at_limit() -> anyhow::Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int64, true),
+        DataField::new("b", DataType::Utf8, false),
+    ]);
+
+    let dropped = Arc::new(AtomicBool::new(false));
+    let guard = DropGuard(dropped.clone());
+    let run: SendableDataBlockStream = Box::pin(
+        create_run(&schema, vec![create_block(
+            &schema,
+            vec![Some(1), Some(3)],
+            vec!["x1", "x3"],
+        )])
+        .map(move |block| {
+            let _ = &guard;
+            block
+        }),
+    );
+    let runs = vec![
+        run,
+        create_run(&schema, vec![create_block(
+            &schema,
+            vec![Some(2), Some(4)],
+            vec!["y2", "y4"],
+        )]),
+    ];
+
+    let descriptions = vec![SortColumnDescription {
+        column_name: "a".to_string(),
+        asc: true,
+        nulls_first: true,
+    }];
+    let mut stream = SortMergeStream::try_create(schema.clone(), runs, descriptions, Some(2), 10)?;
+
+    let block = stream.next().await.unwrap()?;
+    assert_eq!(2, block.num_rows());
+    assert!(dropped.load(Ordering::SeqCst));
+    assert!(stream.next().await.is_none());
+    Ok(())
+}
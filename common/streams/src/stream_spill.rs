@@ -0,0 +1,115 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::task::Context;
+use std::task::Poll;
+
+use common_arrow::arrow::ipc::reader::FileReader;
+use common_arrow::arrow::ipc::writer::FileWriter;
+use common_arrow::arrow::record_batch::RecordBatch;
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchemaRef;
+use common_exception::Result;
+use futures::Stream;
+use tokio::sync::mpsc::channel;
+use tokio::sync::mpsc::Receiver;
+use tokio::sync::mpsc::Sender;
+
+/// The blocks spilled to an anonymous temporary file in the Arrow IPC file format,
+/// the file is removed once it's closed.
+pub struct SpillFile {
+    file: File,
+    writer: FileWriter<File>,
+    rows: usize,
+    bytes: usize,
+}
+
+impl SpillFile {
+    pub fn try_create(schema: DataSchemaRef) -> Result<Self> {
+        let file = tempfile::tempfile()?;
+        let writer = FileWriter::try_new(file.try_clone()?, schema.as_ref())?;
+        Ok(SpillFile {
+            file,
+            writer,
+            rows: 0,
+            bytes: 0,
+        })
+    }
+
+    pub fn write(&mut self, block: &DataBlock) -> Result<()> {
+        if block.num_rows() == 0 {
+            return Ok(());
+        }
+
+        self.rows += block.num_rows();
+        self.bytes += block.memory_size();
+        let batch = RecordBatch::try_from(block.clone())?;
+        Ok(self.writer.write(&batch)?)
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The memory size of the spilled blocks.
+    pub fn memory_size(&self) -> usize {
+        self.bytes
+    }
+
+    /// Finishes the writing and reads the blocks back in the written order.
+    /// The file is read on a blocking thread from the first poll, the blocks are sent
+    /// to the stream over a channel, so the worker is not held while the file is read.
+    pub fn into_stream(self) -> Result<SpillStream> {
+        Ok(SpillStream {
+            file: Some(self),
+            receiver: None,
+        })
+    }
+
+    fn read_blocks(mut self, tx: &Sender<Result<DataBlock>>) -> Result<()> {
+        self.writer.finish()?;
+        self.file.seek(SeekFrom::Start(0))?;
+        for batch in FileReader::try_new(self.file)? {
+            // The receiver is gone once the stream is dropped.
+            if tx.blocking_send(Ok(DataBlock::try_from(batch?)?)).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct SpillStream {
+    // The file to read, until the reader is started.
+    file: Option<SpillFile>,
+    receiver: Option<Receiver<Result<DataBlock>>>,
+}
+
+impl Stream for SpillStream {
+    type Item = Result<DataBlock>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if let Some(file) = self.file.take() {
+            let (tx, rx) = channel(2);
+            tokio::task::spawn_blocking(move || {
+                if let Err(e) = file.read_blocks(&tx) {
+                    tx.blocking_send(Err(e)).ok();
+                }
+            });
+            self.receiver = Some(rx);
+        }
+
+        match self.receiver.as_mut() {
+            Some(receiver) => receiver.poll_recv(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
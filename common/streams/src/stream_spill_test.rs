@@ -0,0 +1,75 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[tokio::test]
+async fn test_spill_file() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_datablocks::*;
+    use common_datavalues::*;
+    use futures::stream::TryStreamExt;
+
+    use crate::*;
+
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int64, false),
+        DataField::new("b", DataType::Utf8, false),
+    ]);
+
+    let mut file = SpillFile::try_create(schema.clone())?;
+    file.write(&DataBlock::create_by_array(schema.clone(), vec![
+        Arc::new(Int64Array::from(vec![1, 2])),
+        Arc::new(StringArray::from(vec!["b1", "b2"])),
+    ]))?;
+    file.write(&DataBlock::empty_with_schema(schema.clone()))?;
+    file.write(&DataBlock::create_by_array(schema.clone(), vec![
+        Arc::new(Int64Array::from(vec![3])),
+        Arc::new(StringArray::from(vec!["b3"])),
+    ]))?;
+    assert_eq!(3, file.num_rows());
+
+    // The blocks are read back in the written order, the empty ones are skipped.
+    let result = file.into_stream()?.try_collect::<Vec<_>>().await?;
+    assert_eq!(2, result.len());
+    let expected = vec![
+        "+---+----+",
+        "| a | b  |",
+        "+---+----+",
+        "| 1 | b1 |",
+        "| 2 | b2 |",
+        "| 3 | b3 |",
+        "+---+----+",
+    ];
+    assert_blocks_eq(expected, result.as_slice());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_spill_file_dropped() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_datablocks::*;
+    use common_datavalues::*;
+    use futures::stream::StreamExt;
+
+    use crate::*;
+
+    let schema = DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int64, false)]);
+
+    let mut file = SpillFile::try_create(schema.clone())?;
+    for i in 0..8 {
+        file.write(&DataBlock::create_by_array(schema.clone(), vec![Arc::new(
+            Int64Array::from(vec![i]),
+        )]))?;
+    }
+
+    // The reader stops once the stream is dropped before the end.
+    let mut stream = file.into_stream()?;
+    let block = stream.next().await.unwrap()?;
+    assert_eq!(1, block.num_rows());
+    drop(stream);
+
+    Ok(())
+}
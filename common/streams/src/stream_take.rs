@@ -15,14 +15,15 @@ use futures::StreamExt;
 use crate::SendableDataBlockStream;
 
 pub struct TakeStream {
-    input: SendableDataBlockStream,
+    // Dropped once enough rows are taken, so the upstream stops producing.
+    input: Option<SendableDataBlockStream>,
     remaining: usize,
 }
 
 impl TakeStream {
     pub fn new(input: SendableDataBlockStream, n: usize) -> Self {
         TakeStream {
-            input,
+            input: Some(input),
             remaining: n,
         }
     }
@@ -32,22 +33,36 @@ impl Stream for TakeStream {
     type Item = Result<DataBlock>;
 
     fn poll_next(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.input.poll_next_unpin(ctx).map(|x| match x {
+        let this = &mut *self;
+        if this.remaining == 0 {
+            this.input = None;
+        }
+
+        let input = match this.input.as_mut() {
+            None => return Poll::Ready(None),
+            Some(input) => input,
+        };
+
+        let remaining = &mut this.remaining;
+        let poll = input.poll_next_unpin(ctx).map(|x| match x {
             Some(Ok(ref block)) => {
                 let rows = block.num_rows();
-                if self.remaining == 0 {
-                    None
-                } else if self.remaining >= rows {
-                    self.remaining -= rows;
+                if *remaining >= rows {
+                    *remaining -= rows;
                     Some(block.clone())
                 } else {
-                    let remaining = self.remaining;
-                    self.remaining = 0;
-                    Some(block.slice(0, remaining))
+                    let n = *remaining;
+                    *remaining = 0;
+                    Some(block.slice(0, n))
                 }
             }
             .map(Ok),
             other => other,
-        })
+        });
+
+        if this.remaining == 0 {
+            this.input = None;
+        }
+        poll
     }
 }
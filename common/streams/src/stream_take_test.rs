@@ -0,0 +1,38 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[tokio::test]
+async fn test_take_stream_stops_pulling() -> anyhow::Result<()> {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
+    use common_datablocks::*;
+    use common_datavalues::*;
+    use futures::stream::StreamExt;
+    use futures::stream::TryStreamExt;
+
+    use crate::*;
+
+    let schema = DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int64, false)]);
+    let block = DataBlock::create_by_array(schema.clone(), vec![Arc::new(Int64Array::from(vec![
+        1, 2, 3,
+    ]))]);
+
+    // An endless input counting the pulled blocks.
+    let pulled = Arc::new(AtomicUsize::new(0));
+    let counter = pulled.clone();
+    let input = futures::stream::repeat(block).map(move |block| {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Ok(block)
+    });
+
+    let stream = TakeStream::new(Box::pin(input), 7);
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let rows: usize = result.iter().map(|block| block.num_rows()).sum();
+    assert_eq!(7, rows);
+    assert_eq!(3, pulled.load(Ordering::Relaxed));
+
+    Ok(())
+}
@@ -7,6 +7,7 @@ use criterion::criterion_main;
 mod suites;
 
 criterion_main! {
+    suites::bench_aggregate_functions::benches,
     suites::bench_aggregate_query_sql::benches,
     suites::bench_filter_query_sql::benches,
     suites::bench_limit_query_sql::benches,
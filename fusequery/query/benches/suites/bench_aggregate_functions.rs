@@ -0,0 +1,70 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::sync::Arc;
+
+use common_aggregate_functions::AggregateFunction;
+use common_aggregate_functions::AggregateFunctionFactory;
+use common_datavalues::DataArrayRef;
+use common_datavalues::DataColumnarValue;
+use common_datavalues::DataField;
+use common_datavalues::DataType;
+use common_datavalues::DataValue;
+use common_datavalues::Int64Array;
+use criterion::black_box;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::Criterion;
+
+const ROWS: usize = 1_000_000;
+
+fn accumulate_row_by_row(func: &mut Box<dyn AggregateFunction>, column: &DataColumnarValue) {
+    for row in 0..ROWS {
+        let value = DataValue::try_from_column(column, row).unwrap();
+        func.accumulate_scalar(&[value]).unwrap();
+    }
+}
+
+fn criterion_benchmark_aggregate_functions(c: &mut Criterion) {
+    let columns: Vec<(&str, DataArrayRef)> = vec![
+        (
+            "non-null",
+            Arc::new(Int64Array::from((0..ROWS as i64).collect::<Vec<_>>())),
+        ),
+        (
+            "nullable",
+            Arc::new(Int64Array::from(
+                (0..ROWS as i64)
+                    .map(|v| if v % 10 == 0 { None } else { Some(v) })
+                    .collect::<Vec<_>>(),
+            )),
+        ),
+    ];
+    let arguments = vec![DataField::new("number", DataType::Int64, true)];
+
+    for (kind, array) in columns {
+        let column: DataColumnarValue = array.into();
+        for name in &["count", "sum", "min", "max", "avg"] {
+            let func = AggregateFunctionFactory::get(name, arguments.clone()).unwrap();
+
+            c.bench_function(&format!("{}({}) batch", name, kind), |b| {
+                b.iter(|| {
+                    let mut func = func.clone();
+                    func.accumulate(&[column.clone()], ROWS).unwrap();
+                    black_box(func.merge_result().unwrap())
+                })
+            });
+            c.bench_function(&format!("{}({}) row by row", name, kind), |b| {
+                b.iter(|| {
+                    let mut func = func.clone();
+                    accumulate_row_by_row(&mut func, &column);
+                    black_box(func.merge_result().unwrap())
+                })
+            });
+        }
+    }
+}
+
+criterion_group!(benches, criterion_benchmark_aggregate_functions);
+criterion_main!(benches);
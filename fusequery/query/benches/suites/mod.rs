@@ -10,6 +10,7 @@ use fuse_query::sessions::FuseQueryContext;
 use fuse_query::sql::PlanParser;
 use futures::StreamExt;
 
+pub mod bench_aggregate_functions;
 pub mod bench_aggregate_query_sql;
 pub mod bench_filter_query_sql;
 pub mod bench_limit_query_sql;
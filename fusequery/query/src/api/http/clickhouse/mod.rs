@@ -0,0 +1,15 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[cfg(test)]
+mod output_format_test;
+#[cfg(test)]
+mod query_test;
+
+mod output_format;
+mod query;
+
+pub use output_format::OutputFormat;
+pub use query::clickhouse_handler;
+pub use query::split_format;
@@ -0,0 +1,228 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use common_arrow::arrow::util::display::array_value_to_string;
+use common_datablocks::DataBlock;
+use common_datavalues::is_numeric;
+use common_datavalues::DataArrayRef;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataType;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_progress::ProgressValues;
+
+/// Output formats of the ClickHouse HTTP interface, chosen by a trailing
+/// `FORMAT <name>` clause or the `default_format` parameter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    TabSeparated,
+    TabSeparatedWithNames,
+    Csv,
+    CsvWithNames,
+    JsonEachRow,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn try_create(name: &str) -> Result<OutputFormat> {
+        match name.to_uppercase().as_str() {
+            "TABSEPARATED" | "TSV" => Ok(OutputFormat::TabSeparated),
+            "TABSEPARATEDWITHNAMES" | "TSVWITHNAMES" => Ok(OutputFormat::TabSeparatedWithNames),
+            "CSV" => Ok(OutputFormat::Csv),
+            "CSVWITHNAMES" => Ok(OutputFormat::CsvWithNames),
+            "JSONEACHROW" => Ok(OutputFormat::JsonEachRow),
+            "JSON" => Ok(OutputFormat::Json),
+            _ => Err(ErrorCode::UnknownFormat(format!(
+                "Unknown output format: {}",
+                name
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::TabSeparated => "TabSeparated",
+            OutputFormat::TabSeparatedWithNames => "TabSeparatedWithNames",
+            OutputFormat::Csv => "CSV",
+            OutputFormat::CsvWithNames => "CSVWithNames",
+            OutputFormat::JsonEachRow => "JSONEachRow",
+            OutputFormat::Json => "JSON",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            OutputFormat::TabSeparated | OutputFormat::TabSeparatedWithNames => {
+                "text/tab-separated-values; charset=UTF-8"
+            }
+            OutputFormat::Csv | OutputFormat::CsvWithNames => "text/csv; charset=UTF-8",
+            OutputFormat::JsonEachRow | OutputFormat::Json => "application/json; charset=UTF-8",
+        }
+    }
+
+    /// Written once before the first block.
+    pub fn serialize_prefix(&self, schema: &DataSchemaRef) -> Vec<u8> {
+        let names = schema.fields().iter().map(|f| f.name().as_str());
+        match self {
+            OutputFormat::TabSeparatedWithNames => {
+                let names = names.map(escape_tab_separated).collect::<Vec<_>>();
+                format!("{}\n", names.join("\t")).into_bytes()
+            }
+            OutputFormat::CsvWithNames => {
+                let names = names.map(quote_csv).collect::<Vec<_>>();
+                format!("{}\n", names.join(",")).into_bytes()
+            }
+            OutputFormat::Json => {
+                let meta = schema
+                    .fields()
+                    .iter()
+                    .map(|f| {
+                        format!(
+                            "\t\t{{\n\t\t\t\"name\": {},\n\t\t\t\"type\": {}\n\t\t}}",
+                            quote_json(f.name()),
+                            quote_json(&format!("{:?}", f.data_type()))
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                format!(
+                    "{{\n\t\"meta\":\n\t[\n{}\n\t],\n\n\t\"data\":\n\t[\n",
+                    meta.join(",\n")
+                )
+                .into_bytes()
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Serializes the rows of a block, `written_rows` is the number of rows written
+    /// by the previous blocks.
+    pub fn serialize_block(&self, block: &DataBlock, written_rows: usize) -> Result<Vec<u8>> {
+        let arrays = block
+            .columns()
+            .iter()
+            .map(|column| column.to_array())
+            .collect::<Result<Vec<_>>>()?;
+        let names = block
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| quote_json(f.name()))
+            .collect::<Vec<_>>();
+
+        let mut buf = String::new();
+        for row in 0..block.num_rows() {
+            match self {
+                OutputFormat::TabSeparated | OutputFormat::TabSeparatedWithNames => {
+                    let values = arrays
+                        .iter()
+                        .map(|array| tab_separated_value(array, row))
+                        .collect::<Result<Vec<_>>>()?;
+                    buf.push_str(&values.join("\t"));
+                    buf.push('\n');
+                }
+                OutputFormat::Csv | OutputFormat::CsvWithNames => {
+                    let values = arrays
+                        .iter()
+                        .map(|array| csv_value(array, row))
+                        .collect::<Result<Vec<_>>>()?;
+                    buf.push_str(&values.join(","));
+                    buf.push('\n');
+                }
+                OutputFormat::JsonEachRow => {
+                    let values = json_object(&names, &arrays, row)?;
+                    buf.push_str(&format!("{{{}}}\n", values.join(",")));
+                }
+                OutputFormat::Json => {
+                    if written_rows + row > 0 {
+                        buf.push_str(",\n");
+                    }
+                    let values = json_object(&names, &arrays, row)?;
+                    buf.push_str(&format!("\t\t{{{}}}", values.join(", ")));
+                }
+            }
+        }
+        Ok(buf.into_bytes())
+    }
+
+    /// Written once after the last block.
+    pub fn serialize_suffix(&self, rows: usize, progress: &ProgressValues) -> Vec<u8> {
+        match self {
+            OutputFormat::Json => {
+                let separator = if rows > 0 { "\n" } else { "" };
+                format!(
+                    "{}\t],\n\n\t\"rows\": {},\n\n\t\"statistics\":\n\t{{\n\t\t\"rows_read\": {},\n\t\t\"bytes_read\": {}\n\t}}\n}}\n",
+                    separator, rows, progress.read_rows, progress.read_bytes
+                )
+                .into_bytes()
+            }
+            _ => vec![],
+        }
+    }
+}
+
+fn is_unquoted(data_type: &DataType) -> bool {
+    is_numeric(data_type) || data_type == &DataType::Boolean
+}
+
+fn tab_separated_value(array: &DataArrayRef, row: usize) -> Result<String> {
+    if array.is_null(row) {
+        return Ok("\\N".to_string());
+    }
+    let value = array_value_to_string(array, row)?;
+    Ok(escape_tab_separated(&value))
+}
+
+fn csv_value(array: &DataArrayRef, row: usize) -> Result<String> {
+    if array.is_null(row) {
+        return Ok("\\N".to_string());
+    }
+    let value = array_value_to_string(array, row)?;
+    if is_unquoted(array.data_type()) {
+        Ok(value)
+    } else {
+        Ok(quote_csv(&value))
+    }
+}
+
+fn json_value(array: &DataArrayRef, row: usize) -> Result<String> {
+    if array.is_null(row) {
+        return Ok("null".to_string());
+    }
+    let value = array_value_to_string(array, row)?;
+    if is_unquoted(array.data_type()) {
+        Ok(value)
+    } else {
+        Ok(quote_json(&value))
+    }
+}
+
+fn json_object(names: &[String], arrays: &[DataArrayRef], row: usize) -> Result<Vec<String>> {
+    names
+        .iter()
+        .zip(arrays.iter())
+        .map(|(name, array)| Ok(format!("{}:{}", name, json_value(array, row)?)))
+        .collect()
+}
+
+fn escape_tab_separated(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn quote_csv(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+fn quote_json(value: &str) -> String {
+    serde_json::Value::String(value.to_string()).to_string()
+}
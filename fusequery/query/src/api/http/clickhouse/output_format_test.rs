@@ -0,0 +1,77 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[test]
+fn test_output_format() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_arrow::arrow::array::*;
+    use common_datablocks::DataBlock;
+    use common_datavalues::*;
+    use common_progress::ProgressValues;
+    use pretty_assertions::assert_eq;
+
+    use crate::api::http::clickhouse::OutputFormat;
+
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int64, true),
+        DataField::new("b", DataType::Utf8, false),
+    ]);
+    let block = DataBlock::create_by_array(schema.clone(), vec![
+        Arc::new(Int64Array::from(vec![Some(1), None])),
+        Arc::new(StringArray::from(vec!["x\ty", "\"z\""])),
+    ]);
+    let progress = ProgressValues {
+        read_rows: 2,
+        read_bytes: 16,
+        total_rows_to_read: 0,
+    };
+
+    let serialize = |format: OutputFormat| -> anyhow::Result<String> {
+        let mut buf = format.serialize_prefix(&schema);
+        buf.extend(format.serialize_block(&block, 0)?);
+        buf.extend(format.serialize_block(&block, 2)?);
+        buf.extend(format.serialize_suffix(4, &progress));
+        Ok(String::from_utf8(buf)?)
+    };
+
+    assert_eq!(
+        serialize(OutputFormat::try_create("TabSeparated")?)?,
+        "1\tx\\ty\n\\N\t\"z\"\n1\tx\\ty\n\\N\t\"z\"\n"
+    );
+    assert_eq!(
+        serialize(OutputFormat::try_create("TSVWithNames")?)?,
+        "a\tb\n1\tx\\ty\n\\N\t\"z\"\n1\tx\\ty\n\\N\t\"z\"\n"
+    );
+    assert_eq!(
+        serialize(OutputFormat::try_create("CSVWithNames")?)?,
+        "\"a\",\"b\"\n1,\"x\ty\"\n\\N,\"\"\"z\"\"\"\n1,\"x\ty\"\n\\N,\"\"\"z\"\"\"\n"
+    );
+    assert_eq!(
+        serialize(OutputFormat::try_create("JSONEachRow")?)?,
+        "{\"a\":1,\"b\":\"x\\ty\"}\n{\"a\":null,\"b\":\"\\\"z\\\"\"}\n{\"a\":1,\"b\":\"x\\ty\"}\n{\"a\":null,\"b\":\"\\\"z\\\"\"}\n"
+    );
+
+    // The JSON format is one document with the schema and the statistics.
+    let json: serde_json::Value = serde_json::from_str(&serialize(OutputFormat::Json)?)?;
+    assert_eq!(json["meta"][0]["name"], "a");
+    assert_eq!(json["meta"][1]["type"], "Utf8");
+    assert_eq!(json["data"].as_array().map(|rows| rows.len()), Some(4));
+    assert_eq!(json["data"][2]["a"], 1);
+    assert_eq!(json["data"][3]["a"], serde_json::Value::Null);
+    assert_eq!(json["rows"], 4);
+    assert_eq!(json["statistics"]["rows_read"], 2);
+
+    // Unknown format.
+    let actual = OutputFormat::try_create("XML");
+    assert!(actual.is_err());
+    if let Err(e) = actual {
+        assert_eq!(
+            "Code: 40, displayText = Unknown output format: XML.",
+            e.to_string()
+        );
+    }
+
+    Ok(())
+}
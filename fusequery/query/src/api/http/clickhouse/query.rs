@@ -0,0 +1,376 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::collections::HashMap;
+
+use warp::Filter;
+
+use crate::clusters::ClusterRef;
+use crate::configs::Config;
+use crate::sessions::SessionManagerRef;
+
+/// ClickHouse compatible HTTP interface:
+/// GET|POST /?query=<sql>[&session_id=<id>[&session_timeout=<seconds>]][&database=<db>][&default_format=<format>][&<setting>=<value>]
+/// The query can also be sent in the body, a query in the URL is prepended to it.
+/// A named session is removed once idle for session_timeout seconds, 60 by default,
+/// and serves one request at a time. The other parameters must be settings.
+pub fn clickhouse_handler(
+    cfg: Config,
+    cluster: ClusterRef,
+    sessions: SessionManagerRef,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path::end()
+        .and(warp::get().or(warp::post()).unify())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(warp::body::bytes())
+        .and(with_config(cfg))
+        .and(with_cluster(cluster))
+        .and(with_sessions(sessions))
+        .and_then(handlers::query)
+}
+
+fn with_config(
+    cfg: Config,
+) -> impl Filter<Extract = (Config,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || cfg.clone())
+}
+
+fn with_cluster(
+    cluster: ClusterRef,
+) -> impl Filter<Extract = (ClusterRef,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || cluster.clone())
+}
+
+fn with_sessions(
+    sessions: SessionManagerRef,
+) -> impl Filter<Extract = (SessionManagerRef,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || sessions.clone())
+}
+
+/// Splits a trailing `FORMAT <name>` clause from the query, sqlparser doesn't know it.
+/// The clause of an INSERT names the input format and is left to the parser.
+pub fn split_format(query: &str) -> (&str, Option<&str>) {
+    let query = query.trim().trim_end_matches(';').trim_end();
+    let is_insert = query
+        .split_whitespace()
+        .next()
+        .map(|keyword| keyword.eq_ignore_ascii_case("INSERT"))
+        .unwrap_or(false);
+
+    if !is_insert {
+        if let Some((rest, name)) = query.rsplit_once(char::is_whitespace) {
+            if let Some((head, keyword)) = rest.trim_end().rsplit_once(char::is_whitespace) {
+                if keyword.eq_ignore_ascii_case("FORMAT") {
+                    return (head.trim_end(), Some(name));
+                }
+            }
+        }
+    }
+    (query, None)
+}
+
+mod handlers {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use common_datablocks::DataBlock;
+    use common_datavalues::DataSchemaRef;
+    use common_exception::ErrorCode;
+    use common_exception::Result;
+    use common_progress::ProgressValues;
+    use futures::StreamExt;
+    use log::error;
+    use tokio::sync::mpsc;
+    use warp::http::header::CONTENT_TYPE;
+    use warp::http::HeaderValue;
+    use warp::http::Response;
+    use warp::http::StatusCode;
+    use warp::hyper::body::Bytes;
+    use warp::hyper::body::Sender;
+    use warp::hyper::Body;
+
+    use crate::api::http::clickhouse::query::split_format;
+    use crate::api::http::clickhouse::OutputFormat;
+    use crate::clusters::ClusterRef;
+    use crate::configs::Config;
+    use crate::interpreters::InterpreterFactory;
+    use crate::sessions::FuseQueryContextRef;
+    use crate::sessions::SessionGuard;
+    use crate::sessions::SessionManager;
+    use crate::sessions::SessionManagerRef;
+    use crate::sql::PlanParser;
+
+    // Parameters of the interface itself, the others must be query settings.
+    const HTTP_PARAMS: [&str; 8] = [
+        "query",
+        "session_id",
+        "session_timeout",
+        "database",
+        "default_format",
+        "query_id",
+        "user",
+        "password",
+    ];
+
+    // The idle seconds before a named session expires, the same limits as ClickHouse.
+    const DEFAULT_SESSION_TIMEOUT: u64 = 60;
+    const MAX_SESSION_TIMEOUT: u64 = 3600;
+
+    struct QueryResult {
+        format: OutputFormat,
+        schema: DataSchemaRef,
+        first: Option<DataBlock>,
+        rest: mpsc::Receiver<Result<DataBlock>>,
+    }
+
+    pub async fn query(
+        params: HashMap<String, String>,
+        body: Bytes,
+        cfg: Config,
+        cluster: ClusterRef,
+        sessions: SessionManagerRef,
+    ) -> Result<Response<Body>, std::convert::Infallible> {
+        // Like ClickHouse, a request without query is a ping.
+        if !params.contains_key("query") && body.is_empty() {
+            return Ok(Response::new(Body::from("Ok.\n")));
+        }
+
+        let timeout = match session_timeout(&params) {
+            Ok(timeout) => timeout,
+            Err(e) => return Ok(error_response(e)),
+        };
+        let session_id = params.get("session_id").cloned();
+        // The named session is held until the response is written, it doesn't expire meanwhile.
+        let session = match &session_id {
+            Some(id) => match SessionManager::try_lock_session(&sessions, id, timeout) {
+                Ok(session) => Some(session),
+                Err(e) => return Ok(error_response(e)),
+            },
+            None => None,
+        };
+        let ctx = match get_context(session_id.as_deref(), &cfg, cluster, &sessions) {
+            Ok(ctx) => ctx,
+            Err(e) => return Ok(error_response(e)),
+        };
+
+        match execute_query(ctx.clone(), &params, &body).await {
+            Ok(result) => {
+                let progress = ctx.get_progress_value();
+                let format = result.format;
+                let (mut sender, body) = Body::channel();
+                tokio::spawn(async move {
+                    write_body(&mut sender, result, ctx.clone()).await;
+                    // Released before the body ends, the next request of the client finds
+                    // the session free.
+                    release_context(&sessions, ctx, session);
+                    drop(sender);
+                });
+
+                let response = Response::builder()
+                    .header(CONTENT_TYPE, format.content_type())
+                    .header("X-ClickHouse-Format", format.name())
+                    .header("X-ClickHouse-Timezone", "UTC")
+                    .header("X-ClickHouse-Progress", progress_header(&progress))
+                    .body(body);
+                match response {
+                    Ok(response) => Ok(response),
+                    Err(e) => Ok(error_response(ErrorCode::from_std_error(e))),
+                }
+            }
+            Err(e) => {
+                release_context(&sessions, ctx, session);
+                Ok(error_response(e))
+            }
+        }
+    }
+
+    // Contexts without session id only live for one query, the named ones until they are idle
+    // for the session timeout, which starts once the session is released.
+    fn release_context(
+        sessions: &SessionManagerRef,
+        ctx: FuseQueryContextRef,
+        session: Option<SessionGuard>,
+    ) {
+        match session {
+            Some(session) => drop(session),
+            None => {
+                sessions.try_remove_context(ctx).ok();
+            }
+        }
+    }
+
+    fn session_timeout(params: &HashMap<String, String>) -> Result<Duration> {
+        let seconds = match params.get("session_timeout") {
+            None => DEFAULT_SESSION_TIMEOUT,
+            Some(value) => value.parse::<u64>().map_err(|_| {
+                ErrorCode::BadArguments(format!("Invalid session timeout: '{}'", value))
+            })?,
+        };
+        if seconds > MAX_SESSION_TIMEOUT {
+            return Err(ErrorCode::BadArguments(format!(
+                "Session timeout {} is larger than {} seconds",
+                seconds, MAX_SESSION_TIMEOUT
+            )));
+        }
+        Ok(Duration::from_secs(seconds))
+    }
+
+    fn get_context(
+        session_id: Option<&str>,
+        cfg: &Config,
+        cluster: ClusterRef,
+        sessions: &SessionManagerRef,
+    ) -> Result<FuseQueryContextRef> {
+        if let Some(ctx) = session_id.and_then(|id| sessions.get_context(id)) {
+            return Ok(ctx);
+        }
+
+        let ctx = match session_id {
+            Some(id) => sessions.try_create_context_with_id(id)?,
+            None => sessions.try_create_context()?,
+        };
+        let ctx = ctx.with_cluster(cluster)?;
+        ctx.set_max_threads(cfg.num_cpus)?;
+        Ok(ctx)
+    }
+
+    async fn execute_query(
+        ctx: FuseQueryContextRef,
+        params: &HashMap<String, String>,
+        body: &[u8],
+    ) -> Result<QueryResult> {
+        // A misspelled setting must not be ignored, nor applied in part.
+        for name in params.keys() {
+            if !HTTP_PARAMS.contains(&name.as_str()) && !ctx.get_settings().has_setting(name) {
+                return Err(ErrorCode::BadArguments(format!(
+                    "Unknown URL parameter: '{}'",
+                    name
+                )));
+            }
+        }
+
+        ctx.reset()?;
+        if let Some(database) = params.get("database") {
+            ctx.set_current_database(database.clone())?;
+        }
+        for (name, value) in params {
+            if HTTP_PARAMS.contains(&name.as_str()) {
+                continue;
+            }
+            if name.eq_ignore_ascii_case("max_threads") {
+                ctx.set_max_threads(value.parse()?)?;
+            } else {
+                ctx.get_settings().update_settings(name, value.clone())?;
+            }
+        }
+
+        let body = std::str::from_utf8(body).map_err(ErrorCode::from_std_error)?;
+        let sql = match params.get("query") {
+            Some(query) if !body.is_empty() => format!("{}\n{}", query, body),
+            Some(query) => query.clone(),
+            None => body.to_string(),
+        };
+
+        let (query, format) = split_format(&sql);
+        let format = match format.or_else(|| params.get("default_format").map(|f| f.as_str())) {
+            Some(name) => OutputFormat::try_create(name)?,
+            None => OutputFormat::TabSeparated,
+        };
+
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(query)?;
+        let interpreter = InterpreterFactory::get(ctx.clone(), plan)?;
+        let schema = interpreter.schema();
+
+        let (tx, mut rx) = mpsc::channel(20);
+        let query = query.to_string();
+        let query_ctx = ctx.clone();
+        ctx.execute_task(async move {
+            match interpreter.execute().await {
+                Ok(mut stream) => {
+                    while let Some(block) = stream.next().await {
+                        // The receiver is gone when the client disconnected.
+                        if tx.send(block).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                Err(e) => {
+                    tx.send(Err(e)).await.ok();
+                }
+            }
+            query_ctx
+                .get_process_list()
+                .on_query_done(&query_ctx, &query);
+        });
+
+        // Errors before the first block are still reported with an error status.
+        let first = match rx.recv().await {
+            Some(Ok(block)) => Some(block),
+            Some(Err(e)) => return Err(e),
+            None => None,
+        };
+
+        Ok(QueryResult {
+            format,
+            schema,
+            first,
+            rest: rx,
+        })
+    }
+
+    async fn write_body(sender: &mut Sender, result: QueryResult, ctx: FuseQueryContextRef) {
+        if let Err(e) = write_blocks(sender, result, ctx).await {
+            // The status is already sent, the error is appended to the output like ClickHouse does.
+            error!("HTTP query error: {:?}", e);
+            sender.send_data(Bytes::from(format!("{}\n", e))).await.ok();
+        }
+    }
+
+    async fn write_blocks(
+        sender: &mut Sender,
+        mut result: QueryResult,
+        ctx: FuseQueryContextRef,
+    ) -> Result<()> {
+        let format = result.format;
+        send_data(sender, format.serialize_prefix(&result.schema)).await?;
+
+        let mut rows = 0;
+        let mut next = result.first.take().map(Ok);
+        while let Some(block) = next {
+            let block = block?;
+            send_data(sender, format.serialize_block(&block, rows)?).await?;
+            rows += block.num_rows();
+            next = result.rest.recv().await;
+        }
+
+        let progress = ctx.get_progress_value();
+        send_data(sender, format.serialize_suffix(rows, &progress)).await
+    }
+
+    async fn send_data(sender: &mut Sender, data: Vec<u8>) -> Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        sender
+            .send_data(Bytes::from(data))
+            .await
+            .map_err(ErrorCode::from_std_error)
+    }
+
+    fn progress_header(progress: &ProgressValues) -> String {
+        format!(
+            "{{\"read_rows\":\"{}\",\"read_bytes\":\"{}\",\"total_rows_to_read\":\"{}\"}}",
+            progress.read_rows, progress.read_bytes, progress.total_rows_to_read
+        )
+    }
+
+    fn error_response(e: ErrorCode) -> Response<Body> {
+        let mut response = Response::new(Body::from(format!("{}\n", e)));
+        *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+        response
+            .headers_mut()
+            .insert("X-ClickHouse-Exception-Code", HeaderValue::from(e.code()));
+        response
+    }
+}
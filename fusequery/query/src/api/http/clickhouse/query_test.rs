@@ -0,0 +1,202 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_clickhouse_handler() -> anyhow::Result<()> {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::api::http::clickhouse::clickhouse_handler;
+    use crate::clusters::Cluster;
+    use crate::configs::Config;
+    use crate::sessions::SessionManager;
+
+    let conf = Config::default();
+    let cluster = Cluster::create_global(conf.clone())?;
+    let sessions = SessionManager::create();
+    let filter = clickhouse_handler(conf, cluster, sessions.clone());
+
+    // Ping.
+    {
+        let res = warp::test::request()
+            .method("GET")
+            .path("/")
+            .reply(&filter)
+            .await;
+        assert_eq!(200, res.status());
+        assert_eq!("Ok.\n", res.body());
+    }
+
+    // Query in the URL.
+    {
+        let res = warp::test::request()
+            .method("GET")
+            .path("/?query=SELECT%20number%20FROM%20numbers(3)%20ORDER%20BY%20number")
+            .reply(&filter)
+            .await;
+        assert_eq!(200, res.status());
+        assert_eq!("TabSeparated", res.headers()["X-ClickHouse-Format"]);
+        assert!(res.headers().contains_key("X-ClickHouse-Progress"));
+        assert_eq!("0\n1\n2\n", res.body());
+    }
+
+    // Query in the body with a format.
+    {
+        let res = warp::test::request()
+            .method("POST")
+            .path("/")
+            .body("SELECT number FROM numbers(2) ORDER BY number FORMAT JSONEachRow")
+            .reply(&filter)
+            .await;
+        assert_eq!(200, res.status());
+        assert_eq!("JSONEachRow", res.headers()["X-ClickHouse-Format"]);
+        assert_eq!("{\"number\":0}\n{\"number\":1}\n", res.body());
+    }
+
+    // Settings in the URL.
+    {
+        let res = warp::test::request()
+            .method("POST")
+            .path("/?max_block_size=1&default_format=CSV")
+            .body("SELECT value FROM system.settings WHERE name = 'max_block_size'")
+            .reply(&filter)
+            .await;
+        assert_eq!(200, res.status());
+        assert_eq!("\"1\"\n", res.body());
+    }
+
+    // Settings are kept by the session.
+    {
+        let res = warp::test::request()
+            .method("POST")
+            .path("/?session_id=s1")
+            .body("SET max_block_size = 2")
+            .reply(&filter)
+            .await;
+        assert_eq!(200, res.status());
+
+        let query = "SELECT value FROM system.settings WHERE name = 'max_block_size'";
+        let res = warp::test::request()
+            .method("POST")
+            .path("/?session_id=s1")
+            .body(query)
+            .reply(&filter)
+            .await;
+        assert_eq!("2\n", res.body());
+
+        let res = warp::test::request()
+            .method("POST")
+            .path("/")
+            .body(query)
+            .reply(&filter)
+            .await;
+        assert_eq!("10000\n", res.body());
+    }
+
+    // The named sessions expire once idle for the session timeout.
+    {
+        let res = warp::test::request()
+            .method("POST")
+            .path("/?session_id=s2&session_timeout=0")
+            .body("SET max_block_size = 3")
+            .reply(&filter)
+            .await;
+        assert_eq!(200, res.status());
+
+        let res = warp::test::request()
+            .method("POST")
+            .path("/?session_id=s2")
+            .body("SELECT value FROM system.settings WHERE name = 'max_block_size'")
+            .reply(&filter)
+            .await;
+        assert_eq!("10000\n", res.body());
+
+        let res = warp::test::request()
+            .method("POST")
+            .path("/?session_id=s2&session_timeout=3601")
+            .body("SELECT 1")
+            .reply(&filter)
+            .await;
+        assert_eq!(500, res.status());
+        assert_eq!("6", res.headers()["X-ClickHouse-Exception-Code"]);
+    }
+
+    // A session serves one request at a time, it doesn't expire while it's used.
+    {
+        let res = warp::test::request()
+            .method("POST")
+            .path("/?session_id=s3&session_timeout=0")
+            .body("SET max_block_size = 4")
+            .reply(&filter)
+            .await;
+        assert_eq!(200, res.status());
+
+        let session = SessionManager::try_lock_session(&sessions, "s3", Duration::from_secs(60))?;
+        assert!(sessions.get_context("s3").is_some());
+        let res = warp::test::request()
+            .method("POST")
+            .path("/?session_id=s3")
+            .body("SELECT 1")
+            .reply(&filter)
+            .await;
+        assert_eq!(500, res.status());
+        assert_eq!("45", res.headers()["X-ClickHouse-Exception-Code"]);
+
+        drop(session);
+        let res = warp::test::request()
+            .method("POST")
+            .path("/?session_id=s3")
+            .body("SELECT value FROM system.settings WHERE name = 'max_block_size'")
+            .reply(&filter)
+            .await;
+        assert_eq!("4\n", res.body());
+    }
+
+    // The unknown parameters are refused.
+    {
+        let res = warp::test::request()
+            .method("POST")
+            .path("/?max_block_sise=1")
+            .body("SELECT 1")
+            .reply(&filter)
+            .await;
+        assert_eq!(500, res.status());
+        assert_eq!("6", res.headers()["X-ClickHouse-Exception-Code"]);
+    }
+
+    // Error.
+    {
+        let res = warp::test::request()
+            .method("POST")
+            .path("/")
+            .body("SELECT * FROM system.xx")
+            .reply(&filter)
+            .await;
+        assert_eq!(500, res.status());
+        assert_eq!("25", res.headers()["X-ClickHouse-Exception-Code"]);
+        assert!(String::from_utf8_lossy(res.body()).starts_with("Code: 25"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_split_format() -> anyhow::Result<()> {
+    use pretty_assertions::assert_eq;
+
+    use crate::api::http::clickhouse::split_format;
+
+    assert_eq!(split_format("SELECT 1"), ("SELECT 1", None));
+    assert_eq!(
+        split_format("SELECT 1 format JSONEachRow;\n"),
+        ("SELECT 1", Some("JSONEachRow"))
+    );
+    assert_eq!(
+        split_format("INSERT INTO t FORMAT Values"),
+        ("INSERT INTO t FORMAT Values", None)
+    );
+
+    Ok(())
+}
@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0.
 
+pub mod clickhouse;
 pub mod debug;
 pub mod router;
 pub mod v1;
@@ -7,15 +7,21 @@ use warp::Filter;
 
 use crate::clusters::ClusterRef;
 use crate::configs::Config;
+use crate::sessions::SessionManagerRef;
 
 pub struct Router {
     cfg: Config,
     cluster: ClusterRef,
+    session_manager: SessionManagerRef,
 }
 
 impl Router {
-    pub fn create(cfg: Config, cluster: ClusterRef) -> Self {
-        Router { cfg, cluster }
+    pub fn create(cfg: Config, cluster: ClusterRef, session_manager: SessionManagerRef) -> Self {
+        Router {
+            cfg,
+            cluster,
+            session_manager,
+        }
     }
 
     pub fn router(
@@ -24,7 +30,12 @@ impl Router {
         let v1 = super::v1::hello::hello_handler(self.cfg.clone())
             .or(super::v1::config::config_handler(self.cfg.clone()))
             .or(super::v1::cluster::cluster_handler(self.cluster.clone()))
-            .or(super::debug::home::debug_handler(self.cfg.clone()));
+            .or(super::debug::home::debug_handler(self.cfg.clone()))
+            .or(super::clickhouse::clickhouse_handler(
+                self.cfg.clone(),
+                self.cluster.clone(),
+                self.session_manager.clone(),
+            ));
         let routes = v1.with(warp::log("v1"));
         Ok(routes)
     }
@@ -7,20 +7,30 @@ use anyhow::Result;
 use crate::api::http::router::Router;
 use crate::clusters::ClusterRef;
 use crate::configs::Config;
+use crate::sessions::SessionManagerRef;
 
 pub struct HttpService {
     cfg: Config,
     cluster: ClusterRef,
+    session_manager: SessionManagerRef,
 }
 
 impl HttpService {
-    pub fn create(cfg: Config, cluster: ClusterRef) -> Self {
-        HttpService { cfg, cluster }
+    pub fn create(cfg: Config, cluster: ClusterRef, session_manager: SessionManagerRef) -> Self {
+        HttpService {
+            cfg,
+            cluster,
+            session_manager,
+        }
     }
 
     pub async fn make_server(&self) -> Result<()> {
         let address = self.cfg.http_api_address.parse::<std::net::SocketAddr>()?;
-        let router = Router::create(self.cfg.clone(), self.cluster.clone());
+        let router = Router::create(
+            self.cfg.clone(),
+            self.cluster.clone(),
+            self.session_manager.clone(),
+        );
         warp::serve(router.router()?).run(address).await;
         Ok(())
     }
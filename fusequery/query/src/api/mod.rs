@@ -10,6 +10,7 @@ mod rpc;
 mod rpc_service;
 
 pub use http_service::HttpService;
+pub use rpc::CancelQueryAction;
 pub use rpc::ExecutePlanWithShuffleAction;
 pub use rpc::FlightClient;
 pub use rpc_service::RpcService;
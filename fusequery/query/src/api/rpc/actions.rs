@@ -13,3 +13,8 @@ pub struct ExecutePlanWithShuffleAction {
     pub scatters: Vec<String>,
     pub scatters_action: Expression,
 }
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct CancelQueryAction {
+    pub query_id: String,
+}
@@ -13,6 +13,7 @@ use tokio::time::Duration;
 use tonic::transport::channel::Channel;
 use tonic::Request;
 
+use crate::api::rpc::actions::CancelQueryAction;
 use crate::api::rpc::actions::ExecutePlanWithShuffleAction;
 use crate::api::rpc::flight_data_stream::FlightDataStream;
 use crate::api::rpc::from_status;
@@ -60,6 +61,19 @@ impl FlightClient {
         Ok(())
     }
 
+    pub async fn cancel_query(&mut self, action: CancelQueryAction, timeout: u64) -> Result<()> {
+        self.do_action(
+            Action {
+                r#type: "CancelQuery".to_string(),
+                body: serde_json::to_string(&action)?.as_bytes().to_vec(),
+            },
+            timeout,
+        )
+        .await?;
+
+        Ok(())
+    }
+
     // Execute do_get.
     async fn do_get(
         &mut self,
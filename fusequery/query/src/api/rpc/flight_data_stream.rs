@@ -2,13 +2,19 @@
 //
 // SPDX-License-Identifier: Apache-2.0.
 
+use std::convert::TryInto;
+
 use common_arrow::arrow::datatypes::SchemaRef;
+use common_arrow::arrow::ipc::writer::IpcWriteOptions;
 use common_arrow::arrow::record_batch::RecordBatch;
+use common_arrow::arrow_flight::utils::flight_data_from_arrow_batch;
+use common_arrow::arrow_flight::utils::flight_data_from_arrow_schema;
 use common_arrow::arrow_flight::utils::flight_data_to_arrow_batch;
 use common_arrow::arrow_flight::FlightData;
 use common_datablocks::DataBlock;
 use common_datavalues::DataColumnarValue;
 use common_exception::ErrorCode;
+use common_streams::SendableDataBlockStream;
 use tokio::sync::mpsc::Receiver;
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::Stream;
@@ -74,4 +80,34 @@ impl FlightDataStream {
             }
         })
     }
+
+    // The schema message goes first, as the clients which don't know the schema expect.
+    #[inline]
+    pub fn from_blocks(
+        schema: SchemaRef,
+        inner: SendableDataBlockStream,
+    ) -> impl Stream<Item = Result<FlightData, ErrorCode>> {
+        let options = IpcWriteOptions::default();
+        let schema_flight_data = flight_data_from_arrow_schema(&*schema, &options);
+
+        let blocks_flight_data = inner.map(move |block| -> Vec<Result<FlightData, ErrorCode>> {
+            let record_batch: Result<RecordBatch, ErrorCode> = block.and_then(|b| b.try_into());
+            match record_batch {
+                Err(error_code) => vec![Err(error_code)],
+                Ok(record_batch) => {
+                    let (dicts, values) = flight_data_from_arrow_batch(&record_batch, &options);
+                    dicts
+                        .into_iter()
+                        .chain(std::iter::once(values))
+                        .map(Ok)
+                        .collect()
+                }
+            }
+        });
+
+        tokio_stream::once(Ok(schema_flight_data)).chain(futures::StreamExt::flat_map(
+            blocks_flight_data,
+            tokio_stream::iter,
+        ))
+    }
 }
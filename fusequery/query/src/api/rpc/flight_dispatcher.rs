@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0.
 
 use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::convert::TryInto;
 use std::sync::Arc;
 
@@ -20,14 +21,19 @@ use tokio::sync::mpsc::Receiver;
 use tokio::sync::mpsc::Sender;
 use tokio_stream::StreamExt;
 
-use crate::api::rpc::flight_scatter::FlightScatterByHash;
 use crate::clusters::ClusterRef;
 use crate::configs::Config;
 use crate::pipelines::processors::Pipeline;
 use crate::pipelines::processors::PipelineBuilder;
+use crate::pipelines::transforms::ScatterByHash;
 use crate::sessions::FuseQueryContextRef;
+use crate::sessions::MemoryReservation;
+use crate::sessions::MemoryTrackerRef;
 use crate::sessions::SessionManagerRef;
 
+/// The capacity of the channel of a flight stream, in flight data.
+pub const FLIGHT_STREAM_BUFFER: usize = 5;
+
 #[derive(Debug)]
 pub struct PrepareStageInfo {
     pub query_id: String,
@@ -49,6 +55,7 @@ pub enum Request {
     PrepareQueryStage(Box<PrepareStageInfo>, Sender<Result<()>>),
     GetStreamInfo(String, Sender<Result<StreamInfo>>),
     TerminalStage(FuseQueryContextRef, String, String),
+    CancelQuery(String, Sender<Result<()>>),
 }
 
 #[derive(Debug)]
@@ -60,6 +67,8 @@ pub struct FlightStreamInfo {
 
 pub struct DispatcherState {
     streams: HashMap<String, FlightStreamInfo>,
+    // The contexts of the running stages, by query_id/stage_id.
+    contexts: HashMap<String, FuseQueryContextRef>,
 }
 
 struct ServerState {
@@ -74,6 +83,39 @@ pub struct FlightDispatcher {
 
 type DataReceiver = Receiver<Result<FlightData>>;
 
+/// The flight data which may be buffered in the channels of the streams, the bytes of
+/// the last FLIGHT_STREAM_BUFFER flight data sent to every stream are reserved from
+/// the memory tracker of the query.
+pub struct FlightBuffers {
+    sent: Vec<VecDeque<usize>>,
+    bytes: usize,
+    reservation: MemoryReservation,
+}
+
+impl FlightBuffers {
+    pub fn create(memory_tracker: MemoryTrackerRef, streams: usize) -> Self {
+        FlightBuffers {
+            sent: (0..streams).map(|_| VecDeque::new()).collect(),
+            bytes: 0,
+            reservation: MemoryReservation::create(memory_tracker),
+        }
+    }
+
+    /// Accounts the flight data before it is sent to the stream.
+    pub fn push(&mut self, stream: usize, flight_data: &FlightData) -> Result<()> {
+        let bytes = flight_data.data_header.len()
+            + flight_data.data_body.len()
+            + flight_data.app_metadata.len();
+        let sent = &mut self.sent[stream];
+        if sent.len() == FLIGHT_STREAM_BUFFER {
+            self.bytes -= sent.pop_front().unwrap_or(0);
+        }
+        sent.push_back(bytes);
+        self.bytes += bytes;
+        self.reservation.resize(self.bytes)
+    }
+}
+
 impl FlightDispatcher {
     pub fn run(&self) -> Sender<Request> {
         let state = self.state.clone();
@@ -127,16 +169,38 @@ impl FlightDispatcher {
                     dispatcher_state
                         .streams
                         .retain(|name, _| !name.starts_with(&stage_stream_prefix));
+                    dispatcher_state.contexts.remove(&stage_stream_prefix);
 
                     if let Err(error) = state.session_manager.try_remove_context(context) {
                         error!("Terminal Stage error: {}", error);
                     }
                 }
+                Request::CancelQuery(query_id, response_sender) => {
+                    Self::cancel_query(&mut dispatcher_state, &query_id);
+                    if let Err(error) = response_sender.send(Ok(())).await {
+                        error!("Cannot push: {}", error);
+                    }
+                }
             };
         }
         // TODO: shutdown
     }
 
+    /// Cancels the running stages of the query, the stages not launched yet are launched
+    /// without streams, then they fail at their first block and terminate.
+    fn cancel_query(state: &mut DispatcherState, query_id: &str) {
+        let query_prefix = format!("{}/", query_id);
+        for (name, context) in &state.contexts {
+            if name.starts_with(&query_prefix) {
+                context.cancel();
+            }
+        }
+
+        state
+            .streams
+            .retain(|name, _| !name.starts_with(&query_prefix));
+    }
+
     async fn get_stream_info(state: &mut DispatcherState, id: &str) -> Result<StreamInfo> {
         match state.streams.get(id) {
             Some(info) => Ok(StreamInfo {
@@ -202,19 +266,28 @@ impl FlightDispatcher {
         let query_id = info.query_id.clone();
         let stage_id = info.stage_id.clone();
         let (context, pipeline) = pipeline?;
-        let flight_scatter = FlightScatterByHash::try_create(
+        state
+            .contexts
+            .insert(format!("{}/{}", query_id, stage_id), context.clone());
+        let flight_scatter = ScatterByHash::try_create(
             info.plan.schema(),
             info.scatters_expression.clone(),
             streams_data_sender.len(),
         )?;
 
+        let buffers =
+            FlightBuffers::create(context.try_get_memory_tracker()?, streams_data_sender.len());
         let stage_context = context.clone();
         stage_context.execute_task(async move {
             let _ = launcher_receiver.recv().await;
 
-            if let Err(error) =
-                Self::receive_data_and_push(pipeline, flight_scatter, streams_data_sender.clone())
-                    .await
+            if let Err(error) = Self::receive_data_and_push(
+                pipeline,
+                flight_scatter,
+                buffers,
+                streams_data_sender.clone(),
+            )
+            .await
             {
                 for sender in &streams_data_sender {
                     let clone_error =
@@ -262,7 +335,8 @@ impl FlightDispatcher {
     #[inline(always)]
     async fn receive_data_and_push(
         mut pipeline: Pipeline,
-        flight_scatter: FlightScatterByHash,
+        flight_scatter: ScatterByHash,
+        mut buffers: FlightBuffers,
         senders: Vec<Sender<Result<FlightData>>>,
     ) -> Result<()> {
         use common_arrow::arrow::ipc::writer::IpcWriteOptions;
@@ -279,6 +353,7 @@ impl FlightDispatcher {
                 let (dicts, values) = flight_data_from_arrow_batch(&record_batch, &options);
                 let normalized_flight_data = dicts.into_iter().chain(std::iter::once(values));
                 for flight_data in normalized_flight_data {
+                    buffers.push(0, &flight_data)?;
                     if let Err(error) = (&senders[0]).send(Ok(flight_data)).await {
                         return Err(ErrorCode::TokioError(format!(
                             "Cannot push data to sender: {}",
@@ -307,6 +382,7 @@ impl FlightDispatcher {
                             dicts.into_iter().chain(std::iter::once(values));
 
                         for flight_data in normalized_flight_data {
+                            buffers.push(index, &flight_data)?;
                             if let Err(error) = (&senders[index]).send(Ok(flight_data)).await {
                                 return Err(ErrorCode::TokioError(format!(
                                     "Cannot push data to sender: {}",
@@ -341,6 +417,7 @@ impl DispatcherState {
     pub fn create() -> DispatcherState {
         DispatcherState {
             streams: HashMap::new(),
+            contexts: HashMap::new(),
         }
     }
 }
@@ -350,8 +427,7 @@ impl FlightStreamInfo {
         schema: &SchemaRef,
         launcher_sender: &Sender<()>,
     ) -> (Sender<Result<FlightData>>, FlightStreamInfo) {
-        // TODO: Back pressure buffer size
-        let (sender, receive) = channel(5);
+        let (sender, receive) = channel(FLIGHT_STREAM_BUFFER);
         (sender, FlightStreamInfo {
             schema: schema.clone(),
             data_receiver: Some(receive),
@@ -248,6 +248,66 @@ async fn test_prepare_stage_with_scatter() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_cancel_query() -> Result<()> {
+    if let (Some(query_id), Some(stage_id), Some(stream_id)) = generate_uuids(3) {
+        let stream_full_id = format!("{}/{}/{}", query_id, stage_id, stream_id);
+        let ctx = crate::tests::try_create_context()?;
+        let test_source = crate::tests::NumberTestData::create(ctx.clone());
+        let read_source_plan = test_source.number_read_source_plan_for_test(5)?;
+        let plan = PlanBuilder::from(&PlanNode::ReadSource(read_source_plan)).build()?;
+
+        let (_dispatcher, request_sender) = create_dispatcher()?;
+        let (prepare_stage_sender, mut prepare_stage_receiver) = channel(1);
+        let prepare_query_stage = Request::PrepareQueryStage(
+            PrepareStageInfo::create(
+                query_id.clone(),
+                stage_id.clone(),
+                plan,
+                vec![stream_id.clone()],
+                Expression::Literal(DataValue::UInt64(Some(1))),
+            ),
+            prepare_stage_sender,
+        );
+        if let Err(error) = request_sender.send(prepare_query_stage).await {
+            assert!(false, "Cannot push in test_cancel_query: {}", error);
+        }
+        prepare_stage_receiver.recv().await.transpose()?;
+
+        // Cancel the query before its streams are fetched.
+        let (cancel_sender, mut cancel_receiver) = channel(1);
+        if let Err(error) = request_sender
+            .send(Request::CancelQuery(query_id.clone(), cancel_sender))
+            .await
+        {
+            assert!(false, "Cannot push in test_cancel_query: {}", error);
+        }
+        cancel_receiver.recv().await.transpose()?;
+
+        // The streams of the cancelled query are removed.
+        let (sender_v, mut receiver) = channel(1);
+        if let Err(error) = request_sender
+            .send(Request::GetStream(stream_full_id.clone(), sender_v))
+            .await
+        {
+            assert!(false, "Cannot push in test_cancel_query: {}", error);
+        }
+
+        match receiver.recv().await.unwrap() {
+            Ok(_) => assert!(false, "Return Ok in test_cancel_query."),
+            Err(error) => {
+                assert_eq!(error.code(), 29);
+                assert_eq!(
+                    error.message(),
+                    format!("Stream {} is not found", stream_full_id)
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn create_dispatcher() -> Result<(FlightDispatcher, Sender<Request>)> {
     let conf = Config::default();
     let sessions = SessionManager::create();
@@ -33,9 +33,12 @@ use tonic::Response as RawResponse;
 use tonic::Status;
 use tonic::Streaming;
 
+use crate::api::rpc::actions::CancelQueryAction;
 use crate::api::rpc::actions::ExecutePlanWithShuffleAction;
 use crate::api::rpc::flight_dispatcher::PrepareStageInfo;
 use crate::api::rpc::flight_dispatcher::Request as DispatcherRequest;
+use crate::api::rpc::flight_sql::is_flight_sql;
+use crate::api::rpc::flight_sql::FlightSqlHandlerRef;
 use crate::api::rpc::to_status;
 use crate::api::rpc::StreamInfo;
 
@@ -44,11 +47,27 @@ pub type FlightStream<T> =
 
 pub struct FuseQueryService {
     dispatcher_sender: Sender<DispatcherRequest>,
+    flight_sql: Option<FlightSqlHandlerRef>,
 }
 
 impl FuseQueryService {
     pub fn create(dispatcher_sender: Sender<DispatcherRequest>) -> FuseQueryService {
-        FuseQueryService { dispatcher_sender }
+        FuseQueryService {
+            dispatcher_sender,
+            flight_sql: None,
+        }
+    }
+
+    /// Serve the Arrow Flight SQL commands besides the query stages.
+    pub fn with_flight_sql(mut self, flight_sql: FlightSqlHandlerRef) -> FuseQueryService {
+        self.flight_sql = Some(flight_sql);
+        self
+    }
+
+    fn flight_sql(&self) -> Result<&FlightSqlHandlerRef, Status> {
+        self.flight_sql
+            .as_ref()
+            .ok_or_else(|| Status::unimplemented("FuseQuery does not enable Flight SQL."))
     }
 }
 
@@ -137,6 +156,13 @@ impl FlightService for FuseQueryService {
                     .and_then(create_flight_info_response)
                     .map_err(to_status)
             }
+            2 => {
+                // DescriptorType::Cmd, Flight SQL command
+                self.flight_sql()?
+                    .get_flight_info(descriptor)
+                    .map(RawResponse::new)
+                    .map_err(to_status)
+            }
             _unimplemented_type => Err(Status::unimplemented(format!(
                 "FuseQuery does not implement Flight type: {}",
                 descriptor.r#type
@@ -176,6 +202,13 @@ impl FlightService for FuseQueryService {
                     .and_then(create_schema_response)
                     .map_err(to_status)
             }
+            2 => {
+                // DescriptorType::Cmd, Flight SQL command
+                self.flight_sql()?
+                    .get_schema(&descriptor.cmd)
+                    .map(|schema| RawResponse::new(SchemaResult { schema }))
+                    .map_err(to_status)
+            }
             _unimplemented_type => Err(Status::unimplemented(format!(
                 "FuseQuery does not implement Flight type: {}",
                 descriptor.r#type
@@ -199,7 +232,18 @@ impl FlightService for FuseQueryService {
             Ok(RawResponse::new(create_stream(receiver.unwrap())))
         }
 
-        match std::str::from_utf8(&request.into_inner().ticket) {
+        let ticket = request.into_inner().ticket;
+        if is_flight_sql(&ticket) {
+            return self
+                .flight_sql()?
+                .do_get(&ticket)
+                .await
+                .map(Some)
+                .and_then(create_stream_response)
+                .map_err(to_status);
+        }
+
+        match std::str::from_utf8(&ticket) {
             Err(utf_8_error) => Err(Status::invalid_argument(utf_8_error.to_string())),
             Ok(ticket) => {
                 // Flight ticket = query_id/stage_id/stream_id
@@ -284,6 +328,37 @@ impl FlightService for FuseQueryService {
                     )))
                 }
             },
+            "CancelQuery" => match std::str::from_utf8(&action.body) {
+                Err(utf_8_error) => Err(Status::invalid_argument(utf_8_error.to_string())),
+                Ok(cancel_query_str) => {
+                    let action = serde_json::from_str::<CancelQueryAction>(cancel_query_str)
+                        .map_err(ErrorCode::from)
+                        .map_err(to_status)?;
+
+                    let (response_sender, mut receiver) = channel(1);
+                    self.dispatcher_sender
+                        .send(DispatcherRequest::CancelQuery(
+                            action.query_id,
+                            response_sender,
+                        ))
+                        .await
+                        .map_err(|error| Status::unknown(error.to_string()))?;
+
+                    Ok(RawResponse::new(once(
+                        receiver
+                            .recv()
+                            .await
+                            .transpose()
+                            .map(|_| FlightResult { body: vec![] }),
+                    )))
+                }
+            },
+            "CreatePreparedStatement" => Ok(RawResponse::new(once(
+                self.flight_sql()?.create_prepared_statement(&action.body),
+            ))),
+            "ClosePreparedStatement" => Ok(RawResponse::new(once(
+                self.flight_sql()?.close_prepared_statement(&action.body),
+            ))),
             _ => Result::Err(Status::unimplemented(format!(
                 "FuseQuery does not implement action: {}.",
                 action.r#type
@@ -294,13 +369,30 @@ impl FlightService for FuseQueryService {
     type ListActionsStream = FlightStream<ActionType>;
 
     async fn list_actions(&self, _: Request<Empty>) -> Response<Self::ListActionsStream> {
+        let mut actions = vec![
+            Ok(ActionType {
+                r#type: "PrepareQueryStage".to_string(),
+                description: "Prepare a query stage that can be sent to the remote after receiving data from remote".to_string(),
+            }),
+            Ok(ActionType {
+                r#type: "CancelQuery".to_string(),
+                description: "Cancel the running query stages of a query".to_string(),
+            }),
+        ];
+
+        if self.flight_sql.is_some() {
+            actions.push(Ok(ActionType {
+                r#type: "CreatePreparedStatement".to_string(),
+                description: "Create a Flight SQL prepared statement".to_string(),
+            }));
+            actions.push(Ok(ActionType {
+                r#type: "ClosePreparedStatement".to_string(),
+                description: "Close a Flight SQL prepared statement".to_string(),
+            }));
+        }
+
         Result::Ok(RawResponse::new(
-            Box::pin(tokio_stream::iter(vec![
-                Ok(ActionType {
-                    r#type: "PrepareQueryStage".to_string(),
-                    description: "Prepare a query stage that can be sent to the remote after receiving data from remote".to_string(),
-                })
-            ])) as FlightStream<ActionType>
+            Box::pin(tokio_stream::iter(actions)) as FlightStream<ActionType>
         ))
     }
 }
@@ -32,7 +32,7 @@ async fn test_service_list_actions() -> Result<()> {
 
     assert!(response.is_ok());
     let list_actions = response.unwrap().into_inner().collect::<Vec<_>>().await;
-    assert_eq!(list_actions.len(), 1);
+    assert_eq!(list_actions.len(), 2);
     assert_eq!(
         list_actions[0].as_ref().unwrap().r#type,
         "PrepareQueryStage".to_string()
@@ -42,6 +42,10 @@ async fn test_service_list_actions() -> Result<()> {
         "Prepare a query stage that can be sent to the remote after receiving data from remote"
             .to_string()
     );
+    assert_eq!(
+        list_actions[1].as_ref().unwrap().r#type,
+        "CancelQuery".to_string()
+    );
 
     Ok(())
 }
@@ -0,0 +1,461 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use common_arrow::arrow::array::BinaryArray;
+use common_arrow::arrow::array::StringArray;
+use common_arrow::arrow::ipc::writer::IpcWriteOptions;
+use common_arrow::arrow_flight::utils::flight_schema_from_arrow_schema;
+use common_arrow::arrow_flight::FlightData;
+use common_arrow::arrow_flight::FlightDescriptor;
+use common_arrow::arrow_flight::FlightEndpoint;
+use common_arrow::arrow_flight::FlightInfo;
+use common_arrow::arrow_flight::Result as FlightResult;
+use common_arrow::arrow_flight::Ticket;
+use common_datablocks::DataBlock;
+use common_datavalues::DataArrayRef;
+use common_datavalues::DataField;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataSchemaRefExt;
+use common_datavalues::DataType;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_flights::protobuf::ActionClosePreparedStatementRequest;
+use common_flights::protobuf::ActionCreatePreparedStatementRequest;
+use common_flights::protobuf::ActionCreatePreparedStatementResult;
+use common_flights::protobuf::Any;
+use common_flights::protobuf::CommandGetSchemas;
+use common_flights::protobuf::CommandGetTables;
+use common_flights::protobuf::CommandPreparedStatementQuery;
+use common_flights::protobuf::CommandStatementQuery;
+use common_flights::protobuf::TicketStatementQuery;
+use common_infallible::RwLock;
+use common_streams::DataBlockStream;
+use common_streams::SendableDataBlockStream;
+use log::error;
+use prost::Message;
+use tokio::sync::mpsc::channel;
+use tokio::sync::mpsc::Receiver;
+use tokio_stream::StreamExt;
+
+use crate::api::rpc::flight_data_stream::FlightDataStream;
+use crate::api::rpc::flight_dispatcher::FlightBuffers;
+use crate::api::rpc::flight_dispatcher::FLIGHT_STREAM_BUFFER;
+use crate::clusters::ClusterRef;
+use crate::configs::Config;
+use crate::datasources::Table;
+use crate::interpreters::InterpreterFactory;
+use crate::sessions::FuseQueryContextRef;
+use crate::sessions::SessionManagerRef;
+use crate::sql::PlanParser;
+
+const TYPE_URL_PREFIX: &str = "type.googleapis.com/arrow.flight.protocol.sql.";
+
+const SYSTEM_TABLE: &str = "SYSTEM TABLE";
+const TABLE: &str = "TABLE";
+
+// The prepared statements not closed by the clients are dropped once unused for the timeout,
+// the least recently used ones are dropped beyond the max.
+const MAX_PREPARED_STATEMENTS: usize = 1024;
+const PREPARED_STATEMENT_TIMEOUT: Duration = Duration::from_secs(3600);
+
+struct PreparedStatement {
+    query: String,
+    last_used: Instant,
+}
+
+/// Arrow Flight SQL on top of the Flight service, the results are sent as Arrow batches.
+/// The SQL parser has no placeholders, so the prepared statements take no parameters.
+pub struct FlightSqlHandler {
+    conf: Config,
+    cluster: ClusterRef,
+    session_manager: SessionManagerRef,
+    // Prepared statement handle to query.
+    prepared_statements: RwLock<HashMap<Vec<u8>, PreparedStatement>>,
+}
+
+pub type FlightSqlHandlerRef = Arc<FlightSqlHandler>;
+
+impl FlightSqlHandler {
+    pub fn create(
+        conf: Config,
+        cluster: ClusterRef,
+        session_manager: SessionManagerRef,
+    ) -> FlightSqlHandlerRef {
+        Arc::new(FlightSqlHandler {
+            conf,
+            cluster,
+            session_manager,
+            prepared_statements: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// The command is the cmd of a FlightDescriptor, its single endpoint is served by do_get.
+    pub fn get_flight_info(&self, descriptor: FlightDescriptor) -> Result<FlightInfo> {
+        let (schema, ticket) = self.get_schema_and_ticket(&descriptor.cmd)?;
+        Ok(FlightInfo {
+            schema: ipc_schema(&schema),
+            flight_descriptor: Some(descriptor),
+            endpoint: vec![FlightEndpoint {
+                ticket: Some(Ticket { ticket }),
+                location: vec![],
+            }],
+            total_records: -1,
+            total_bytes: -1,
+        })
+    }
+
+    pub fn get_schema(&self, cmd: &[u8]) -> Result<Vec<u8>> {
+        let (schema, _) = self.get_schema_and_ticket(cmd)?;
+        Ok(ipc_schema(&schema))
+    }
+
+    pub async fn do_get(&self, ticket: &[u8]) -> Result<Receiver<Result<FlightData>>> {
+        let ctx = self
+            .session_manager
+            .try_create_context()?
+            .with_cluster(self.cluster.clone())?;
+        ctx.set_max_threads(self.conf.num_cpus)?;
+
+        let (schema, stream) = match execute_ticket(ctx.clone(), ticket).await {
+            Ok(result) => result,
+            Err(error) => {
+                self.session_manager.try_remove_context(ctx)?;
+                return Err(error);
+            }
+        };
+
+        let (sender, receiver) = channel(FLIGHT_STREAM_BUFFER);
+        let mut buffers = FlightBuffers::create(ctx.try_get_memory_tracker()?, 1);
+        let session_manager = self.session_manager.clone();
+        ctx.clone().execute_task(async move {
+            let mut flight_data_stream = FlightDataStream::from_blocks(schema, stream);
+            while let Some(flight_data) = flight_data_stream.next().await {
+                // The query fails once the buffered flight data exceed the memory limit.
+                let flight_data = match flight_data {
+                    Ok(flight_data) => buffers.push(0, &flight_data).map(|_| flight_data),
+                    Err(error) => Err(error),
+                };
+                let failed = flight_data.is_err();
+                if let Err(error) = sender.send(flight_data).await {
+                    error!("Cannot push: {}", error);
+                    break;
+                }
+                if failed {
+                    break;
+                }
+            }
+
+            if let Err(error) = session_manager.try_remove_context(ctx) {
+                error!("Cannot destroy FuseQueryContext: {}", error);
+            }
+        });
+        Ok(receiver)
+    }
+
+    pub fn create_prepared_statement(&self, body: &[u8]) -> Result<FlightResult> {
+        let request: ActionCreatePreparedStatementRequest =
+            unpack_as("ActionCreatePreparedStatementRequest", body)?;
+        let schema = self.plan_schema(&request.query)?;
+
+        let handle = uuid::Uuid::new_v4().to_string().into_bytes();
+        let now = Instant::now();
+        let mut prepared_statements = self.prepared_statements.write();
+        prepared_statements.retain(|_, statement| {
+            now.duration_since(statement.last_used) < PREPARED_STATEMENT_TIMEOUT
+        });
+        if prepared_statements.len() >= MAX_PREPARED_STATEMENTS {
+            let least_recently_used = prepared_statements
+                .iter()
+                .min_by_key(|(_, statement)| statement.last_used)
+                .map(|(handle, _)| handle.clone());
+            if let Some(handle) = least_recently_used {
+                prepared_statements.remove(&handle);
+            }
+        }
+        prepared_statements.insert(handle.clone(), PreparedStatement {
+            query: request.query,
+            last_used: now,
+        });
+        drop(prepared_statements);
+
+        let result = ActionCreatePreparedStatementResult {
+            prepared_statement_handle: handle,
+            dataset_schema: ipc_schema(&schema),
+            parameter_schema: vec![],
+        };
+        Ok(FlightResult {
+            body: pack("ActionCreatePreparedStatementResult", &result)?,
+        })
+    }
+
+    pub fn close_prepared_statement(&self, body: &[u8]) -> Result<FlightResult> {
+        let request: ActionClosePreparedStatementRequest =
+            unpack_as("ActionClosePreparedStatementRequest", body)?;
+        self.prepared_statements
+            .write()
+            .remove(&request.prepared_statement_handle);
+        Ok(FlightResult { body: vec![] })
+    }
+
+    fn get_schema_and_ticket(&self, cmd: &[u8]) -> Result<(DataSchemaRef, Vec<u8>)> {
+        let (name, value) = unpack(cmd)?;
+        let query = match name.as_str() {
+            "CommandStatementQuery" => decode::<CommandStatementQuery>(&name, &value)?.query,
+            "CommandPreparedStatementQuery" => {
+                let command = decode::<CommandPreparedStatementQuery>(&name, &value)?;
+                let mut prepared_statements = self.prepared_statements.write();
+                let statement = prepared_statements
+                    .get_mut(&command.prepared_statement_handle)
+                    .ok_or_else(|| ErrorCode::BadArguments("Unknown prepared statement handle"))?;
+                statement.last_used = Instant::now();
+                statement.query.clone()
+            }
+            // The metadata commands are their own tickets.
+            _ => return Ok((metadata_schema(&name, &value)?, cmd.to_vec())),
+        };
+
+        let schema = self.plan_schema(&query)?;
+        let ticket = pack("TicketStatementQuery", &TicketStatementQuery {
+            statement_handle: query.into_bytes(),
+        })?;
+        Ok((schema, ticket))
+    }
+
+    fn plan_schema(&self, query: &str) -> Result<DataSchemaRef> {
+        let ctx = self.session_manager.try_create_context()?;
+        let schema = PlanParser::create(ctx.clone())
+            .build_from_sql(query)
+            .and_then(|plan| InterpreterFactory::get(ctx.clone(), plan))
+            .map(|interpreter| interpreter.schema());
+        self.session_manager.try_remove_context(ctx)?;
+        schema
+    }
+}
+
+async fn execute_ticket(
+    ctx: FuseQueryContextRef,
+    ticket: &[u8],
+) -> Result<(DataSchemaRef, SendableDataBlockStream)> {
+    let (name, value) = unpack(ticket)?;
+    if name != "TicketStatementQuery" {
+        let block = metadata_block(&ctx, &name, &value)?;
+        let schema = block.schema().clone();
+        let stream = DataBlockStream::create(schema.clone(), None, vec![block]);
+        return Ok((schema, Box::pin(stream)));
+    }
+
+    let ticket = decode::<TicketStatementQuery>(&name, &value)?;
+    let query = String::from_utf8(ticket.statement_handle)
+        .map_err(|e| ErrorCode::BadBytes(format!("Bad statement handle: {}", e)))?;
+    let plan = PlanParser::create(ctx.clone()).build_from_sql(&query)?;
+    let interpreter = InterpreterFactory::get(ctx, plan)?;
+    Ok((interpreter.schema(), interpreter.execute().await?))
+}
+
+fn metadata_schema(name: &str, value: &[u8]) -> Result<DataSchemaRef> {
+    Ok(match name {
+        "CommandGetCatalogs" => {
+            DataSchemaRefExt::create(vec![DataField::new("catalog_name", DataType::Utf8, false)])
+        }
+        "CommandGetSchemas" => DataSchemaRefExt::create(vec![
+            DataField::new("catalog_name", DataType::Utf8, true),
+            DataField::new("db_schema_name", DataType::Utf8, false),
+        ]),
+        "CommandGetTables" => {
+            let command = decode::<CommandGetTables>(name, value)?;
+            let mut fields = vec![
+                DataField::new("catalog_name", DataType::Utf8, true),
+                DataField::new("db_schema_name", DataType::Utf8, true),
+                DataField::new("table_name", DataType::Utf8, false),
+                DataField::new("table_type", DataType::Utf8, false),
+            ];
+            if command.include_schema {
+                fields.push(DataField::new("table_schema", DataType::Binary, false));
+            }
+            DataSchemaRefExt::create(fields)
+        }
+        "CommandGetTableTypes" => {
+            DataSchemaRefExt::create(vec![DataField::new("table_type", DataType::Utf8, false)])
+        }
+        _ => {
+            return Err(ErrorCode::UnImplement(format!(
+                "Unsupported Flight SQL command: {}",
+                name
+            )))
+        }
+    })
+}
+
+fn metadata_block(ctx: &FuseQueryContextRef, name: &str, value: &[u8]) -> Result<DataBlock> {
+    let schema = metadata_schema(name, value)?;
+    let datasource = ctx.get_datasource();
+
+    // There are no catalogs, only the databases as schemas.
+    Ok(match name {
+        "CommandGetCatalogs" => DataBlock::create_by_array(schema, vec![Arc::new(
+            StringArray::from(Vec::<&str>::new()),
+        )]),
+        "CommandGetSchemas" => {
+            let command = decode::<CommandGetSchemas>(name, value)?;
+            let mut databases = datasource.get_databases()?;
+            databases.retain(|database| {
+                command.catalog.is_empty() && like(&command.db_schema_filter_pattern, database)
+            });
+
+            DataBlock::create_by_array(schema, vec![
+                Arc::new(StringArray::from(vec![None::<&str>; databases.len()])),
+                Arc::new(StringArray::from(
+                    databases.iter().map(|v| v.as_str()).collect::<Vec<_>>(),
+                )),
+            ])
+        }
+        "CommandGetTables" => {
+            let command = decode::<CommandGetTables>(name, value)?;
+            let mut tables = datasource.get_all_tables()?;
+            tables.retain(|(database, table)| {
+                command.catalog.is_empty()
+                    && like(&command.db_schema_filter_pattern, database)
+                    && like(&command.table_name_filter_pattern, table.name())
+                    && (command.table_types.is_empty()
+                        || command
+                            .table_types
+                            .iter()
+                            .any(|v| v == table_type(database)))
+            });
+
+            let mut columns: Vec<DataArrayRef> = vec![
+                Arc::new(StringArray::from(vec![None::<&str>; tables.len()])),
+                Arc::new(StringArray::from(
+                    tables.iter().map(|(d, _)| d.as_str()).collect::<Vec<_>>(),
+                )),
+                Arc::new(StringArray::from(
+                    tables.iter().map(|(_, t)| t.name()).collect::<Vec<_>>(),
+                )),
+                Arc::new(StringArray::from(
+                    tables
+                        .iter()
+                        .map(|(d, _)| table_type(d))
+                        .collect::<Vec<_>>(),
+                )),
+            ];
+            if command.include_schema {
+                let schemas = tables
+                    .iter()
+                    .map(|(_, table)| table.schema().map(|schema| ipc_schema(&schema)))
+                    .collect::<Result<Vec<_>>>()?;
+                columns.push(Arc::new(BinaryArray::from(
+                    schemas.iter().map(|v| v.as_slice()).collect::<Vec<_>>(),
+                )));
+            }
+            DataBlock::create_by_array(schema, columns)
+        }
+        _ => DataBlock::create_by_array(schema, vec![Arc::new(StringArray::from(vec![
+            SYSTEM_TABLE,
+            TABLE,
+        ]))]),
+    })
+}
+
+fn table_type(database: &str) -> &'static str {
+    match database {
+        "system" => SYSTEM_TABLE,
+        _ => TABLE,
+    }
+}
+
+fn ipc_schema(schema: &DataSchemaRef) -> Vec<u8> {
+    let options = IpcWriteOptions::default();
+    flight_schema_from_arrow_schema(schema, &options).schema
+}
+
+/// The SQL LIKE pattern of the metadata commands, an empty pattern matches all.
+pub fn like(pattern: &str, value: &str) -> bool {
+    if pattern.is_empty() {
+        return true;
+    }
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let value = value.chars().collect::<Vec<_>>();
+
+    // The positions in the pattern and the value, and the last % with the value position it
+    // matched up to, where the match goes back to when the rest of the pattern fails.
+    let (mut p, mut v) = (0, 0);
+    let mut last_percent: Option<(usize, usize)> = None;
+    while v < value.len() {
+        match pattern.get(p) {
+            Some('%') => {
+                last_percent = Some((p, v));
+                p += 1;
+            }
+            Some(c) if *c == '_' || *c == value[v] => {
+                p += 1;
+                v += 1;
+            }
+            _ => match last_percent {
+                // The % matches one more char of the value.
+                Some((percent, matched)) => {
+                    last_percent = Some((percent, matched + 1));
+                    p = percent + 1;
+                    v = matched + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '%')
+}
+
+/// The Flight SQL messages are packed in a google.protobuf.Any.
+pub fn pack<M: Message>(name: &str, message: &M) -> Result<Vec<u8>> {
+    let mut value = vec![];
+    message
+        .encode(&mut value)
+        .map_err(|e| ErrorCode::BadBytes(format!("Cannot encode {}: {}", name, e)))?;
+
+    let mut buf = vec![];
+    Any {
+        type_url: format!("{}{}", TYPE_URL_PREFIX, name),
+        value,
+    }
+    .encode(&mut buf)
+    .map_err(|e| ErrorCode::BadBytes(format!("Cannot encode {}: {}", name, e)))?;
+    Ok(buf)
+}
+
+/// Returns the message name and its bytes.
+pub fn unpack(buf: &[u8]) -> Result<(String, Vec<u8>)> {
+    let any = Any::decode(buf)
+        .map_err(|e| ErrorCode::BadBytes(format!("Cannot decode Flight SQL command: {}", e)))?;
+    match any.type_url.strip_prefix(TYPE_URL_PREFIX) {
+        Some(name) => Ok((name.to_string(), any.value)),
+        None => Err(ErrorCode::BadBytes(format!(
+            "Unknown Flight SQL type: {}",
+            any.type_url
+        ))),
+    }
+}
+
+/// Tells the Flight SQL tickets apart from the stream names of the query stages.
+pub fn is_flight_sql(buf: &[u8]) -> bool {
+    unpack(buf).is_ok()
+}
+
+fn unpack_as<M: Message + Default>(name: &str, buf: &[u8]) -> Result<M> {
+    let (actual, value) = unpack(buf)?;
+    if actual != name {
+        return Err(ErrorCode::BadBytes(format!(
+            "Expected {}, but got {}",
+            name, actual
+        )));
+    }
+    decode(name, &value)
+}
+
+fn decode<M: Message + Default>(name: &str, value: &[u8]) -> Result<M> {
+    M::decode(value).map_err(|e| ErrorCode::BadBytes(format!("Cannot decode {}: {}", name, e)))
+}
@@ -0,0 +1,231 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::convert::TryFrom;
+
+use common_arrow::arrow::ipc::convert;
+use common_arrow::arrow_flight::flight_descriptor::DescriptorType;
+use common_arrow::arrow_flight::flight_service_server::FlightService;
+use common_arrow::arrow_flight::utils::flight_data_to_arrow_batch;
+use common_arrow::arrow_flight::Action;
+use common_arrow::arrow_flight::FlightDescriptor;
+use common_arrow::arrow_flight::Ticket;
+use common_datablocks::DataBlock;
+use common_exception::Result;
+use common_flights::protobuf::ActionClosePreparedStatementRequest;
+use common_flights::protobuf::ActionCreatePreparedStatementRequest;
+use common_flights::protobuf::ActionCreatePreparedStatementResult;
+use common_flights::protobuf::CommandGetTables;
+use common_flights::protobuf::CommandPreparedStatementQuery;
+use common_flights::protobuf::CommandStatementQuery;
+use prost::Message;
+use tokio_stream::StreamExt;
+use tonic::Request;
+
+use crate::api::rpc::flight_sql::*;
+use crate::api::rpc::FuseQueryService;
+use crate::clusters::Cluster;
+use crate::configs::Config;
+use crate::sessions::SessionManager;
+
+fn create_service() -> Result<FuseQueryService> {
+    let conf = Config::default();
+    let cluster = Cluster::create_global(conf.clone())?;
+    let flight_sql = FlightSqlHandler::create(conf, cluster, SessionManager::create());
+
+    let (sender, _) = tokio::sync::mpsc::channel(1);
+    Ok(FuseQueryService::create(sender).with_flight_sql(flight_sql))
+}
+
+// Runs the command through get_flight_info and do_get.
+async fn query_command(service: &FuseQueryService, cmd: Vec<u8>) -> Result<Vec<DataBlock>> {
+    let info = service
+        .get_flight_info(Request::new(FlightDescriptor {
+            r#type: DescriptorType::Cmd as i32,
+            cmd,
+            path: vec![],
+        }))
+        .await
+        .map_err(crate::api::rpc::from_status)?
+        .into_inner();
+    let schema = std::sync::Arc::new(convert::schema_from_bytes(&info.schema)?);
+
+    let ticket = info.endpoint[0].ticket.clone().unwrap();
+    let mut stream = service
+        .do_get(Request::new(ticket))
+        .await
+        .map_err(crate::api::rpc::from_status)?
+        .into_inner();
+
+    // The schema goes first.
+    let schema_data = stream.next().await.unwrap().unwrap();
+    assert_eq!(
+        *schema,
+        convert::schema_from_bytes(&schema_data.data_header)?
+    );
+
+    let mut blocks = vec![];
+    while let Some(flight_data) = stream.next().await {
+        let flight_data = flight_data.map_err(crate::api::rpc::from_status)?;
+        let batch = flight_data_to_arrow_batch(&flight_data, schema.clone(), &[])?;
+        blocks.push(DataBlock::try_from(batch)?);
+    }
+    Ok(blocks)
+}
+
+#[test]
+fn test_like() -> Result<()> {
+    assert!(like("", "system"));
+    assert!(like("system", "system"));
+    assert!(like("sys%", "system"));
+    assert!(like("%tem", "system"));
+    assert!(like("s_s%m", "system"));
+    assert!(like("%", ""));
+    assert!(!like("sys", "system"));
+    assert!(!like("_", ""));
+    assert!(!like("%x%", "system"));
+    assert!(like("%s%t%", "system"));
+    assert!(like("%%m", "system"));
+    assert!(!like("system_", "system"));
+
+    // The patterns with many % don't backtrack exponentially.
+    let value = "a".repeat(64);
+    assert!(!like("%a%a%a%a%a%a%a%a%a%a%b", &value));
+    assert!(like("%a%a%a%a%a%a%a%a%a%a%", &value));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_flight_sql_statement() -> Result<()> {
+    let service = create_service()?;
+
+    let cmd = pack("CommandStatementQuery", &CommandStatementQuery {
+        query: "SELECT number FROM numbers(3) ORDER BY number".to_string(),
+    })?;
+    let blocks = query_command(&service, cmd).await?;
+
+    let expected = vec![
+        "+--------+",
+        "| number |",
+        "+--------+",
+        "| 0      |",
+        "| 1      |",
+        "| 2      |",
+        "+--------+",
+    ];
+    common_datablocks::assert_blocks_eq(expected, blocks.as_slice());
+
+    // Unknown table, the error comes with the flight info.
+    let cmd = pack("CommandStatementQuery", &CommandStatementQuery {
+        query: "SELECT * FROM system.xx".to_string(),
+    })?;
+    let result = query_command(&service, cmd).await;
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.code(), 25);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_flight_sql_get_tables() -> Result<()> {
+    let service = create_service()?;
+
+    let cmd = pack("CommandGetTables", &CommandGetTables {
+        catalog: "".to_string(),
+        db_schema_filter_pattern: "sys%".to_string(),
+        table_name_filter_pattern: "o_e".to_string(),
+        table_types: vec!["SYSTEM TABLE".to_string()],
+        include_schema: false,
+    })?;
+    let blocks = query_command(&service, cmd).await?;
+
+    let expected = vec![
+        "+--------------+----------------+------------+--------------+",
+        "| catalog_name | db_schema_name | table_name | table_type   |",
+        "+--------------+----------------+------------+--------------+",
+        "|              | system         | one        | SYSTEM TABLE |",
+        "+--------------+----------------+------------+--------------+",
+    ];
+    common_datablocks::assert_blocks_eq(expected, blocks.as_slice());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_flight_sql_prepared_statement() -> Result<()> {
+    let service = create_service()?;
+
+    // Create.
+    let body = pack(
+        "ActionCreatePreparedStatementRequest",
+        &ActionCreatePreparedStatementRequest {
+            query: "SELECT number + 1 AS n FROM numbers(1)".to_string(),
+        },
+    )?;
+    let mut results = service
+        .do_action(Request::new(Action {
+            r#type: "CreatePreparedStatement".to_string(),
+            body,
+        }))
+        .await
+        .map_err(crate::api::rpc::from_status)?
+        .into_inner();
+    let body = results.next().await.unwrap().unwrap().body;
+    let (name, value) = unpack(&body)?;
+    assert_eq!(name, "ActionCreatePreparedStatementResult");
+    let result = ActionCreatePreparedStatementResult::decode(value.as_slice()).unwrap();
+    let schema = convert::schema_from_bytes(&result.dataset_schema)?;
+    assert_eq!(schema.field(0).name(), "n");
+
+    // Query.
+    let handle = result.prepared_statement_handle;
+    let cmd = pack(
+        "CommandPreparedStatementQuery",
+        &CommandPreparedStatementQuery {
+            prepared_statement_handle: handle.clone(),
+        },
+    )?;
+    let blocks = query_command(&service, cmd.clone()).await?;
+
+    let expected = vec!["+---+", "| n |", "+---+", "| 1 |", "+---+"];
+    common_datablocks::assert_blocks_eq(expected, blocks.as_slice());
+
+    // Close, the handle is gone.
+    let body = pack(
+        "ActionClosePreparedStatementRequest",
+        &ActionClosePreparedStatementRequest {
+            prepared_statement_handle: handle,
+        },
+    )?;
+    service
+        .do_action(Request::new(Action {
+            r#type: "ClosePreparedStatement".to_string(),
+            body,
+        }))
+        .await
+        .map_err(crate::api::rpc::from_status)?;
+    assert!(query_command(&service, cmd).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_flight_sql_disabled() -> Result<()> {
+    let (sender, _) = tokio::sync::mpsc::channel(1);
+    let service = FuseQueryService::create(sender);
+
+    let cmd = pack("CommandStatementQuery", &CommandStatementQuery {
+        query: "SELECT 1".to_string(),
+    })?;
+    let response = service.do_get(Request::new(Ticket { ticket: cmd })).await;
+    assert!(response.is_err());
+    if let Err(status) = response {
+        assert_eq!(status.code(), tonic::Code::Unimplemented);
+    }
+
+    Ok(())
+}
@@ -7,16 +7,19 @@ mod flight_dispatcher_test;
 
 #[cfg(test)]
 mod flight_service_new_test;
+#[cfg(test)]
+mod flight_sql_test;
 
 mod actions;
 mod flight_client_new;
 mod flight_data_stream;
 mod flight_dispatcher;
-mod flight_scatter;
 mod flight_service_new;
+mod flight_sql;
 
 use std::sync::Arc;
 
+pub use actions::CancelQueryAction;
 pub use actions::ExecutePlanWithShuffleAction;
 use common_exception::exception::ErrorCodeBacktrace;
 use common_exception::ErrorCode;
@@ -25,6 +28,7 @@ pub use flight_dispatcher::FlightDispatcher;
 pub use flight_dispatcher::StreamInfo;
 pub use flight_service_new::FlightStream;
 pub use flight_service_new::FuseQueryService;
+pub use flight_sql::FlightSqlHandler;
 use tonic::Code;
 use tonic::Status;
 
@@ -8,6 +8,7 @@ use common_arrow::arrow_flight::flight_service_server::FlightServiceServer;
 use tonic::transport::Server;
 
 use crate::api::rpc::FlightDispatcher;
+use crate::api::rpc::FlightSqlHandler;
 use crate::api::rpc::FuseQueryService;
 use crate::clusters::ClusterRef;
 use crate::configs::Config;
@@ -42,7 +43,13 @@ impl RpcService {
 
         // Flight service:
         let dispatcher_request_sender = flight_dispatcher.run();
-        let service = FuseQueryService::create(dispatcher_request_sender);
+        let flight_sql = FlightSqlHandler::create(
+            self.conf.clone(),
+            self.cluster.clone(),
+            self.session_manager.clone(),
+        );
+        let service =
+            FuseQueryService::create(dispatcher_request_sender).with_flight_sql(flight_sql);
 
         Server::builder()
             .add_service(FlightServiceServer::new(service))
@@ -10,6 +10,7 @@ use fuse_query::configs::Config;
 use fuse_query::metrics::MetricService;
 use fuse_query::servers::ClickHouseHandler;
 use fuse_query::servers::MySQLHandler;
+use fuse_query::servers::PostgresHandler;
 use fuse_query::sessions::SessionManager;
 use log::info;
 
@@ -39,7 +40,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut tasks = vec![];
     let cluster = Cluster::create_global(conf.clone())?;
-    let session_manager = SessionManager::create();
+    let session_manager = SessionManager::from_conf(conf.clone());
 
     // MySQL handler.
     {
@@ -75,6 +76,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         );
     }
 
+    // Postgres handler.
+    {
+        let handler =
+            PostgresHandler::create(conf.clone(), cluster.clone(), session_manager.clone());
+
+        tasks.push(tokio::spawn(async move {
+            handler.start().await.expect("Postgres handler error");
+        }));
+
+        info!(
+            "Postgres handler listening on {}:{}, Usage: psql -h {} -p {} -d default",
+            conf.postgres_handler_host,
+            conf.postgres_handler_port,
+            conf.postgres_handler_host,
+            conf.postgres_handler_port
+        );
+    }
+
     // Metric API service.
     {
         let srv = MetricService::create(conf.clone());
@@ -86,7 +105,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // HTTP API service.
     {
-        let srv = HttpService::create(conf.clone(), cluster.clone());
+        let srv = HttpService::create(conf.clone(), cluster.clone(), session_manager.clone());
         tasks.push(tokio::spawn(async move {
             srv.make_server().await.expect("HTTP service error");
         }));
@@ -75,6 +75,16 @@ pub struct Config {
     )]
     pub clickhouse_handler_thread_num: u64,
 
+    #[structopt(
+        long,
+        env = "FUSE_QUERY_POSTGRES_HANDLER_HOST",
+        default_value = "127.0.0.1"
+    )]
+    pub postgres_handler_host: String,
+
+    #[structopt(long, env = "FUSE_QUERY_POSTGRES_HANDLER_PORT", default_value = "5432")]
+    pub postgres_handler_port: u16,
+
     #[structopt(
         long,
         env = "FUSE_QUERY_FLIGHT_API_ADDRESS",
@@ -96,6 +106,10 @@ pub struct Config {
     )]
     pub metric_api_address: String,
 
+    // The maximum bytes buffered by all the queries of the server, 0 for unlimited.
+    #[structopt(long, env = "FUSE_QUERY_MAX_SERVER_MEMORY_USAGE", default_value = "0")]
+    pub max_server_memory_usage: u64,
+
     #[structopt(long, env = "STORE_API_ADDRESS", default_value = "127.0.0.1:9191")]
     pub store_api_address: String,
 
@@ -121,9 +135,12 @@ impl Config {
             clickhouse_handler_host: "127.0.0.1".to_string(),
             clickhouse_handler_port: 9000,
             clickhouse_handler_thread_num: 256,
+            postgres_handler_host: "127.0.0.1".to_string(),
+            postgres_handler_port: 5432,
             flight_api_address: "127.0.0.1:9090".to_string(),
             http_api_address: "127.0.0.1:8080".to_string(),
             metric_api_address: "127.0.0.1:7070".to_string(),
+            max_server_memory_usage: 0,
             store_api_address: "127.0.0.1:9191".to_string(),
             store_api_username: "root".to_string(),
             store_api_password: "root".to_string(),
@@ -19,9 +19,12 @@ fn test_config() -> common_exception::Result<()> {
             clickhouse_handler_host: "127.0.0.1".to_string(),
             clickhouse_handler_port: 9000,
             clickhouse_handler_thread_num: 256,
+            postgres_handler_host: "127.0.0.1".to_string(),
+            postgres_handler_port: 5432,
             flight_api_address: "127.0.0.1:9090".to_string(),
             http_api_address: "127.0.0.1:8080".to_string(),
             metric_api_address: "127.0.0.1:7070".to_string(),
+            max_server_memory_usage: 0,
             store_api_address: "127.0.0.1:9191".to_string(),
             store_api_username: "root".to_string(),
             store_api_password: "root".to_string(),
@@ -155,6 +155,16 @@ impl DataSource {
         }
     }
 
+    /// The `ver` of the table in the store metadata, changed by every modification of the table.
+    pub async fn get_remote_table_version(&self, db_name: &str, table_name: &str) -> Result<i64> {
+        let cli_provider = self.remote_factory.store_client_provider();
+        let mut store_cli = cli_provider.try_get_client().await?;
+        let res = store_cli
+            .get_table(db_name.to_string(), table_name.to_string())
+            .await?;
+        Ok(res.ver)
+    }
+
     pub fn get_all_tables(&self) -> Result<Vec<(String, Arc<dyn Table>)>> {
         let mut results = vec![];
         for (k, v) in self.databases.read().iter() {
@@ -0,0 +1,36 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+
+use pretty_assertions::assert_eq;
+
+use crate::datasources::HyperLogLog;
+
+fn hash(v: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hasher.write_u64(v);
+    hasher.finish()
+}
+
+#[test]
+fn test_hyper_log_log() {
+    let mut hll = HyperLogLog::create();
+    assert_eq!(0, hll.count());
+
+    // A few distinct values are counted exactly.
+    for v in 0..100 {
+        hll.add_hash(hash(v % 10));
+    }
+    assert_eq!(10, hll.count());
+
+    // The large counts are within a few standard errors.
+    let mut hll = HyperLogLog::create();
+    for v in 0..1_000_000 {
+        hll.add_hash(hash(v));
+    }
+    let error = (hll.count() as f64 - 1_000_000.0).abs() / 1_000_000.0;
+    assert!(error < 0.03, "error: {}", error);
+}
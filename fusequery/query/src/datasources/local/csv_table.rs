@@ -10,10 +10,12 @@ use anyhow::Context;
 use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_infallible::RwLock;
 use common_planners::ReadDataSourcePlan;
 use common_planners::ScanPlan;
 use common_planners::Statistics;
 use common_planners::TableOptions;
+use common_planners::TableStatistics;
 use common_streams::SendableDataBlockStream;
 
 use crate::datasources::local::CsvTableStream;
@@ -27,6 +29,7 @@ pub struct CsvTable {
     schema: DataSchemaRef,
     file: String,
     has_header: bool,
+    statistics: RwLock<Option<TableStatistics>>,
 }
 
 impl CsvTable {
@@ -52,6 +55,7 @@ impl CsvTable {
             schema,
             file,
             has_header,
+            statistics: RwLock::new(None),
         }))
     }
 }
@@ -78,6 +82,20 @@ impl Table for CsvTable {
         true
     }
 
+    // The table meta of the local tables is in memory, so are the statistics.
+    fn statistics(&self, _ctx: FuseQueryContextRef) -> Result<Option<TableStatistics>> {
+        Ok(self.statistics.read().clone())
+    }
+
+    fn set_statistics(
+        &self,
+        _ctx: FuseQueryContextRef,
+        statistics: Option<TableStatistics>,
+    ) -> Result<()> {
+        *self.statistics.write() = statistics;
+        Ok(())
+    }
+
     fn read_plan(
         &self,
         ctx: FuseQueryContextRef,
@@ -109,11 +127,17 @@ impl Table for CsvTable {
         })
     }
 
-    async fn read(&self, ctx: FuseQueryContextRef) -> Result<SendableDataBlockStream> {
+    async fn read(
+        &self,
+        ctx: FuseQueryContextRef,
+        source_plan: &ReadDataSourcePlan,
+    ) -> Result<SendableDataBlockStream> {
         Ok(Box::pin(CsvTableStream::try_create(
             ctx,
             self.schema.clone(),
             self.file.clone(),
+            &source_plan.scan_plan.filters,
+            source_plan.scan_plan.limit,
         )?))
     }
 }
@@ -4,22 +4,33 @@
 
 use std::convert::TryInto;
 use std::fs::File;
+use std::sync::Arc;
 use std::task::Poll;
 
 use anyhow::Context;
+use common_arrow::arrow;
 use common_arrow::arrow::csv;
 use common_datablocks::DataBlock;
+use common_datavalues as datavalues;
+use common_datavalues::BooleanArray;
 use common_datavalues::DataSchemaRef;
+use common_datavalues::DataSchemaRefExt;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_planners::Expression;
 use futures::Stream;
 
+use crate::pipelines::transforms::ExpressionExecutor;
 use crate::sessions::FuseQueryContextRef;
 
 pub struct CsvTableStream {
     ctx: FuseQueryContextRef,
     file: String,
     schema: DataSchemaRef,
+    // The executor and the column name of the push down filters.
+    filter: Option<(Arc<ExpressionExecutor>, String)>,
+    // The rows left to read of the push down limit.
+    remaining: Option<usize>,
 }
 
 impl CsvTableStream {
@@ -27,11 +38,76 @@ impl CsvTableStream {
         ctx: FuseQueryContextRef,
         schema: DataSchemaRef,
         file: String,
+        filters: &[Expression],
+        limit: Option<usize>,
     ) -> Result<Self> {
-        Ok(CsvTableStream { ctx, file, schema })
+        let filter = match filters.split_first() {
+            None => None,
+            Some((first, rest)) => {
+                let predicate = rest
+                    .iter()
+                    .fold(first.clone(), |acc, filter| acc.and(filter.clone()));
+
+                let mut fields = schema.fields().clone();
+                fields.push(predicate.to_data_field(&schema)?);
+                let executor = ExpressionExecutor::try_create(
+                    "csv filter executor",
+                    schema.clone(),
+                    DataSchemaRefExt::create(fields),
+                    vec![predicate.clone()],
+                    false,
+                )?;
+                executor.validate()?;
+                Some((Arc::new(executor), predicate.column_name()))
+            }
+        };
+
+        Ok(CsvTableStream {
+            ctx,
+            file,
+            schema,
+            filter,
+            remaining: limit,
+        })
+    }
+
+    // Drops the rows which don't match the push down filters.
+    fn filter_block(&self, block: DataBlock) -> Result<DataBlock> {
+        match &self.filter {
+            None => Ok(block),
+            Some((executor, column_name)) => {
+                let filter_block = executor.execute(&block)?;
+                let filter_array = filter_block.try_column_by_name(column_name)?.to_array()?;
+                let filter_array = datavalues::downcast_array!(filter_array, BooleanArray)?;
+
+                let batch = block.try_into()?;
+                let batch = arrow::compute::filter_record_batch(&batch, filter_array)?;
+                batch.try_into()
+            }
+        }
     }
 
-    pub fn try_get_one_block(&self) -> Result<Option<DataBlock>> {
+    // Takes the rows of the push down limit from the block.
+    fn limit_block(&mut self, block: DataBlock) -> DataBlock {
+        match self.remaining.as_mut() {
+            Some(remaining) if *remaining < block.num_rows() => {
+                let rows = *remaining;
+                *remaining = 0;
+                block.slice(0, rows)
+            }
+            Some(remaining) => {
+                *remaining -= block.num_rows();
+                block
+            }
+            None => block,
+        }
+    }
+
+    pub fn try_get_one_block(&mut self) -> Result<Option<DataBlock>> {
+        if self.remaining == Some(0) {
+            return Ok(None);
+        }
+
         let partitions = self.ctx.try_get_partitions(1)?;
         if partitions.is_empty() {
             return Ok(None);
@@ -40,7 +116,11 @@ impl CsvTableStream {
         let part = partitions[0].clone();
         let names: Vec<_> = part.name.split('-').collect();
         let begin: usize = names[1].parse()?;
-        let end: usize = names[2].parse()?;
+        let mut end: usize = names[2].parse()?;
+        if let (None, Some(remaining)) = (&self.filter, self.remaining) {
+            // Without filters, the lines after the limit are not needed.
+            end = end.min(begin.saturating_add(remaining));
+        }
         let bounds = Some((begin, end));
         let block_size = end - begin;
 
@@ -63,6 +143,8 @@ impl CsvTableStream {
                 record
                     .map_err(ErrorCode::from)
                     .and_then(|record| record.try_into())
+                    .and_then(|block| self.filter_block(block))
+                    .map(|block| self.limit_block(block))
             })
             .map(|data_block| data_block.map(Some))
             .unwrap_or_else(|| Ok(None))
@@ -73,7 +155,7 @@ impl Stream for CsvTableStream {
     type Item = Result<DataBlock>;
 
     fn poll_next(
-        self: std::pin::Pin<&mut Self>,
+        mut self: std::pin::Pin<&mut Self>,
         _: &mut std::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
         let block = self.try_get_one_block()?;
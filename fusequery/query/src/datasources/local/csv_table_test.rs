@@ -45,9 +45,9 @@ async fn test_csv_table() -> anyhow::Result<()> {
         limit: None,
     };
     let source_plan = table.read_plan(ctx.clone(), &scan_plan, ctx.get_max_threads()? as usize)?;
-    ctx.try_set_partitions(source_plan.partitions)?;
+    ctx.try_set_partitions(source_plan.partitions.clone())?;
 
-    let stream = table.read(ctx).await?;
+    let stream = table.read(ctx, &source_plan).await?;
     let result = stream.try_collect::<Vec<_>>().await?;
     let block = &result[0];
     assert_eq!(block.num_columns(), 1);
@@ -118,9 +118,9 @@ async fn test_csv_table_parse_error() -> anyhow::Result<()> {
         limit: None,
     };
     let source_plan = table.read_plan(ctx.clone(), &scan_plan, ctx.get_max_threads()? as usize)?;
-    ctx.try_set_partitions(source_plan.partitions)?;
+    ctx.try_set_partitions(source_plan.partitions.clone())?;
 
-    let stream = table.read(ctx).await?;
+    let stream = table.read(ctx, &source_plan).await?;
     let result = stream.try_collect::<Vec<_>>().await;
     assert_eq!(true, result.is_err());
     if let Err(e) = result {
@@ -132,3 +132,120 @@ async fn test_csv_table_parse_error() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_csv_table_with_filters() -> anyhow::Result<()> {
+    use std::env;
+
+    use common_datavalues::*;
+    use common_planners::*;
+    use futures::TryStreamExt;
+
+    use crate::datasources::local::*;
+
+    let options: TableOptions = [(
+        "location".to_string(),
+        env::current_dir()?
+            .join("../../tests/data/sample.csv")
+            .display()
+            .to_string(),
+    )]
+    .iter()
+    .cloned()
+    .collect();
+
+    let ctx = crate::tests::try_create_context()?;
+    let table = CsvTable::try_create(
+        "default".into(),
+        "test_csv".into(),
+        DataSchemaRefExt::create(vec![DataField::new("column1", DataType::UInt64, false)]).into(),
+        options,
+    )?;
+
+    let scan_plan = &ScanPlan {
+        schema_name: "".to_string(),
+        table_schema: DataSchemaRefExt::create(vec![]),
+        table_args: None,
+        projection: None,
+        projected_schema: DataSchemaRefExt::create(vec![DataField::new(
+            "column1",
+            DataType::UInt64,
+            false,
+        )]),
+        filters: vec![
+            col("column1").gt(lit(2u64)),
+            col("column1").lt_eq(lit(4u64)),
+        ],
+        limit: None,
+    };
+    let source_plan = table.read_plan(ctx.clone(), &scan_plan, ctx.get_max_threads()? as usize)?;
+    ctx.try_set_partitions(source_plan.partitions.clone())?;
+
+    let stream = table.read(ctx, &source_plan).await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+
+    let expected = vec![
+        "+---------+",
+        "| column1 |",
+        "+---------+",
+        "| 3       |",
+        "| 4       |",
+        "+---------+",
+    ];
+    common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_csv_table_with_limit() -> anyhow::Result<()> {
+    use std::env;
+
+    use common_datavalues::*;
+    use common_planners::*;
+    use futures::TryStreamExt;
+
+    use crate::datasources::local::*;
+
+    let options: TableOptions = [(
+        "location".to_string(),
+        env::current_dir()?
+            .join("../../tests/data/sample.csv")
+            .display()
+            .to_string(),
+    )]
+    .iter()
+    .cloned()
+    .collect();
+
+    let ctx = crate::tests::try_create_context()?;
+    let table = CsvTable::try_create(
+        "default".into(),
+        "test_csv".into(),
+        DataSchemaRefExt::create(vec![DataField::new("column1", DataType::UInt64, false)]).into(),
+        options,
+    )?;
+
+    let scan_plan = &ScanPlan {
+        schema_name: "".to_string(),
+        table_schema: DataSchemaRefExt::create(vec![]),
+        table_args: None,
+        projection: None,
+        projected_schema: DataSchemaRefExt::create(vec![DataField::new(
+            "column1",
+            DataType::UInt64,
+            false,
+        )]),
+        filters: vec![],
+        limit: Some(4),
+    };
+    let source_plan = table.read_plan(ctx.clone(), &scan_plan, ctx.get_max_threads()? as usize)?;
+    ctx.try_set_partitions(source_plan.partitions.clone())?;
+
+    let stream = table.read(ctx, &source_plan).await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let rows: usize = result.iter().map(|block| block.num_rows()).sum();
+    assert_eq!(rows, 4);
+
+    Ok(())
+}
@@ -8,11 +8,13 @@ use std::sync::Arc;
 use common_datablocks::DataBlock;
 use common_datavalues::DataSchemaRef;
 use common_exception::Result;
+use common_infallible::RwLock;
 use common_planners::Partition;
 use common_planners::ReadDataSourcePlan;
 use common_planners::ScanPlan;
 use common_planners::Statistics;
 use common_planners::TableOptions;
+use common_planners::TableStatistics;
 use common_streams::DataBlockStream;
 use common_streams::SendableDataBlockStream;
 
@@ -23,6 +25,7 @@ pub struct NullTable {
     db: String,
     name: String,
     schema: DataSchemaRef,
+    statistics: RwLock<Option<TableStatistics>>,
 }
 
 impl NullTable {
@@ -32,7 +35,12 @@ impl NullTable {
         schema: DataSchemaRef,
         _options: TableOptions,
     ) -> Result<Box<dyn Table>> {
-        let table = Self { db, name, schema };
+        let table = Self {
+            db,
+            name,
+            schema,
+            statistics: RwLock::new(None),
+        };
         Ok(Box::new(table))
     }
 }
@@ -59,6 +67,20 @@ impl Table for NullTable {
         true
     }
 
+    // The table meta of the local tables is in memory, so are the statistics.
+    fn statistics(&self, _ctx: FuseQueryContextRef) -> Result<Option<TableStatistics>> {
+        Ok(self.statistics.read().clone())
+    }
+
+    fn set_statistics(
+        &self,
+        _ctx: FuseQueryContextRef,
+        statistics: Option<TableStatistics>,
+    ) -> Result<()> {
+        *self.statistics.write() = statistics;
+        Ok(())
+    }
+
     fn read_plan(
         &self,
         _ctx: FuseQueryContextRef,
@@ -80,7 +102,11 @@ impl Table for NullTable {
         })
     }
 
-    async fn read(&self, _ctx: FuseQueryContextRef) -> Result<SendableDataBlockStream> {
+    async fn read(
+        &self,
+        _ctx: FuseQueryContextRef,
+        _source_plan: &ReadDataSourcePlan,
+    ) -> Result<SendableDataBlockStream> {
         let block = DataBlock::empty_with_schema(self.schema.clone());
 
         Ok(Box::pin(DataBlockStream::create(
@@ -9,21 +9,29 @@ use std::sync::Arc;
 
 use common_arrow::parquet::arrow::ArrowReader;
 use common_arrow::parquet::arrow::ParquetFileArrowReader;
+use common_arrow::parquet::file::metadata::RowGroupMetaData;
 use common_arrow::parquet::file::reader::SerializedFileReader;
+use common_arrow::parquet::file::statistics::Statistics as ParquetStatistics;
 use common_datablocks::DataBlock;
 use common_datavalues::DataSchemaRef;
+use common_datavalues::DataType;
+use common_datavalues::DataValue;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_infallible::RwLock;
+use common_planners::ColumnRange;
+use common_planners::Expression;
 use common_planners::Partition;
 use common_planners::ReadDataSourcePlan;
 use common_planners::ScanPlan;
 use common_planners::Statistics;
 use common_planners::TableOptions;
+use common_planners::TableStatistics;
 use common_streams::ParquetStream;
 use common_streams::SendableDataBlockStream;
-use crossbeam::channel::bounded;
-use crossbeam::channel::Receiver;
-use crossbeam::channel::Sender;
+use tokio::sync::mpsc::channel;
+use tokio::sync::mpsc::Receiver;
+use tokio::sync::mpsc::Sender;
 use tokio::task;
 
 use crate::datasources::Table;
@@ -34,6 +42,7 @@ pub struct ParquetTable {
     name: String,
     schema: DataSchemaRef,
     file: String,
+    statistics: RwLock<Option<TableStatistics>>,
 }
 
 impl ParquetTable {
@@ -51,6 +60,7 @@ impl ParquetTable {
                     name,
                     schema,
                     file: file.trim_matches(|s| s == '\'' || s == '"').to_string(),
+                    statistics: RwLock::new(None),
                 };
                 Ok(Box::new(table))
             }
@@ -61,26 +71,125 @@ impl ParquetTable {
     }
 }
 
+// The min/max of the column chunk from the row group statistics.
+fn column_min_max(
+    row_group: &RowGroupMetaData,
+    name: &str,
+    data_type: &DataType,
+) -> Option<(DataValue, DataValue)> {
+    let column = row_group
+        .columns()
+        .iter()
+        .find(|column| column.column_descr().name() == name)?;
+    let statistics = column.statistics()?;
+    if !statistics.has_min_max_set() {
+        return None;
+    }
+
+    match (statistics, data_type) {
+        (ParquetStatistics::Int32(s), DataType::Date32) => Some((
+            DataValue::Date32(Some(*s.min())),
+            DataValue::Date32(Some(*s.max())),
+        )),
+        (ParquetStatistics::Int64(s), DataType::Date64) => Some((
+            DataValue::Date64(Some(*s.min())),
+            DataValue::Date64(Some(*s.max())),
+        )),
+        (ParquetStatistics::Int32(s), _) => Some((
+            DataValue::Int32(Some(*s.min())),
+            DataValue::Int32(Some(*s.max())),
+        )),
+        (ParquetStatistics::Int64(s), _) => Some((
+            DataValue::Int64(Some(*s.min())),
+            DataValue::Int64(Some(*s.max())),
+        )),
+        (ParquetStatistics::Float(s), _) => Some((
+            DataValue::Float32(Some(*s.min())),
+            DataValue::Float32(Some(*s.max())),
+        )),
+        (ParquetStatistics::Double(s), _) => Some((
+            DataValue::Float64(Some(*s.min())),
+            DataValue::Float64(Some(*s.max())),
+        )),
+        (ParquetStatistics::ByteArray(s), DataType::Utf8) => Some((
+            DataValue::Binary(Some(s.min().data().to_vec())),
+            DataValue::Binary(Some(s.max().data().to_vec())),
+        )),
+        _ => None,
+    }
+}
+
 fn read_file(
     file: &str,
     tx: Sender<Option<Result<DataBlock>>>,
     projection: &[usize],
+    schema: DataSchemaRef,
+    filters: &[Expression],
+    limit: Option<usize>,
 ) -> Result<()> {
     let file_reader = File::open(file).map_err(|e| ErrorCode::CannotReadFile(e.to_string()))?;
-    let file_reader = SerializedFileReader::new(file_reader)
+    let mut file_reader = SerializedFileReader::new(file_reader)
         .map_err(|e| ErrorCode::ParquetError(e.to_string()))?;
+
+    // Skip the row groups whose min/max statistics can't match the push down filters.
+    // Only the signed integers, floats, dates and strings sort the same way in the statistics.
+    let ranges: Vec<_> = schema
+        .fields()
+        .iter()
+        .filter(|field| {
+            matches!(
+                field.data_type(),
+                DataType::Int8
+                    | DataType::Int16
+                    | DataType::Int32
+                    | DataType::Int64
+                    | DataType::Float32
+                    | DataType::Float64
+                    | DataType::Date32
+                    | DataType::Date64
+                    | DataType::Utf8
+            )
+        })
+        .map(|field| {
+            let range = ColumnRange::from_filters(filters, field.name(), field.data_type());
+            (field.name().clone(), field.data_type().clone(), range)
+        })
+        .filter(|(_, _, range)| range.min.is_some() || range.max.is_some())
+        .collect();
+    if !ranges.is_empty() {
+        file_reader.filter_row_groups(&|row_group: &RowGroupMetaData, _: usize| {
+            ranges.iter().all(|(name, data_type, range)| {
+                match column_min_max(row_group, name, data_type) {
+                    Some((min, max)) => range.may_overlap(&min, &max),
+                    None => !range.is_empty(),
+                }
+            })
+        });
+    }
+
     let mut arrow_reader = ParquetFileArrowReader::new(Arc::new(file_reader));
 
     // TODO projection, row filters, batch size configurable, schema judgement
-    let batch_size = 2048;
+    let batch_size = limit.map_or(2048, |limit| limit.min(2048).max(1));
+    let mut remaining = limit;
     let mut batch_reader = arrow_reader
         .get_record_reader_by_columns(projection.to_owned(), batch_size)
         .map_err(|exception| ErrorCode::ParquetError(exception.to_string()))?;
 
     loop {
+        if remaining == Some(0) {
+            break;
+        }
+
         match batch_reader.next() {
             Some(Ok(batch)) => {
-                tx.send(Some(Ok(batch.try_into()?)))
+                let mut block: DataBlock = batch.try_into()?;
+                if let Some(remaining) = remaining.as_mut() {
+                    let rows = std::cmp::min(*remaining, block.num_rows());
+                    block = block.slice(0, rows);
+                    *remaining -= rows;
+                }
+                tx.blocking_send(Some(Ok(block)))
                     .map_err(|e| ErrorCode::UnknownException(e.to_string()))?;
             }
             None => {
@@ -89,7 +198,7 @@ fn read_file(
             Some(Err(e)) => {
                 let err_msg = format!("Error reading batch from {:?}: {}", file, e.to_string());
 
-                tx.send(Some(Result::Err(ErrorCode::CannotReadFile(
+                tx.blocking_send(Some(Result::Err(ErrorCode::CannotReadFile(
                     err_msg.clone(),
                 ))))
                 .map_err(|send_error| ErrorCode::UnknownException(send_error.to_string()))?;
@@ -123,6 +232,20 @@ impl Table for ParquetTable {
         true
     }
 
+    // The table meta of the local tables is in memory, so are the statistics.
+    fn statistics(&self, _ctx: FuseQueryContextRef) -> Result<Option<TableStatistics>> {
+        Ok(self.statistics.read().clone())
+    }
+
+    fn set_statistics(
+        &self,
+        _ctx: FuseQueryContextRef,
+        statistics: Option<TableStatistics>,
+    ) -> Result<()> {
+        *self.statistics.write() = statistics;
+        Ok(())
+    }
+
     fn read_plan(
         &self,
         _ctx: FuseQueryContextRef,
@@ -147,16 +270,23 @@ impl Table for ParquetTable {
         })
     }
 
-    async fn read(&self, _ctx: FuseQueryContextRef) -> Result<SendableDataBlockStream> {
+    async fn read(
+        &self,
+        _ctx: FuseQueryContextRef,
+        source_plan: &ReadDataSourcePlan,
+    ) -> Result<SendableDataBlockStream> {
         type BlockSender = Sender<Option<Result<DataBlock>>>;
         type BlockReceiver = Receiver<Option<Result<DataBlock>>>;
 
-        let (response_tx, response_rx): (BlockSender, BlockReceiver) = bounded(2);
+        let (response_tx, response_rx): (BlockSender, BlockReceiver) = channel(2);
 
         let file = self.file.clone();
         let projection: Vec<usize> = (0..self.schema.fields().len()).collect();
+        let schema = self.schema.clone();
+        let filters = source_plan.scan_plan.filters.clone();
+        let limit = source_plan.scan_plan.limit;
         task::spawn_blocking(move || {
-            if let Err(e) = read_file(&file, response_tx, &projection) {
+            if let Err(e) = read_file(&file, response_tx, &projection, schema, &filters, limit) {
                 println!("Parquet reader thread terminated due to error: {:?}", e);
             }
         });
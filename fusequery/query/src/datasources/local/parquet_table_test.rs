@@ -30,16 +30,119 @@ async fn test_parquet_table() -> anyhow::Result<()> {
         DataSchemaRefExt::create(vec![DataField::new("id", DataType::Int32, false)]).clone(),
         options,
     )?;
-    table.read_plan(
+    let source_plan = table.read_plan(
         ctx.clone(),
         &ScanPlan::empty(),
         ctx.get_max_threads()? as usize,
     )?;
 
-    let stream = table.read(ctx).await?;
+    let stream = table.read(ctx, &source_plan).await?;
     let blocks = stream.try_collect::<Vec<_>>().await?;
     let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
 
     assert_eq!(rows, 8);
     Ok(())
 }
+
+#[tokio::test]
+async fn test_parquet_table_with_filters() -> anyhow::Result<()> {
+    use std::env;
+    use std::sync::Arc;
+
+    use common_datavalues::*;
+    use common_planners::*;
+    use futures::TryStreamExt;
+
+    use crate::datasources::local::*;
+
+    let options: TableOptions = [(
+        "location".to_string(),
+        env::current_dir()?
+            .join("../../tests/data/alltypes_plain.parquet")
+            .display()
+            .to_string(),
+    )]
+    .iter()
+    .cloned()
+    .collect();
+
+    let ctx = crate::tests::try_create_context()?;
+    let table = ParquetTable::try_create(
+        "default".into(),
+        "test_parquet".into(),
+        DataSchemaRefExt::create(vec![DataField::new("id", DataType::Int32, false)]).clone(),
+        options,
+    )?;
+
+    // The file has no statistics, the row groups are only skipped when the filters can't match anything.
+    for (filter, expect_rows) in vec![
+        (col("id").gt(lit(3i32)), 8),
+        (col("id").gt(lit(100i32)), 8),
+        (col("id").gt(lit(10i32)).and(col("id").lt(lit(5i32))), 0),
+    ] {
+        let mut source_plan = table.read_plan(
+            ctx.clone(),
+            &ScanPlan::empty(),
+            ctx.get_max_threads()? as usize,
+        )?;
+        source_plan.scan_plan = Arc::new(ScanPlan {
+            filters: vec![filter],
+            ..ScanPlan::empty()
+        });
+
+        let stream = table.read(ctx.clone(), &source_plan).await?;
+        let blocks = stream.try_collect::<Vec<_>>().await?;
+        let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+        assert_eq!(rows, expect_rows);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_parquet_table_with_limit() -> anyhow::Result<()> {
+    use std::env;
+    use std::sync::Arc;
+
+    use common_datavalues::*;
+    use common_planners::*;
+    use futures::TryStreamExt;
+
+    use crate::datasources::local::*;
+
+    let options: TableOptions = [(
+        "location".to_string(),
+        env::current_dir()?
+            .join("../../tests/data/alltypes_plain.parquet")
+            .display()
+            .to_string(),
+    )]
+    .iter()
+    .cloned()
+    .collect();
+
+    let ctx = crate::tests::try_create_context()?;
+    let table = ParquetTable::try_create(
+        "default".into(),
+        "test_parquet".into(),
+        DataSchemaRefExt::create(vec![DataField::new("id", DataType::Int32, false)]).clone(),
+        options,
+    )?;
+
+    let mut source_plan = table.read_plan(
+        ctx.clone(),
+        &ScanPlan::empty(),
+        ctx.get_max_threads()? as usize,
+    )?;
+    source_plan.scan_plan = Arc::new(ScanPlan {
+        limit: Some(3),
+        ..ScanPlan::empty()
+    });
+
+    let stream = table.read(ctx, &source_plan).await?;
+    let blocks = stream.try_collect::<Vec<_>>().await?;
+    let rows: usize = blocks.iter().map(|block| block.num_rows()).sum();
+    assert_eq!(rows, 3);
+
+    Ok(())
+}
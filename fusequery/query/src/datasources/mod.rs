@@ -5,13 +5,19 @@
 #[cfg(test)]
 mod common_test;
 #[cfg(test)]
+mod hyper_log_log_test;
+#[cfg(test)]
+mod statistics_collector_test;
+#[cfg(test)]
 mod tests;
 
 mod common;
 mod database;
 mod datasource;
+mod hyper_log_log;
 mod local;
 mod remote;
+mod statistics_collector;
 mod system;
 mod table;
 mod table_function;
@@ -19,5 +25,7 @@ mod table_function;
 pub use common::Common;
 pub use database::Database;
 pub use datasource::DataSource;
+pub use hyper_log_log::HyperLogLog;
+pub use statistics_collector::StatisticsCollector;
 pub use table::Table;
 pub use table_function::TableFunction;
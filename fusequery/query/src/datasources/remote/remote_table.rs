@@ -10,12 +10,14 @@ use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_flights::ScanPartitionResult;
+use common_planners::AggregatorPartialPlan;
 use common_planners::InsertIntoPlan;
 use common_planners::Partition;
 use common_planners::ReadDataSourcePlan;
 use common_planners::ScanPlan;
 use common_planners::Statistics;
 use common_planners::TableOptions;
+use common_planners::TableStatistics;
 use common_streams::SendableDataBlockStream;
 
 use crate::datasources::remote::StoreClientProvider;
@@ -28,6 +30,7 @@ pub struct RemoteTable {
     pub(crate) name: String,
     pub(crate) schema: DataSchemaRef,
     pub(crate) store_client_provider: StoreClientProvider,
+    statistics: RwLock<Option<TableStatistics>>,
 }
 
 impl RemoteTable {
@@ -70,6 +73,50 @@ impl Table for RemoteTable {
         false
     }
 
+    // The statistics are stored with the table meta of the store, which drops them on append.
+    fn statistics(&self, ctx: FuseQueryContextRef) -> Result<Option<TableStatistics>> {
+        let (tx, rx) = channel();
+        let cli_provider = self.store_client_provider.clone();
+        let db_name = self.db.clone();
+        let tbl_name = self.name.clone();
+        ctx.execute_task(async move {
+            let statistics = match cli_provider.try_get_client().await {
+                Ok(mut client) => client
+                    .get_table_statistics(db_name, tbl_name)
+                    .await
+                    .map_err(ErrorCode::from),
+                Err(e) => Err(e),
+            };
+            let _ = tx.send(statistics);
+        });
+
+        rx.recv().map_err(ErrorCode::from_std_error)?
+    }
+
+    fn set_statistics(
+        &self,
+        ctx: FuseQueryContextRef,
+        statistics: Option<TableStatistics>,
+    ) -> Result<()> {
+        let (tx, rx) = channel();
+        let cli_provider = self.store_client_provider.clone();
+        let db_name = self.db.clone();
+        let tbl_name = self.name.clone();
+        ctx.execute_task(async move {
+            let res = match cli_provider.try_get_client().await {
+                Ok(mut client) => client
+                    .set_table_statistics(db_name, tbl_name, statistics)
+                    .await
+                    .map(|_| ())
+                    .map_err(ErrorCode::from),
+                Err(e) => Err(e),
+            };
+            let _ = tx.send(res);
+        });
+
+        rx.recv().map_err(ErrorCode::from_std_error)?
+    }
+
     fn read_plan(
         &self,
         ctx: FuseQueryContextRef,
@@ -104,8 +151,21 @@ impl Table for RemoteTable {
             .map(|v| self.partitions_to_plan(v, scan.clone()))
     }
 
-    async fn read(&self, ctx: FuseQueryContextRef) -> Result<SendableDataBlockStream> {
-        self.do_read(ctx).await
+    async fn read(
+        &self,
+        ctx: FuseQueryContextRef,
+        source_plan: &ReadDataSourcePlan,
+    ) -> Result<SendableDataBlockStream> {
+        self.do_read(ctx, source_plan, None).await
+    }
+
+    async fn read_partial_aggregated(
+        &self,
+        ctx: FuseQueryContextRef,
+        source_plan: &ReadDataSourcePlan,
+        aggregation: &AggregatorPartialPlan,
+    ) -> Result<SendableDataBlockStream> {
+        self.do_read(ctx, source_plan, Some(aggregation)).await
     }
 
     async fn append_data(&self, _ctx: FuseQueryContextRef, plan: InsertIntoPlan) -> Result<()> {
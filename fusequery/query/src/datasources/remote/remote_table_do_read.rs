@@ -2,9 +2,12 @@
 //
 // SPDX-License-Identifier: Apache-2.0.
 
+use std::sync::Arc;
+
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_flights::ReadAction;
+use common_planners::AggregatorPartialPlan;
 use common_planners::PlanNode;
 use common_planners::ReadDataSourcePlan;
 use common_streams::ProgressStream;
@@ -19,29 +22,46 @@ impl RemoteTable {
     pub(super) async fn do_read(
         &self,
         ctx: FuseQueryContextRef,
+        source_plan: &ReadDataSourcePlan,
+        aggregation: Option<&AggregatorPartialPlan>,
     ) -> Result<SendableDataBlockStream> {
         let client = self.store_client_provider.try_get_client().await?;
-        let schema = self.schema.clone();
+        let schema = source_plan.schema.clone();
         let db = self.db.to_string();
         let tbl = self.name.to_string();
+        let scan_plan = source_plan.scan_plan.clone();
         let progress_callback = ctx.progress_callback();
 
+        let read_source = PlanNode::ReadSource(ReadDataSourcePlan {
+            db,
+            table: tbl,
+            schema,
+            scan_plan,
+            remote: true,
+            ..ReadDataSourcePlan::empty()
+        });
+        // The store computes the partial aggregation states of each part next to the data.
+        let push_down = match aggregation {
+            None => read_source,
+            Some(aggregation) => PlanNode::AggregatorPartial(AggregatorPartialPlan {
+                input: Arc::new(read_source),
+                ..aggregation.clone()
+            }),
+        };
+
         let iter = std::iter::from_fn(move || match ctx.try_get_partitions(1) {
             Err(_) => None,
             Ok(parts) if parts.is_empty() => None,
             Ok(parts) => Some(ReadAction {
                 partition: parts[0].clone(),
-                push_down: PlanNode::ReadSource(ReadDataSourcePlan {
-                    db: db.clone(),
-                    table: tbl.clone(),
-                    schema: schema.clone(),
-                    remote: true,
-                    ..ReadDataSourcePlan::empty()
-                }),
+                push_down: push_down.clone(),
             }),
         });
 
-        let schema = self.schema.clone();
+        let schema = match aggregation {
+            None => source_plan.schema.clone(),
+            Some(aggregation) => aggregation.schema(),
+        };
         let parts = futures::stream::iter(iter);
         let streams = parts.then(move |parts| {
             let mut client = client.clone();
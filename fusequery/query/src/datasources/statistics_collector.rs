@@ -0,0 +1,269 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::Hasher;
+
+use common_arrow::arrow::array::Array;
+use common_datablocks::DataBlock;
+use common_datavalues::DataArrayHashDispatcher;
+use common_datavalues::DataColumnarValue;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataValue;
+use common_datavalues::FuseDataHasher;
+use common_datavalues::UInt64Array;
+use common_exception::Result;
+use common_planners::compare_values;
+use common_planners::ColumnStatistics;
+use common_planners::TableStatistics;
+use rand::Rng;
+
+use crate::datasources::HyperLogLog;
+
+// The histogram is built from a uniform sample of the values of each column.
+const SAMPLE_SIZE: usize = 10000;
+const HISTOGRAM_BUCKETS: usize = 16;
+
+/// Collects the table statistics from the blocks of a full table scan, for ANALYZE TABLE.
+pub struct StatisticsCollector {
+    schema: DataSchemaRef,
+    rows: usize,
+    bytes: usize,
+    columns: Vec<ColumnCollector>,
+}
+
+struct ColumnCollector {
+    min: Option<DataValue>,
+    max: Option<DataValue>,
+    // Min and max are dropped once a value is not comparable with them.
+    comparable: bool,
+    null_count: usize,
+    // The distinct count is estimated from the hashes of the native values.
+    distinct: HyperLogLog,
+    // Reservoir sample of the non-null values.
+    sample: Vec<DataValue>,
+    values: usize,
+}
+
+impl StatisticsCollector {
+    pub fn create(schema: DataSchemaRef) -> Self {
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|_| ColumnCollector {
+                min: None,
+                max: None,
+                comparable: true,
+                null_count: 0,
+                distinct: HyperLogLog::create(),
+                sample: vec![],
+                values: 0,
+            })
+            .collect();
+
+        StatisticsCollector {
+            schema,
+            rows: 0,
+            bytes: 0,
+            columns,
+        }
+    }
+
+    pub fn append(&mut self, block: &DataBlock) -> Result<()> {
+        self.rows += block.num_rows();
+        self.bytes += block.memory_size();
+
+        let mut rng = rand::thread_rng();
+        for (index, field) in self.schema.fields().iter().enumerate() {
+            let array = block.try_array_by_name(field.name())?;
+            let column = &mut self.columns[index];
+
+            // The types the dispatcher can't hash as an array, such as boolean, are hashed by value.
+            let hashes = DataArrayHashDispatcher::<NativeHasher>::dispatch(
+                &DataColumnarValue::Array(array.clone()),
+            )
+            .and_then(|hashes| hashes.to_array())
+            .ok();
+            let hashes = hashes
+                .as_ref()
+                .and_then(|hashes| hashes.as_any().downcast_ref::<UInt64Array>());
+
+            for row in 0..array.len() {
+                let value = DataValue::try_from_array(&array, row)?;
+                let hash = match hashes {
+                    Some(hashes) => hashes.value(row),
+                    None => hash_value(&value),
+                };
+                column.append(value, hash, &mut rng);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn finalize(self) -> TableStatistics {
+        let columns = self
+            .schema
+            .fields()
+            .iter()
+            .zip(self.columns.into_iter())
+            .map(|(field, column)| (field.name().clone(), column.finalize()))
+            .collect::<HashMap<_, _>>();
+
+        TableStatistics {
+            rows: self.rows,
+            bytes: self.bytes,
+            columns,
+        }
+    }
+}
+
+impl ColumnCollector {
+    fn append(&mut self, value: DataValue, hash: u64, rng: &mut impl Rng) {
+        if value.is_null() || matches!(value, DataValue::Null) {
+            self.null_count += 1;
+            return;
+        }
+
+        if self.comparable {
+            self.update_min_max(&value);
+        }
+
+        self.distinct.add_hash(hash);
+
+        self.values += 1;
+        if self.sample.len() < SAMPLE_SIZE {
+            self.sample.push(value);
+        } else {
+            let index = rng.gen_range(0..self.values);
+            if index < SAMPLE_SIZE {
+                self.sample[index] = value;
+            }
+        }
+    }
+
+    fn update_min_max(&mut self, value: &DataValue) {
+        let (min, max) = match (&self.min, &self.max) {
+            (Some(min), Some(max)) => (compare_values(value, min), compare_values(value, max)),
+            _ => {
+                self.min = Some(value.clone());
+                self.max = Some(value.clone());
+                return;
+            }
+        };
+
+        match (min, max) {
+            (Some(min), Some(max)) => {
+                if min == Ordering::Less {
+                    self.min = Some(value.clone());
+                }
+                if max == Ordering::Greater {
+                    self.max = Some(value.clone());
+                }
+            }
+            _ => {
+                self.comparable = false;
+                self.min = None;
+                self.max = None;
+            }
+        }
+    }
+
+    fn finalize(mut self) -> ColumnStatistics {
+        let mut histogram = vec![];
+        if self.comparable && !self.sample.is_empty() {
+            self.sample
+                .sort_by(|a, b| compare_values(a, b).unwrap_or(Ordering::Equal));
+
+            // The upper bound of each bucket is the last value of it.
+            let buckets = std::cmp::min(HISTOGRAM_BUCKETS, self.sample.len());
+            for bucket in 1..=buckets {
+                let index = bucket * self.sample.len() / buckets - 1;
+                histogram.push(self.sample[index].clone());
+            }
+        }
+
+        ColumnStatistics {
+            min: self.min,
+            max: self.max,
+            null_count: self.null_count,
+            distinct_count: self.distinct.count(),
+            histogram,
+        }
+    }
+}
+
+// The hash of a single value, the nested types without a native hash are hashed by display.
+fn hash_value(value: &DataValue) -> u64 {
+    let hash = DataArrayHashDispatcher::<NativeHasher>::dispatch(&DataColumnarValue::Constant(
+        value.clone(),
+        1,
+    ));
+    match hash {
+        Ok(DataColumnarValue::Constant(DataValue::UInt64(Some(hash)), _)) => hash,
+        _ => NativeHasher::hash_bytes(value.to_string().as_bytes()),
+    }
+}
+
+// SipHash of the native values, the HyperLogLog needs the uniformly distributed hashes.
+struct NativeHasher;
+
+impl NativeHasher {
+    fn hash<F: FnOnce(&mut DefaultHasher)>(write: F) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        write(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl FuseDataHasher for NativeHasher {
+    fn hash_bool(v: &bool) -> u64 {
+        Self::hash(|h| h.write_u8(*v as u8))
+    }
+
+    fn hash_i8(v: &i8) -> u64 {
+        Self::hash(|h| h.write_i8(*v))
+    }
+
+    fn hash_i16(v: &i16) -> u64 {
+        Self::hash(|h| h.write_i16(*v))
+    }
+
+    fn hash_i32(v: &i32) -> u64 {
+        Self::hash(|h| h.write_i32(*v))
+    }
+
+    fn hash_i64(v: &i64) -> u64 {
+        Self::hash(|h| h.write_i64(*v))
+    }
+
+    fn hash_u8(v: &u8) -> u64 {
+        Self::hash(|h| h.write_u8(*v))
+    }
+
+    fn hash_u16(v: &u16) -> u64 {
+        Self::hash(|h| h.write_u16(*v))
+    }
+
+    fn hash_u32(v: &u32) -> u64 {
+        Self::hash(|h| h.write_u32(*v))
+    }
+
+    fn hash_u64(v: &u64) -> u64 {
+        Self::hash(|h| h.write_u64(*v))
+    }
+
+    fn hash_f32(v: &f32) -> u64 {
+        Self::hash(|h| h.write_u32(v.to_bits()))
+    }
+
+    fn hash_f64(v: &f64) -> u64 {
+        Self::hash(|h| h.write_u64(v.to_bits()))
+    }
+
+    fn hash_bytes(bytes: &[u8]) -> u64 {
+        Self::hash(|h| h.write(bytes))
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::sync::Arc;
+
+use common_arrow::arrow::array::*;
+use common_datablocks::DataBlock;
+use common_datavalues::*;
+use common_exception::Result;
+use common_planners::ColumnStatistics;
+use pretty_assertions::assert_eq;
+
+use crate::datasources::StatisticsCollector;
+
+#[test]
+fn test_statistics_collector() -> Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int64, true),
+        DataField::new("b", DataType::Utf8, false),
+    ]);
+
+    let mut collector = StatisticsCollector::create(schema.clone());
+    collector.append(&DataBlock::create_by_array(schema.clone(), vec![
+        Arc::new(Int64Array::from(vec![Some(3), None, Some(1)])),
+        Arc::new(StringArray::from(vec!["x", "y", "x"])),
+    ]))?;
+    collector.append(&DataBlock::create_by_array(schema.clone(), vec![
+        Arc::new(Int64Array::from(vec![Some(3), Some(2)])),
+        Arc::new(StringArray::from(vec!["z", "x"])),
+    ]))?;
+
+    let stats = collector.finalize();
+    assert_eq!(5, stats.rows);
+    assert!(stats.bytes > 0);
+
+    let int64 = |v: i64| DataValue::Int64(Some(v));
+    assert_eq!(
+        Some(&ColumnStatistics {
+            min: Some(int64(1)),
+            max: Some(int64(3)),
+            null_count: 1,
+            distinct_count: 3,
+            histogram: vec![int64(1), int64(2), int64(3), int64(3)],
+        }),
+        stats.columns.get("a")
+    );
+
+    let utf8 = |v: &str| DataValue::Utf8(Some(v.to_string()));
+    assert_eq!(
+        Some(&ColumnStatistics {
+            min: Some(utf8("x")),
+            max: Some(utf8("z")),
+            null_count: 0,
+            distinct_count: 3,
+            histogram: vec![utf8("x"), utf8("x"), utf8("x"), utf8("y"), utf8("z")],
+        }),
+        stats.columns.get("b")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_statistics_collector_distinct_count() -> Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::Int64, false),
+        DataField::new("b", DataType::Boolean, false),
+    ]);
+
+    // The distinct count of the many values is estimated.
+    let mut collector = StatisticsCollector::create(schema.clone());
+    for block in 0..10 {
+        collector.append(&DataBlock::create_by_array(schema.clone(), vec![
+            Arc::new(Int64Array::from(
+                (0..10000).map(|v| block * 10000 + v).collect::<Vec<i64>>(),
+            )),
+            Arc::new(BooleanArray::from(
+                (0..10000).map(|v| v % 2 == 0).collect::<Vec<bool>>(),
+            )),
+        ]))?;
+    }
+
+    let stats = collector.finalize();
+    assert_eq!(100000, stats.rows);
+
+    let a = stats.columns.get("a").unwrap().distinct_count as f64;
+    assert!(
+        (a - 100000.0).abs() / 100000.0 < 0.03,
+        "distinct count: {}",
+        a
+    );
+    assert_eq!(2, stats.columns.get("b").unwrap().distinct_count);
+
+    Ok(())
+}
@@ -12,13 +12,13 @@ async fn test_tables_table() -> anyhow::Result<()> {
 
     let ctx = crate::tests::try_create_context()?;
     let table = DatabasesTable::create();
-    table.read_plan(
+    let source_plan = table.read_plan(
         ctx.clone(),
         &ScanPlan::empty(),
         ctx.get_max_threads()? as usize,
     )?;
 
-    let stream = table.read(ctx).await?;
+    let stream = table.read(ctx, &source_plan).await?;
     let result = stream.try_collect::<Vec<_>>().await?;
     let block = &result[0];
     assert_eq!(block.num_columns(), 1);
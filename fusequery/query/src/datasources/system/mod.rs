@@ -13,6 +13,10 @@ mod functions_table_test;
 #[cfg(test)]
 mod numbers_table_test;
 #[cfg(test)]
+mod processes_table_test;
+#[cfg(test)]
+mod query_cache_table_test;
+#[cfg(test)]
 mod settings_table_test;
 #[cfg(test)]
 mod tables_table_test;
@@ -24,6 +28,8 @@ mod functions_table;
 mod numbers_stream;
 mod numbers_table;
 mod one_table;
+mod processes_table;
+mod query_cache_table;
 mod settings_table;
 mod system_database;
 mod system_factory;
@@ -36,6 +42,8 @@ pub use functions_table::FunctionsTable;
 pub use numbers_stream::NumbersStream;
 pub use numbers_table::NumbersTable;
 pub use one_table::OneTable;
+pub use processes_table::ProcessesTable;
+pub use query_cache_table::QueryCacheTable;
 pub use settings_table::SettingsTable;
 pub use system_database::SystemDatabase;
 pub use system_factory::SystemFactory;
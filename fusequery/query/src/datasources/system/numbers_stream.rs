@@ -15,8 +15,11 @@ use common_arrow::arrow::buffer::Buffer;
 use common_arrow::arrow::datatypes::DataType;
 use common_datablocks::DataBlock;
 use common_datavalues::DataSchemaRef;
+use common_datavalues::DataValue;
 use common_datavalues::UInt64Array;
 use common_exception::Result;
+use common_planners::ColumnRange;
+use common_planners::Expression;
 use common_streams::ProgressStream;
 use futures::stream::Stream;
 
@@ -33,21 +36,78 @@ pub struct NumbersStream {
     schema: DataSchemaRef,
     block_index: usize,
     blocks: Vec<BlockRange>,
+    // The numbers in [min, max) can match the push down filters.
+    min: u64,
+    max: u64,
+    // The rows left to read of the push down limit.
+    remaining: Option<u64>,
 }
 
 impl NumbersStream {
-    pub fn try_create(ctx: FuseQueryContextRef, schema: DataSchemaRef) -> Result<ProgressStream> {
+    pub fn try_create(
+        ctx: FuseQueryContextRef,
+        schema: DataSchemaRef,
+        filters: &[Expression],
+        limit: Option<usize>,
+    ) -> Result<ProgressStream> {
+        let (min, max) = Self::filters_bounds(filters);
         let stream = Box::pin(NumbersStream {
             ctx: ctx.clone(),
             schema,
             block_index: 0,
             blocks: vec![],
+            min,
+            max,
+            remaining: limit.map(|v| v as u64),
         });
         ProgressStream::try_create(stream, ctx.progress_callback()?)
     }
 
+    fn filters_bounds(filters: &[Expression]) -> (u64, u64) {
+        let range = ColumnRange::from_filters(filters, "number", &DataType::UInt64);
+        if range.is_empty() {
+            return (0, 0);
+        }
+
+        let to_i128 = |value: &DataValue, round: fn(f64) -> f64| -> Option<i128> {
+            let float = |v: f64| Some(round(v)).filter(|v| v.is_finite()).map(|v| v as i128);
+            match value {
+                DataValue::Int8(Some(v)) => Some(*v as i128),
+                DataValue::Int16(Some(v)) => Some(*v as i128),
+                DataValue::Int32(Some(v)) => Some(*v as i128),
+                DataValue::Int64(Some(v)) => Some(*v as i128),
+                DataValue::UInt8(Some(v)) => Some(*v as i128),
+                DataValue::UInt16(Some(v)) => Some(*v as i128),
+                DataValue::UInt32(Some(v)) => Some(*v as i128),
+                DataValue::UInt64(Some(v)) => Some(*v as i128),
+                DataValue::Float32(Some(v)) => float(*v as f64),
+                DataValue::Float64(Some(v)) => float(*v),
+                _ => None,
+            }
+        };
+        let min = range
+            .min
+            .as_ref()
+            .and_then(|v| to_i128(v, f64::ceil))
+            .unwrap_or(0);
+        let max = range
+            .max
+            .as_ref()
+            .and_then(|v| to_i128(v, f64::floor))
+            .map(|v| v + 1)
+            .unwrap_or(u64::MAX as i128);
+
+        let clamp = |v: i128| v.max(0).min(u64::MAX as i128) as u64;
+        (clamp(min), clamp(max))
+    }
+
     fn try_get_one_block(&mut self) -> Result<Option<DataBlock>> {
-        if (self.block_index as usize) == self.blocks.len() {
+        if self.remaining == Some(0) {
+            return Ok(None);
+        }
+
+        // The blocks out of the filters range are skipped, fetch the partitions until one block left.
+        while (self.block_index as usize) == self.blocks.len() {
             let partitions = self.ctx.try_get_partitions(1)?;
             if partitions.is_empty() {
                 return Ok(None);
@@ -58,8 +118,14 @@ impl NumbersStream {
 
             for part in partitions {
                 let names: Vec<_> = part.name.split('-').collect();
-                let begin: u64 = names[1].parse()?;
-                let end: u64 = names[2].parse()?;
+                let begin: u64 = names[1].parse::<u64>()?.max(self.min);
+                let mut end: u64 = names[2].parse::<u64>()?.min(self.max);
+                if let Some(remaining) = self.remaining {
+                    end = end.min(begin.saturating_add(remaining));
+                }
+                if begin >= end {
+                    continue;
+                }
 
                 let diff = end - begin;
                 let block_nums = diff / block_size;
@@ -85,8 +151,12 @@ impl NumbersStream {
             self.block_index = 0;
         }
 
-        let current = self.blocks[self.block_index].clone();
+        let mut current = self.blocks[self.block_index].clone();
         self.block_index += 1;
+        if let Some(remaining) = self.remaining.as_mut() {
+            current.end = current.end.min(current.begin + *remaining);
+            *remaining -= current.end - current.begin;
+        }
 
         Ok(if current.begin == current.end {
             None
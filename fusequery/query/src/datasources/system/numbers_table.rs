@@ -117,10 +117,16 @@ impl Table for NumbersTable {
         })
     }
 
-    async fn read(&self, ctx: FuseQueryContextRef) -> Result<SendableDataBlockStream> {
+    async fn read(
+        &self,
+        ctx: FuseQueryContextRef,
+        source_plan: &ReadDataSourcePlan,
+    ) -> Result<SendableDataBlockStream> {
         Ok(Box::pin(NumbersStream::try_create(
             ctx,
             self.schema.clone(),
+            &source_plan.scan_plan.filters,
+            source_plan.scan_plan.limit,
         )?))
     }
 }
@@ -28,9 +28,9 @@ async fn test_number_table() -> anyhow::Result<()> {
         limit: None,
     };
     let source_plan = table.read_plan(ctx.clone(), scan, ctx.get_max_threads()? as usize)?;
-    ctx.try_set_partitions(source_plan.partitions)?;
+    ctx.try_set_partitions(source_plan.partitions.clone())?;
 
-    let stream = table.read(ctx).await?;
+    let stream = table.read(ctx, &source_plan).await?;
     let result = stream.try_collect::<Vec<_>>().await?;
     let block = &result[0];
     assert_eq!(block.num_columns(), 1);
@@ -53,3 +53,106 @@ async fn test_number_table() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_number_table_with_filters() -> anyhow::Result<()> {
+    use common_datavalues::*;
+    use common_planners::*;
+    use futures::TryStreamExt;
+
+    use crate::datasources::system::*;
+    use crate::datasources::*;
+
+    let ctx = crate::tests::try_create_context()?;
+    let table = NumbersTable::create("numbers_mt");
+
+    let scan = &ScanPlan {
+        schema_name: "scan_test".to_string(),
+        table_schema: DataSchemaRefExt::create(vec![]),
+        table_args: Some(Expression::Literal(DataValue::UInt64(Some(100)))),
+        projection: None,
+        projected_schema: DataSchemaRefExt::create(vec![DataField::new(
+            "number",
+            DataType::UInt64,
+            false,
+        )]),
+        filters: vec![],
+        limit: None,
+    };
+    let mut source_plan = table.read_plan(ctx.clone(), scan, ctx.get_max_threads()? as usize)?;
+    source_plan.scan_plan = std::sync::Arc::new(ScanPlan {
+        filters: vec![
+            col("number").gt(lit(95u64)),
+            lit(98.5f64).gt_eq(col("number")),
+        ],
+        ..scan.clone()
+    });
+    ctx.try_set_partitions(source_plan.partitions.clone())?;
+
+    // Only the numbers in [95, 98] are read, the filter transform takes the rest.
+    let stream = table.read(ctx.clone(), &source_plan).await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let expected = vec![
+        "+--------+",
+        "| number |",
+        "+--------+",
+        "| 95     |",
+        "| 96     |",
+        "| 97     |",
+        "| 98     |",
+        "+--------+",
+    ];
+    common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+
+    // Nothing matches.
+    source_plan.scan_plan = std::sync::Arc::new(ScanPlan {
+        filters: vec![col("number").gt(lit(1000u64))],
+        ..scan.clone()
+    });
+    ctx.try_set_partitions(source_plan.partitions.clone())?;
+
+    let stream = table.read(ctx, &source_plan).await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    assert!(result.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_number_table_with_limit() -> anyhow::Result<()> {
+    use common_datavalues::*;
+    use common_planners::*;
+    use futures::TryStreamExt;
+
+    use crate::datasources::system::*;
+    use crate::datasources::*;
+
+    let ctx = crate::tests::try_create_context()?;
+    let table = NumbersTable::create("numbers_mt");
+
+    let scan = &ScanPlan {
+        schema_name: "scan_test".to_string(),
+        table_schema: DataSchemaRefExt::create(vec![]),
+        table_args: Some(Expression::Literal(DataValue::UInt64(Some(
+            1_000_000_000_000,
+        )))),
+        projection: None,
+        projected_schema: DataSchemaRefExt::create(vec![DataField::new(
+            "number",
+            DataType::UInt64,
+            false,
+        )]),
+        filters: vec![],
+        limit: Some(5),
+    };
+    let source_plan = table.read_plan(ctx.clone(), scan, ctx.get_max_threads()? as usize)?;
+    ctx.try_set_partitions(source_plan.partitions.clone())?;
+
+    // The stream stops after the limit, without generating the ranges of the whole table.
+    let stream = table.read(ctx, &source_plan).await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let rows: usize = result.iter().map(|block| block.num_rows()).sum();
+    assert_eq!(5, rows);
+
+    Ok(())
+}
@@ -77,7 +77,11 @@ impl Table for OneTable {
         })
     }
 
-    async fn read(&self, _: FuseQueryContextRef) -> Result<SendableDataBlockStream> {
+    async fn read(
+        &self,
+        _: FuseQueryContextRef,
+        _source_plan: &ReadDataSourcePlan,
+    ) -> Result<SendableDataBlockStream> {
         let block = DataBlock::create_by_array(self.schema.clone(), vec![Arc::new(
             UInt8Array::from(vec![1u8]),
         )]);
@@ -0,0 +1,116 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_datavalues::DataField;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataSchemaRefExt;
+use common_datavalues::DataType;
+use common_datavalues::StringArray;
+use common_datavalues::UInt64Array;
+use common_exception::Result;
+use common_planners::Partition;
+use common_planners::ReadDataSourcePlan;
+use common_planners::ScanPlan;
+use common_planners::Statistics;
+use common_streams::DataBlockStream;
+use common_streams::SendableDataBlockStream;
+
+use crate::datasources::Table;
+use crate::sessions::FuseQueryContextRef;
+
+pub struct ProcessesTable {
+    schema: DataSchemaRef,
+}
+
+impl ProcessesTable {
+    pub fn create() -> Self {
+        ProcessesTable {
+            schema: DataSchemaRefExt::create(vec![
+                DataField::new("id", DataType::Utf8, false),
+                DataField::new("database", DataType::Utf8, false),
+                DataField::new("read_rows", DataType::UInt64, false),
+                DataField::new("read_bytes", DataType::UInt64, false),
+                DataField::new("memory_usage", DataType::UInt64, false),
+                DataField::new("peak_memory_usage", DataType::UInt64, false),
+            ]),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for ProcessesTable {
+    fn name(&self) -> &str {
+        "processes"
+    }
+
+    fn engine(&self) -> &str {
+        "SystemProcesses"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> Result<DataSchemaRef> {
+        Ok(self.schema.clone())
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    fn read_plan(
+        &self,
+        _ctx: FuseQueryContextRef,
+        scan: &ScanPlan,
+        _partitions: usize,
+    ) -> Result<ReadDataSourcePlan> {
+        Ok(ReadDataSourcePlan {
+            db: "system".to_string(),
+            table: self.name().to_string(),
+            schema: self.schema.clone(),
+            partitions: vec![Partition {
+                name: "".to_string(),
+                version: 0,
+            }],
+            statistics: Statistics::default(),
+            description: "(Read from system.processes table)".to_string(),
+            scan_plan: Arc::new(scan.clone()),
+            remote: false,
+        })
+    }
+
+    async fn read(
+        &self,
+        ctx: FuseQueryContextRef,
+        _source_plan: &ReadDataSourcePlan,
+    ) -> Result<SendableDataBlockStream> {
+        let infos = ctx.get_process_list().get_infos()?;
+
+        let ids: Vec<&str> = infos.iter().map(|x| x.id.as_str()).collect();
+        let databases: Vec<&str> = infos.iter().map(|x| x.database.as_str()).collect();
+        let read_rows: Vec<u64> = infos.iter().map(|x| x.read_rows as u64).collect();
+        let read_bytes: Vec<u64> = infos.iter().map(|x| x.read_bytes as u64).collect();
+        let memory_usage: Vec<u64> = infos.iter().map(|x| x.memory_usage).collect();
+        let peak_memory_usage: Vec<u64> = infos.iter().map(|x| x.peak_memory_usage).collect();
+
+        let block = DataBlock::create_by_array(self.schema.clone(), vec![
+            Arc::new(StringArray::from(ids)),
+            Arc::new(StringArray::from(databases)),
+            Arc::new(UInt64Array::from(read_rows)),
+            Arc::new(UInt64Array::from(read_bytes)),
+            Arc::new(UInt64Array::from(memory_usage)),
+            Arc::new(UInt64Array::from(peak_memory_usage)),
+        ]);
+        Ok(Box::pin(DataBlockStream::create(
+            self.schema.clone(),
+            None,
+            vec![block],
+        )))
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_processes_table() -> anyhow::Result<()> {
+    use common_planners::*;
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::datasources::system::*;
+    use crate::datasources::*;
+    use crate::sessions::ProcessList;
+
+    let process_list = ProcessList::create(0);
+    let ctx = crate::tests::try_create_context()?.with_process_list(process_list.clone())?;
+    let tracker = ctx.try_get_memory_tracker()?;
+    tracker.alloc(1024)?;
+    tracker.free(1000);
+
+    let table = ProcessesTable::create();
+    let source_plan = table.read_plan(
+        ctx.clone(),
+        &ScanPlan::empty(),
+        ctx.get_max_threads()? as usize,
+    )?;
+
+    let stream = table.read(ctx.clone(), &source_plan).await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let block = &result[0];
+    assert_eq!(block.num_columns(), 6);
+    assert_eq!(block.num_rows(), 1);
+
+    let column = |name: &str| -> anyhow::Result<String> {
+        let array = block.try_array_by_name(name)?;
+        Ok(common_datavalues::DataValue::try_from_array(&array, 0)?.to_string())
+    };
+    assert_eq!(column("id")?, "2021");
+    assert_eq!(column("database")?, "default");
+    assert_eq!(column("memory_usage")?, "24");
+    assert_eq!(column("peak_memory_usage")?, "1024");
+
+    // The server-wide tracker is the parent of the query tracker.
+    assert_eq!(process_list.get_memory_tracker().get_usage(), 24);
+
+    process_list.remove(&ctx.get_id());
+    assert_eq!(process_list.get_infos()?.len(), 0);
+
+    Ok(())
+}
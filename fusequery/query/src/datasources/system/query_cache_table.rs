@@ -0,0 +1,120 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_datavalues::DataField;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataSchemaRefExt;
+use common_datavalues::DataType;
+use common_datavalues::StringArray;
+use common_datavalues::UInt64Array;
+use common_exception::Result;
+use common_planners::Partition;
+use common_planners::ReadDataSourcePlan;
+use common_planners::ScanPlan;
+use common_planners::Statistics;
+use common_streams::DataBlockStream;
+use common_streams::SendableDataBlockStream;
+
+use crate::datasources::Table;
+use crate::sessions::FuseQueryContextRef;
+
+pub struct QueryCacheTable {
+    schema: DataSchemaRef,
+}
+
+impl QueryCacheTable {
+    pub fn create() -> Self {
+        QueryCacheTable {
+            schema: DataSchemaRefExt::create(vec![
+                DataField::new("plan", DataType::Utf8, false),
+                DataField::new("tables", DataType::Utf8, false),
+                DataField::new("rows", DataType::UInt64, false),
+                DataField::new("bytes", DataType::UInt64, false),
+                DataField::new("hits", DataType::UInt64, false),
+                DataField::new("misses", DataType::UInt64, false),
+                DataField::new("age_seconds", DataType::UInt64, false),
+            ]),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for QueryCacheTable {
+    fn name(&self) -> &str {
+        "query_cache"
+    }
+
+    fn engine(&self) -> &str {
+        "SystemQueryCache"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> Result<DataSchemaRef> {
+        Ok(self.schema.clone())
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    fn read_plan(
+        &self,
+        _ctx: FuseQueryContextRef,
+        scan: &ScanPlan,
+        _partitions: usize,
+    ) -> Result<ReadDataSourcePlan> {
+        Ok(ReadDataSourcePlan {
+            db: "system".to_string(),
+            table: self.name().to_string(),
+            schema: self.schema.clone(),
+            partitions: vec![Partition {
+                name: "".to_string(),
+                version: 0,
+            }],
+            statistics: Statistics::default(),
+            description: "(Read from system.query_cache table)".to_string(),
+            scan_plan: Arc::new(scan.clone()),
+            remote: false,
+        })
+    }
+
+    async fn read(
+        &self,
+        ctx: FuseQueryContextRef,
+        _source_plan: &ReadDataSourcePlan,
+    ) -> Result<SendableDataBlockStream> {
+        let infos = ctx.get_query_cache().get_infos();
+
+        let plans: Vec<&str> = infos.iter().map(|x| x.plan.as_str()).collect();
+        let tables: Vec<String> = infos.iter().map(|x| x.tables.join(", ")).collect();
+        let tables: Vec<&str> = tables.iter().map(|x| x.as_str()).collect();
+        let rows: Vec<u64> = infos.iter().map(|x| x.rows as u64).collect();
+        let bytes: Vec<u64> = infos.iter().map(|x| x.bytes as u64).collect();
+        let hits: Vec<u64> = infos.iter().map(|x| x.hits).collect();
+        let misses: Vec<u64> = infos.iter().map(|x| x.misses).collect();
+        let ages: Vec<u64> = infos.iter().map(|x| x.age.as_secs()).collect();
+
+        let block = DataBlock::create_by_array(self.schema.clone(), vec![
+            Arc::new(StringArray::from(plans)),
+            Arc::new(StringArray::from(tables)),
+            Arc::new(UInt64Array::from(rows)),
+            Arc::new(UInt64Array::from(bytes)),
+            Arc::new(UInt64Array::from(hits)),
+            Arc::new(UInt64Array::from(misses)),
+            Arc::new(UInt64Array::from(ages)),
+        ]);
+        Ok(Box::pin(DataBlockStream::create(
+            self.schema.clone(),
+            None,
+            vec![block],
+        )))
+    }
+}
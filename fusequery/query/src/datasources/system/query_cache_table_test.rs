@@ -0,0 +1,53 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_query_cache_table() -> anyhow::Result<()> {
+    use common_planners::*;
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::datasources::system::*;
+    use crate::datasources::*;
+    use crate::interpreters::*;
+    use crate::sql::*;
+
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings()
+        .update_settings("use_query_cache", "1".to_string())?;
+    for query in &[
+        "create table default.a(a bigint) Engine = Null",
+        "select a from default.a",
+        "select a from default.a",
+        "select a from default.a",
+    ] {
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(query)?;
+        let executor = InterpreterFactory::get(ctx.clone(), plan)?;
+        executor.execute().await?.try_collect::<Vec<_>>().await?;
+    }
+
+    let table = QueryCacheTable::create();
+    let source_plan = table.read_plan(
+        ctx.clone(),
+        &ScanPlan::empty(),
+        ctx.get_max_threads()? as usize,
+    )?;
+
+    let stream = table.read(ctx, &source_plan).await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let block = &result[0];
+    assert_eq!(block.num_columns(), 7);
+    assert_eq!(block.num_rows(), 1);
+
+    let column = |name: &str| -> anyhow::Result<String> {
+        let array = block.try_array_by_name(name)?;
+        Ok(common_datavalues::DataValue::try_from_array(&array, 0)?.to_string())
+    };
+    assert_eq!(column("tables")?, "default.a");
+    assert_eq!(column("rows")?, "0");
+    assert_eq!(column("hits")?, "2");
+    assert_eq!(column("misses")?, "1");
+
+    Ok(())
+}
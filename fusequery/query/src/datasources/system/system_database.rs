@@ -34,6 +34,8 @@ impl SystemDatabase {
             Arc::new(system::TablesTable::create()),
             Arc::new(system::ClustersTable::create()),
             Arc::new(system::DatabasesTable::create()),
+            Arc::new(system::QueryCacheTable::create()),
+            Arc::new(system::ProcessesTable::create()),
         ];
         let mut tables: HashMap<String, Arc<dyn Table>> = HashMap::default();
         for tbl in table_list.iter() {
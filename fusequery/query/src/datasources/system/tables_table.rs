@@ -81,7 +81,11 @@ impl Table for TablesTable {
         })
     }
 
-    async fn read(&self, ctx: FuseQueryContextRef) -> Result<SendableDataBlockStream> {
+    async fn read(
+        &self,
+        ctx: FuseQueryContextRef,
+        _source_plan: &ReadDataSourcePlan,
+    ) -> Result<SendableDataBlockStream> {
         let database_tables = ctx.get_datasource().get_all_tables()?;
 
         let databases: Vec<&str> = database_tables.iter().map(|(d, _)| d.as_str()).collect();
@@ -7,9 +7,11 @@ use std::any::Any;
 use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_planners::AggregatorPartialPlan;
 use common_planners::InsertIntoPlan;
 use common_planners::ReadDataSourcePlan;
 use common_planners::ScanPlan;
+use common_planners::TableStatistics;
 use common_streams::SendableDataBlockStream;
 
 use crate::sessions::FuseQueryContextRef;
@@ -29,8 +31,44 @@ pub trait Table: Sync + Send {
         scan: &ScanPlan,
         partitions: usize,
     ) -> Result<ReadDataSourcePlan>;
-    // Read block data from the underling.
-    async fn read(&self, ctx: FuseQueryContextRef) -> Result<SendableDataBlockStream>;
+    // The statistics collected by ANALYZE TABLE, None if the table is not analyzed or
+    // modified since.
+    fn statistics(&self, _ctx: FuseQueryContextRef) -> Result<Option<TableStatistics>> {
+        Ok(None)
+    }
+    // Store the statistics with the table meta, None drops them.
+    fn set_statistics(
+        &self,
+        _ctx: FuseQueryContextRef,
+        statistics: Option<TableStatistics>,
+    ) -> Result<()> {
+        match statistics {
+            None => Ok(()),
+            Some(_) => Err(ErrorCode::UnImplement(format!(
+                "statistics for table {} is not implemented",
+                self.name()
+            ))),
+        }
+    }
+    // Read block data from the underling, the scan plan of the source plan has the push downs.
+    async fn read(
+        &self,
+        ctx: FuseQueryContextRef,
+        source_plan: &ReadDataSourcePlan,
+    ) -> Result<SendableDataBlockStream>;
+
+    // Read the partial aggregation states of the source, computed by the underling next to the data.
+    async fn read_partial_aggregated(
+        &self,
+        _ctx: FuseQueryContextRef,
+        _source_plan: &ReadDataSourcePlan,
+        _aggregation: &AggregatorPartialPlan,
+    ) -> Result<SendableDataBlockStream> {
+        Err(ErrorCode::UnImplement(format!(
+            "aggregation push down for table {} is not implemented",
+            self.name()
+        )))
+    }
 
     // temporary added, pls feel free to rm it
     async fn append_data(
@@ -33,6 +33,9 @@ impl Interpreter for DropDatabaseInterpreter {
     async fn execute(&self) -> Result<SendableDataBlockStream> {
         let datasource = self.ctx.get_datasource();
         datasource.drop_database(self.plan.clone()).await?;
+        self.ctx
+            .get_query_cache()
+            .invalidate_database(&self.plan.db);
 
         Ok(Box::pin(DataBlockStream::create(
             self.plan.schema(),
@@ -32,6 +32,18 @@ impl ExplainInterpreter {
     pub fn try_create(ctx: FuseQueryContextRef, explain: ExplainPlan) -> Result<InterpreterPtr> {
         Ok(Arc::new(ExplainInterpreter { ctx, explain }))
     }
+
+    // One row for the plan before the optimizers and one row for each optimizer applied.
+    fn explain_optimizer(&self, optimizers: &mut Optimizers) -> Result<Vec<String>> {
+        let mut results = vec![format!("Before optimization:\n{:?}", self.explain.input)];
+        for step in optimizers.optimize_with_steps(&self.explain.input)? {
+            results.push(match step.plan {
+                Some(plan) => format!("{} (iteration {}):\n{:?}", step.name, step.iteration, plan),
+                None => format!("{} (iteration {}): unchanged", step.name, step.iteration),
+            });
+        }
+        Ok(results)
+    }
 }
 
 #[async_trait::async_trait]
@@ -44,21 +56,29 @@ impl Interpreter for ExplainInterpreter {
         let schema =
             DataSchemaRefExt::create(vec![DataField::new("explain", DataType::Utf8, false)]);
 
-        let plan = Optimizers::create(self.ctx.clone()).optimize(&self.explain.input)?;
-        let result = match self.explain.typ {
+        let mut optimizers = Optimizers::create(self.ctx.clone())?;
+        let results = match self.explain.typ {
+            ExplainType::Optimizer => self.explain_optimizer(&mut optimizers)?,
             ExplainType::Graph => {
-                format!("{}", plan.display_graphviz())
+                let plan = optimizers.optimize(&self.explain.input)?;
+                vec![format!("{}", plan.display_graphviz())]
             }
             ExplainType::Pipeline => {
+                let plan = optimizers.optimize(&self.explain.input)?;
                 let pipeline = PipelineBuilder::create(self.ctx.clone(), plan).build()?;
-                format!("{:?}", pipeline)
+                vec![
+                    format!("{:?}", pipeline),
+                    format!("{}", pipeline.display_ports()),
+                ]
+            }
+            _ => {
+                let plan = optimizers.optimize(&self.explain.input)?;
+                vec![format!("{:?}", plan)]
             }
-            _ => format!("{:?}", plan),
         };
+        let results = results.iter().map(|v| v.as_str()).collect::<Vec<_>>();
         let block =
-            DataBlock::create_by_array(schema.clone(), vec![Arc::new(StringArray::from(vec![
-                result.as_str(),
-            ]))]);
+            DataBlock::create_by_array(schema.clone(), vec![Arc::new(StringArray::from(results))]);
         debug!("Explain executor result: {:?}", block);
 
         Ok(Box::pin(DataBlockStream::create(schema, None, vec![block])))
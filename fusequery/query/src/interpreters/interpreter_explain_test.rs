@@ -25,14 +25,14 @@ async fn test_explain_interpreter() -> anyhow::Result<()> {
         assert_eq!(block.num_columns(), 1);
 
         let expected = vec![
-            "+-----------------------------------------------------------------------------------------------------------------------+",
-            "| explain                                                                                                               |",
-            "+-----------------------------------------------------------------------------------------------------------------------+",
-            "| Projection: number:UInt64                                                                                             |",
-            "|   Having: ((number + 1) = 4)                                                                                          |",
-            "|     Filter: ((number + 1) = 4)                                                                                        |",
-            "|       ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80] |",
-            "+-----------------------------------------------------------------------------------------------------------------------+",
+            "+----------------------------------------------------------------------------------------------------------------------------------------------------------------+",
+            "| explain                                                                                                                                                        |",
+            "+----------------------------------------------------------------------------------------------------------------------------------------------------------------+",
+            "| Projection: number:UInt64                                                                                                                                      |",
+            "|   Having: ((number + 1) = 4)                                                                                                                                   |",
+            "|     Filter: ((number + 1) = 4)                                                                                                                                 |",
+            "|       ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80], push down filters: [((number + 1) = 4)] |",
+            "+----------------------------------------------------------------------------------------------------------------------------------------------------------------+",
         ];
         common_datablocks::assert_blocks_eq(expected, result.as_slice());
     } else {
@@ -41,3 +41,31 @@ async fn test_explain_interpreter() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_explain_optimizer_interpreter() -> anyhow::Result<()> {
+    use common_planners::*;
+    use futures::TryStreamExt;
+
+    use crate::interpreters::*;
+    use crate::sql::*;
+
+    let ctx = crate::tests::try_create_context()?;
+
+    if let PlanNode::Explain(plan) = PlanParser::create(ctx.clone())
+        .build_from_sql("explain optimizer select number from numbers_mt(10) where 1 = 1 limit 1")?
+    {
+        let executor = ExplainInterpreter::try_create(ctx, plan)?;
+        let stream = executor.execute().await?;
+        let result = stream.try_collect::<Vec<_>>().await?;
+
+        // The plan before the optimizers and one row for each optimizer applied.
+        let block = &result[0];
+        assert_eq!(block.num_columns(), 1);
+        assert_eq!(block.num_rows(), 7);
+    } else {
+        assert!(false)
+    }
+
+    Ok(())
+}
@@ -4,6 +4,7 @@
 
 use std::sync::Arc;
 
+use common_exception::ErrorCode;
 use common_exception::Result;
 use common_planners::InsertIntoPlan;
 use common_streams::DataBlockStream;
@@ -31,12 +32,22 @@ impl Interpreter for InsertIntoInterpreter {
     }
 
     async fn execute(&self) -> Result<SendableDataBlockStream> {
+        if !self.plan.has_input_stream() {
+            return Err(ErrorCode::EmptyData(format!(
+                "No data to insert into {}.{}, the rows must be sent by the client",
+                self.plan.db_name, self.plan.tbl_name
+            )));
+        }
+
         let datasource = self.ctx.get_datasource();
         let database = datasource.get_database(self.plan.db_name.as_str())?;
         let table = database.get_table(self.plan.tbl_name.as_str())?;
         table
             .append_data(self.ctx.clone(), self.plan.clone())
             .await?;
+        self.ctx
+            .get_query_cache()
+            .invalidate_table(&self.plan.db_name, &self.plan.tbl_name);
         Ok(Box::pin(DataBlockStream::create(
             self.plan.schema(),
             None,
@@ -3,21 +3,32 @@
 // SPDX-License-Identifier: Apache-2.0.
 
 use std::collections::HashSet;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
 
+use common_datablocks::DataBlock;
 use common_datavalues::DataSchemaRef;
-use common_exception::ErrorCode;
 use common_exception::Result;
+use common_planners::PlanNode;
 use common_planners::SelectPlan;
+use common_streams::DataBlockStream;
 use common_streams::SendableDataBlockStream;
 use common_tracing::tracing;
+use futures::Stream;
+use futures::TryStreamExt;
 
+use crate::api::CancelQueryAction;
+use crate::clusters::Node;
 use crate::interpreters::plan_scheduler::PlanScheduler;
 use crate::interpreters::Interpreter;
 use crate::interpreters::InterpreterPtr;
 use crate::optimizers::Optimizers;
 use crate::pipelines::processors::PipelineBuilder;
 use crate::sessions::FuseQueryContextRef;
+use crate::sessions::QueryCacheKey;
 
 pub struct SelectInterpreter {
     ctx: FuseQueryContextRef,
@@ -28,6 +39,82 @@ impl SelectInterpreter {
     pub fn try_create(ctx: FuseQueryContextRef, select: SelectPlan) -> Result<InterpreterPtr> {
         Ok(Arc::new(SelectInterpreter { ctx, select }))
     }
+
+    async fn execute_plan(&self, plan: &PlanNode) -> Result<SendableDataBlockStream> {
+        let scheduled_actions = PlanScheduler::reschedule(self.ctx.clone(), plan)?;
+
+        let timeout = self.ctx.get_settings().get_flight_client_timeout()?;
+        let mut prepared_nodes: Vec<Arc<Node>> = vec![];
+        let mut prepared_names = HashSet::new();
+        for (node, action) in scheduled_actions.remote_actions.iter() {
+            // The stages may be prepared even if the response is lost.
+            if prepared_names.insert(node.name.clone()) {
+                prepared_nodes.push(node.clone());
+            }
+
+            let prepared = match node.get_flight_client().await {
+                Ok(mut flight_client) => {
+                    flight_client
+                        .prepare_query_stage(action.clone(), timeout)
+                        .await
+                }
+                Err(error) => Err(error),
+            };
+
+            if let Err(error) = prepared {
+                cancel_remote_stages(&self.ctx, prepared_nodes);
+                return Err(error);
+            }
+        }
+
+        let pipeline =
+            PipelineBuilder::create(self.ctx.clone(), scheduled_actions.local_plan.clone()).build();
+        let stream = match pipeline {
+            Ok(mut pipeline) => pipeline.execute().await,
+            Err(error) => Err(error),
+        };
+
+        match stream {
+            Ok(stream) if prepared_nodes.is_empty() => Ok(stream),
+            Ok(stream) => Ok(Box::pin(RemoteStagesStream {
+                ctx: self.ctx.clone(),
+                input: stream,
+                nodes: prepared_nodes,
+                finished: false,
+            })),
+            Err(error) => {
+                cancel_remote_stages(&self.ctx, prepared_nodes);
+                Err(error)
+            }
+        }
+    }
+
+    // The result of the plan from the query cache, or executes the plan and caches the result.
+    async fn execute_with_cache(
+        &self,
+        plan: &PlanNode,
+        key: QueryCacheKey,
+    ) -> Result<SendableDataBlockStream> {
+        let settings = self.ctx.get_settings();
+        let ttl = Duration::from_secs(settings.get_query_cache_ttl_seconds()?);
+        let query_cache = self.ctx.get_query_cache();
+
+        let blocks = match query_cache.get(&key, ttl) {
+            Some(blocks) => blocks,
+            None => {
+                let stream = self.execute_plan(plan).await?;
+                let blocks = stream.try_collect::<Vec<_>>().await?;
+                let max_bytes = settings.get_query_cache_max_bytes()? as usize;
+                query_cache.put(key, blocks.clone(), ttl, max_bytes);
+                blocks
+            }
+        };
+        Ok(Box::pin(DataBlockStream::create(
+            self.select.schema(),
+            None,
+            blocks,
+        )))
+    }
 }
 
 #[async_trait::async_trait]
@@ -42,37 +129,75 @@ impl Interpreter for SelectInterpreter {
 
     #[tracing::instrument(level = "info", skip(self), fields(ctx.id = self.ctx.get_id().as_str()))]
     async fn execute(&self) -> Result<SendableDataBlockStream> {
-        let plan = Optimizers::create(self.ctx.clone()).optimize(&self.select.input)?;
+        let plan = Optimizers::create(self.ctx.clone())?.optimize(&self.select.input)?;
 
-        let scheduled_actions = PlanScheduler::reschedule(self.ctx.clone(), &plan)?;
-
-        let remote_actions_ref = &scheduled_actions.remote_actions;
-        let prepare_error_handler = move |error: ErrorCode, end: usize| {
-            let mut killed_set = HashSet::new();
-            for (node, _) in remote_actions_ref.iter().take(end) {
-                if killed_set.get(&node.name).is_none() {
-                    // TODO: ISSUE-204 kill prepared query stage
-                    killed_set.insert(node.name.clone());
-                }
+        if self.ctx.get_settings().get_use_query_cache()? != 0 {
+            if let Some(key) = QueryCacheKey::try_create(&self.ctx, &plan).await? {
+                return self.execute_with_cache(&plan, key).await;
             }
+        }
+        self.execute_plan(&plan).await
+    }
+}
 
-            Result::Err(error)
-        };
+/// Cancels the prepared stages of the query on the remote nodes.
+fn cancel_remote_stages(ctx: &FuseQueryContextRef, nodes: Vec<Arc<Node>>) {
+    if nodes.is_empty() {
+        return;
+    }
 
-        let timeout = self.ctx.get_settings().get_flight_client_timeout()?;
-        for (index, (node, action)) in scheduled_actions.remote_actions.iter().enumerate() {
-            let mut flight_client = node.get_flight_client().await?;
-            if let Err(error) = flight_client
-                .prepare_query_stage(action.clone(), timeout)
-                .await
-            {
-                return prepare_error_handler(error, index);
+    let query_id = ctx.get_id();
+    let timeout = ctx
+        .get_settings()
+        .get_flight_client_timeout()
+        .unwrap_or_default();
+    ctx.execute_task(async move {
+        for node in nodes {
+            let action = CancelQueryAction {
+                query_id: query_id.clone(),
+            };
+            let cancelled = match node.get_flight_client().await {
+                Ok(mut flight_client) => flight_client.cancel_query(action, timeout).await,
+                Err(error) => Err(error),
+            };
+
+            if let Err(error) = cancelled {
+                tracing::error!(
+                    "Cannot cancel the query {} on the node {}: {}",
+                    query_id,
+                    node.name,
+                    error
+                );
             }
         }
+    });
+}
 
-        PipelineBuilder::create(self.ctx.clone(), scheduled_actions.local_plan.clone())
-            .build()?
-            .execute()
-            .await
+/// The result of a distributed query, the remote stages are cancelled if it is dropped
+/// before the end, because the query failed, timed out or the client went away.
+struct RemoteStagesStream {
+    ctx: FuseQueryContextRef,
+    input: SendableDataBlockStream,
+    nodes: Vec<Arc<Node>>,
+    finished: bool,
+}
+
+impl Stream for RemoteStagesStream {
+    type Item = Result<DataBlock>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = self.input.as_mut().poll_next(cx);
+        if let Poll::Ready(None) = poll {
+            self.finished = true;
+        }
+        poll
+    }
+}
+
+impl Drop for RemoteStagesStream {
+    fn drop(&mut self) {
+        if !self.finished {
+            cancel_remote_stages(&self.ctx, std::mem::take(&mut self.nodes));
+        }
     }
 }
@@ -0,0 +1,101 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_datavalues::DataValue;
+use common_datavalues::StringArray;
+use common_datavalues::UInt64Array;
+use common_exception::Result;
+use common_planners::AnalyzeTablePlan;
+use common_planners::ScanPlan;
+use common_streams::DataBlockStream;
+use common_streams::SendableDataBlockStream;
+use futures::StreamExt;
+
+use crate::datasources::StatisticsCollector;
+use crate::interpreters::Interpreter;
+use crate::interpreters::InterpreterPtr;
+use crate::sessions::FuseQueryContextRef;
+
+/// Scans the whole table and stores the table and column statistics with the table for the cost model.
+pub struct AnalyzeTableInterpreter {
+    ctx: FuseQueryContextRef,
+    plan: AnalyzeTablePlan,
+}
+
+impl AnalyzeTableInterpreter {
+    pub fn try_create(ctx: FuseQueryContextRef, plan: AnalyzeTablePlan) -> Result<InterpreterPtr> {
+        Ok(Arc::new(AnalyzeTableInterpreter { ctx, plan }))
+    }
+}
+
+#[async_trait::async_trait]
+impl Interpreter for AnalyzeTableInterpreter {
+    fn name(&self) -> &str {
+        "AnalyzeTableInterpreter"
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        let datasource = self.ctx.get_datasource();
+        let table = datasource.get_table(&self.plan.db, &self.plan.table)?;
+        let table_schema = table.schema()?;
+
+        let scan = ScanPlan {
+            schema_name: self.plan.db.clone(),
+            table_schema: table_schema.clone(),
+            projected_schema: table_schema.clone(),
+            ..ScanPlan::empty()
+        };
+        let max_threads = self.ctx.get_max_threads()? as usize;
+        let source_plan = table.read_plan(self.ctx.clone(), &scan, max_threads)?;
+        self.ctx
+            .try_set_partitions(source_plan.partitions.clone())?;
+
+        let mut collector = StatisticsCollector::create(table_schema.clone());
+        let mut stream = table.read(self.ctx.clone(), &source_plan).await?;
+        while let Some(block) = stream.next().await {
+            collector.append(&block?)?;
+        }
+
+        let stats = collector.finalize();
+        table.set_statistics(self.ctx.clone(), Some(stats.clone()))?;
+
+        let mut names = vec![];
+        let mut mins = vec![];
+        let mut maxs = vec![];
+        let mut null_counts = vec![];
+        let mut distinct_counts = vec![];
+        for field in table_schema.fields() {
+            if let Some(column) = stats.columns.get(field.name()) {
+                let display = |v: &Option<DataValue>| {
+                    v.as_ref()
+                        .map_or_else(|| "NULL".to_string(), |v| v.to_string())
+                };
+                names.push(field.name().clone());
+                mins.push(display(&column.min));
+                maxs.push(display(&column.max));
+                null_counts.push(column.null_count as u64);
+                distinct_counts.push(column.distinct_count as u64);
+            }
+        }
+
+        let schema = self.plan.schema();
+        let block = DataBlock::create_by_array(schema.clone(), vec![
+            Arc::new(StringArray::from(
+                names.iter().map(|v| v.as_str()).collect::<Vec<_>>(),
+            )),
+            Arc::new(StringArray::from(
+                mins.iter().map(|v| v.as_str()).collect::<Vec<_>>(),
+            )),
+            Arc::new(StringArray::from(
+                maxs.iter().map(|v| v.as_str()).collect::<Vec<_>>(),
+            )),
+            Arc::new(UInt64Array::from(null_counts)),
+            Arc::new(UInt64Array::from(distinct_counts)),
+        ]);
+        Ok(Box::pin(DataBlockStream::create(schema, None, vec![block])))
+    }
+}
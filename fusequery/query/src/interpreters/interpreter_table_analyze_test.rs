@@ -0,0 +1,73 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_analyze_table_interpreter() -> anyhow::Result<()> {
+    use std::env;
+
+    use common_datavalues::DataValue;
+    use common_planners::*;
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::interpreters::*;
+    use crate::sql::*;
+
+    let ctx = crate::tests::try_create_context()?;
+    let file = env::current_dir()?
+        .join("../../tests/data/sample.csv")
+        .display()
+        .to_string();
+
+    // Create table.
+    {
+        let sql = format!(
+            "create table default.test_csv (id int,name varchar(255),rank int) Engine = CSV location = '{}'",
+            file
+        );
+        if let PlanNode::CreateTable(plan) = PlanParser::create(ctx.clone()).build_from_sql(&sql)? {
+            let executor = CreateTableInterpreter::try_create(ctx.clone(), plan.clone())?;
+            let _ = executor.execute().await?;
+        }
+    }
+
+    // Analyze table.
+    {
+        if let PlanNode::AnalyzeTable(plan) =
+            PlanParser::create(ctx.clone()).build_from_sql("analyze table test_csv")?
+        {
+            let executor = AnalyzeTableInterpreter::try_create(ctx.clone(), plan.clone())?;
+            assert_eq!(executor.name(), "AnalyzeTableInterpreter");
+            let stream = executor.execute().await?;
+            let result = stream.try_collect::<Vec<_>>().await?;
+            let expected = vec![
+                "+--------+-----------+------------+------------+----------------+",
+                "| column | min       | max        | null_count | distinct_count |",
+                "+--------+-----------+------------+------------+----------------+",
+                "| id     | 1         | 6          | 0          | 6              |",
+                "| name   | 'Beijing' | 'Shenzhen' | 0          | 4              |",
+                "| rank   | 55        | 100        | 0          | 6              |",
+                "+--------+-----------+------------+------------+----------------+",
+            ];
+            common_datablocks::assert_blocks_eq(expected, result.as_slice());
+        } else {
+            assert!(false)
+        }
+    }
+
+    // The statistics are stored with the table for the planner.
+    {
+        let stats = ctx
+            .get_table("default", "test_csv")?
+            .statistics(ctx.clone())?
+            .unwrap();
+        assert_eq!(6, stats.rows);
+        let rank = stats.columns.get("rank").unwrap();
+        assert_eq!(6, rank.histogram.len());
+        assert_eq!(Some(&DataValue::Int32(Some(55))), rank.histogram.first());
+        assert_eq!(Some(&DataValue::Int32(Some(100))), rank.histogram.last());
+    }
+
+    Ok(())
+}
@@ -34,6 +34,9 @@ impl Interpreter for CreateTableInterpreter {
         let datasource = self.ctx.get_datasource();
         let database = datasource.get_database(self.plan.db.as_str())?;
         database.create_table(self.plan.clone()).await?;
+        self.ctx
+            .get_query_cache()
+            .invalidate_table(&self.plan.db, &self.plan.table);
 
         Ok(Box::pin(DataBlockStream::create(
             self.plan.schema.clone(),
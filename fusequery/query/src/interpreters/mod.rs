@@ -13,6 +13,8 @@ mod interpreter_select_test;
 #[cfg(test)]
 mod interpreter_setting_test;
 #[cfg(test)]
+mod interpreter_table_analyze_test;
+#[cfg(test)]
 mod interpreter_table_create_test;
 #[cfg(test)]
 mod interpreter_table_drop_test;
@@ -29,6 +31,7 @@ mod interpreter_factory;
 mod interpreter_insert_into;
 mod interpreter_select;
 mod interpreter_setting;
+mod interpreter_table_analyze;
 mod interpreter_table_create;
 mod interpreter_table_drop;
 mod interpreter_use_database;
@@ -43,6 +46,7 @@ pub use interpreter_factory::InterpreterFactory;
 pub use interpreter_insert_into::InsertIntoInterpreter;
 pub use interpreter_select::SelectInterpreter;
 pub use interpreter_setting::SettingInterpreter;
+pub use interpreter_table_analyze::AnalyzeTableInterpreter;
 pub use interpreter_table_create::CreateTableInterpreter;
 pub use interpreter_table_drop::DropTableInterpreter;
 pub use interpreter_use_database::UseDatabaseInterpreter;
@@ -0,0 +1,239 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::cmp::Ordering;
+
+use common_datavalues::DataValue;
+use common_exception::Result;
+use common_planners::compare_values;
+use common_planners::ColumnStatistics;
+use common_planners::Expression;
+use common_planners::PlanNode;
+use common_planners::ReadDataSourcePlan;
+use common_planners::TableStatistics;
+
+use crate::sessions::FuseQueryContextRef;
+
+// The selectivity of the predicates the statistics can't estimate.
+const DEFAULT_EQ_SELECTIVITY: f64 = 0.1;
+const DEFAULT_RANGE_SELECTIVITY: f64 = 1.0 / 3.0;
+
+/// Estimates the rows and bytes of the plans from the statistics collected by ANALYZE TABLE,
+/// falls back to the statistics of the read source plan if the table is not analyzed.
+pub struct CostModel {
+    ctx: FuseQueryContextRef,
+}
+
+impl CostModel {
+    pub fn create(ctx: FuseQueryContextRef) -> Self {
+        CostModel { ctx }
+    }
+
+    /// The estimated (rows, bytes) the source reads after the push down filters.
+    pub fn estimate_read(&self, plan: &ReadDataSourcePlan) -> Result<(usize, usize)> {
+        match self.table_statistics(&plan.db, &plan.table)? {
+            None => Ok((plan.statistics.read_rows, plan.statistics.read_bytes)),
+            Some(stats) => {
+                let selectivity = plan
+                    .scan_plan
+                    .filters
+                    .iter()
+                    .map(|filter| Self::selectivity(&stats, filter))
+                    .product::<f64>();
+                Ok((
+                    (stats.rows as f64 * selectivity).round() as usize,
+                    (stats.bytes as f64 * selectivity).round() as usize,
+                ))
+            }
+        }
+    }
+
+    /// Whether the source is worth reading by all the nodes of the cluster.
+    pub fn should_distribute(&self, plan: &ReadDataSourcePlan) -> Result<bool> {
+        let settings = self.ctx.get_settings();
+        let rows_threshold = settings.get_min_distributed_rows()? as usize;
+        let bytes_threshold = settings.get_min_distributed_bytes()? as usize;
+
+        let (rows, bytes) = self.estimate_read(plan)?;
+        Ok(rows >= rows_threshold || bytes >= bytes_threshold)
+    }
+
+    /// The estimated number of groups of the aggregation on the input, the product of the
+    /// distinct counts of the group by columns. None if any of them has no statistics.
+    pub fn estimate_groups(
+        &self,
+        group_expr: &[Expression],
+        input: &PlanNode,
+    ) -> Result<Option<usize>> {
+        let mut source = None;
+        input.walk_preorder(|node| -> Result<bool> {
+            if let PlanNode::ReadSource(plan) = node {
+                source = Some(plan.clone());
+            }
+            Ok(source.is_none())
+        })?;
+
+        let source = match source {
+            Some(source) => source,
+            None => return Ok(None),
+        };
+        let stats = match self.table_statistics(&source.db, &source.table)? {
+            Some(stats) => stats,
+            None => return Ok(None),
+        };
+
+        let mut groups: usize = 1;
+        for expr in group_expr {
+            let column = match expr {
+                Expression::Column(name) => stats.columns.get(name),
+                Expression::Alias(_, expr) => match expr.as_ref() {
+                    Expression::Column(name) => stats.columns.get(name),
+                    _ => None,
+                },
+                _ => None,
+            };
+
+            match column {
+                // NULL is a group too.
+                Some(column) => {
+                    let distinct = column.distinct_count + (column.null_count > 0) as usize;
+                    groups = groups.saturating_mul(std::cmp::max(distinct, 1));
+                }
+                None => return Ok(None),
+            }
+        }
+
+        let (rows, _) = self.estimate_read(&source)?;
+        Ok(Some(std::cmp::min(groups, rows)))
+    }
+
+    /// Whether the partial aggregation results are small enough to be merged on one node,
+    /// instead of shuffling them to all the nodes by the group by keys.
+    pub fn prefer_convergent_aggregate(
+        &self,
+        group_expr: &[Expression],
+        input: &PlanNode,
+    ) -> Result<bool> {
+        if group_expr.is_empty() {
+            return Ok(true);
+        }
+
+        let max_groups = self
+            .ctx
+            .get_settings()
+            .get_max_convergent_aggregate_groups()? as usize;
+        Ok(match self.estimate_groups(group_expr, input)? {
+            Some(groups) => groups <= max_groups,
+            None => false,
+        })
+    }
+
+    // The statistics stored with the table by ANALYZE TABLE, None for the table functions.
+    fn table_statistics(&self, db: &str, table: &str) -> Result<Option<TableStatistics>> {
+        match self.ctx.get_table(db, table) {
+            Ok(table) => table.statistics(self.ctx.clone()),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// The estimated fraction of the rows matching the predicate, between 0 and 1.
+    fn selectivity(stats: &TableStatistics, predicate: &Expression) -> f64 {
+        match predicate {
+            Expression::BinaryExpression { left, op, right } => {
+                match (left.as_ref(), op.to_lowercase().as_str(), right.as_ref()) {
+                    (_, "and", _) => {
+                        Self::selectivity(stats, left) * Self::selectivity(stats, right)
+                    }
+                    (_, "or", _) => {
+                        let (l, r) = (
+                            Self::selectivity(stats, left),
+                            Self::selectivity(stats, right),
+                        );
+                        l + r - l * r
+                    }
+                    (Expression::Column(name), op, Expression::Literal(value)) => {
+                        Self::comparison_selectivity(stats.columns.get(name), op, value)
+                    }
+                    (Expression::Literal(value), op, Expression::Column(name)) => {
+                        // 1 < a => a > 1
+                        let op = match op {
+                            "<" => ">",
+                            "<=" => ">=",
+                            ">" => "<",
+                            ">=" => "<=",
+                            other => other,
+                        };
+                        Self::comparison_selectivity(stats.columns.get(name), op, value)
+                    }
+                    (_, "=", _) => DEFAULT_EQ_SELECTIVITY,
+                    (_, "!=", _) | (_, "<>", _) => 1.0 - DEFAULT_EQ_SELECTIVITY,
+                    (_, "<", _) | (_, "<=", _) | (_, ">", _) | (_, ">=", _) => {
+                        DEFAULT_RANGE_SELECTIVITY
+                    }
+                    _ => 1.0,
+                }
+            }
+            Expression::UnaryExpression { op, expr } if op.to_lowercase() == "not" => {
+                1.0 - Self::selectivity(stats, expr)
+            }
+            Expression::Literal(DataValue::Boolean(Some(true))) => 1.0,
+            Expression::Literal(_) => 0.0,
+            _ => 1.0,
+        }
+    }
+
+    fn comparison_selectivity(
+        column: Option<&ColumnStatistics>,
+        op: &str,
+        value: &DataValue,
+    ) -> f64 {
+        let column = match column {
+            Some(column) => column,
+            None => {
+                return match op {
+                    "=" => DEFAULT_EQ_SELECTIVITY,
+                    "!=" | "<>" => 1.0 - DEFAULT_EQ_SELECTIVITY,
+                    _ => DEFAULT_RANGE_SELECTIVITY,
+                };
+            }
+        };
+
+        let eq = if column.distinct_count == 0 {
+            0.0
+        } else {
+            1.0 / column.distinct_count as f64
+        };
+        match op {
+            "=" => eq,
+            "!=" | "<>" => 1.0 - eq,
+            "<" | "<=" => match Self::fraction_below(column, value) {
+                Some(fraction) if op == "<=" => (fraction + eq).min(1.0),
+                Some(fraction) => fraction,
+                None => DEFAULT_RANGE_SELECTIVITY,
+            },
+            ">" | ">=" => match Self::fraction_below(column, value) {
+                Some(fraction) if op == ">" => (1.0 - fraction - eq).max(0.0),
+                Some(fraction) => 1.0 - fraction,
+                None => DEFAULT_RANGE_SELECTIVITY,
+            },
+            _ => 1.0,
+        }
+    }
+
+    // The fraction of the non-null values less than the value, from the histogram buckets.
+    fn fraction_below(column: &ColumnStatistics, value: &DataValue) -> Option<f64> {
+        if column.histogram.is_empty() {
+            return None;
+        }
+
+        let mut below = 0;
+        for bound in &column.histogram {
+            match compare_values(bound, value)? {
+                Ordering::Less => below += 1,
+                _ => break,
+            }
+        }
+        Some(below as f64 / column.histogram.len() as f64)
+    }
+}
@@ -0,0 +1,188 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_datavalues::*;
+use common_exception::Result;
+use common_planners::*;
+use pretty_assertions::assert_eq;
+
+use crate::interpreters::CreateTableInterpreter;
+use crate::interpreters::Interpreter;
+use crate::optimizers::CostModel;
+use crate::sessions::FuseQueryContextRef;
+use crate::sql::PlanParser;
+
+async fn create_context() -> Result<FuseQueryContextRef> {
+    let ctx = crate::tests::try_create_context()?;
+    let sql = "create table default.test (a bigint, b bigint) Engine = Null";
+    if let PlanNode::CreateTable(plan) = PlanParser::create(ctx.clone()).build_from_sql(sql)? {
+        let executor = CreateTableInterpreter::try_create(ctx.clone(), plan)?;
+        let _ = executor.execute().await?;
+    }
+    Ok(ctx)
+}
+
+fn create_source_plan(filters: Vec<Expression>) -> ReadDataSourcePlan {
+    ReadDataSourcePlan {
+        db: "default".to_string(),
+        table: "test".to_string(),
+        schema: DataSchemaRefExt::create(vec![
+            DataField::new("a", DataType::Int64, false),
+            DataField::new("b", DataType::Int64, true),
+        ]),
+        partitions: vec![],
+        statistics: Statistics {
+            read_rows: 100,
+            read_bytes: 1600,
+        },
+        description: "".to_string(),
+        scan_plan: Arc::new(ScanPlan {
+            filters,
+            ..ScanPlan::empty()
+        }),
+        remote: false,
+    }
+}
+
+fn create_table_statistics() -> TableStatistics {
+    let mut columns = HashMap::new();
+    columns.insert("a".to_string(), ColumnStatistics {
+        min: Some(DataValue::Int64(Some(1))),
+        max: Some(DataValue::Int64(Some(1000))),
+        null_count: 0,
+        distinct_count: 100,
+        histogram: (1..=10).map(|i| DataValue::Int64(Some(i * 100))).collect(),
+    });
+    columns.insert("b".to_string(), ColumnStatistics {
+        min: Some(DataValue::Int64(Some(1))),
+        max: Some(DataValue::Int64(Some(5))),
+        null_count: 10,
+        distinct_count: 5,
+        histogram: vec![],
+    });
+
+    TableStatistics {
+        rows: 1000,
+        bytes: 8000,
+        columns,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_cost_model_estimate_read() -> Result<()> {
+    let ctx = create_context().await?;
+    let cost_model = CostModel::create(ctx.clone());
+
+    // Not analyzed, the statistics of the source plan.
+    let plan = create_source_plan(vec![col("a").eq(lit(5i64))]);
+    assert_eq!((100, 1600), cost_model.estimate_read(&plan)?);
+
+    ctx.get_table("default", "test")?
+        .set_statistics(ctx.clone(), Some(create_table_statistics()))?;
+
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        filters: Vec<Expression>,
+        expect: (usize, usize),
+    }
+
+    let tests = vec![
+        Test {
+            name: "no-filters",
+            filters: vec![],
+            expect: (1000, 8000),
+        },
+        Test {
+            name: "eq",
+            filters: vec![col("a").eq(lit(5i64))],
+            expect: (10, 80),
+        },
+        Test {
+            name: "lt",
+            filters: vec![col("a").lt(lit(300i64))],
+            expect: (200, 1600),
+        },
+        Test {
+            name: "mirrored-lt",
+            filters: vec![lit(300i64).gt(col("a"))],
+            expect: (200, 1600),
+        },
+        Test {
+            name: "gt-eq",
+            filters: vec![col("a").gt_eq(lit(300i64))],
+            expect: (800, 6400),
+        },
+        Test {
+            name: "and",
+            filters: vec![col("a").lt(lit(300i64)).and(col("a").eq(lit(5i64)))],
+            expect: (2, 16),
+        },
+        Test {
+            name: "or",
+            filters: vec![col("a").eq(lit(5i64)).or(col("b").eq(lit(1i64)))],
+            expect: (208, 1664),
+        },
+        Test {
+            name: "unknown-column",
+            filters: vec![col("c").eq(lit(1i64))],
+            expect: (100, 800),
+        },
+    ];
+
+    for test in tests {
+        let plan = create_source_plan(test.filters);
+        let actual = cost_model.estimate_read(&plan)?;
+        assert_eq!(test.expect, actual, "{:#?}", test.name);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_cost_model_distribute_and_groups() -> Result<()> {
+    let ctx = create_context().await?;
+    let cost_model = CostModel::create(ctx.clone());
+    let plan = create_source_plan(vec![]);
+    let input = PlanNode::ReadSource(plan.clone());
+
+    // Not analyzed.
+    assert_eq!(None, cost_model.estimate_groups(&[col("b")], &input)?);
+    assert!(!cost_model.prefer_convergent_aggregate(&[col("b")], &input)?);
+    assert!(cost_model.prefer_convergent_aggregate(&[], &input)?);
+
+    ctx.get_table("default", "test")?
+        .set_statistics(ctx.clone(), Some(create_table_statistics()))?;
+
+    // NULL is a group of b.
+    assert_eq!(Some(6), cost_model.estimate_groups(&[col("b")], &input)?);
+    assert_eq!(
+        Some(600),
+        cost_model.estimate_groups(&[col("a"), col("b")], &input)?
+    );
+    assert_eq!(
+        Some(1000),
+        cost_model.estimate_groups(&[col("a"), col("b"), col("a")], &input)?
+    );
+    assert_eq!(
+        None,
+        cost_model.estimate_groups(&[add(col("a"), lit(1i64))], &input)?
+    );
+
+    assert!(cost_model.prefer_convergent_aggregate(&[col("a"), col("b")], &input)?);
+    ctx.get_settings()
+        .update_settings("max_convergent_aggregate_groups", "100".to_string())?;
+    assert!(!cost_model.prefer_convergent_aggregate(&[col("a"), col("b")], &input)?);
+
+    assert!(!cost_model.should_distribute(&plan)?);
+    ctx.get_settings()
+        .update_settings("min_distributed_rows", "500".to_string())?;
+    assert!(cost_model.should_distribute(&plan)?);
+    assert!(!cost_model.should_distribute(&create_source_plan(vec![col("a").eq(lit(5i64))]))?);
+
+    Ok(())
+}
@@ -2,22 +2,39 @@
 //
 // SPDX-License-Identifier: Apache-2.0.
 
+#[cfg(test)]
+mod cost_model_test;
+#[cfg(test)]
+mod optimizer_common_subexpression_test;
 #[cfg(test)]
 mod optimizer_constant_folding_test;
 #[cfg(test)]
+mod optimizer_limit_push_down_test;
+#[cfg(test)]
+mod optimizer_predicate_push_down_test;
+#[cfg(test)]
 mod optimizer_projection_push_down_test;
 #[cfg(test)]
 mod optimizer_scatters_test;
 #[cfg(test)]
 mod optimizer_test;
 
+mod cost_model;
 mod optimizer;
+mod optimizer_common_subexpression;
 mod optimizer_constant_folding;
+mod optimizer_limit_push_down;
+mod optimizer_predicate_push_down;
 mod optimizer_projection_push_down;
 mod optimizer_scatters;
 
+pub use cost_model::CostModel;
 pub use optimizer::Optimizer;
 pub use optimizer::Optimizers;
+pub use optimizer_common_subexpression::CommonSubexpressionEliminationOptimizer;
+pub use optimizer_constant_folding::is_deterministic;
 pub use optimizer_constant_folding::ConstantFoldingOptimizer;
+pub use optimizer_limit_push_down::LimitPushDownOptimizer;
+pub use optimizer_predicate_push_down::PredicatePushDownOptimizer;
 pub use optimizer_projection_push_down::ProjectionPushDownOptimizer;
 pub use optimizer_scatters::ScattersOptimizer;
@@ -7,34 +7,146 @@ use common_planners::PlanNode;
 use common_tracing::tracing;
 
 use crate::optimizers::optimizer_scatters::ScattersOptimizer;
+use crate::optimizers::CommonSubexpressionEliminationOptimizer;
+use crate::optimizers::ConstantFoldingOptimizer;
+use crate::optimizers::LimitPushDownOptimizer;
+use crate::optimizers::PredicatePushDownOptimizer;
 use crate::optimizers::ProjectionPushDownOptimizer;
 use crate::sessions::FuseQueryContextRef;
 
+// The rules stop if the plan is still changing after so many rounds.
+const MAX_ITERATIONS: usize = 16;
+
 pub trait Optimizer {
     fn name(&self) -> &str;
+
+    /// The names of the plan nodes the optimizer rewrites, such as "FilterPlan".
+    /// The optimizer is skipped if the plan has none of them, empty means it matches any plan.
+    fn pattern(&self) -> &[&str] {
+        &[]
+    }
+
     fn optimize(&mut self, plan: &PlanNode) -> Result<PlanNode>;
 }
 
+/// One application of an optimizer, the plan is None if the optimizer didn't change the plan.
+pub struct OptimizeStep {
+    pub name: String,
+    pub iteration: usize,
+    pub plan: Option<PlanNode>,
+}
+
 pub struct Optimizers {
-    inner: Vec<Box<dyn Optimizer>>,
+    // The rewrite rules, run in order again and again until the plan doesn't change.
+    rules: Vec<Box<dyn Optimizer>>,
+    // Run once after the rules, such as splitting the plan into the distributed stages.
+    finalizers: Vec<Box<dyn Optimizer>>,
 }
 
 impl Optimizers {
-    pub fn create(ctx: FuseQueryContextRef) -> Self {
-        let optimizers: Vec<Box<dyn Optimizer>> = vec![
-            Box::new(ProjectionPushDownOptimizer::create(ctx.clone())),
-            Box::new(ScattersOptimizer::create(ctx)),
-        ];
-        Optimizers { inner: optimizers }
+    pub fn create(ctx: FuseQueryContextRef) -> Result<Self> {
+        let settings = ctx.get_settings();
+        let mut rules: Vec<Box<dyn Optimizer>> = vec![];
+        if settings.get_enable_constant_folding()? != 0 {
+            rules.push(Box::new(ConstantFoldingOptimizer::create(ctx.clone())));
+        }
+        if settings.get_enable_predicate_push_down()? != 0 {
+            rules.push(Box::new(PredicatePushDownOptimizer::create(ctx.clone())));
+        }
+        if settings.get_enable_limit_push_down()? != 0 {
+            rules.push(Box::new(LimitPushDownOptimizer::create(ctx.clone())));
+        }
+        if settings.get_enable_projection_push_down()? != 0 {
+            rules.push(Box::new(ProjectionPushDownOptimizer::create(ctx.clone())));
+        }
+        if settings.get_enable_common_subexpression_elimination()? != 0 {
+            rules.push(Box::new(CommonSubexpressionEliminationOptimizer::create(
+                ctx.clone(),
+            )));
+        }
+
+        Ok(Optimizers {
+            rules,
+            finalizers: vec![Box::new(ScattersOptimizer::create(ctx))],
+        })
     }
 
     pub fn optimize(&mut self, plan: &PlanNode) -> Result<PlanNode> {
+        self.optimize_impl(plan, None)
+    }
+
+    /// Optimizes the plan and records the plan after each optimizer, for EXPLAIN OPTIMIZER.
+    pub fn optimize_with_steps(&mut self, plan: &PlanNode) -> Result<Vec<OptimizeStep>> {
+        let mut steps = vec![];
+        self.optimize_impl(plan, Some(&mut steps))?;
+        Ok(steps)
+    }
+
+    fn optimize_impl(
+        &mut self,
+        plan: &PlanNode,
+        mut steps: Option<&mut Vec<OptimizeStep>>,
+    ) -> Result<PlanNode> {
         let mut plan = plan.clone();
-        for optimizer in self.inner.iter_mut() {
-            tracing::debug!("Before {} \n{:?}", optimizer.name(), plan);
-            plan = optimizer.optimize(&plan)?;
-            tracing::debug!("After {} \n{:?}", optimizer.name(), plan);
+        for iteration in 1..=MAX_ITERATIONS {
+            let mut changed = false;
+            for rule in self.rules.iter_mut() {
+                if !Self::match_pattern(&plan, rule.pattern())? {
+                    continue;
+                }
+
+                let new_plan = Self::apply(rule.as_mut(), &plan, iteration, &mut steps)?;
+                if new_plan != plan {
+                    changed = true;
+                    plan = new_plan;
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        for finalizer in self.finalizers.iter_mut() {
+            plan = Self::apply(finalizer.as_mut(), &plan, 1, &mut steps)?;
         }
         Ok(plan)
     }
+
+    fn apply(
+        optimizer: &mut dyn Optimizer,
+        plan: &PlanNode,
+        iteration: usize,
+        steps: &mut Option<&mut Vec<OptimizeStep>>,
+    ) -> Result<PlanNode> {
+        tracing::debug!("Before {} \n{:?}", optimizer.name(), plan);
+        let new_plan = optimizer.optimize(plan)?;
+        tracing::debug!("After {} \n{:?}", optimizer.name(), new_plan);
+
+        if let Some(steps) = steps {
+            steps.push(OptimizeStep {
+                name: optimizer.name().to_string(),
+                iteration,
+                plan: if &new_plan == plan {
+                    None
+                } else {
+                    Some(new_plan.clone())
+                },
+            });
+        }
+        Ok(new_plan)
+    }
+
+    fn match_pattern(plan: &PlanNode, pattern: &[&str]) -> Result<bool> {
+        if pattern.is_empty() {
+            return Ok(true);
+        }
+
+        let mut matched = false;
+        plan.walk_preorder(|node| -> Result<bool> {
+            matched = matched || pattern.contains(&node.name());
+            Ok(!matched)
+        })?;
+        Ok(matched)
+    }
 }
@@ -0,0 +1,216 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use common_datavalues::DataSchemaRef;
+use common_exception::Result;
+use common_planners::Expression;
+use common_planners::ExpressionPlan;
+use common_planners::FilterPlan;
+use common_planners::HavingPlan;
+use common_planners::LimitPlan;
+use common_planners::PlanBuilder;
+use common_planners::PlanNode;
+use common_planners::PlanRewriter;
+use common_planners::ProjectionPlan;
+use common_planners::RewriteHelper;
+use common_planners::SortPlan;
+
+use crate::optimizers::optimizer_constant_folding::is_deterministic;
+use crate::optimizers::Optimizer;
+use crate::sessions::FuseQueryContextRef;
+
+/// Computes the subexpressions evaluated by more than one plan node once, such as f(x) in:
+/// SELECT f(x) + 1, f(x) * 2 FROM t WHERE f(x) > 0
+/// An expression plan is added below these nodes to compute them as hidden columns,
+/// the nodes above skip evaluating them because the columns are already in the input blocks,
+/// and the projection drops them at last.
+pub struct CommonSubexpressionEliminationOptimizer {}
+
+struct CommonSubexpressionEliminationImpl {}
+
+impl<'plan> PlanRewriter<'plan> for CommonSubexpressionEliminationImpl {
+    fn rewrite_projection(&mut self, plan: &ProjectionPlan) -> Result<PlanNode> {
+        self.rewrite_segment(&PlanNode::Projection(plan.clone()))
+    }
+
+    fn rewrite_expression(&mut self, plan: &ExpressionPlan) -> Result<PlanNode> {
+        self.rewrite_segment(&PlanNode::Expression(plan.clone()))
+    }
+
+    fn rewrite_filter(&mut self, plan: &FilterPlan) -> Result<PlanNode> {
+        self.rewrite_segment(&PlanNode::Filter(plan.clone()))
+    }
+
+    fn rewrite_having(&mut self, plan: &HavingPlan) -> Result<PlanNode> {
+        self.rewrite_segment(&PlanNode::Having(plan.clone()))
+    }
+
+    fn rewrite_sort(&mut self, plan: &SortPlan) -> Result<PlanNode> {
+        self.rewrite_segment(&PlanNode::Sort(plan.clone()))
+    }
+
+    fn rewrite_limit(&mut self, plan: &LimitPlan) -> Result<PlanNode> {
+        self.rewrite_segment(&PlanNode::Limit(plan.clone()))
+    }
+}
+
+impl CommonSubexpressionEliminationImpl {
+    pub fn new() -> CommonSubexpressionEliminationImpl {
+        CommonSubexpressionEliminationImpl {}
+    }
+
+    // The nodes below the top of a segment, they output all the columns of the input.
+    fn is_pass_through(plan: &PlanNode) -> bool {
+        matches!(
+            plan,
+            PlanNode::Expression(_)
+                | PlanNode::Filter(_)
+                | PlanNode::Having(_)
+                | PlanNode::Sort(_)
+                | PlanNode::Limit(_)
+        )
+    }
+
+    // The segment is the plan and the pass through nodes below it, the hidden columns computed
+    // at the bottom of the segment are available to all of its nodes.
+    fn rewrite_segment(&mut self, plan: &PlanNode) -> Result<PlanNode> {
+        // From the top to the bottom.
+        let mut nodes = vec![plan.clone()];
+        let mut base = plan.input(0);
+        while Self::is_pass_through(&base) {
+            nodes.push(base.as_ref().clone());
+            base = base.input(0);
+        }
+
+        let mut new_plan = self.rewrite_plan_node(&base)?;
+        let common = Self::common_subexpressions(&nodes, &base.schema())?;
+        if common.is_empty() {
+            for node in nodes.iter().rev() {
+                let mut node = node.clone();
+                node.set_inputs(vec![&new_plan])?;
+                new_plan = node;
+            }
+            return Ok(new_plan);
+        }
+
+        new_plan = PlanBuilder::from(&new_plan)
+            .expression(&common, "Common Subexpressions")?
+            .build()?;
+        for node in nodes.iter().rev() {
+            new_plan = match node {
+                // The schema of the expression plan includes the input columns.
+                PlanNode::Expression(plan) => PlanBuilder::from(&new_plan)
+                    .expression(&plan.exprs, &plan.desc)?
+                    .build()?,
+                _ => {
+                    let mut node = node.clone();
+                    node.set_inputs(vec![&new_plan])?;
+                    node
+                }
+            };
+        }
+        Ok(new_plan)
+    }
+
+    // The subexpressions evaluated by more than one node of the segment and computable from
+    // the columns of the base, in the order of the first use from the bottom.
+    fn common_subexpressions(
+        nodes: &[PlanNode],
+        base_schema: &DataSchemaRef,
+    ) -> Result<Vec<Expression>> {
+        let mut uses: Vec<(Expression, usize)> = vec![];
+        for node in nodes.iter().rev() {
+            let exprs = match node {
+                PlanNode::Projection(plan) => plan.expr.clone(),
+                PlanNode::Expression(plan) => plan.exprs.clone(),
+                PlanNode::Filter(plan) => vec![plan.predicate.clone()],
+                PlanNode::Having(plan) => vec![plan.predicate.clone()],
+                _ => vec![],
+            };
+
+            let input_schema = node.input(0).schema();
+            let mut evaluated = vec![];
+            for expr in exprs.iter() {
+                collect_evaluated(expr, &input_schema, &mut evaluated)?;
+            }
+
+            for expr in evaluated {
+                match uses.iter_mut().find(|(used, _)| used == &expr) {
+                    Some((_, count)) => *count += 1,
+                    None => uses.push((expr, 1)),
+                }
+            }
+        }
+
+        let mut common = vec![];
+        for (expr, count) in uses {
+            if count > 1 && is_computable(&expr, base_schema)? {
+                common.push(expr);
+            }
+        }
+        Ok(common)
+    }
+}
+
+// Collects the function calls the expression executor evaluates for the expression,
+// the columns already in the input are not evaluated again.
+fn collect_evaluated(
+    expr: &Expression,
+    input_schema: &DataSchemaRef,
+    evaluated: &mut Vec<Expression>,
+) -> Result<()> {
+    if input_schema.field_with_name(&expr.column_name()).is_ok() {
+        return Ok(());
+    }
+
+    match expr {
+        Expression::AggregateFunction { .. } => return Ok(()),
+        Expression::UnaryExpression { .. }
+        | Expression::BinaryExpression { .. }
+        | Expression::ScalarFunction { .. }
+        | Expression::Cast { .. } => {
+            if !evaluated.contains(expr) {
+                evaluated.push(expr.clone());
+            }
+        }
+        _ => {}
+    }
+
+    for child in RewriteHelper::expression_plan_children(expr)? {
+        collect_evaluated(&child, input_schema, evaluated)?;
+    }
+    Ok(())
+}
+
+fn is_computable(expr: &Expression, base_schema: &DataSchemaRef) -> Result<bool> {
+    for column in RewriteHelper::expression_plan_columns(expr)? {
+        if let Expression::Column(name) = column {
+            if base_schema.field_with_name(&name).is_err() {
+                return Ok(false);
+            }
+        }
+    }
+    is_deterministic(expr)
+}
+
+impl Optimizer for CommonSubexpressionEliminationOptimizer {
+    fn name(&self) -> &str {
+        "CommonSubexpressionElimination"
+    }
+
+    fn pattern(&self) -> &[&str] {
+        &["FilterPlan", "HavingPlan", "ExpressionPlan"]
+    }
+
+    fn optimize(&mut self, plan: &PlanNode) -> Result<PlanNode> {
+        let mut visitor = CommonSubexpressionEliminationImpl::new();
+        visitor.rewrite_plan_node(plan)
+    }
+}
+
+impl CommonSubexpressionEliminationOptimizer {
+    pub fn create(_ctx: FuseQueryContextRef) -> Self {
+        CommonSubexpressionEliminationOptimizer {}
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use pretty_assertions::assert_eq;
+
+use crate::optimizers::*;
+use crate::sql::*;
+
+#[test]
+fn test_common_subexpression_elimination_optimizer() -> anyhow::Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        query: &'static str,
+        expect: &'static str,
+    }
+
+    let tests = vec![
+        Test {
+            name: "filter-and-projection",
+            query: "select number % 3 + 1 as a, (number % 3) * 2 as b from numbers_mt(10) where number % 3 > 0",
+            expect: "\
+            Projection: ((number % 3) + 1) as a:UInt64, ((number % 3) * 2) as b:UInt64\
+            \n  Expression: ((number % 3) + 1):UInt64, ((number % 3) * 2):UInt64 (Before Projection)\
+            \n    Filter: ((number % 3) > 0)\
+            \n      Expression: (number % 3):UInt64 (Common Subexpressions)\
+            \n        ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]",
+        },
+        Test {
+            name: "filter-and-group-by",
+            query: "select sum(number % 3) from numbers_mt(10) where number % 3 > 0",
+            expect: "\
+            Projection: sum((number % 3)):UInt64\
+            \n  AggregatorFinal: groupBy=[[]], aggr=[[sum((number % 3))]]\
+            \n    AggregatorPartial: groupBy=[[]], aggr=[[sum((number % 3))]]\
+            \n      Expression: (number % 3):UInt64 (Before GroupBy)\
+            \n        Filter: ((number % 3) > 0)\
+            \n          Expression: (number % 3):UInt64 (Common Subexpressions)\
+            \n            ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]",
+        },
+        Test {
+            name: "no-common-subexpressions",
+            query: "select number + 1 from numbers_mt(10) where number % 3 > 0",
+            expect: "\
+            Projection: (number + 1):UInt64\
+            \n  Expression: (number + 1):UInt64 (Before Projection)\
+            \n    Filter: ((number % 3) > 0)\
+            \n      ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]",
+        },
+    ];
+
+    for test in tests {
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(test.query)?;
+
+        let mut optimizer = CommonSubexpressionEliminationOptimizer::create(ctx.clone());
+        let optimized = optimizer.optimize(&plan)?;
+        let actual = format!("{:?}", optimized);
+        assert_eq!(test.expect, actual, "{:#?}", test.name);
+
+        // The columns computed once are not computed again.
+        let reoptimized = optimizer.optimize(&optimized)?;
+        assert_eq!(optimized, reoptimized, "{:#?}", test.name);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_common_subexpression_elimination_execute() -> anyhow::Result<()> {
+    use futures::TryStreamExt;
+
+    use crate::interpreters::*;
+
+    let ctx = crate::tests::try_create_context()?;
+    let plan = PlanParser::create(ctx.clone()).build_from_sql(
+        "select number % 3 + 1 as a, (number % 3) * 2 as b from numbers_mt(6) where number % 3 > 0 order by number % 3 + 1, b",
+    )?;
+    let executor = InterpreterFactory::get(ctx, plan)?;
+    let stream = executor.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let expected = vec![
+        "+---+---+",
+        "| a | b |",
+        "+---+---+",
+        "| 2 | 2 |",
+        "| 2 | 2 |",
+        "| 3 | 4 |",
+        "| 3 | 4 |",
+        "+---+---+",
+    ];
+    common_datablocks::assert_blocks_eq(expected, result.as_slice());
+
+    Ok(())
+}
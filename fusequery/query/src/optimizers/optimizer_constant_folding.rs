@@ -2,103 +2,169 @@
 //
 // SPDX-License-Identifier: Apache-2.0.
 
+use std::cmp::Ordering;
 use std::sync::Arc;
 
 use common_arrow::arrow::datatypes::DataType;
+use common_datavalues::DataColumnarValue;
 use common_datavalues::DataSchemaRef;
 use common_datavalues::DataValue;
 use common_exception::Result;
+use common_functions::CastFunction;
+use common_functions::Function;
+use common_functions::FunctionFactory;
+use common_planners::compare_values;
+use common_planners::ColumnRange;
 use common_planners::Expression;
 use common_planners::ExpressionPlan;
 use common_planners::FilterPlan;
+use common_planners::HavingPlan;
 use common_planners::PlanNode;
 use common_planners::PlanRewriter;
+use common_planners::ReadDataSourcePlan;
+use common_planners::RewriteHelper;
+use common_planners::Statistics;
 
 use crate::optimizers::Optimizer;
 use crate::sessions::FuseQueryContextRef;
 
+/// Folds the constant expressions and simplifies the expressions of the filters and the expressions:
+/// 1. deterministic functions of constants are evaluated: 1 + 2 => 3
+/// 2. boolean simplification: a and true => a, not not a => a, a = true => a
+/// 3. arithmetic identities: a + 0 => a, a * 1 => a
+/// 4. comparison normalization: 1 < a => a > 1
+/// A filter which can't match any row, such as 1 = 2 or a > 5 and a < 3, reads nothing from the sources.
 pub struct ConstantFoldingOptimizer {}
 
-fn is_boolean_type(schema: &DataSchemaRef, expr: &Expression) -> Result<bool> {
-    if let DataType::Boolean = expr.to_data_field(schema)?.data_type() {
-        return Ok(true);
+// The type is unknown if the expression refers to the columns out of the schema, such as the aggregate
+// functions in the having.
+fn is_boolean_type(schema: &DataSchemaRef, expr: &Expression) -> bool {
+    matches!(expr.to_data_type(schema), Ok(DataType::Boolean))
+}
+
+fn is_boolean_literal(expr: &Expression, value: Option<bool>) -> bool {
+    matches!(expr, Expression::Literal(DataValue::Boolean(v)) if *v == value)
+}
+
+fn is_number_literal(expr: &Expression, value: i64) -> bool {
+    match expr {
+        Expression::Literal(v) => {
+            compare_values(v, &DataValue::Int64(Some(value))) == Some(Ordering::Equal)
+        }
+        _ => false,
+    }
+}
+
+/// Whether the expression is evaluated to the same value every time.
+pub fn is_deterministic(expr: &Expression) -> Result<bool> {
+    Ok(match expr {
+        Expression::Alias(_, expr)
+        | Expression::Cast { expr, .. }
+        | Expression::Sort { expr, .. } => is_deterministic(expr)?,
+        Expression::UnaryExpression { op, expr } => {
+            FunctionFactory::get(op)?.is_deterministic() && is_deterministic(expr)?
+        }
+        Expression::BinaryExpression { left, op, right } => {
+            FunctionFactory::get(op)?.is_deterministic()
+                && is_deterministic(left)?
+                && is_deterministic(right)?
+        }
+        Expression::ScalarFunction { op, args } => {
+            let mut deterministic = FunctionFactory::get(op)?.is_deterministic();
+            for arg in args {
+                deterministic = deterministic && is_deterministic(arg)?;
+            }
+            deterministic
+        }
+        Expression::AggregateFunction { args, .. } => {
+            let mut deterministic = true;
+            for arg in args {
+                deterministic = deterministic && is_deterministic(arg)?;
+            }
+            deterministic
+        }
+        _ => true,
+    })
+}
+
+/// Evaluates the function if all the arguments are literals, the errors are left to the execution.
+fn evaluate(func: Box<dyn Function>, args: &[Expression]) -> Option<Expression> {
+    if !func.is_deterministic() {
+        return None;
+    }
+
+    let mut columns = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg {
+            Expression::Literal(value) => {
+                columns.push(DataColumnarValue::Constant(value.clone(), 1))
+            }
+            _ => return None,
+        }
+    }
+
+    let value = match func.eval(&columns, 1).ok()? {
+        DataColumnarValue::Constant(value, _) => value,
+        DataColumnarValue::Array(array) => DataValue::try_from_array(&array, 0).ok()?,
+    };
+    Some(Expression::Literal(value))
+}
+
+/// The expression can be replaced by the operand only if the result type is kept.
+fn is_same_type(schema: &DataSchemaRef, expr: &Expression, operand: &Expression) -> bool {
+    match (expr.to_data_type(schema), operand.to_data_type(schema)) {
+        (Ok(expr_type), Ok(operand_type)) => expr_type == operand_type,
+        _ => false,
     }
-    Ok(false)
 }
 
 struct ConstantFoldingImpl {}
 
 fn constant_folding(schema: &DataSchemaRef, expr: Expression) -> Result<Expression> {
+    match &expr {
+        Expression::Column(_) | Expression::Literal(_) => return Ok(expr),
+        // The expression is computed by the input already, such as the group by keys.
+        _ if schema.field_with_name(&expr.column_name()).is_ok() => return Ok(expr),
+        _ => {}
+    }
+
     let new_expr = match expr {
-        Expression::BinaryExpression { left, op, right } => match op.as_str() {
-            "=" => match (left.as_ref(), right.as_ref()) {
-                (
-                    Expression::Literal(DataValue::Boolean(l)),
-                    Expression::Literal(DataValue::Boolean(r)),
-                ) => match (l, r) {
-                    (Some(l), Some(r)) => Expression::Literal(DataValue::Boolean(Some(l == r))),
-                    _ => Expression::Literal(DataValue::Boolean(None)),
-                },
-                (Expression::Literal(DataValue::Boolean(b)), _)
-                    if is_boolean_type(schema, &right)? =>
-                {
-                    match b {
-                        Some(true) => *right,
-                        // Fix this after we implement NOT
-                        Some(false) => Expression::BinaryExpression { left, op, right },
-                        None => Expression::Literal(DataValue::Boolean(None)),
-                    }
-                }
-                (_, Expression::Literal(DataValue::Boolean(b)))
-                    if is_boolean_type(schema, &left)? =>
-                {
-                    match b {
-                        Some(true) => *left,
-                        // Fix this after we implement NOT
-                        Some(false) => Expression::BinaryExpression { left, op, right },
-                        None => Expression::Literal(DataValue::Boolean(None)),
-                    }
-                }
-                _ => Expression::BinaryExpression {
-                    left,
-                    op: "=".to_string(),
-                    right,
-                },
-            },
-            "!=" => match (left.as_ref(), right.as_ref()) {
-                (
-                    Expression::Literal(DataValue::Boolean(l)),
-                    Expression::Literal(DataValue::Boolean(r)),
-                ) => match (l, r) {
-                    (Some(l), Some(r)) => Expression::Literal(DataValue::Boolean(Some(l != r))),
-                    _ => Expression::Literal(DataValue::Boolean(None)),
-                },
-                (Expression::Literal(DataValue::Boolean(b)), _)
-                    if is_boolean_type(schema, &right)? =>
-                {
-                    match b {
-                        Some(true) => Expression::BinaryExpression { left, op, right },
-                        Some(false) => *right,
-                        None => Expression::Literal(DataValue::Boolean(None)),
-                    }
-                }
-                (_, Expression::Literal(DataValue::Boolean(b)))
-                    if is_boolean_type(schema, &left)? =>
-                {
-                    match b {
-                        Some(true) => Expression::BinaryExpression { left, op, right },
-                        Some(false) => *left,
-                        None => Expression::Literal(DataValue::Boolean(None)),
+        Expression::Alias(name, expr) => {
+            Expression::Alias(name, Box::new(constant_folding(schema, *expr)?))
+        }
+        Expression::UnaryExpression { op, expr } => {
+            let expr = constant_folding(schema, *expr)?;
+            simplify_unary(schema, op, expr)?
+        }
+        Expression::BinaryExpression { left, op, right } => {
+            let left = constant_folding(schema, *left)?;
+            let right = constant_folding(schema, *right)?;
+            simplify_binary(schema, op, left, right)?
+        }
+        Expression::ScalarFunction { op, args } => {
+            let args = args
+                .into_iter()
+                .map(|arg| constant_folding(schema, arg))
+                .collect::<Result<Vec<_>>>()?;
+            match evaluate(FunctionFactory::get(&op)?, &args) {
+                Some(folded) => folded,
+                None => Expression::ScalarFunction { op, args },
+            }
+        }
+        Expression::Cast { expr, data_type } => {
+            let expr = constant_folding(schema, *expr)?;
+            let args = [expr];
+            match evaluate(CastFunction::create(data_type.clone()), &args) {
+                Some(folded) => folded,
+                None => {
+                    let [expr] = args;
+                    Expression::Cast {
+                        expr: Box::new(expr),
+                        data_type,
                     }
                 }
-                _ => Expression::BinaryExpression {
-                    left,
-                    op: "!=".to_string(),
-                    right,
-                },
-            },
-            _ => Expression::BinaryExpression { left, op, right },
-        },
+            }
+        }
         expr => {
             // do nothing
             expr
@@ -107,32 +173,241 @@ fn constant_folding(schema: &DataSchemaRef, expr: Expression) -> Result<Expressi
     Ok(new_expr)
 }
 
+fn simplify_unary(schema: &DataSchemaRef, op: String, expr: Expression) -> Result<Expression> {
+    let args = [expr];
+    if let Some(folded) = evaluate(FunctionFactory::get(&op)?, &args) {
+        return Ok(folded);
+    }
+
+    let [expr] = args;
+    match (op.to_lowercase().as_str(), expr) {
+        // not not a => a
+        ("not", Expression::UnaryExpression { op: inner_op, expr })
+            if inner_op.to_lowercase() == "not" && is_boolean_type(schema, &expr) =>
+        {
+            Ok(*expr)
+        }
+        (_, expr) => Ok(Expression::UnaryExpression {
+            op,
+            expr: Box::new(expr),
+        }),
+    }
+}
+
+fn simplify_binary(
+    schema: &DataSchemaRef,
+    op: String,
+    left: Expression,
+    right: Expression,
+) -> Result<Expression> {
+    let args = [left, right];
+    if let Some(folded) = evaluate(FunctionFactory::get(&op)?, &args) {
+        return Ok(folded);
+    }
+
+    let [left, right] = args;
+    let expr = Expression::BinaryExpression {
+        left: Box::new(left),
+        op: op.clone(),
+        right: Box::new(right),
+    };
+    let (left, right) = match &expr {
+        Expression::BinaryExpression { left, right, .. } => (left.as_ref(), right.as_ref()),
+        _ => unreachable!(),
+    };
+
+    let new_expr = match op.to_lowercase().as_str() {
+        "and" => {
+            if (is_boolean_literal(left, Some(false)) && is_deterministic(right)?)
+                || (is_boolean_literal(right, Some(false)) && is_deterministic(left)?)
+            {
+                Expression::Literal(DataValue::Boolean(Some(false)))
+            } else if is_boolean_literal(left, Some(true)) && is_boolean_type(schema, right) {
+                right.clone()
+            } else if is_boolean_literal(right, Some(true)) && is_boolean_type(schema, left) {
+                left.clone()
+            } else {
+                expr
+            }
+        }
+        "or" => {
+            if (is_boolean_literal(left, Some(true)) && is_deterministic(right)?)
+                || (is_boolean_literal(right, Some(true)) && is_deterministic(left)?)
+            {
+                Expression::Literal(DataValue::Boolean(Some(true)))
+            } else if is_boolean_literal(left, Some(false)) && is_boolean_type(schema, right) {
+                right.clone()
+            } else if is_boolean_literal(right, Some(false)) && is_boolean_type(schema, left) {
+                left.clone()
+            } else {
+                expr
+            }
+        }
+        "=" | "!=" | "<>" => match (left, right) {
+            (Expression::Literal(DataValue::Boolean(b)), other)
+            | (other, Expression::Literal(DataValue::Boolean(b)))
+                if is_boolean_type(schema, other) =>
+            {
+                // a = true => a, a != true => not a
+                match (op.as_str() == "=", b) {
+                    (_, None) => Expression::Literal(DataValue::Boolean(None)),
+                    (true, Some(true)) | (false, Some(false)) => other.clone(),
+                    _ => common_planners::not(other.clone()),
+                }
+            }
+            (Expression::Literal(_), Expression::Literal(_)) => expr,
+            // 1 = a => a = 1
+            (Expression::Literal(_), _) => swap_binary(&op, right, left),
+            _ => expr,
+        },
+        "<" | "<=" | ">" | ">=" => match (left, right) {
+            (Expression::Literal(_), Expression::Literal(_)) => expr,
+            // 1 < a => a > 1
+            (Expression::Literal(_), _) => {
+                let swapped_op = match op.as_str() {
+                    "<" => ">",
+                    "<=" => ">=",
+                    ">" => "<",
+                    _ => "<=",
+                };
+                swap_binary(swapped_op, right, left)
+            }
+            _ => expr,
+        },
+        "+" => {
+            if is_number_literal(right, 0) && is_same_type(schema, &expr, left) {
+                left.clone()
+            } else if is_number_literal(left, 0) && is_same_type(schema, &expr, right) {
+                right.clone()
+            } else {
+                expr
+            }
+        }
+        "-" if is_number_literal(right, 0) && is_same_type(schema, &expr, left) => left.clone(),
+        "*" => {
+            if is_number_literal(right, 1) && is_same_type(schema, &expr, left) {
+                left.clone()
+            } else if is_number_literal(left, 1) && is_same_type(schema, &expr, right) {
+                right.clone()
+            } else {
+                expr
+            }
+        }
+        "/" if is_number_literal(right, 1) && is_same_type(schema, &expr, left) => left.clone(),
+        _ => expr,
+    };
+    Ok(new_expr)
+}
+
+fn swap_binary(op: &str, left: &Expression, right: &Expression) -> Expression {
+    Expression::BinaryExpression {
+        left: Box::new(left.clone()),
+        op: op.to_string(),
+        right: Box::new(right.clone()),
+    }
+}
+
+/// Whether the predicate can't match any row.
+fn is_contradiction(schema: &DataSchemaRef, predicate: &Expression) -> Result<bool> {
+    if let Expression::Literal(value) = predicate {
+        return Ok(is_boolean_literal(predicate, Some(false)) || value.is_null());
+    }
+
+    for column in RewriteHelper::expression_plan_columns(predicate)? {
+        // The columns out of the schema, such as the aggregate functions, are unknown.
+        let data_type = match column.to_data_type(schema) {
+            Ok(data_type) => data_type,
+            Err(_) => continue,
+        };
+        let range = ColumnRange::from_filters(
+            std::slice::from_ref(predicate),
+            &column.column_name(),
+            &data_type,
+        );
+        if range.is_empty() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+impl ConstantFoldingImpl {
+    pub fn new() -> ConstantFoldingImpl {
+        ConstantFoldingImpl {}
+    }
+
+    /// Folds the predicate of the filter or having, None if it's always true and the plan can be removed.
+    fn rewrite_predicate(
+        &mut self,
+        predicate: &Expression,
+        input: &PlanNode,
+    ) -> Result<(Option<Expression>, PlanNode)> {
+        let schema = input.schema();
+        let predicate = constant_folding(&schema, predicate.clone())?;
+        let input = self.rewrite_plan_node(input)?;
+
+        if is_boolean_literal(&predicate, Some(true)) {
+            return Ok((None, input));
+        }
+        if is_contradiction(&schema, &predicate)? {
+            let input = EmptySourceImpl {}.rewrite_plan_node(&input)?;
+            let predicate = Expression::Literal(DataValue::Boolean(Some(false)));
+            return Ok((Some(predicate), input));
+        }
+        Ok((Some(predicate), input))
+    }
+}
+
 impl<'plan> PlanRewriter<'plan> for ConstantFoldingImpl {
     fn rewrite_filter(&mut self, plan: &FilterPlan) -> Result<PlanNode> {
-        let schema = plan.schema();
-        let mut new_plan = plan.clone();
-        new_plan.predicate = constant_folding(&schema, plan.predicate.clone())?;
-        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
-        Ok(PlanNode::Filter(new_plan))
+        match self.rewrite_predicate(&plan.predicate, &plan.input)? {
+            (None, input) => Ok(input),
+            (Some(predicate), input) => Ok(PlanNode::Filter(FilterPlan {
+                predicate,
+                input: Arc::new(input),
+            })),
+        }
+    }
+
+    fn rewrite_having(&mut self, plan: &HavingPlan) -> Result<PlanNode> {
+        match self.rewrite_predicate(&plan.predicate, &plan.input)? {
+            (None, input) => Ok(input),
+            (Some(predicate), input) => Ok(PlanNode::Having(HavingPlan {
+                predicate,
+                input: Arc::new(input),
+            })),
+        }
     }
 
     fn rewrite_expression(&mut self, plan: &ExpressionPlan) -> Result<PlanNode> {
         let mut new_exprs = Vec::new();
-        let schema = plan.schema();
+        let schema = plan.input.schema();
 
         for e in plan.exprs.as_slice() {
-            let new_expr = constant_folding(&schema, e.clone())?;
+            // Keep the column name, the plans above refer to the column by name.
+            let name = e.column_name();
+            let new_expr = match constant_folding(&schema, e.clone())? {
+                new_expr if new_expr.column_name() == name => new_expr,
+                new_expr => Expression::Alias(name, Box::new(new_expr)),
+            };
             new_exprs.push(new_expr);
         }
         let mut new_plan = plan.clone();
         new_plan.exprs = new_exprs;
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
         Ok(PlanNode::Expression(new_plan))
     }
 }
 
-impl ConstantFoldingImpl {
-    pub fn new() -> ConstantFoldingImpl {
-        ConstantFoldingImpl {}
+/// Clears the partitions of the sources under a contradiction, nothing needs to be read.
+struct EmptySourceImpl {}
+
+impl<'plan> PlanRewriter<'plan> for EmptySourceImpl {
+    fn rewrite_read_data_source(&mut self, plan: &ReadDataSourcePlan) -> Result<PlanNode> {
+        let mut new_plan = plan.clone();
+        new_plan.partitions = vec![];
+        new_plan.statistics = Statistics::default();
+        Ok(PlanNode::ReadSource(new_plan))
     }
 }
 
@@ -141,6 +416,10 @@ impl Optimizer for ConstantFoldingOptimizer {
         "ConstantFolding"
     }
 
+    fn pattern(&self) -> &[&str] {
+        &["FilterPlan", "HavingPlan", "ExpressionPlan"]
+    }
+
     fn optimize(&mut self, plan: &PlanNode) -> Result<PlanNode> {
         let mut visitor = ConstantFoldingImpl::new();
         visitor.rewrite_plan_node(plan)
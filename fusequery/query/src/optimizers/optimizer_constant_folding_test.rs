@@ -13,6 +13,7 @@ mod tests {
 
     use crate::optimizers::optimizer_test::*;
     use crate::optimizers::*;
+    use crate::sql::*;
 
     #[test]
     fn test_constant_folding_optimizer() -> anyhow::Result<()> {
@@ -65,4 +66,148 @@ mod tests {
         assert_eq!(expect, actual);
         Ok(())
     }
+
+    #[test]
+    fn test_constant_folding_optimizer_rules() -> anyhow::Result<()> {
+        let ctx = crate::tests::try_create_context()?;
+
+        let statistics = Statistics {
+            read_rows: 100,
+            read_bytes: 800,
+        };
+        let source_plan = PlanNode::ReadSource(ReadDataSourcePlan {
+            db: "system".to_string(),
+            table: "test".to_string(),
+            schema: DataSchemaRefExt::create(vec![
+                DataField::new("a", DataType::Int64, false),
+                DataField::new("b", DataType::Boolean, false),
+            ]),
+            partitions: generate_partitions(8, 100),
+            statistics,
+            description: "".to_string(),
+            scan_plan: Arc::new(ScanPlan::empty()),
+            remote: false,
+        });
+
+        #[allow(dead_code)]
+        struct Test {
+            name: &'static str,
+            predicate: Expression,
+            expect: &'static str,
+        }
+
+        let tests = vec![
+            Test {
+                name: "fold-functions",
+                predicate: col("a").gt(add(lit(1i64), lit(2i64))),
+                expect: "\
+                Filter: (a > 3)\
+                \n  ReadDataSource: scan partitions: [8], scan schema: [a:Int64, b:Boolean], statistics: [read_rows: 100, read_bytes: 800]",
+            },
+            Test {
+                name: "boolean",
+                predicate: col("b").and(lit(true)).or(lit(false)),
+                expect: "\
+                Filter: b\
+                \n  ReadDataSource: scan partitions: [8], scan schema: [a:Int64, b:Boolean], statistics: [read_rows: 100, read_bytes: 800]",
+            },
+            Test {
+                name: "not-not",
+                predicate: not(not(col("b"))),
+                expect: "\
+                Filter: b\
+                \n  ReadDataSource: scan partitions: [8], scan schema: [a:Int64, b:Boolean], statistics: [read_rows: 100, read_bytes: 800]",
+            },
+            Test {
+                name: "boolean-not-equal",
+                predicate: col("b").not_eq(lit(true)),
+                expect: "\
+                Filter: (not b)\
+                \n  ReadDataSource: scan partitions: [8], scan schema: [a:Int64, b:Boolean], statistics: [read_rows: 100, read_bytes: 800]",
+            },
+            Test {
+                name: "arithmetic-identities",
+                predicate: add(col("a"), lit(0i64)).gt(Expression::BinaryExpression {
+                    left: Box::new(lit(1i64)),
+                    op: "*".to_string(),
+                    right: Box::new(col("a")),
+                }),
+                expect: "\
+                Filter: (a > a)\
+                \n  ReadDataSource: scan partitions: [8], scan schema: [a:Int64, b:Boolean], statistics: [read_rows: 100, read_bytes: 800]",
+            },
+            Test {
+                name: "comparison-normalization",
+                predicate: lit(1i64).lt(col("a")),
+                expect: "\
+                Filter: (a > 1)\
+                \n  ReadDataSource: scan partitions: [8], scan schema: [a:Int64, b:Boolean], statistics: [read_rows: 100, read_bytes: 800]",
+            },
+            Test {
+                name: "tautology",
+                predicate: lit(1i64).eq(lit(1i64)).or(col("b")),
+                expect: "\
+                ReadDataSource: scan partitions: [8], scan schema: [a:Int64, b:Boolean], statistics: [read_rows: 100, read_bytes: 800]",
+            },
+            Test {
+                name: "contradiction",
+                predicate: col("b").and(lit(1i64).eq(lit(2i64))),
+                expect: "\
+                Filter: false\
+                \n  ReadDataSource: scan partitions: [0], scan schema: [a:Int64, b:Boolean], statistics: [read_rows: 0, read_bytes: 0]",
+            },
+            Test {
+                name: "empty-between",
+                predicate: col("a").gt_eq(lit(5i64)).and(lit(3i64).gt_eq(col("a"))),
+                expect: "\
+                Filter: false\
+                \n  ReadDataSource: scan partitions: [0], scan schema: [a:Int64, b:Boolean], statistics: [read_rows: 0, read_bytes: 0]",
+            },
+            Test {
+                name: "non-deterministic",
+                predicate: Expression::ScalarFunction {
+                    op: "sleep".to_string(),
+                    args: vec![lit(0i64)],
+                }
+                .eq(add(lit(1i64), lit(1i64)))
+                .and(lit(false)),
+                expect: "\
+                Filter: ((sleep(0) = 2) and false)\
+                \n  ReadDataSource: scan partitions: [8], scan schema: [a:Int64, b:Boolean], statistics: [read_rows: 100, read_bytes: 800]",
+            },
+        ];
+
+        for test in tests {
+            let plan = PlanBuilder::from(&source_plan)
+                .filter(test.predicate)?
+                .build()?;
+
+            let mut optimizer = ConstantFoldingOptimizer::create(ctx.clone());
+            let optimized = optimizer.optimize(&plan)?;
+            let actual = format!("{:?}", optimized);
+            assert_eq!(test.expect, actual, "{:#?}", test.name);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_constant_folding_optimizer_keep_names() -> anyhow::Result<()> {
+        let ctx = crate::tests::try_create_context()?;
+
+        let plan = PlanParser::create(ctx.clone())
+            .build_from_sql("select number + 0, 1 + 2 from numbers_mt(10) where 2 > number")?;
+
+        let mut optimizer = ConstantFoldingOptimizer::create(ctx);
+        let optimized = optimizer.optimize(&plan)?;
+
+        let expect = "\
+        Projection: (number + 0):UInt64, (1 + 2):UInt64\
+        \n  Expression: number as (number + 0):UInt64, 3 as (1 + 2):UInt64 (Before Projection)\
+        \n    Filter: (number < 2)\
+        \n      ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]";
+        let actual = format!("{:?}", optimized);
+        assert_eq!(expect, actual);
+        Ok(())
+    }
 }
@@ -0,0 +1,136 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_planners::AggregatorFinalPlan;
+use common_planners::AggregatorPartialPlan;
+use common_planners::ExpressionPlan;
+use common_planners::FilterPlan;
+use common_planners::HavingPlan;
+use common_planners::LimitByPlan;
+use common_planners::LimitPlan;
+use common_planners::PlanNode;
+use common_planners::PlanRewriter;
+use common_planners::ProjectionPlan;
+use common_planners::ReadDataSourcePlan;
+use common_planners::ScanPlan;
+use common_planners::SortPlan;
+
+use crate::optimizers::Optimizer;
+use crate::sessions::FuseQueryContextRef;
+
+/// Pushes the limit down into the scan plan of the read source when there is no
+/// filter, sort or aggregation between them, each source stops reading after limit + offset rows.
+pub struct LimitPushDownOptimizer {}
+
+struct LimitPushDownImpl {
+    // The max rows the plan being rewritten needs to output.
+    limit: Option<usize>,
+}
+
+impl<'plan> PlanRewriter<'plan> for LimitPushDownImpl {
+    fn rewrite_aggregate_partial(&mut self, plan: &AggregatorPartialPlan) -> Result<PlanNode> {
+        self.limit = None;
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::AggregatorPartial(new_plan))
+    }
+
+    fn rewrite_aggregate_final(&mut self, plan: &AggregatorFinalPlan) -> Result<PlanNode> {
+        self.limit = None;
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::AggregatorFinal(new_plan))
+    }
+
+    fn rewrite_projection(&mut self, plan: &ProjectionPlan) -> Result<PlanNode> {
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::Projection(new_plan))
+    }
+
+    fn rewrite_expression(&mut self, plan: &ExpressionPlan) -> Result<PlanNode> {
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::Expression(new_plan))
+    }
+
+    fn rewrite_filter(&mut self, plan: &FilterPlan) -> Result<PlanNode> {
+        self.limit = None;
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::Filter(new_plan))
+    }
+
+    fn rewrite_having(&mut self, plan: &HavingPlan) -> Result<PlanNode> {
+        self.limit = None;
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::Having(new_plan))
+    }
+
+    fn rewrite_sort(&mut self, plan: &SortPlan) -> Result<PlanNode> {
+        self.limit = None;
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::Sort(new_plan))
+    }
+
+    fn rewrite_limit(&mut self, plan: &LimitPlan) -> Result<PlanNode> {
+        self.limit = plan.n.map(|n| n + plan.offset);
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::Limit(new_plan))
+    }
+
+    fn rewrite_limit_by(&mut self, plan: &LimitByPlan) -> Result<PlanNode> {
+        self.limit = None;
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::LimitBy(new_plan))
+    }
+
+    fn rewrite_read_data_source(&mut self, plan: &ReadDataSourcePlan) -> Result<PlanNode> {
+        let limit = match (self.limit.take(), plan.scan_plan.limit) {
+            (Some(l), Some(r)) => Some(std::cmp::min(l, r)),
+            (l, r) => l.or(r),
+        };
+
+        let mut new_plan = plan.clone();
+        new_plan.scan_plan = Arc::new(ScanPlan {
+            limit,
+            ..plan.scan_plan.as_ref().clone()
+        });
+        Ok(PlanNode::ReadSource(new_plan))
+    }
+}
+
+impl LimitPushDownImpl {
+    pub fn new() -> LimitPushDownImpl {
+        LimitPushDownImpl { limit: None }
+    }
+}
+
+impl Optimizer for LimitPushDownOptimizer {
+    fn name(&self) -> &str {
+        "LimitPushDown"
+    }
+
+    fn pattern(&self) -> &[&str] {
+        &["LimitPlan"]
+    }
+
+    fn optimize(&mut self, plan: &PlanNode) -> Result<PlanNode> {
+        let mut visitor = LimitPushDownImpl::new();
+        visitor.rewrite_plan_node(plan)
+    }
+}
+
+impl LimitPushDownOptimizer {
+    pub fn create(_ctx: FuseQueryContextRef) -> LimitPushDownOptimizer {
+        LimitPushDownOptimizer {}
+    }
+}
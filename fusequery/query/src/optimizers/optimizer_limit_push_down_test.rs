@@ -0,0 +1,109 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::sync::Arc;
+
+use common_datavalues::*;
+use common_planners::*;
+use pretty_assertions::assert_eq;
+
+use crate::optimizers::*;
+use crate::sql::*;
+
+#[test]
+fn test_limit_push_down_optimizer_sql() -> anyhow::Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+
+    let plan = PlanParser::create(ctx.clone())
+        .build_from_sql("select number from numbers_mt(100) limit 10 offset 5")?;
+
+    let mut optimizer = LimitPushDownOptimizer::create(ctx);
+    let optimized = optimizer.optimize(&plan)?;
+
+    let expect = "\
+        Limit: 10, 5\
+        \n  Projection: number:UInt64\
+        \n    ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 100, read_bytes: 800], push down limit: 15";
+    let actual = format!("{:?}", optimized);
+    assert_eq!(expect, actual);
+
+    Ok(())
+}
+
+#[test]
+fn test_limit_push_down_optimizer() -> anyhow::Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+
+    let source_plan = PlanNode::ReadSource(ReadDataSourcePlan {
+        db: "system".to_string(),
+        table: "test".to_string(),
+        schema: DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int64, false)]),
+        partitions: vec![],
+        statistics: Statistics::default(),
+        description: "".to_string(),
+        scan_plan: Arc::new(ScanPlan::empty()),
+        remote: false,
+    });
+
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        plan: PlanNode,
+        expect: &'static str,
+    }
+
+    let tests = vec![
+        Test {
+            name: "projection",
+            plan: PlanBuilder::from(&source_plan)
+                .project(&[add(col("a"), lit(1i64)).alias("b")])?
+                .limit(5)?
+                .build()?,
+            expect: "\
+            Limit: 5\
+            \n  Projection: (a + 1) as b:Int64\
+            \n    ReadDataSource: scan partitions: [0], scan schema: [a:Int64], statistics: [read_rows: 0, read_bytes: 0], push down limit: 5",
+        },
+        Test {
+            name: "filter",
+            plan: PlanBuilder::from(&source_plan)
+                .filter(col("a").gt(lit(1i64)))?
+                .limit(5)?
+                .build()?,
+            expect: "\
+            Limit: 5\
+            \n  Filter: (a > 1)\
+            \n    ReadDataSource: scan partitions: [0], scan schema: [a:Int64], statistics: [read_rows: 0, read_bytes: 0]",
+        },
+        Test {
+            name: "sort",
+            plan: PlanBuilder::from(&source_plan)
+                .sort(&[col("a")])?
+                .limit(5)?
+                .build()?,
+            expect: "\
+            Limit: 5\
+            \n  Sort: a:Int64\
+            \n    ReadDataSource: scan partitions: [0], scan schema: [a:Int64], statistics: [read_rows: 0, read_bytes: 0]",
+        },
+        Test {
+            name: "limit-all",
+            plan: PlanBuilder::from(&source_plan)
+                .limit_offset(None, 5)?
+                .build()?,
+            expect: "\
+            Limit: all, 5\
+            \n  ReadDataSource: scan partitions: [0], scan schema: [a:Int64], statistics: [read_rows: 0, read_bytes: 0]",
+        },
+    ];
+
+    for test in tests {
+        let mut optimizer = LimitPushDownOptimizer::create(ctx.clone());
+        let optimized = optimizer.optimize(&test.plan)?;
+        let actual = format!("{:?}", optimized);
+        assert_eq!(test.expect, actual, "{:#?}", test.name);
+    }
+
+    Ok(())
+}
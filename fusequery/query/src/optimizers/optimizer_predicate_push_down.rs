@@ -0,0 +1,216 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_planners::AggregatorFinalPlan;
+use common_planners::AggregatorPartialPlan;
+use common_planners::Expression;
+use common_planners::ExpressionPlan;
+use common_planners::FilterPlan;
+use common_planners::HavingPlan;
+use common_planners::LimitByPlan;
+use common_planners::LimitPlan;
+use common_planners::PlanNode;
+use common_planners::PlanRewriter;
+use common_planners::ProjectionPlan;
+use common_planners::ReadDataSourcePlan;
+use common_planners::RewriteHelper;
+use common_planners::ScanPlan;
+use common_planners::SortPlan;
+use common_planners::StagePlan;
+
+use crate::optimizers::is_deterministic;
+use crate::optimizers::Optimizer;
+use crate::sessions::FuseQueryContextRef;
+
+/// Pushes the conjunctive predicates of the filters down into the scan plan of the read source.
+/// The filter plan is kept, the pushed filters are only hints for the table to skip data,
+/// so a table may return more rows than the filters match but never less.
+pub struct PredicatePushDownOptimizer {}
+
+struct PredicatePushDownImpl {
+    // The filters in terms of the output columns of the plan being rewritten.
+    filters: Vec<Expression>,
+}
+
+impl<'plan> PlanRewriter<'plan> for PredicatePushDownImpl {
+    fn rewrite_aggregate_partial(&mut self, plan: &AggregatorPartialPlan) -> Result<PlanNode> {
+        self.filters.clear();
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::AggregatorPartial(new_plan))
+    }
+
+    fn rewrite_aggregate_final(&mut self, plan: &AggregatorFinalPlan) -> Result<PlanNode> {
+        self.filters.clear();
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::AggregatorFinal(new_plan))
+    }
+
+    fn rewrite_stage(&mut self, plan: &StagePlan) -> Result<PlanNode> {
+        self.filters.clear();
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::Stage(new_plan))
+    }
+
+    fn rewrite_projection(&mut self, plan: &ProjectionPlan) -> Result<PlanNode> {
+        self.rewrite_filters_through(&plan.expr)?;
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::Projection(new_plan))
+    }
+
+    fn rewrite_expression(&mut self, plan: &ExpressionPlan) -> Result<PlanNode> {
+        self.rewrite_filters_through(&plan.exprs)?;
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::Expression(new_plan))
+    }
+
+    fn rewrite_filter(&mut self, plan: &FilterPlan) -> Result<PlanNode> {
+        let mut conjunctions = vec![];
+        split_conjunctions(&plan.predicate, &mut conjunctions);
+        for conjunction in conjunctions {
+            if is_pushable(&conjunction)? {
+                self.filters.push(conjunction);
+            }
+        }
+
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::Filter(new_plan))
+    }
+
+    fn rewrite_having(&mut self, plan: &HavingPlan) -> Result<PlanNode> {
+        self.filters.clear();
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::Having(new_plan))
+    }
+
+    fn rewrite_sort(&mut self, plan: &SortPlan) -> Result<PlanNode> {
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::Sort(new_plan))
+    }
+
+    fn rewrite_limit(&mut self, plan: &LimitPlan) -> Result<PlanNode> {
+        self.filters.clear();
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::Limit(new_plan))
+    }
+
+    fn rewrite_limit_by(&mut self, plan: &LimitByPlan) -> Result<PlanNode> {
+        self.filters.clear();
+        let mut new_plan = plan.clone();
+        new_plan.input = Arc::new(self.rewrite_plan_node(&plan.input)?);
+        Ok(PlanNode::LimitBy(new_plan))
+    }
+
+    fn rewrite_read_data_source(&mut self, plan: &ReadDataSourcePlan) -> Result<PlanNode> {
+        let mut filters = plan.scan_plan.filters.clone();
+        for filter in std::mem::take(&mut self.filters) {
+            // Discard the filters on the columns the source does not have.
+            let columns = RewriteHelper::expression_plan_columns(&filter)?;
+            let all_found = columns.iter().all(|column| match column {
+                Expression::Column(name) => plan.schema.index_of(name).is_ok(),
+                _ => false,
+            });
+            if all_found && !filters.contains(&filter) {
+                filters.push(filter);
+            }
+        }
+
+        let mut new_plan = plan.clone();
+        new_plan.scan_plan = Arc::new(ScanPlan {
+            filters,
+            ..plan.scan_plan.as_ref().clone()
+        });
+        Ok(PlanNode::ReadSource(new_plan))
+    }
+}
+
+impl PredicatePushDownImpl {
+    pub fn new() -> PredicatePushDownImpl {
+        PredicatePushDownImpl { filters: vec![] }
+    }
+
+    // Rewrites the filters from the output columns of the expressions to the input columns.
+    fn rewrite_filters_through(&mut self, exprs: &[Expression]) -> Result<()> {
+        let mut map = HashMap::new();
+        for expr in exprs {
+            match expr {
+                Expression::Alias(name, expr) => {
+                    map.insert(name.clone(), expr.as_ref().clone());
+                }
+                Expression::Column(_) => {}
+                _ => {
+                    map.insert(expr.column_name(), expr.clone());
+                }
+            }
+        }
+
+        self.filters = RewriteHelper::rewrite_alias_exprs(&map, &self.filters)?;
+        Ok(())
+    }
+}
+
+// Splits the predicate "a and b and c" into [a, b, c].
+fn split_conjunctions(predicate: &Expression, conjunctions: &mut Vec<Expression>) {
+    match predicate {
+        Expression::BinaryExpression { left, op, right } if op.to_lowercase() == "and" => {
+            split_conjunctions(left, conjunctions);
+            split_conjunctions(right, conjunctions);
+        }
+        Expression::Alias(_, expr) => split_conjunctions(expr, conjunctions),
+        other => conjunctions.push(other.clone()),
+    }
+}
+
+// Only the predicates on the columns of the rows can be pushed down.
+// A non-deterministic predicate (e.g. with sleep) must be evaluated by the filter only.
+fn is_pushable(expr: &Expression) -> Result<bool> {
+    if !is_deterministic(expr)? {
+        return Ok(false);
+    }
+
+    match expr {
+        Expression::AggregateFunction { .. } | Expression::Wildcard => Ok(false),
+        _ => {
+            for child in RewriteHelper::expression_plan_children(expr)? {
+                if !is_pushable(&child)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+    }
+}
+
+impl Optimizer for PredicatePushDownOptimizer {
+    fn name(&self) -> &str {
+        "PredicatePushDown"
+    }
+
+    fn pattern(&self) -> &[&str] {
+        &["FilterPlan"]
+    }
+
+    fn optimize(&mut self, plan: &PlanNode) -> Result<PlanNode> {
+        let mut visitor = PredicatePushDownImpl::new();
+        visitor.rewrite_plan_node(plan)
+    }
+}
+
+impl PredicatePushDownOptimizer {
+    pub fn create(_ctx: FuseQueryContextRef) -> PredicatePushDownOptimizer {
+        PredicatePushDownOptimizer {}
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::sync::Arc;
+
+use common_datavalues::*;
+use common_planners::*;
+use pretty_assertions::assert_eq;
+
+use crate::optimizers::*;
+use crate::sql::*;
+
+#[test]
+fn test_predicate_push_down_optimizer_sql() -> anyhow::Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+
+    #[allow(dead_code)]
+    struct Test {
+        name: &'static str,
+        query: &'static str,
+        expect: &'static str,
+    }
+
+    let tests = vec![
+        Test {
+            name: "conjunctions",
+            query: "select number from numbers_mt(10) where number > 1 and (number + 1) < 8 and number > 1",
+            expect: "\
+            Projection: number:UInt64\
+            \n  Filter: (((number > 1) and ((number + 1) < 8)) and (number > 1))\
+            \n    ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80], push down filters: [(number > 1), ((number + 1) < 8)]",
+        },
+        Test {
+            name: "disjunction",
+            query: "select number from numbers_mt(10) where number > 1 or number < 8",
+            expect: "\
+            Projection: number:UInt64\
+            \n  Filter: ((number > 1) or (number < 8))\
+            \n    ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80], push down filters: [((number > 1) or (number < 8))]",
+        },
+        Test {
+            name: "non-deterministic-not-pushed",
+            query: "select number from numbers_mt(10) where number > 1 and sleep(0) = 0",
+            expect: "\
+            Projection: number:UInt64\
+            \n  Filter: ((number > 1) and (sleep(0) = 0))\
+            \n    ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80], push down filters: [(number > 1)]",
+        },
+        Test {
+            name: "having-not-pushed",
+            query: "select number from numbers_mt(10) having number > 1",
+            expect: "\
+            Projection: number:UInt64\
+            \n  Having: (number > 1)\
+            \n    ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80]",
+        },
+    ];
+
+    for test in tests {
+        let plan = PlanParser::create(ctx.clone()).build_from_sql(test.query)?;
+
+        let mut optimizer = PredicatePushDownOptimizer::create(ctx.clone());
+        let optimized = optimizer.optimize(&plan)?;
+        let actual = format!("{:?}", optimized);
+        assert_eq!(test.expect, actual, "{:#?}", test.name);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_predicate_push_down_optimizer_through_projection() -> anyhow::Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+
+    let source_plan = PlanNode::ReadSource(ReadDataSourcePlan {
+        db: "system".to_string(),
+        table: "test".to_string(),
+        schema: DataSchemaRefExt::create(vec![
+            DataField::new("a", DataType::Int64, false),
+            DataField::new("b", DataType::Int64, false),
+        ]),
+        partitions: vec![],
+        statistics: Statistics::default(),
+        description: "".to_string(),
+        scan_plan: Arc::new(ScanPlan::empty()),
+        remote: false,
+    });
+
+    // SELECT * FROM (SELECT a AS x, b + 1 AS y FROM test) WHERE x > 1 AND y < 10 AND sum(x) > 1
+    let plan = PlanBuilder::from(&source_plan)
+        .project(&[col("a").alias("x"), add(col("b"), lit(1i64)).alias("y")])?
+        .filter(
+            col("x")
+                .gt(lit(1i64))
+                .and(col("y").lt(lit(10i64)))
+                .and(sum(col("x")).gt(lit(1i64))),
+        )?
+        .build()?;
+
+    let mut optimizer = PredicatePushDownOptimizer::create(ctx);
+    let optimized = optimizer.optimize(&plan)?;
+
+    let expect = "\
+        Filter: (((x > 1) and (y < 10)) and (sum(x) > 1))\
+        \n  Projection: a as x:Int64, (b + 1) as y:Int64\
+        \n    ReadDataSource: scan partitions: [0], scan schema: [a:Int64, b:Int64], statistics: [read_rows: 0, read_bytes: 0], push down filters: [(a > 1), ((b + 1) < 10)]";
+    let actual = format!("{:?}", optimized);
+    assert_eq!(expect, actual);
+
+    // The filters above the limit change the rows the limit takes, they stay where they are.
+    let plan = PlanBuilder::from(&source_plan)
+        .limit(10)?
+        .filter(col("a").gt(lit(1i64)))?
+        .build()?;
+    let optimized = optimizer.optimize(&plan)?;
+
+    let expect = "\
+        Filter: (a > 1)\
+        \n  Limit: 10\
+        \n    ReadDataSource: scan partitions: [0], scan schema: [a:Int64, b:Int64], statistics: [read_rows: 0, read_bytes: 0]";
+    let actual = format!("{:?}", optimized);
+    assert_eq!(expect, actual);
+
+    Ok(())
+}
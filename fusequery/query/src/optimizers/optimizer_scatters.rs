@@ -15,11 +15,13 @@ use common_planners::ReadDataSourcePlan;
 use common_planners::StageKind;
 use common_planners::StagePlan;
 
+use crate::optimizers::CostModel;
 use crate::optimizers::Optimizer;
 use crate::sessions::FuseQueryContextRef;
 
 pub struct ScattersOptimizer {
     ctx: FuseQueryContextRef,
+    cost_model: CostModel,
 }
 
 enum OptimizeKind {
@@ -29,7 +31,10 @@ enum OptimizeKind {
 
 impl ScattersOptimizer {
     pub fn create(ctx: FuseQueryContextRef) -> Self {
-        ScattersOptimizer { ctx }
+        ScattersOptimizer {
+            cost_model: CostModel::create(ctx.clone()),
+            ctx,
+        }
     }
 
     fn converge_stage_if_scattered(
@@ -67,13 +72,7 @@ impl ScattersOptimizer {
             .get_datasource()
             .get_table(plan.db.as_str(), plan.table.as_str())?;
 
-        let settings = self.ctx.get_settings();
-        let rows_threshold = settings.get_min_distributed_rows()? as usize;
-        let bytes_threshold = settings.get_min_distributed_bytes()? as usize;
-        if read_table.is_local()
-            && (plan.statistics.read_rows >= rows_threshold
-                || plan.statistics.read_bytes >= bytes_threshold)
-        {
+        if read_table.is_local() && self.cost_model.should_distribute(plan)? {
             // TODO: Need implement blockNumber function.
             // We use the blockNumber function as the scatter expr.
             // This will be DataBlock based round robin scheduling.
@@ -120,39 +119,39 @@ impl ScattersOptimizer {
             }));
         }
 
-        match plan.group_expr.len() {
-            0 => {
-                // If no group by we convergent it in local node
-                status.push(OptimizeKind::Local);
-                Ok(PlanNode::Stage(StagePlan {
-                    kind: StageKind::Convergent,
-                    scatters_expr: Expression::Literal(DataValue::UInt64(Some(0))),
-                    input: Arc::new(PlanNode::AggregatorPartial(AggregatorPartialPlan {
-                        group_expr: plan.group_expr.clone(),
-                        aggr_expr: plan.aggr_expr.clone(),
-                        schema: plan.schema.clone(),
-                        input: Arc::new(input),
-                    })),
-                }))
-            }
-            _ => {
-                // Keep running in cluster mode
-                status.push(OptimizeKind::Scattered);
-                Ok(PlanNode::Stage(StagePlan {
-                    kind: StageKind::Normal,
-                    scatters_expr: Expression::ScalarFunction {
-                        op: String::from("sipHash"),
-                        args: vec![Expression::Column(String::from("_group_by_key"))],
-                    },
-                    input: Arc::new(PlanNode::AggregatorPartial(AggregatorPartialPlan {
-                        group_expr: plan.group_expr.clone(),
-                        aggr_expr: plan.aggr_expr.clone(),
-                        schema: plan.schema.clone(),
-                        input: Arc::new(input),
-                    })),
-                }))
-            }
+        // If no group by or the estimated groups are few, we convergent it in local node
+        if self
+            .cost_model
+            .prefer_convergent_aggregate(&plan.group_expr, &input)?
+        {
+            status.push(OptimizeKind::Local);
+            return Ok(PlanNode::Stage(StagePlan {
+                kind: StageKind::Convergent,
+                scatters_expr: Expression::Literal(DataValue::UInt64(Some(0))),
+                input: Arc::new(PlanNode::AggregatorPartial(AggregatorPartialPlan {
+                    group_expr: plan.group_expr.clone(),
+                    aggr_expr: plan.aggr_expr.clone(),
+                    schema: plan.schema.clone(),
+                    input: Arc::new(input),
+                })),
+            }));
         }
+
+        // Keep running in cluster mode
+        status.push(OptimizeKind::Scattered);
+        Ok(PlanNode::Stage(StagePlan {
+            kind: StageKind::Normal,
+            scatters_expr: Expression::ScalarFunction {
+                op: String::from("sipHash"),
+                args: vec![Expression::Column(String::from("_group_by_key"))],
+            },
+            input: Arc::new(PlanNode::AggregatorPartial(AggregatorPartialPlan {
+                group_expr: plan.group_expr.clone(),
+                aggr_expr: plan.aggr_expr.clone(),
+                schema: plan.schema.clone(),
+                input: Arc::new(input),
+            })),
+        }))
     }
 }
 
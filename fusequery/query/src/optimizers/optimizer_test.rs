@@ -3,6 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0.
 
 use common_planners::*;
+use pretty_assertions::assert_eq;
+
+use crate::optimizers::Optimizers;
+use crate::sql::PlanParser;
 
 pub fn generate_partitions(workers: u64, total: u64) -> Partitions {
     let part_size = total / workers;
@@ -29,3 +33,59 @@ pub fn generate_partitions(workers: u64, total: u64) -> Partitions {
     }
     partitions
 }
+
+#[test]
+fn test_optimizers_to_fixed_point() -> anyhow::Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    let plan = PlanParser::create(ctx.clone())
+        .build_from_sql("select number from numbers_mt(10) where 1 = 1 limit 1")?;
+
+    let steps = Optimizers::create(ctx)?.optimize_with_steps(&plan)?;
+    let actual = steps
+        .iter()
+        .map(|step| (step.name.as_str(), step.iteration, step.plan.is_some()))
+        .collect::<Vec<_>>();
+
+    // The filter is removed by the constant folding, the predicate push down has nothing to match.
+    let expect = vec![
+        ("ConstantFolding", 1, true),
+        ("LimitPushDown", 1, true),
+        ("ProjectionPushDown", 1, false),
+        ("LimitPushDown", 2, false),
+        ("ProjectionPushDown", 2, false),
+        ("Scatters", 1, false),
+    ];
+    assert_eq!(expect, actual);
+
+    let expect = "\
+    Limit: 1\
+    \n  Projection: number:UInt64\
+    \n    ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80], push down limit: 1";
+    let actual = format!("{:?}", steps[1].plan.as_ref().unwrap());
+    assert_eq!(expect, actual);
+
+    Ok(())
+}
+
+#[test]
+fn test_optimizers_disable_rules() -> anyhow::Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings()
+        .update_settings("enable_constant_folding", "0".to_string())?;
+    ctx.get_settings()
+        .update_settings("enable_limit_push_down", "0".to_string())?;
+
+    let plan = PlanParser::create(ctx.clone())
+        .build_from_sql("select number from numbers_mt(10) where 1 = 1 limit 1")?;
+    let optimized = Optimizers::create(ctx)?.optimize(&plan)?;
+
+    let expect = "\
+    Limit: 1\
+    \n  Projection: number:UInt64\
+    \n    Filter: (1 = 1)\
+    \n      ReadDataSource: scan partitions: [8], scan schema: [number:UInt64], statistics: [read_rows: 10, read_bytes: 80], push down filters: [(1 = 1)]";
+    let actual = format!("{:?}", optimized);
+    assert_eq!(expect, actual);
+
+    Ok(())
+}
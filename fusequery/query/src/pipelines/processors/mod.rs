@@ -9,25 +9,50 @@ mod pipeline_builder_test;
 #[cfg(test)]
 mod pipeline_display_test;
 #[cfg(test)]
+mod pipeline_executor_test;
+#[cfg(test)]
 mod pipeline_walker_test;
 #[cfg(test)]
 mod processor_empty_test;
 #[cfg(test)]
+mod processor_hash_partition_test;
+#[cfg(test)]
+mod processor_merge_sorted_test;
+#[cfg(test)]
 mod processor_merge_test;
+#[cfg(test)]
+mod processor_resize_test;
 
 mod pipe;
 mod pipeline;
 mod pipeline_builder;
 mod pipeline_display;
+mod pipeline_executor;
 mod pipeline_walker;
+mod port;
 mod processor;
 mod processor_empty;
+mod processor_hash_partition;
 mod processor_merge;
+mod processor_merge_sorted;
+mod processor_resize;
 
 pub use pipe::Pipe;
 pub use pipeline::Pipeline;
 pub use pipeline_builder::PipelineBuilder;
+pub use pipeline_executor::PipelineExecutor;
+pub use pipeline_executor::PipelineExecutorRef;
+pub use port::create_port;
+pub use port::InputPort;
+pub use port::OutputPort;
+pub use port::TryPushError;
+pub use port::PORT_CAPACITY;
 pub use processor::FormatterSettings;
 pub use processor::Processor;
 pub use processor_empty::EmptyProcessor;
+pub use processor_hash_partition::HashPartitionProcessor;
 pub use processor_merge::MergeProcessor;
+pub use processor_merge_sorted::MergeSortedProcessor;
+pub use processor_resize::Distribution;
+pub use processor_resize::Distributor;
+pub use processor_resize::ResizeProcessor;
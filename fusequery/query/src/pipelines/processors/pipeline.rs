@@ -4,13 +4,19 @@
 
 use std::sync::Arc;
 
+use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_planners::Expression;
+use common_streams::AbortStream;
 use common_streams::SendableDataBlockStream;
 
+use crate::pipelines::processors::HashPartitionProcessor;
 use crate::pipelines::processors::MergeProcessor;
+use crate::pipelines::processors::MergeSortedProcessor;
 use crate::pipelines::processors::Pipe;
 use crate::pipelines::processors::Processor;
+use crate::pipelines::processors::ResizeProcessor;
 use crate::sessions::FuseQueryContextRef;
 
 pub struct Pipeline {
@@ -107,10 +113,127 @@ impl Pipeline {
         Ok(())
     }
 
+    /// Merge the sorted processors into one-way by a streaming k-way merge.
+    ///
+    /// processor1 --
+    ///               \
+    /// processor2      --> merge sorted processor
+    ///               /
+    /// processor3 --
+    ///
+    pub fn merge_sorted_processor(
+        &mut self,
+        schema: DataSchemaRef,
+        exprs: Vec<Expression>,
+        limit: Option<usize>,
+        block_size: usize,
+    ) -> Result<()> {
+        let last_pipe = self.last_pipe()?;
+        if last_pipe.nums() > 1 {
+            let mut merge = MergeSortedProcessor::try_create(
+                self.ctx.clone(),
+                schema,
+                exprs,
+                limit,
+                block_size,
+            )?;
+            for x in last_pipe.processors() {
+                merge.connect_to(x.clone())?;
+            }
+            let mut new_pipe = Pipe::create();
+            new_pipe.add(Arc::from(merge));
+            self.pipes.push(new_pipe);
+        }
+        Ok(())
+    }
+
+    /// Resize the N-ways processors into M-ways, the blocks are distributed by round-robin.
+    ///
+    /// processor1 --        -- resize processor1
+    ///               \    /
+    /// processor2     ----     -- resize processor2
+    ///               /    \
+    /// processor3 --        -- resize processor3
+    ///                         -- resize processor4
+    ///
+    pub fn resize(&mut self, ways: usize) -> Result<()> {
+        let last_pipe = self.last_pipe()?;
+        if ways <= 1 {
+            return self.merge_processor();
+        }
+        if last_pipe.nums() == ways {
+            return Ok(());
+        }
+
+        let mut outputs = ResizeProcessor::create(self.ctx.clone(), ways);
+        // The outputs share the inputs, they are connected once.
+        for x in last_pipe.processors() {
+            outputs[0].connect_to(x.clone())?;
+        }
+        let mut new_pipe = Pipe::create();
+        for output in outputs {
+            new_pipe.add(Arc::new(output));
+        }
+        self.pipes.push(new_pipe);
+        Ok(())
+    }
+
+    /// Partition the N-ways processors into M-ways by the hash of the key,
+    /// the rows of the same key go to the same processor.
+    ///
+    /// processor1 --        -- partition processor1 (hash % 3 == 0)
+    ///               \    /
+    /// processor2     ----     -- partition processor2 (hash % 3 == 1)
+    ///                    \
+    ///                      -- partition processor3 (hash % 3 == 2)
+    ///
+    pub fn partition_by_hash(
+        &mut self,
+        ways: usize,
+        schema: DataSchemaRef,
+        key: Expression,
+    ) -> Result<()> {
+        let last_pipe = self.last_pipe()?;
+        if ways <= 1 {
+            return self.merge_processor();
+        }
+
+        let mut outputs = HashPartitionProcessor::try_create(self.ctx.clone(), schema, key, ways)?;
+        // The outputs share the inputs, they are connected once.
+        for x in last_pipe.processors() {
+            outputs[0].connect_to(x.clone())?;
+        }
+        let mut new_pipe = Pipe::create();
+        for output in outputs {
+            new_pipe.add(Arc::new(output));
+        }
+        self.pipes.push(new_pipe);
+        Ok(())
+    }
+
     pub async fn execute(&mut self) -> Result<SendableDataBlockStream> {
         if self.last_pipe()?.nums() > 1 {
             self.merge_processor()?;
         }
-        self.last_pipe()?.first().execute().await
+        // The blocks of the long-running transforms, such as the merge of the spilled runs,
+        // are checked too, not only the blocks read by the sources.
+        let processor = self.last_pipe()?.first();
+        let deadline = self.ctx.get_deadline()?;
+        let stream = match deadline {
+            None => processor.execute().await?,
+            Some(deadline) => {
+                match tokio::time::timeout_at(deadline.into(), processor.execute()).await {
+                    Ok(stream) => stream?,
+                    Err(_) => {
+                        // The processors did not start before the max_execution_time.
+                        self.ctx.check_cancelled()?;
+                        return Err(ErrorCode::Timeout("Timeout exceeded"));
+                    }
+                }
+            }
+        };
+        Ok(Box::pin(
+            AbortStream::try_create(stream, self.ctx.abort_callback()?)?.with_deadline(deadline),
+        ))
     }
 }
@@ -8,6 +8,7 @@ use common_exception::ErrorCode;
 use common_exception::Result;
 use common_planners::AggregatorFinalPlan;
 use common_planners::AggregatorPartialPlan;
+use common_planners::Expression;
 use common_planners::ExpressionPlan;
 use common_planners::FilterPlan;
 use common_planners::HavingPlan;
@@ -62,34 +63,55 @@ impl PipelineBuilder {
             }
         })?;
 
+        // The partial aggregation computed by the remote table, the sources return the states
+        // and the column-only expressions between them and the aggregator are not needed.
+        let mut aggregation_push_down = None;
+        if self.ctx.get_settings().get_enable_aggregation_push_down()? != 0 {
+            self.plan.walk_preorder(|node| -> Result<bool> {
+                match node {
+                    PlanNode::AggregatorPartial(plan) => {
+                        aggregation_push_down = Self::aggregation_push_down(plan);
+                        Ok(aggregation_push_down.is_none())
+                    }
+                    _ => Ok(true),
+                }
+            })?;
+        }
+
+        let mut pushed_down = false;
         let mut pipeline = Pipeline::create(self.ctx.clone());
         self.plan.walk_postorder(|node| -> Result<bool> {
             match node {
+                PlanNode::ReadSource(plan) => match &aggregation_push_down {
+                    Some((source_plan, aggregation)) if source_plan == plan => {
+                        pushed_down = true;
+                        self.visit_read_data_source_plan(&mut pipeline, plan, Some(aggregation))
+                    }
+                    _ => self.visit_read_data_source_plan(&mut pipeline, plan, None),
+                },
+                PlanNode::Expression(_) if pushed_down => Ok(true),
+                PlanNode::AggregatorPartial(_) if pushed_down => {
+                    pushed_down = false;
+                    Ok(true)
+                }
                 PlanNode::Select(_) => Ok(true),
                 PlanNode::Stage(plan) => self.visit_stage_plan(&mut pipeline, &plan),
                 PlanNode::Remote(plan) => self.visit_remote_plan(&mut pipeline, &plan),
-                PlanNode::Expression(plan) => {
-                    PipelineBuilder::visit_expression_plan(&mut pipeline, plan)
-                }
-                PlanNode::Projection(plan) => {
-                    PipelineBuilder::visit_projection_plan(&mut pipeline, plan)
-                }
+                PlanNode::Expression(plan) => self.visit_expression_plan(&mut pipeline, plan),
+                PlanNode::Projection(plan) => self.visit_projection_plan(&mut pipeline, plan),
                 PlanNode::AggregatorPartial(plan) => {
-                    PipelineBuilder::visit_aggregator_partial_plan(&mut pipeline, plan)
+                    self.visit_aggregator_partial_plan(&mut pipeline, plan)
                 }
                 PlanNode::AggregatorFinal(plan) => {
-                    PipelineBuilder::visit_aggregator_final_plan(&mut pipeline, plan)
+                    self.visit_aggregator_final_plan(&mut pipeline, plan)
                 }
                 PlanNode::Filter(plan) => PipelineBuilder::visit_filter_plan(&mut pipeline, plan),
                 PlanNode::Having(plan) => PipelineBuilder::visit_having_plan(&mut pipeline, plan),
-                PlanNode::Sort(plan) => {
-                    PipelineBuilder::visit_sort_plan(limit, &mut pipeline, plan)
-                }
+                PlanNode::Sort(plan) => self.visit_sort_plan(limit, &mut pipeline, plan),
                 PlanNode::Limit(plan) => PipelineBuilder::visit_limit_plan(&mut pipeline, plan),
                 PlanNode::LimitBy(plan) => {
                     PipelineBuilder::visit_limit_by_plan(&mut pipeline, plan)
                 }
-                PlanNode::ReadSource(plan) => self.visit_read_data_source_plan(&mut pipeline, plan),
                 other => Result::Err(ErrorCode::UnknownPlan(format!(
                     "Build pipeline from the plan node unsupported:{:?}",
                     other.name()
@@ -120,7 +142,21 @@ impl PipelineBuilder {
         Ok(true)
     }
 
-    fn visit_expression_plan(pipeline: &mut Pipeline, plan: &ExpressionPlan) -> Result<bool> {
+    // The blocks after a merge come in no order, the expressions on them
+    // are spread over max_threads processors again.
+    fn resize_after_merge(&self, pipeline: &mut Pipeline) -> Result<()> {
+        if pipeline.last_pipe()?.name() == "MergeProcessor" {
+            pipeline.resize(self.ctx.get_max_threads()? as usize)?;
+        }
+        Ok(())
+    }
+
+    fn visit_expression_plan(
+        &self,
+        pipeline: &mut Pipeline,
+        plan: &ExpressionPlan,
+    ) -> Result<bool> {
+        self.resize_after_merge(pipeline)?;
         pipeline.add_simple_transform(|| {
             Ok(Box::new(ExpressionTransform::try_create(
                 plan.input.schema(),
@@ -131,7 +167,12 @@ impl PipelineBuilder {
         Ok(true)
     }
 
-    fn visit_projection_plan(pipeline: &mut Pipeline, plan: &ProjectionPlan) -> Result<bool> {
+    fn visit_projection_plan(
+        &self,
+        pipeline: &mut Pipeline,
+        plan: &ProjectionPlan,
+    ) -> Result<bool> {
+        self.resize_after_merge(pipeline)?;
         pipeline.add_simple_transform(|| {
             Ok(Box::new(ProjectionTransform::try_create(
                 plan.input.schema(),
@@ -143,6 +184,7 @@ impl PipelineBuilder {
     }
 
     fn visit_aggregator_partial_plan(
+        &self,
         pipeline: &mut Pipeline,
         plan: &AggregatorPartialPlan,
     ) -> Result<bool> {
@@ -155,39 +197,71 @@ impl PipelineBuilder {
                 )?))
             })?;
         } else {
+            let max_bytes = self
+                .ctx
+                .get_settings()
+                .get_max_bytes_before_external_group_by()? as usize;
+            let memory_tracker = self.ctx.try_get_memory_tracker()?;
             pipeline.add_simple_transform(|| {
-                Ok(Box::new(GroupByPartialTransform::create(
-                    plan.schema(),
-                    plan.input.schema(),
-                    plan.aggr_expr.clone(),
-                    plan.group_expr.clone(),
-                )))
+                Ok(Box::new(
+                    GroupByPartialTransform::create(
+                        plan.schema(),
+                        plan.input.schema(),
+                        plan.aggr_expr.clone(),
+                        plan.group_expr.clone(),
+                    )
+                    .with_external_group_by(max_bytes)
+                    .with_memory_tracker(memory_tracker.clone()),
+                ))
             })?;
         }
         Ok(true)
     }
 
     fn visit_aggregator_final_plan(
+        &self,
         pipeline: &mut Pipeline,
         plan: &AggregatorFinalPlan,
     ) -> Result<bool> {
-        pipeline.merge_processor()?;
         if plan.group_expr.is_empty() {
+            pipeline.merge_processor()?;
+            let memory_tracker = self.ctx.try_get_memory_tracker()?;
             pipeline.add_simple_transform(|| {
-                Ok(Box::new(AggregatorFinalTransform::try_create(
-                    plan.schema(),
-                    plan.schema_before_group_by.clone(),
-                    plan.aggr_expr.clone(),
-                )?))
+                Ok(Box::new(
+                    AggregatorFinalTransform::try_create(
+                        plan.schema(),
+                        plan.schema_before_group_by.clone(),
+                        plan.aggr_expr.clone(),
+                    )?
+                    .with_memory_tracker(memory_tracker.clone()),
+                ))
             })?;
         } else {
+            // The partial states of a group meet in one processor by the hash of the group key,
+            // the groups are merged in parallel and the stages after them keep the ways.
+            let ways = self.ctx.get_max_threads()? as usize;
+            pipeline.partition_by_hash(ways, plan.input.schema(), Expression::ScalarFunction {
+                op: String::from("sipHash"),
+                args: vec![Expression::Column(String::from("_group_by_key"))],
+            })?;
+            let max_bytes = self
+                .ctx
+                .get_settings()
+                .get_max_bytes_before_external_group_by()? as usize;
+            let merge_threads = std::cmp::max(ways / pipeline.nums(), 1);
+            let memory_tracker = self.ctx.try_get_memory_tracker()?;
             pipeline.add_simple_transform(|| {
-                Ok(Box::new(GroupByFinalTransform::create(
-                    plan.schema(),
-                    plan.schema_before_group_by.clone(),
-                    plan.aggr_expr.clone(),
-                    plan.group_expr.clone(),
-                )))
+                Ok(Box::new(
+                    GroupByFinalTransform::create(
+                        plan.schema(),
+                        plan.schema_before_group_by.clone(),
+                        plan.aggr_expr.clone(),
+                        plan.group_expr.clone(),
+                    )
+                    .with_external_group_by(max_bytes)
+                    .with_merge_threads(merge_threads)
+                    .with_memory_tracker(memory_tracker.clone()),
+                ))
             })?;
         }
         Ok(true)
@@ -216,10 +290,16 @@ impl PipelineBuilder {
     }
 
     fn visit_sort_plan(
+        &self,
         limit: Option<usize>,
         pipeline: &mut Pipeline,
         plan: &SortPlan,
     ) -> Result<bool> {
+        let settings = self.ctx.get_settings();
+        let max_bytes = settings.get_max_bytes_before_external_sort()? as usize;
+        let block_size = settings.get_max_block_size()? as usize;
+        let memory_tracker = self.ctx.try_get_memory_tracker()?;
+
         // processor 1: block ---> sort_stream
         // processor 2: block ---> sort_stream
         // processor 3: block ---> sort_stream
@@ -235,28 +315,19 @@ impl PipelineBuilder {
         // processor 2: [sorted blocks ...] ---> merge to one sorted block
         // processor 3: [sorted blocks ...] ---> merge to one sorted block
         pipeline.add_simple_transform(|| {
-            Ok(Box::new(SortMergeTransform::try_create(
-                plan.schema(),
-                plan.order_by.clone(),
-                limit,
-            )?))
+            Ok(Box::new(
+                SortMergeTransform::try_create(plan.schema(), plan.order_by.clone(), limit)?
+                    .with_external_sort(max_bytes, block_size)
+                    .with_memory_tracker(memory_tracker.clone()),
+            ))
         })?;
 
         // processor1 sorted block --
         //                             \
-        // processor2 sorted block ----> processor  --> merge to one sorted block
+        // processor2 sorted block ----> merge sorted processor --> sorted blocks
         //                             /
         // processor3 sorted block --
-        if pipeline.last_pipe()?.nums() > 1 {
-            pipeline.merge_processor()?;
-            pipeline.add_simple_transform(|| {
-                Ok(Box::new(SortMergeTransform::try_create(
-                    plan.schema(),
-                    plan.order_by.clone(),
-                    limit,
-                )?))
-            })?;
-        }
+        pipeline.merge_sorted_processor(plan.schema(), plan.order_by.clone(), limit, block_size)?;
         Ok(true)
     }
 
@@ -279,10 +350,59 @@ impl PipelineBuilder {
         Ok(false)
     }
 
+    // The remote read source and the partial aggregation on it for the store to compute, when
+    // there are only column-only expressions between them and the aggregation is on columns.
+    fn aggregation_push_down(
+        plan: &AggregatorPartialPlan,
+    ) -> Option<(ReadDataSourcePlan, AggregatorPartialPlan)> {
+        let mut input = plan.input.as_ref();
+        let source_plan = loop {
+            match input {
+                PlanNode::Expression(expression) => {
+                    let is_column_or_literal = |expr: &Expression| {
+                        matches!(expr, Expression::Column(_) | Expression::Literal(_))
+                    };
+                    if !expression.exprs.iter().all(is_column_or_literal) {
+                        return None;
+                    }
+                    input = expression.input.as_ref();
+                }
+                PlanNode::ReadSource(source_plan) if source_plan.remote => break source_plan,
+                _ => return None,
+            }
+        };
+
+        let has_column = |expr: &Expression| match expr {
+            Expression::Column(name) => source_plan.schema.field_with_name(name).is_ok(),
+            Expression::Literal(_) => true,
+            _ => false,
+        };
+        let group_by_columns = plan.group_expr.iter().all(|expr| match expr {
+            Expression::Column(_) => has_column(expr),
+            _ => false,
+        });
+        let aggregate_columns = plan.aggr_expr.iter().all(|expr| match expr {
+            Expression::AggregateFunction { distinct, args, .. } => {
+                !distinct && args.iter().all(has_column)
+            }
+            _ => false,
+        });
+        if !group_by_columns || !aggregate_columns {
+            return None;
+        }
+
+        let aggregation = AggregatorPartialPlan {
+            input: Arc::new(PlanNode::ReadSource(source_plan.clone())),
+            ..plan.clone()
+        };
+        Some((source_plan.clone(), aggregation))
+    }
+
     fn visit_read_data_source_plan(
         &self,
         pipeline: &mut Pipeline,
         plan: &ReadDataSourcePlan,
+        aggregation: Option<&AggregatorPartialPlan>,
     ) -> Result<bool> {
         // Bind plan partitions to context.
         self.ctx.try_set_partitions(plan.partitions.clone())?;
@@ -292,12 +412,14 @@ impl PipelineBuilder {
         let workers = std::cmp::max(max_threads, 1);
 
         for _i in 0..workers {
-            let source = SourceTransform::try_create(
-                self.ctx.clone(),
-                plan.db.as_str(),
-                plan.table.as_str(),
-                plan.remote,
-            )?;
+            let source = match aggregation {
+                None => SourceTransform::try_create(self.ctx.clone(), plan.clone())?,
+                Some(aggregation) => SourceTransform::try_create_partial_aggregated(
+                    self.ctx.clone(),
+                    plan.clone(),
+                    aggregation.clone(),
+                )?,
+            };
             pipeline.add_source(Arc::new(source))?;
         }
         Ok(true)
@@ -30,11 +30,10 @@ async fn test_local_pipeline_builds() -> anyhow::Result<()> {
 
             pipeline: "\
             ProjectionTransform × 1 processor\
-            \n  SortMergeTransform × 1 processor\
-            \n    Merge (SortMergeTransform × 8 processors) to (SortMergeTransform × 1)\
-            \n      SortMergeTransform × 8 processors\
-            \n        SortPartialTransform × 8 processors\
-            \n          SourceTransform × 8 processors",
+            \n  MergeSortedProcessor × 1 processor\
+            \n    SortMergeTransform × 8 processors\
+            \n      SortPartialTransform × 8 processors\
+            \n        SourceTransform × 8 processors",
 
 
             block: vec![
@@ -66,11 +65,10 @@ async fn test_local_pipeline_builds() -> anyhow::Result<()> {
 
             pipeline: "\
             ProjectionTransform × 1 processor\
-            \n  SortMergeTransform × 1 processor\
-            \n    Merge (SortMergeTransform × 8 processors) to (SortMergeTransform × 1)\
-            \n      SortMergeTransform × 8 processors\
-            \n        SortPartialTransform × 8 processors\
-            \n          SourceTransform × 8 processors",
+            \n  MergeSortedProcessor × 1 processor\
+            \n    SortMergeTransform × 8 processors\
+            \n      SortPartialTransform × 8 processors\
+            \n        SourceTransform × 8 processors",
 
             block: vec![
                 "+----+----+",
@@ -102,12 +100,11 @@ async fn test_local_pipeline_builds() -> anyhow::Result<()> {
 
             pipeline: "\
             ProjectionTransform × 1 processor\
-            \n  SortMergeTransform × 1 processor\
-            \n    Merge (SortMergeTransform × 8 processors) to (SortMergeTransform × 1)\
-            \n      SortMergeTransform × 8 processors\
-            \n        SortPartialTransform × 8 processors\
-            \n          ExpressionTransform × 8 processors\
-            \n            SourceTransform × 8 processors",
+            \n  MergeSortedProcessor × 1 processor\
+            \n    SortMergeTransform × 8 processors\
+            \n      SortPartialTransform × 8 processors\
+            \n        ExpressionTransform × 8 processors\
+            \n          SourceTransform × 8 processors",
 
             block: vec![
                 "+----+----+",
@@ -147,3 +144,76 @@ async fn test_local_pipeline_builds() -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+#[test]
+fn test_aggregation_push_down_pipeline_builds() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_arrow::arrow::datatypes::DataType;
+    use common_datavalues::DataField;
+    use common_datavalues::DataSchema;
+    use common_planners::*;
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::processors::*;
+
+    let schema = Arc::new(DataSchema::new(vec![
+        DataField::new("k", DataType::Utf8, false),
+        DataField::new("v", DataType::Int64, false),
+    ]));
+    let source = PlanNode::ReadSource(ReadDataSourcePlan {
+        schema: schema.clone(),
+        remote: true,
+        ..ReadDataSourcePlan::empty()
+    });
+    let aggregate = |exprs: &[Expression]| -> anyhow::Result<PlanNode> {
+        let aggr_expr = vec![sum(col("v"))];
+        let group_expr = vec![col("k")];
+        Ok(PlanBuilder::from(&source)
+            .expression(exprs, "Before GroupBy")?
+            .aggregate_partial(&aggr_expr, &group_expr)?
+            .aggregate_final(schema.clone(), &aggr_expr, &group_expr)?
+            .build()?)
+    };
+
+    let ctx = crate::tests::try_create_context()?;
+
+    // The store computes the partial states.
+    let plan = aggregate(&[col("k"), col("v")])?;
+    let pipeline = PipelineBuilder::create(ctx.clone(), plan.clone()).build()?;
+    assert_eq!(
+        format!("{:?}", pipeline),
+        "\
+        GroupByFinalTransform × 8 processors\
+        \n  Partition by hash (SourceTransform(AggregatorPartial) × 1 processor) to (GroupByFinalTransform × 8)\
+        \n    SourceTransform(AggregatorPartial) × 1 processor"
+    );
+
+    // The expression must be computed before the aggregation.
+    let plan_with_expression = aggregate(&[col("k"), col("v"), add(col("v"), lit(1i64))])?;
+    let pipeline = PipelineBuilder::create(ctx.clone(), plan_with_expression).build()?;
+    assert_eq!(
+        format!("{:?}", pipeline),
+        "\
+        GroupByFinalTransform × 8 processors\
+        \n  Partition by hash (GroupByPartialTransform × 1 processor) to (GroupByFinalTransform × 8)\
+        \n    GroupByPartialTransform × 1 processor\
+        \n      ExpressionTransform × 1 processor\
+        \n        SourceTransform × 1 processor"
+    );
+
+    // Disabled by the setting.
+    ctx.get_settings()
+        .update_settings("enable_aggregation_push_down", "0".to_string())?;
+    let pipeline = PipelineBuilder::create(ctx, plan).build()?;
+    assert_eq!(
+        format!("{:?}", pipeline),
+        "\
+        GroupByFinalTransform × 8 processors\
+        \n  Partition by hash (GroupByPartialTransform × 1 processor) to (GroupByFinalTransform × 8)\
+        \n    GroupByPartialTransform × 1 processor\
+        \n      ExpressionTransform × 1 processor\
+        \n        SourceTransform × 1 processor"
+    );
+    Ok(())
+}
@@ -6,6 +6,7 @@ use std::fmt;
 use std::fmt::Display;
 
 use crate::pipelines::processors::Pipeline;
+use crate::pipelines::processors::PORT_CAPACITY;
 
 impl Pipeline {
     pub fn display_indent(&self) -> impl fmt::Display + '_ {
@@ -35,21 +36,22 @@ impl Pipeline {
 
                         match processor.name() {
                             "EmptyProcessor" => write!(f, "")?,
-                            "MergeProcessor" => {
+                            "MergeProcessor" | "ResizeProcessor" | "HashPartitionProcessor" => {
                                 let mut pipes = self.0.pipes();
                                 pipes.reverse();
 
-                                let prev_pipe = pipes[index - 1].clone();
-                                let prev_name = prev_pipe.name().to_string();
-                                let prev_ways = prev_pipe.nums();
-
                                 let post_pipe = pipes[index + 1].clone();
                                 let post_name = post_pipe.name().to_string();
                                 let post_ways = post_pipe.nums();
 
                                 write!(
                                     f,
-                                    "Merge ({} × {} {}) to ({} × {})",
+                                    "{} ({} × {} {}) to ",
+                                    match processor.name() {
+                                        "MergeProcessor" => "Merge",
+                                        "ResizeProcessor" => "Resize",
+                                        _ => "Partition by hash",
+                                    },
                                     post_name,
                                     post_ways,
                                     if post_ways == 1 {
@@ -57,9 +59,22 @@ impl Pipeline {
                                     } else {
                                         "processors"
                                     },
-                                    prev_name,
-                                    prev_ways,
                                 )?;
+
+                                // The top pipe of the pipeline has no next processor, its
+                                // outputs are the outputs of the pipeline.
+                                match index {
+                                    0 => write!(
+                                        f,
+                                        "({} {})",
+                                        ways,
+                                        if ways == 1 { "output" } else { "outputs" }
+                                    )?,
+                                    _ => {
+                                        let prev_pipe = pipes[index - 1].clone();
+                                        write!(f, "({} × {})", prev_pipe.name(), prev_pipe.nums())?
+                                    }
+                                }
                             }
                             "RemoteTransform" => {
                                 let name = processor.name();
@@ -98,6 +113,51 @@ impl Pipeline {
         Wrapper(self)
     }
 
+    /// The edges between the pipes, from the sources to the output. The inputs of a merge
+    /// or a resize are driven by the executor and push to bounded ports, the other processors pull.
+    pub fn display_ports(&self) -> impl fmt::Display + '_ {
+        struct Wrapper<'a>(&'a Pipeline);
+        impl<'a> fmt::Display for Wrapper<'a> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let pipes = self.0.pipes();
+                for (index, edge) in pipes.windows(2).enumerate() {
+                    if index > 0 {
+                        writeln!(f)?;
+                    }
+
+                    let (upstream, downstream) = (&edge[0], &edge[1]);
+                    write!(
+                        f,
+                        "{} × {} -> {} × {}",
+                        upstream.name(),
+                        upstream.nums(),
+                        downstream.name(),
+                        downstream.nums()
+                    )?;
+
+                    match downstream.name() {
+                        "MergeProcessor" | "MergeSortedProcessor" if upstream.nums() > 1 => write!(
+                            f,
+                            ": pushed ({} ports of {} morsels)",
+                            upstream.nums(),
+                            PORT_CAPACITY
+                        )?,
+                        "ResizeProcessor" | "HashPartitionProcessor" => write!(
+                            f,
+                            ": pushed ({} ports of {} morsels, redistributed to {} ports)",
+                            upstream.nums(),
+                            PORT_CAPACITY,
+                            downstream.nums()
+                        )?,
+                        _ => write!(f, ": pulled")?,
+                    }
+                }
+                Ok(())
+            }
+        }
+        Wrapper(self)
+    }
+
     pub fn display_graphviz(&self) -> impl fmt::Display + '_ {
         struct Wrapper<'a>(&'a Pipeline);
         impl<'a> fmt::Display for Wrapper<'a> {
@@ -26,5 +26,39 @@ async fn test_pipeline_display() -> anyhow::Result<()> {
     \n                SourceTransform × 8 processors";
     let actual = format!("{:?}", pipeline);
     assert_eq!(expect, actual);
+
+    let expect = "SourceTransform × 8 -> FilterTransform × 8: pulled\
+    \nFilterTransform × 8 -> ExpressionTransform × 8: pulled\
+    \nExpressionTransform × 8 -> AggregatorPartialTransform × 8: pulled\
+    \nAggregatorPartialTransform × 8 -> MergeProcessor × 1: pushed (8 ports of 2 morsels)\
+    \nMergeProcessor × 1 -> AggregatorFinalTransform × 1: pulled\
+    \nAggregatorFinalTransform × 1 -> ExpressionTransform × 1: pulled\
+    \nExpressionTransform × 1 -> ProjectionTransform × 1: pulled\
+    \nProjectionTransform × 1 -> LimitTransform × 1: pulled";
+    let actual = format!("{}", pipeline.display_ports());
+    assert_eq!(expect, actual);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_pipeline_display_merge_on_top() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::processors::*;
+
+    let ctx = crate::tests::try_create_context()?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    let mut pipeline = Pipeline::create(ctx.clone());
+    pipeline.add_source(Arc::new(test_source.number_source_transform_for_test(8)?))?;
+    pipeline.add_source(Arc::new(test_source.number_source_transform_for_test(8)?))?;
+    pipeline.merge_processor()?;
+
+    let expect = "Merge (SourceTransform × 2 processors) to (1 output)\
+    \n  SourceTransform × 2 processors";
+    let actual = format!("{:?}", pipeline);
+    assert_eq!(expect, actual);
     Ok(())
 }
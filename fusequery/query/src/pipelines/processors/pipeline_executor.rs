@@ -0,0 +1,97 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_infallible::RwLock;
+use common_runtime::Runtime;
+use lazy_static::lazy_static;
+use tokio::task::JoinHandle;
+use tokio_stream::StreamExt;
+
+use crate::pipelines::processors::create_port;
+use crate::pipelines::processors::InputPort;
+use crate::pipelines::processors::Processor;
+use crate::pipelines::processors::PORT_CAPACITY;
+
+/// A fixed pool of workers shared by the queries of the server, so the concurrent queries
+/// share the same threads instead of each bringing its own. Each processor feeding a merge
+/// runs as a task on it, reading the stream of the processor into a bounded port.
+/// It has no scheduler of its own, the tasks are scheduled by the tokio runtime of the pool
+/// and a task only waits while its port is full.
+pub struct PipelineExecutor {
+    workers: usize,
+    runtime: Runtime,
+}
+
+pub type PipelineExecutorRef = Arc<PipelineExecutor>;
+
+lazy_static! {
+    // The executor of the contexts created outside of a session manager, such as by the tests
+    // and the benchmarks, shared by all of them.
+    static ref DEFAULT_PIPELINE_EXECUTOR: RwLock<Option<PipelineExecutorRef>> = RwLock::new(None);
+}
+
+impl PipelineExecutor {
+    pub fn try_create(workers: usize) -> Result<PipelineExecutorRef> {
+        let workers = std::cmp::max(workers, 1);
+        Ok(Arc::new(PipelineExecutor {
+            workers,
+            runtime: Runtime::with_worker_threads(workers)?,
+        }))
+    }
+
+    /// The executor shared by the contexts without the executor of a session manager,
+    /// created with the first of them.
+    pub fn try_get_default() -> Result<PipelineExecutorRef> {
+        let mut executor = DEFAULT_PIPELINE_EXECUTOR.write();
+        match &*executor {
+            Some(executor) => Ok(executor.clone()),
+            None => {
+                let created = PipelineExecutor::try_create(num_cpus::get())?;
+                *executor = Some(created.clone());
+                Ok(created)
+            }
+        }
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Spawns a task forwarding the blocks of the processor to a bounded port, returns the
+    /// reading side of the port. The task waits while the port is full and stops once the port
+    /// is dropped or the processor fails.
+    pub fn execute_processor(&self, processor: Arc<dyn Processor>) -> InputPort {
+        let (output, input) = create_port(PORT_CAPACITY);
+        self.runtime.spawn(async move {
+            let mut stream = match processor.execute().await {
+                Ok(stream) => stream,
+                Err(error) => {
+                    output.push(Err(error)).await;
+                    return;
+                }
+            };
+
+            while let Some(morsel) = stream.next().await {
+                let failed = morsel.is_err();
+                if !output.push(morsel).await || failed {
+                    return;
+                }
+            }
+        });
+        input
+    }
+
+    /// Runs the task on the workers, such as the distribution of the blocks to the ports.
+    pub fn execute_task<T>(&self, task: T) -> JoinHandle<T::Output>
+    where
+        T: Future + Send + 'static,
+        T::Output: Send + 'static,
+    {
+        self.runtime.spawn(task)
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_pipeline_executor() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::processors::*;
+    use crate::tests;
+
+    let ctx = crate::tests::try_create_context()?;
+    let test_source = tests::NumberTestData::create(ctx.clone());
+    let source = test_source.number_source_transform_for_test(4)?;
+
+    let executor = PipelineExecutor::try_create(2)?;
+    assert_eq!(executor.workers(), 2);
+
+    let port = executor.execute_processor(Arc::new(source));
+    let result = port.into_stream().try_collect::<Vec<_>>().await?;
+    let expected = vec![
+        "+--------+",
+        "| number |",
+        "+--------+",
+        "| 0      |",
+        "| 1      |",
+        "| 2      |",
+        "| 3      |",
+        "+--------+",
+    ];
+    common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_port_backpressure() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_datablocks::DataBlock;
+    use common_datavalues::DataSchema;
+    use futures::FutureExt;
+    use futures::StreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::processors::*;
+
+    let (output, input) = create_port(1);
+    let block = DataBlock::empty_with_schema(Arc::new(DataSchema::empty()));
+    assert!(output.push(Ok(block.clone())).await);
+
+    // The port is full, the push waits until the morsel is read.
+    assert_eq!(output.push(Ok(block.clone())).now_or_never(), None);
+
+    let mut stream = input.into_stream();
+    assert_eq!(stream.next().await.unwrap()?.num_rows(), 0);
+    assert_eq!(output.push(Ok(block.clone())).now_or_never(), Some(true));
+
+    // The processor stops once the port is dropped.
+    drop(stream);
+    assert!(!output.push(Ok(block)).await);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_pipeline_executor_default() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    // The contexts outside of a session manager share the default executor.
+    let ctx1 = crate::tests::try_create_context()?;
+    let ctx2 = crate::tests::try_create_context()?;
+    assert!(Arc::ptr_eq(
+        &ctx1.try_get_pipeline_executor()?,
+        &ctx2.try_get_pipeline_executor()?
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use common_datablocks::DataBlock;
+use common_exception::Result;
+use common_streams::SendableDataBlockStream;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio_stream::wrappers::ReceiverStream;
+
+/// The morsels buffered by a port before the processor pushing to it waits.
+pub const PORT_CAPACITY: usize = 2;
+
+/// The side of a port a processor pushes its morsels to.
+pub struct OutputPort {
+    sender: mpsc::Sender<Result<DataBlock>>,
+}
+
+/// The side of a port the downstream processor reads the morsels from.
+pub struct InputPort {
+    receiver: mpsc::Receiver<Result<DataBlock>>,
+}
+
+/// A bounded channel of morsels (blocks) between two processors, the backpressure of the pipeline.
+pub fn create_port(capacity: usize) -> (OutputPort, InputPort) {
+    let (sender, receiver) = mpsc::channel(std::cmp::max(capacity, 1));
+    (OutputPort { sender }, InputPort { receiver })
+}
+
+/// Why the morsel was not pushed without waiting.
+pub enum TryPushError {
+    /// The port is full, the morsel is given back.
+    Full(Result<DataBlock>),
+    /// The input port is dropped.
+    Closed,
+}
+
+impl OutputPort {
+    /// Pushes the morsel, waiting while the port is full.
+    /// Returns false once the input port is dropped, the processor should stop.
+    pub async fn push(&self, morsel: Result<DataBlock>) -> bool {
+        self.sender.send(morsel).await.is_ok()
+    }
+
+    /// Pushes the morsel if the port is not full.
+    pub fn try_push(&self, morsel: Result<DataBlock>) -> std::result::Result<(), TryPushError> {
+        self.sender.try_send(morsel).map_err(|e| match e {
+            TrySendError::Full(morsel) => TryPushError::Full(morsel),
+            TrySendError::Closed(_) => TryPushError::Closed,
+        })
+    }
+
+    /// Waits until the port has room for a morsel.
+    /// Returns false once the input port is dropped.
+    pub async fn ready(&self) -> bool {
+        self.sender.reserve().await.is_ok()
+    }
+}
+
+impl InputPort {
+    pub fn into_stream(self) -> SendableDataBlockStream {
+        Box::pin(ReceiverStream::new(self.receiver))
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_datavalues::DataSchemaRef;
+use common_exception::Result;
+use common_planners::Expression;
+use common_streams::SendableDataBlockStream;
+
+use crate::pipelines::processors::Distribution;
+use crate::pipelines::processors::Distributor;
+use crate::pipelines::processors::Processor;
+use crate::pipelines::transforms::ScatterByHash;
+use crate::sessions::FuseQueryContextRef;
+
+/// One of the outputs of a N-ways to M-ways hash partition, the rows of the inputs are
+/// scattered to the outputs by the hash of the key so that equal keys meet in the same output.
+pub struct HashPartitionProcessor {
+    distributor: Arc<Distributor>,
+    output: usize,
+}
+
+impl HashPartitionProcessor {
+    /// The outputs of the partition, the key expression must return the hash of the row.
+    pub fn try_create(
+        ctx: FuseQueryContextRef,
+        schema: DataSchemaRef,
+        key: Expression,
+        ways: usize,
+    ) -> Result<Vec<Self>> {
+        let ways = std::cmp::max(ways, 1);
+        let scatter = ScatterByHash::try_create(schema, key, ways)?;
+        let distributor = Distributor::create(ctx, Distribution::Hash(scatter), ways);
+        Ok((0..ways)
+            .map(|output| HashPartitionProcessor {
+                distributor: distributor.clone(),
+                output,
+            })
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for HashPartitionProcessor {
+    fn name(&self) -> &str {
+        "HashPartitionProcessor"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn Processor>) -> Result<()> {
+        self.distributor.connect_to(input);
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        self.distributor.inputs()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        self.distributor.execute(self.output)
+    }
+}
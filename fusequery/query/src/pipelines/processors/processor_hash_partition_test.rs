@@ -0,0 +1,82 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_processor_hash_partition() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_datavalues::DataValue;
+    use common_planners::*;
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::processors::*;
+    use crate::tests;
+
+    let ctx = crate::tests::try_create_context()?;
+    let test_source = tests::NumberTestData::create(ctx.clone());
+
+    let mut pipeline = Pipeline::create(ctx.clone());
+
+    let source = test_source.number_source_transform_for_test(16)?;
+    pipeline.add_source(Arc::new(source))?;
+    pipeline.partition_by_hash(4, test_source.number_schema_for_test()?, col("number"))?;
+    assert_eq!(pipeline.nums(), 4);
+
+    // The rows of the same key are in the same output.
+    let mut rows = 0;
+    for (way, output) in pipeline.last_pipe()?.processors().iter().enumerate() {
+        let blocks = output.execute().await?.try_collect::<Vec<_>>().await?;
+        for block in blocks {
+            for row in 0..block.num_rows() {
+                match DataValue::try_from_column(block.column(0), row)? {
+                    DataValue::UInt64(Some(number)) => assert_eq!(number as usize % 4, way),
+                    other => panic!("Unexpected value {:?}", other),
+                }
+                rows += 1;
+            }
+        }
+    }
+    assert_eq!(rows, 16);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_processor_hash_partition_output_read_last() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_planners::*;
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::processors::*;
+    use crate::tests;
+
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings()
+        .update_settings("max_block_size", "1".to_string())?;
+    let test_source = tests::NumberTestData::create(ctx.clone());
+
+    let mut pipeline = Pipeline::create(ctx.clone());
+
+    let source = test_source.number_source_transform_for_test(20)?;
+    pipeline.add_source(Arc::new(source))?;
+    pipeline.partition_by_hash(2, test_source.number_schema_for_test()?, col("number"))?;
+
+    let mut streams = vec![];
+    for output in pipeline.last_pipe()?.processors() {
+        streams.push(output.execute().await?);
+    }
+
+    // The blocks of the first output are queued while the second one is read to the end.
+    let mut rows = vec![];
+    for stream in streams.into_iter().rev() {
+        let blocks = stream.try_collect::<Vec<_>>().await?;
+        rows.push(blocks.iter().map(|block| block.num_rows()).sum::<usize>());
+    }
+    assert_eq!(vec![10, 10], rows);
+
+    Ok(())
+}
@@ -5,14 +5,9 @@
 use std::any::Any;
 use std::sync::Arc;
 
-use common_datablocks::DataBlock;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_streams::SendableDataBlockStream;
-use log::error;
-use tokio::sync::mpsc;
-use tokio_stream::wrappers::ReceiverStream;
-use tokio_stream::StreamExt;
 
 use crate::pipelines::processors::Processor;
 use crate::sessions::FuseQueryContextRef;
@@ -58,42 +53,14 @@ impl Processor for MergeProcessor {
             )),
             1 => self.inputs[0].execute().await,
             _ => {
-                let (sender, receiver) = mpsc::channel::<Result<DataBlock>>(inputs);
-                for i in 0..inputs {
-                    let input = self.inputs[i].clone();
-                    let sender = sender.clone();
-                    self.ctx.execute_task(async move {
-                        let mut stream = match input.execute().await {
-                            Err(e) => {
-                                if let Err(error) = sender.send(Result::Err(e)).await {
-                                    error!("Merge processor cannot push data: {}", error);
-                                }
-                                return;
-                            }
-                            Ok(stream) => stream,
-                        };
-
-                        while let Some(item) = stream.next().await {
-                            match item {
-                                Ok(item) => {
-                                    if let Err(error) = sender.send(Ok(item)).await {
-                                        // Stop pulling data
-                                        error!("Merge processor cannot push data: {}", error);
-                                        return;
-                                    }
-                                }
-                                Err(error) => {
-                                    // Stop pulling data
-                                    if let Err(error) = sender.send(Err(error)).await {
-                                        error!("Merge processor cannot push data: {}", error);
-                                    }
-                                    return;
-                                }
-                            }
-                        }
-                    });
-                }
-                Ok(Box::pin(ReceiverStream::new(receiver)))
+                // The inputs are driven by the executor, the morsels are read as they come.
+                let executor = self.ctx.try_get_pipeline_executor()?;
+                let ports = self
+                    .inputs
+                    .iter()
+                    .map(|input| executor.execute_processor(input.clone()).into_stream())
+                    .collect::<Vec<_>>();
+                Ok(Box::pin(futures::stream::select_all(ports)))
             }
         }
     }
@@ -0,0 +1,91 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_datavalues::DataSchemaRef;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_planners::Expression;
+use common_streams::SendableDataBlockStream;
+use common_streams::SortMergeStream;
+
+use crate::pipelines::processors::Processor;
+use crate::pipelines::transforms::get_sort_descriptions;
+use crate::sessions::FuseQueryContextRef;
+
+/// Merges the sorted inputs into one sorted output by a streaming k-way merge,
+/// the blocks are emitted as soon as they are merged and the inputs stop at the limit.
+pub struct MergeSortedProcessor {
+    ctx: FuseQueryContextRef,
+    schema: DataSchemaRef,
+    exprs: Vec<Expression>,
+    limit: Option<usize>,
+    block_size: usize,
+    inputs: Vec<Arc<dyn Processor>>,
+}
+
+impl MergeSortedProcessor {
+    pub fn try_create(
+        ctx: FuseQueryContextRef,
+        schema: DataSchemaRef,
+        exprs: Vec<Expression>,
+        limit: Option<usize>,
+        block_size: usize,
+    ) -> Result<Self> {
+        Ok(MergeSortedProcessor {
+            ctx,
+            schema,
+            exprs,
+            limit,
+            block_size,
+            inputs: vec![],
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for MergeSortedProcessor {
+    fn name(&self) -> &str {
+        "MergeSortedProcessor"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn Processor>) -> Result<()> {
+        self.inputs.push(input);
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        self.inputs.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        if self.inputs.is_empty() {
+            return Result::Err(ErrorCode::IllegalTransformConnectionState(
+                "Merge sorted processor inputs cannot be zero",
+            ));
+        }
+
+        // Every input is driven by the executor, the merge reads one port ahead of every input.
+        let executor = self.ctx.try_get_pipeline_executor()?;
+        let streams = self
+            .inputs
+            .iter()
+            .map(|input| executor.execute_processor(input.clone()).into_stream())
+            .collect::<Vec<_>>();
+
+        Ok(Box::pin(SortMergeStream::try_create(
+            self.schema.clone(),
+            streams,
+            get_sort_descriptions(&self.schema, &self.exprs)?,
+            self.limit,
+            self.block_size,
+        )?))
+    }
+}
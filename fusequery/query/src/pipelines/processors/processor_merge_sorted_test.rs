@@ -0,0 +1,69 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_processor_merge_sorted() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_planners::*;
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::processors::*;
+    use crate::pipelines::transforms::*;
+    use crate::tests;
+
+    let ctx = crate::tests::try_create_context()?;
+    let test_source = tests::NumberTestData::create(ctx.clone());
+
+    let mut pipeline = Pipeline::create(ctx.clone());
+    let a = test_source.number_source_transform_for_test(4)?;
+    pipeline.add_source(Arc::new(a))?;
+    let b = test_source.number_source_transform_for_test(4)?;
+    pipeline.add_source(Arc::new(b))?;
+
+    let sort_expression = &[sort("number", false, false)];
+    let plan = PlanBuilder::create(test_source.number_schema_for_test()?)
+        .sort(sort_expression)?
+        .build()?;
+
+    pipeline.add_simple_transform(|| {
+        Ok(Box::new(SortPartialTransform::try_create(
+            plan.schema(),
+            sort_expression.to_vec(),
+            None,
+        )?))
+    })?;
+    pipeline.add_simple_transform(|| {
+        Ok(Box::new(SortMergeTransform::try_create(
+            plan.schema(),
+            sort_expression.to_vec(),
+            None,
+        )?))
+    })?;
+
+    // The merge stops at the limit, in blocks of 2 rows.
+    pipeline.merge_sorted_processor(plan.schema(), sort_expression.to_vec(), Some(3), 2)?;
+    assert_eq!(pipeline.last_pipe()?.name(), "MergeSortedProcessor");
+
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    assert_eq!(
+        vec![2, 1],
+        result.iter().map(|b| b.num_rows()).collect::<Vec<_>>()
+    );
+
+    let expected = vec![
+        "+--------+",
+        "| number |",
+        "+--------+",
+        "| 3      |",
+        "| 3      |",
+        "| 2      |",
+        "+--------+",
+    ];
+    common_datablocks::assert_blocks_eq(expected, result.as_slice());
+
+    Ok(())
+}
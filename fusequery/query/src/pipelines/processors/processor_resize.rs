@@ -0,0 +1,328 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_infallible::Mutex;
+use common_infallible::RwLock;
+use common_streams::SendableDataBlockStream;
+use tokio_stream::StreamExt;
+
+use crate::pipelines::processors::create_port;
+use crate::pipelines::processors::InputPort;
+use crate::pipelines::processors::OutputPort;
+use crate::pipelines::processors::Processor;
+use crate::pipelines::processors::TryPushError;
+use crate::pipelines::processors::PORT_CAPACITY;
+use crate::pipelines::transforms::ScatterByHash;
+use crate::sessions::FuseQueryContextRef;
+
+/// How the blocks of the inputs are distributed to the outputs.
+pub enum Distribution {
+    RoundRobin,
+    Hash(ScatterByHash),
+}
+
+/// The inputs and the output ports shared by the outputs of a resize.
+/// The first output executed starts the distribution of the blocks to all the outputs.
+pub struct Distributor {
+    ctx: FuseQueryContextRef,
+    distribution: Arc<Distribution>,
+    inputs: RwLock<Vec<Arc<dyn Processor>>>,
+    outputs: Mutex<Option<Vec<Option<InputPort>>>>,
+    ways: usize,
+}
+
+impl Distributor {
+    pub fn create(ctx: FuseQueryContextRef, distribution: Distribution, ways: usize) -> Arc<Self> {
+        Arc::new(Distributor {
+            ctx,
+            distribution: Arc::new(distribution),
+            inputs: RwLock::new(vec![]),
+            outputs: Mutex::new(None),
+            ways: std::cmp::max(ways, 1),
+        })
+    }
+
+    pub fn ways(&self) -> usize {
+        self.ways
+    }
+
+    pub fn connect_to(&self, input: Arc<dyn Processor>) {
+        self.inputs.write().push(input);
+    }
+
+    pub fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        self.inputs.read().clone()
+    }
+
+    pub fn execute(&self, output: usize) -> Result<SendableDataBlockStream> {
+        let mut outputs = self.outputs.lock();
+        if outputs.is_none() {
+            *outputs = Some(self.start()?);
+        }
+
+        match outputs.as_mut().and_then(|ports| ports[output].take()) {
+            Some(port) => Ok(port.into_stream()),
+            None => Result::Err(ErrorCode::IllegalPipelineState(format!(
+                "Output {} of the resize is already executed",
+                output
+            ))),
+        }
+    }
+
+    fn start(&self) -> Result<Vec<Option<InputPort>>> {
+        let inputs = self.inputs();
+        if inputs.is_empty() {
+            return Result::Err(ErrorCode::IllegalTransformConnectionState(
+                "Resize processor inputs cannot be zero",
+            ));
+        }
+
+        let executor = self.ctx.try_get_pipeline_executor()?;
+        let mut stream = futures::stream::select_all(
+            inputs
+                .into_iter()
+                .map(|input| executor.execute_processor(input).into_stream()),
+        );
+
+        let (senders, receivers): (Vec<OutputPort>, Vec<InputPort>) =
+            (0..self.ways).map(|_| create_port(PORT_CAPACITY)).unzip();
+
+        let distribution = self.distribution.clone();
+        executor.execute_task(async move {
+            let mut outputs = Outputs::create(senders);
+            let mut next = 0;
+            while !outputs.all_closed() {
+                // The input is read while an output can take a block without waiting,
+                // the full outputs are flushed meanwhile and don't hold up the others.
+                let block = if !outputs.any_idle() {
+                    outputs.wait_and_flush().await;
+                    continue;
+                } else if outputs.any_pending() {
+                    tokio::select! {
+                        block = stream.next() => block,
+                        _ = outputs.wait_ready() => {
+                            outputs.flush_all();
+                            continue;
+                        }
+                    }
+                } else {
+                    stream.next().await
+                };
+
+                let block = match block {
+                    Some(Ok(block)) => block,
+                    Some(Err(error)) => {
+                        // One failed output fails the query, the others end with the ports.
+                        outputs.push_to_open(Err(error));
+                        break;
+                    }
+                    None => break,
+                };
+
+                match distribution.as_ref() {
+                    Distribution::RoundRobin => {
+                        // The blocks go to the outputs still read and not full.
+                        let ways = outputs.ways();
+                        if let Some(index) = (0..ways)
+                            .map(|i| (next + i) % ways)
+                            .find(|i| outputs.idle(*i))
+                        {
+                            outputs.push(index, Ok(block));
+                            next = (index + 1) % ways;
+                        }
+                    }
+                    Distribution::Hash(scatter) => match scatter.execute(&block) {
+                        Ok(scattered) => {
+                            // The blocks of a full output are queued until it's read.
+                            for (index, block) in scattered.into_iter().enumerate() {
+                                if block.num_rows() > 0 {
+                                    outputs.push(index, Ok(block));
+                                }
+                            }
+                        }
+                        Err(error) => {
+                            outputs.push_to_open(Err(error));
+                            break;
+                        }
+                    },
+                }
+            }
+
+            // The outputs end as soon as their queued blocks are pushed.
+            outputs.close_flushed();
+            while outputs.any_pending() {
+                outputs.wait_and_flush().await;
+                outputs.close_flushed();
+            }
+        });
+
+        Ok(receivers.into_iter().map(Some).collect())
+    }
+}
+
+/// The output ports of a distributor with the blocks queued for the full ones,
+/// the blocks are pushed without waiting so that an output read late doesn't
+/// stop the blocks to the other outputs.
+struct Outputs {
+    senders: Vec<Option<OutputPort>>,
+    pending: Vec<VecDeque<Result<DataBlock>>>,
+    closed: Vec<bool>,
+}
+
+impl Outputs {
+    fn create(senders: Vec<OutputPort>) -> Self {
+        let ways = senders.len();
+        Outputs {
+            senders: senders.into_iter().map(Some).collect(),
+            pending: (0..ways).map(|_| VecDeque::new()).collect(),
+            closed: vec![false; ways],
+        }
+    }
+
+    fn ways(&self) -> usize {
+        self.senders.len()
+    }
+
+    fn all_closed(&self) -> bool {
+        self.closed.iter().all(|closed| *closed)
+    }
+
+    // The output is read and has no queued blocks.
+    fn idle(&self, index: usize) -> bool {
+        !self.closed[index] && self.pending[index].is_empty()
+    }
+
+    fn any_idle(&self) -> bool {
+        (0..self.ways()).any(|index| self.idle(index))
+    }
+
+    fn any_pending(&self) -> bool {
+        self.pending.iter().any(|pending| !pending.is_empty())
+    }
+
+    fn push(&mut self, index: usize, morsel: Result<DataBlock>) {
+        if !self.closed[index] {
+            self.pending[index].push_back(morsel);
+            self.flush(index);
+        }
+    }
+
+    fn push_to_open(&mut self, morsel: Result<DataBlock>) {
+        if let Some(open) = self.closed.iter().position(|closed| !closed) {
+            self.push(open, morsel);
+        }
+    }
+
+    fn flush(&mut self, index: usize) {
+        let sender = match &self.senders[index] {
+            Some(sender) => sender,
+            None => return,
+        };
+        while let Some(morsel) = self.pending[index].pop_front() {
+            match sender.try_push(morsel) {
+                Ok(_) => {}
+                Err(TryPushError::Full(morsel)) => {
+                    self.pending[index].push_front(morsel);
+                    return;
+                }
+                Err(TryPushError::Closed) => {
+                    // The closed outputs are skipped, their blocks are dropped.
+                    self.closed[index] = true;
+                    self.pending[index].clear();
+                }
+            }
+        }
+    }
+
+    fn flush_all(&mut self) {
+        for index in 0..self.ways() {
+            self.flush(index);
+        }
+    }
+
+    // Waits until one of the outputs with queued blocks has room.
+    async fn wait_ready(&self) {
+        let ready = (0..self.ways())
+            .filter(|index| !self.pending[*index].is_empty())
+            .filter_map(|index| self.senders[index].as_ref())
+            .map(|sender| Box::pin(sender.ready()))
+            .collect::<Vec<_>>();
+        if !ready.is_empty() {
+            futures::future::select_all(ready).await;
+        }
+    }
+
+    // Drops the ports of the outputs without queued blocks, their readers reach the end.
+    fn close_flushed(&mut self) {
+        for index in 0..self.ways() {
+            if self.pending[index].is_empty() {
+                self.senders[index] = None;
+                self.closed[index] = true;
+            }
+        }
+    }
+
+    async fn wait_and_flush(&mut self) {
+        self.wait_ready().await;
+        self.flush_all();
+    }
+}
+
+/// One of the outputs of a N-ways to M-ways resize, the blocks of the inputs
+/// are distributed to the outputs by round-robin.
+///
+/// processor1 --        -- resize processor1
+///               \    /
+/// processor2     ----     -- resize processor2
+///               /    \
+/// processor3 --        -- resize processor3
+///                         -- resize processor4
+pub struct ResizeProcessor {
+    distributor: Arc<Distributor>,
+    output: usize,
+}
+
+impl ResizeProcessor {
+    /// The outputs of the resize, they share the inputs.
+    pub fn create(ctx: FuseQueryContextRef, ways: usize) -> Vec<Self> {
+        let distributor = Distributor::create(ctx, Distribution::RoundRobin, ways);
+        (0..distributor.ways())
+            .map(|output| ResizeProcessor {
+                distributor: distributor.clone(),
+                output,
+            })
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl Processor for ResizeProcessor {
+    fn name(&self) -> &str {
+        "ResizeProcessor"
+    }
+
+    fn connect_to(&mut self, input: Arc<dyn Processor>) -> Result<()> {
+        self.distributor.connect_to(input);
+        Ok(())
+    }
+
+    fn inputs(&self) -> Vec<Arc<dyn Processor>> {
+        self.distributor.inputs()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn execute(&self) -> Result<SendableDataBlockStream> {
+        self.distributor.execute(self.output)
+    }
+}
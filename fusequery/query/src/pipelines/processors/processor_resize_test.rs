@@ -0,0 +1,93 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_processor_resize() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::processors::*;
+    use crate::tests;
+
+    let ctx = crate::tests::try_create_context()?;
+    let test_source = tests::NumberTestData::create(ctx.clone());
+
+    let mut pipeline = Pipeline::create(ctx.clone());
+
+    let source = test_source.number_source_transform_for_test(4)?;
+    pipeline.add_source(Arc::new(source))?;
+    pipeline.resize(3)?;
+    assert_eq!(pipeline.nums(), 3);
+    assert_eq!(
+        format!("{:?}", pipeline),
+        "Resize (SourceTransform × 1 processor) to (ResizeProcessor × 3)\
+        \n  SourceTransform × 1 processor"
+    );
+
+    // Every output is read, the blocks are not lost whichever output they go to.
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    let expected = vec![
+        "+--------+",
+        "| number |",
+        "+--------+",
+        "| 0      |",
+        "| 1      |",
+        "| 2      |",
+        "| 3      |",
+        "+--------+",
+    ];
+    common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+
+    // The output can't be executed twice.
+    let output = pipeline.pipe_by_index(1).processor_by_index(0);
+    let actual = output.execute().await.err().unwrap();
+    assert_eq!(
+        actual.message(),
+        "Output 0 of the resize is already executed"
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_processor_resize_output_read_last() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::processors::*;
+    use crate::tests;
+
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings()
+        .update_settings("max_block_size", "1".to_string())?;
+    let test_source = tests::NumberTestData::create(ctx.clone());
+
+    let mut pipeline = Pipeline::create(ctx.clone());
+
+    let source = test_source.number_source_transform_for_test(20)?;
+    pipeline.add_source(Arc::new(source))?;
+    pipeline.resize(3)?;
+
+    let mut streams = vec![];
+    for output in pipeline.last_pipe()?.processors() {
+        streams.push(output.execute().await?);
+    }
+
+    // The first output is not read until the others are done, they don't wait for it.
+    let mut rows = vec![];
+    for stream in streams.into_iter().rev() {
+        let blocks = stream.try_collect::<Vec<_>>().await?;
+        rows.push(blocks.iter().map(|block| block.num_rows()).sum::<usize>());
+    }
+    assert_eq!(20, rows.iter().sum::<usize>());
+    // The first output takes no more than its port and the one block queued for it.
+    assert!(rows[2] <= PORT_CAPACITY + 1);
+
+    Ok(())
+}
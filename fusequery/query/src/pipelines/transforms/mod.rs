@@ -13,7 +13,9 @@ pub use transform_limit::LimitTransform;
 pub use transform_limit_by::LimitByTransform;
 pub use transform_projection::ProjectionTransform;
 pub use transform_remote::RemoteTransform;
+pub use transform_scatter_by_hash::ScatterByHash;
 pub use transform_sort_merge::SortMergeTransform;
+pub use transform_sort_partial::get_sort_descriptions;
 pub use transform_sort_partial::SortPartialTransform;
 pub use transform_source::SourceTransform;
 
@@ -30,6 +32,8 @@ mod transform_group_by_final_test;
 #[cfg(test)]
 mod transform_group_by_partial_test;
 #[cfg(test)]
+mod transform_group_by_two_level_test;
+#[cfg(test)]
 mod transform_limit_by_test;
 #[cfg(test)]
 mod transform_limit_test;
@@ -47,10 +51,12 @@ mod transform_expression_executor;
 mod transform_filter;
 mod transform_group_by_final;
 mod transform_group_by_partial;
+mod transform_group_by_two_level;
 mod transform_limit;
 mod transform_limit_by;
 mod transform_projection;
 mod transform_remote;
+mod transform_scatter_by_hash;
 mod transform_sort_merge;
 mod transform_sort_partial;
 mod transform_source;
@@ -19,11 +19,16 @@ use futures::stream::StreamExt;
 
 use crate::pipelines::processors::EmptyProcessor;
 use crate::pipelines::processors::Processor;
+use crate::sessions::MemoryReservation;
+use crate::sessions::MemoryTracker;
+use crate::sessions::MemoryTrackerRef;
 
 pub struct AggregatorFinalTransform {
     funcs: Vec<Box<dyn AggregateFunction>>,
     schema: DataSchemaRef,
     input: Arc<dyn Processor>,
+    // The merged states are accounted to the tracker of the query.
+    memory_tracker: MemoryTrackerRef,
 }
 
 impl AggregatorFinalTransform {
@@ -40,8 +45,14 @@ impl AggregatorFinalTransform {
             funcs,
             schema,
             input: Arc::new(EmptyProcessor::create()),
+            memory_tracker: MemoryTracker::create("query", 0),
         })
     }
+
+    pub fn with_memory_tracker(mut self, memory_tracker: MemoryTrackerRef) -> Self {
+        self.memory_tracker = memory_tracker;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -70,17 +81,18 @@ impl Processor for AggregatorFinalTransform {
         let mut stream = self.input.execute().await?;
 
         let start = Instant::now();
+        let mut reservation = MemoryReservation::create(self.memory_tracker.clone());
         while let Some(block) = stream.next().await {
             let block = block?;
             for (i, func) in funcs.iter_mut().enumerate() {
-                if let DataValue::Utf8(Some(col)) = DataValue::try_from_column(block.column(i), 0)?
+                if let DataValue::Binary(Some(col)) =
+                    DataValue::try_from_column(block.column(i), 0)?
                 {
-                    let val: DataValue = serde_json::from_str(&col)?;
-                    if let DataValue::Struct(states) = val {
-                        func.merge(&states)?;
-                    }
+                    let states = func.deserialize(&col)?;
+                    func.merge(&states)?;
                 }
             }
+            reservation.resize(funcs.iter().map(|func| func.memory_size()).sum())?;
         }
         let delta = start.elapsed();
         tracing::debug!("Aggregator final cost: {:?}", delta);
@@ -7,11 +7,10 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use common_aggregate_functions::AggregateFunction;
+use common_arrow::arrow::array::BinaryBuilder;
 use common_datablocks::DataBlock;
 use common_datavalues::DataArrayRef;
 use common_datavalues::DataSchemaRef;
-use common_datavalues::DataValue;
-use common_datavalues::StringArray;
 use common_exception::Result;
 use common_planners::Expression;
 use common_streams::DataBlockStream;
@@ -100,10 +99,11 @@ impl Processor for AggregatorPartialTransform {
         let mut columns: Vec<DataArrayRef> = vec![];
         for func in funcs.iter() {
             // Column.
-            let states = DataValue::Struct(func.accumulate_result()?);
-            let ser = serde_json::to_string(&states)?;
-            let col = Arc::new(StringArray::from(vec![ser.as_str()]));
-            columns.push(col);
+            let mut states = vec![];
+            func.serialize(&mut states)?;
+            let mut builder = BinaryBuilder::new(1);
+            builder.append_value(&states)?;
+            columns.push(Arc::new(builder.finish()));
         }
 
         let block = DataBlock::create_by_array(self.schema.clone(), columns);
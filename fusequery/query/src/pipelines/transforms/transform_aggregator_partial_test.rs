@@ -6,6 +6,7 @@
 async fn test_transform_partial_aggregator() -> anyhow::Result<()> {
     use std::sync::Arc;
 
+    use common_datavalues::DataValue;
     use common_planners::*;
     use common_planners::{self};
     use futures::TryStreamExt;
@@ -44,14 +45,21 @@ async fn test_transform_partial_aggregator() -> anyhow::Result<()> {
     let block = &result[0];
     assert_eq!(block.num_columns(), 2);
 
-    let expected = vec![
-        "+-------------------------------------+--------------------------------------------------------------------+",
-        "| sum(number)                         | avg(number)                                                        |",
-        "+-------------------------------------+--------------------------------------------------------------------+",
-        "| {\"Struct\":[{\"UInt64\":19999900000}]} | {\"Struct\":[{\"Struct\":[{\"UInt64\":19999900000},{\"UInt64\":200000}]}]} |",
-        "+-------------------------------------+--------------------------------------------------------------------+",
-    ];
-    common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+    // The binary states of sum(number) and avg(number).
+    let expected = vec![vec![DataValue::UInt64(Some(19999900000))], vec![
+        DataValue::Struct(vec![
+            DataValue::UInt64(Some(19999900000)),
+            DataValue::UInt64(Some(200000)),
+        ]),
+    ]];
+    for (i, expr) in aggr_exprs.iter().enumerate() {
+        let func = expr.to_aggregate_function(&source_schema)?;
+        let states = match DataValue::try_from_column(block.column(i), 0)? {
+            DataValue::Binary(Some(bytes)) => func.deserialize(&bytes)?,
+            other => anyhow::bail!("Unexpected states: {:?}", other),
+        };
+        assert_eq!(states, expected[i]);
+    }
 
     Ok(())
 }
@@ -3,31 +3,26 @@
 // SPDX-License-Identifier: Apache-2.0.
 
 use std::any::Any;
-use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
 
-use common_aggregate_functions::AggregateFunction;
 use common_datablocks::DataBlock;
-use common_datavalues::DataArrayRef;
 use common_datavalues::DataSchemaRef;
-use common_datavalues::DataValue;
 use common_exception::Result;
-use common_infallible::RwLock;
 use common_planners::Expression;
-use common_streams::DataBlockStream;
 use common_streams::SendableDataBlockStream;
 use common_tracing::tracing;
 use futures::stream::StreamExt;
 
 use crate::pipelines::processors::EmptyProcessor;
 use crate::pipelines::processors::Processor;
-
-// Table for <group_key, indices>
-type GroupFuncTable = RwLock<HashMap<Vec<u8>, Vec<Box<dyn AggregateFunction>>, ahash::RandomState>>;
-
-// Group Key ==> Group by values
-type GroupKeyTable = RwLock<HashMap<Vec<u8>, Vec<DataValue>>>;
+use crate::pipelines::transforms::transform_group_by_two_level::group_by_buckets;
+use crate::pipelines::transforms::transform_group_by_two_level::SpilledBuckets;
+use crate::pipelines::transforms::transform_group_by_two_level::SpilledGroupByMerger;
+use crate::pipelines::transforms::transform_group_by_two_level::GROUP_BY_BUCKETS;
+use crate::sessions::MemoryReservation;
+use crate::sessions::MemoryTracker;
+use crate::sessions::MemoryTrackerRef;
 
 pub struct GroupByFinalTransform {
     aggr_exprs: Vec<Expression>,
@@ -35,8 +30,12 @@ pub struct GroupByFinalTransform {
     schema: DataSchemaRef,
     schema_before_group_by: DataSchemaRef,
     input: Arc<dyn Processor>,
-    groups: GroupFuncTable,
-    keys: GroupKeyTable,
+    // The buckets are spilled to the temp files past these bytes, 0 to disable.
+    max_bytes_before_external_group_by: usize,
+    // The number of the buckets merged at the same time.
+    merge_threads: usize,
+    // The buffered blocks and the merged states are accounted to the tracker of the query.
+    memory_tracker: MemoryTrackerRef,
 }
 
 impl GroupByFinalTransform {
@@ -52,10 +51,27 @@ impl GroupByFinalTransform {
             schema,
             schema_before_group_by,
             input: Arc::new(EmptyProcessor::create()),
-            groups: RwLock::new(HashMap::default()),
-            keys: RwLock::new(HashMap::default()),
+            max_bytes_before_external_group_by: 0,
+            merge_threads: 1,
+            memory_tracker: MemoryTracker::create("query", 0),
         }
     }
+
+    /// Spills the buckets to disk once the partial states exceed max_bytes.
+    pub fn with_external_group_by(mut self, max_bytes: usize) -> Self {
+        self.max_bytes_before_external_group_by = max_bytes;
+        self
+    }
+
+    pub fn with_merge_threads(mut self, merge_threads: usize) -> Self {
+        self.merge_threads = merge_threads;
+        self
+    }
+
+    pub fn with_memory_tracker(mut self, memory_tracker: MemoryTrackerRef) -> Self {
+        self.memory_tracker = memory_tracker;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -85,119 +101,57 @@ impl Processor for GroupByFinalTransform {
             .map(|x| x.to_aggregate_function(&self.schema_before_group_by))
             .collect::<Result<Vec<_>>>()?;
 
-        let aggr_funcs_len = aggr_funcs.len();
         let group_expr_len = self.group_exprs.len();
 
+        // Scatter the partial states to the buckets by the group key,
+        // the groups of different buckets are disjoint.
         let start = Instant::now();
+        let mut buckets: Vec<Vec<DataBlock>> = (0..GROUP_BY_BUCKETS).map(|_| vec![]).collect();
+        let mut bytes = 0;
+        let mut spills = vec![];
+        // The schema of the partial states, the spill files are written with it.
+        let mut input_schema = self.schema.clone();
+        let mut reservation = MemoryReservation::create(self.memory_tracker.clone());
         let mut stream = self.input.execute().await?;
         while let Some(block) = stream.next().await {
-            let mut groups = self.groups.write();
-            let mut keys = self.keys.write();
             let block = block?;
-            for row in 0..block.num_rows() {
-                if let DataValue::Binary(Some(group_key)) =
-                    DataValue::try_from_column(block.column(1 + aggr_funcs_len), row)?
-                {
-                    match groups.get_mut(&group_key) {
-                        None => {
-                            let mut funcs = aggr_funcs.clone();
-                            for (i, func) in funcs.iter_mut().enumerate() {
-                                if let DataValue::Utf8(Some(col)) =
-                                    DataValue::try_from_column(block.column(i), row)?
-                                {
-                                    let val: DataValue = serde_json::from_str(&col)?;
-                                    if let DataValue::Struct(states) = val {
-                                        func.merge(&states)?;
-                                    }
-                                }
-                            }
-                            groups.insert(group_key.clone(), funcs);
-
-                            if let DataValue::Utf8(Some(col)) =
-                                DataValue::try_from_column(block.column(aggr_funcs_len), row)?
-                            {
-                                let val: DataValue = serde_json::from_str(&col)?;
-                                if let DataValue::Struct(states) = val {
-                                    keys.insert(group_key.clone(), states);
-                                }
-                            }
-                        }
-                        Some(funcs) => {
-                            for (i, func) in funcs.iter_mut().enumerate() {
-                                if let DataValue::Utf8(Some(col)) =
-                                    DataValue::try_from_column(block.column(i), row)?
-                                {
-                                    let val: DataValue = serde_json::from_str(&col)?;
-                                    if let DataValue::Struct(states) = val {
-                                        func.merge(&states)?;
-                                    }
-                                }
-                            }
-                        }
-                    };
-                }
+            if block.num_rows() == 0 {
+                continue;
             }
-        }
-        let delta = start.elapsed();
-        tracing::debug!("Group by final cost: {:?}", delta);
-
-        // Collect the merge states.
-        let groups = self.groups.read();
-        let keys = self.keys.read();
-
-        let mut group_values: Vec<Vec<DataValue>> = {
-            let mut values = vec![];
-            for _i in 0..group_expr_len {
-                values.push(vec![])
-            }
-            values
-        };
-
-        let mut aggr_values: Vec<Vec<DataValue>> = {
-            let mut values = vec![];
-            for _i in 0..aggr_funcs_len {
-                values.push(vec![])
-            }
-            values
-        };
-        for (key, aggr_funcs) in groups.iter() {
-            if let Some(values) = keys.get(key) {
-                for (i, value) in values.iter().enumerate() {
-                    group_values[i].push(value.clone());
+            input_schema = block.schema().clone();
+            let indices = group_by_buckets(&block)?;
+            let scattered = DataBlock::scatter_block(&block, &indices, GROUP_BY_BUCKETS)?;
+            for (bucket, block) in scattered.into_iter().enumerate() {
+                if block.num_rows() > 0 {
+                    bytes += block.memory_size();
+                    buckets[bucket].push(block);
                 }
             }
-
-            for (i, func) in aggr_funcs.iter().enumerate() {
-                let merge = func.merge_result()?;
-                aggr_values[i].push(merge);
-            }
-        }
-
-        // Build final state block.
-        let mut columns: Vec<DataArrayRef> = Vec::with_capacity(aggr_funcs_len + group_expr_len);
-
-        for value in &aggr_values {
-            if !value.is_empty() {
-                columns.push(DataValue::try_into_data_array(value.as_slice())?);
+            reservation.resize(bytes)?;
+
+            if self.max_bytes_before_external_group_by > 0
+                && bytes > self.max_bytes_before_external_group_by
+            {
+                tracing::debug!("Group by final spill {} bytes", bytes);
+                spills.push(SpilledBuckets::try_create_from_blocks(
+                    &input_schema,
+                    &buckets,
+                )?);
+                buckets.iter_mut().for_each(|blocks| blocks.clear());
+                bytes = 0;
+                reservation.resize(bytes)?;
             }
         }
-
-        for value in &group_values {
-            if !value.is_empty() {
-                columns.push(DataValue::try_into_data_array(value.as_slice())?);
-            }
-        }
-
-        let mut blocks = vec![];
-        if !columns.is_empty() {
-            let block = DataBlock::create_by_array(self.schema.clone(), columns);
-            blocks.push(block);
-        }
-
-        Ok(Box::pin(DataBlockStream::create(
-            self.schema.clone(),
-            None,
-            blocks,
-        )))
+        let delta = start.elapsed();
+        tracing::debug!("Group by final scatter cost: {:?}", delta);
+
+        // The buckets are merged one by one, at most merge_threads of them at the same time.
+        // The buffered buckets are accounted by the merger from now on.
+        drop(reservation);
+        let merger = SpilledGroupByMerger::create(input_schema, aggr_funcs, spills, buckets)
+            .with_final(self.schema.clone(), group_expr_len)
+            .with_merge_threads(self.merge_threads)
+            .with_memory_tracker(self.memory_tracker.clone())?;
+        Ok(merger.into_stream())
     }
 }
@@ -77,3 +77,150 @@ async fn test_transform_final_group_by() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_transform_final_group_by_with_spill() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_planners::*;
+    use common_planners::{self};
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::processors::*;
+    use crate::pipelines::transforms::*;
+
+    let ctx = crate::tests::try_create_context()?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    // sum(number), avg(number)
+    let aggr_exprs = &[sum(col("number")), avg(col("number"))];
+    let group_exprs = &[col("number")];
+    let aggr_partial = PlanBuilder::create(test_source.number_schema_for_test()?)
+        .aggregate_partial(aggr_exprs, group_exprs)?
+        .build()?;
+    let aggr_final = PlanBuilder::create(test_source.number_schema_for_test()?)
+        .aggregate_final(
+            test_source.number_schema_for_test()?,
+            aggr_exprs,
+            group_exprs,
+        )?
+        .build()?;
+
+    // Every number is read twice, the partial states meet in the final stage.
+    let mut pipeline = Pipeline::create(ctx.clone());
+    let source_schema = test_source.number_schema_for_test()?;
+    pipeline.add_source(Arc::new(test_source.number_source_transform_for_test(6)?))?;
+    pipeline.add_source(Arc::new(test_source.number_source_transform_for_test(6)?))?;
+    pipeline.add_simple_transform(|| {
+        Ok(Box::new(GroupByPartialTransform::create(
+            aggr_partial.schema(),
+            source_schema.clone(),
+            aggr_exprs.to_vec(),
+            group_exprs.to_vec(),
+        )))
+    })?;
+    pipeline.merge_processor()?;
+
+    // The buckets are spilled after every block and merged two at a time.
+    pipeline.add_simple_transform(|| {
+        Ok(Box::new(
+            GroupByFinalTransform::create(
+                aggr_final.schema(),
+                source_schema.clone(),
+                aggr_exprs.to_vec(),
+                group_exprs.to_vec(),
+            )
+            .with_external_group_by(1)
+            .with_merge_threads(2),
+        ))
+    })?;
+
+    // Result.
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    assert_eq!(
+        result.iter().map(|block| block.num_rows()).sum::<usize>(),
+        6
+    );
+
+    let expected = vec![
+        "+-------------+-------------+--------+",
+        "| sum(number) | avg(number) | number |",
+        "+-------------+-------------+--------+",
+        "| 0           | 0           | 0      |",
+        "| 10          | 5           | 5      |",
+        "| 2           | 1           | 1      |",
+        "| 4           | 2           | 2      |",
+        "| 6           | 3           | 3      |",
+        "| 8           | 4           | 4      |",
+        "+-------------+-------------+--------+",
+    ];
+    common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_final_group_by_memory_limit() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_planners::*;
+    use common_planners::{self};
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::processors::*;
+    use crate::pipelines::transforms::*;
+
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings()
+        .update_settings("max_memory_usage", "1".to_string())?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    let aggr_exprs = &[sum(col("number"))];
+    let group_exprs = &[col("number")];
+    let aggr_partial = PlanBuilder::create(test_source.number_schema_for_test()?)
+        .aggregate_partial(aggr_exprs, group_exprs)?
+        .build()?;
+    let aggr_final = PlanBuilder::create(test_source.number_schema_for_test()?)
+        .aggregate_final(
+            test_source.number_schema_for_test()?,
+            aggr_exprs,
+            group_exprs,
+        )?
+        .build()?;
+
+    let mut pipeline = Pipeline::create(ctx.clone());
+    let source_schema = test_source.number_schema_for_test()?;
+    pipeline.add_source(Arc::new(test_source.number_source_transform_for_test(5)?))?;
+    pipeline.add_simple_transform(|| {
+        Ok(Box::new(GroupByPartialTransform::create(
+            aggr_partial.schema(),
+            source_schema.clone(),
+            aggr_exprs.to_vec(),
+            group_exprs.to_vec(),
+        )))
+    })?;
+
+    // The buffered partial states exceed the max_memory_usage of the query.
+    let memory_tracker = ctx.try_get_memory_tracker()?;
+    pipeline.add_simple_transform(|| {
+        Ok(Box::new(
+            GroupByFinalTransform::create(
+                aggr_final.schema(),
+                source_schema.clone(),
+                aggr_exprs.to_vec(),
+                group_exprs.to_vec(),
+            )
+            .with_memory_tracker(memory_tracker.clone()),
+        ))
+    })?;
+
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await;
+    assert_eq!(result.unwrap_err().code(), 42);
+    assert_eq!(memory_tracker.get_usage(), 0);
+
+    Ok(())
+}
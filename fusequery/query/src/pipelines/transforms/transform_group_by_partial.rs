@@ -3,21 +3,16 @@
 // SPDX-License-Identifier: Apache-2.0.
 
 use std::any::Any;
-use std::collections::HashMap;
+use std::hash::Hash;
 use std::sync::Arc;
 use std::time::Instant;
 
-use common_aggregate_functions::AggregateFunction;
-use common_arrow::arrow::array::BinaryBuilder;
-use common_arrow::arrow::array::StringBuilder;
 use common_datablocks::DataBlock;
-use common_datavalues::DataArrayRef;
-use common_datavalues::DataColumnarValue;
+use common_datablocks::HashMethod;
+use common_datablocks::HashMethodKind;
 use common_datavalues::DataSchemaRef;
 use common_datavalues::DataSchemaRefExt;
-use common_datavalues::DataValue;
 use common_exception::Result;
-use common_infallible::RwLock;
 use common_planners::Expression;
 use common_streams::DataBlockStream;
 use common_streams::SendableDataBlockStream;
@@ -26,18 +21,12 @@ use futures::stream::StreamExt;
 
 use crate::pipelines::processors::EmptyProcessor;
 use crate::pipelines::processors::Processor;
-
-// Table for <group_key, ((function, column_name, args), keys) >
-type GroupFuncTable = RwLock<
-    HashMap<
-        Vec<u8>,
-        (
-            Vec<(Box<dyn AggregateFunction>, String, Vec<String>)>,
-            Vec<DataValue>,
-        ),
-        ahash::RandomState,
-    >,
->;
+use crate::pipelines::transforms::transform_group_by_two_level::GroupStates;
+use crate::pipelines::transforms::transform_group_by_two_level::SpilledBuckets;
+use crate::pipelines::transforms::transform_group_by_two_level::SpilledGroupByMerger;
+use crate::sessions::MemoryReservation;
+use crate::sessions::MemoryTracker;
+use crate::sessions::MemoryTrackerRef;
 
 pub struct GroupByPartialTransform {
     aggr_exprs: Vec<Expression>,
@@ -45,7 +34,10 @@ pub struct GroupByPartialTransform {
     schema: DataSchemaRef,
     schema_before_group_by: DataSchemaRef,
     input: Arc<dyn Processor>,
-    groups: GroupFuncTable,
+    // The buckets are spilled to the temp files past these bytes, 0 to disable.
+    max_bytes_before_external_group_by: usize,
+    // The groups are accounted to the tracker of the query.
+    memory_tracker: MemoryTrackerRef,
 }
 
 impl GroupByPartialTransform {
@@ -61,9 +53,109 @@ impl GroupByPartialTransform {
             schema,
             schema_before_group_by,
             input: Arc::new(EmptyProcessor::create()),
-            groups: RwLock::new(HashMap::default()),
+            max_bytes_before_external_group_by: 0,
+            memory_tracker: MemoryTracker::create("query", 0),
         }
     }
+
+    /// Spills the buckets to disk once the groups exceed max_bytes,
+    /// the spilled buckets are merged bucket by bucket at the end.
+    pub fn with_external_group_by(mut self, max_bytes: usize) -> Self {
+        self.max_bytes_before_external_group_by = max_bytes;
+        self
+    }
+
+    pub fn with_memory_tracker(mut self, memory_tracker: MemoryTrackerRef) -> Self {
+        self.memory_tracker = memory_tracker;
+        self
+    }
+
+    async fn execute_with_method<Method>(&self, method: Method) -> Result<SendableDataBlockStream>
+    where
+        Method: HashMethod + Send + Sync,
+        Method::HashKey: Eq + Hash + Clone,
+    {
+        let start = Instant::now();
+        let schema_before_group_by = self.schema_before_group_by.clone();
+
+        let funcs = self
+            .aggr_exprs
+            .iter()
+            .map(|x| x.to_aggregate_function(&schema_before_group_by))
+            .collect::<Result<Vec<_>>>()?;
+        let args = self
+            .aggr_exprs
+            .iter()
+            .map(|x| x.to_aggregate_function_names())
+            .collect::<Result<Vec<_>>>()?;
+        let cols = self
+            .group_exprs
+            .iter()
+            .map(|x| x.column_name())
+            .collect::<Vec<_>>();
+
+        let mut groups = GroupStates::<Method::HashKey>::create(&funcs);
+        let mut spills = vec![];
+        let mut reservation = MemoryReservation::create(self.memory_tracker.clone());
+        let mut stream = self.input.execute().await?;
+        while let Some(block) = stream.next().await {
+            let block = block?;
+            let group_columns = cols
+                .iter()
+                .map(|col| block.try_column_by_name(col))
+                .collect::<Result<Vec<_>>>()?;
+
+            // 1.1, 1.2 and 1.3.
+            groups.accumulate(&method, &args, &group_columns, &block)?;
+            reservation.resize(groups.memory_size())?;
+
+            // Flush the buckets to disk, they are merged when the input is finished.
+            if self.max_bytes_before_external_group_by > 0
+                && groups.memory_size() > self.max_bytes_before_external_group_by
+            {
+                tracing::debug!("Group by partial spill {} bytes", groups.memory_size());
+                spills.push(SpilledBuckets::try_create(&self.schema, &groups)?);
+                groups = GroupStates::create(&funcs);
+                reservation.resize(groups.memory_size())?;
+            }
+        }
+
+        let delta = start.elapsed();
+        tracing::debug!("Group by partial cost: {:?}", delta);
+
+        if !spills.is_empty() {
+            let buckets = groups
+                .states_blocks(&self.schema)?
+                .into_iter()
+                .map(|block| block.into_iter().collect())
+                .collect();
+            // The groups in memory are accounted by the merger from now on.
+            drop(reservation);
+            let merger = SpilledGroupByMerger::create(self.schema.clone(), funcs, spills, buckets)
+                .with_memory_tracker(self.memory_tracker.clone())?;
+            return Ok(merger.into_stream());
+        }
+
+        if groups.is_empty() {
+            return Ok(Box::pin(DataBlockStream::create(
+                DataSchemaRefExt::create(vec![]),
+                None,
+                vec![],
+            )));
+        }
+
+        // A block for every bucket.
+        let blocks = groups
+            .states_blocks(&self.schema)?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        Ok(Box::pin(DataBlockStream::create(
+            self.schema.clone(),
+            None,
+            blocks,
+        )))
+    }
 }
 
 #[async_trait::async_trait]
@@ -95,7 +187,7 @@ impl Processor for GroupByPartialTransform {
     /// 4, 5
     ///
     /// grouping by [A%3]
-    /// 1.1)
+    /// 1.1) make the hash key of every row, the integer keys are packed into a fixed-width key
     /// row_idx, group_key, A
     /// 0, 1, 1
     /// 1, 2, 2
@@ -103,125 +195,36 @@ impl Processor for GroupByPartialTransform {
     /// 3, 1, 4
     /// 4, 2, 5
     ///
-    /// 1.2) make indices group(for vector compute)
-    /// group_key, indices
-    /// 0, [2]
-    /// 1, [0, 3]
-    /// 2, [1, 4]
+    /// 1.2) find the group of every row
+    /// row_idx, group
+    /// 0, 0
+    /// 1, 1
+    /// 2, 2
+    /// 3, 0
+    /// 4, 1
     ///
-    /// 1.3) apply aggregate function(SUM(A)) to the take block
+    /// 1.3) apply aggregate function(SUM(A)) to the groups of the rows in one pass
     /// group_key, SUM(A)
     /// <0, 3>
     /// <1, 1+4>
     /// <2, 2+5>
     async fn execute(&self) -> Result<SendableDataBlockStream> {
         tracing::debug!("execute...");
-        let aggr_len = self.aggr_exprs.len();
-        let start = Instant::now();
-        let schema_before_group_by = self.schema_before_group_by.clone();
-
-        let mut stream = self.input.execute().await?;
-
-        while let Some(block) = stream.next().await {
-            let block = block?;
-            let cols = self
-                .group_exprs
-                .iter()
-                .map(|x| x.column_name())
-                .collect::<Vec<_>>();
-
-            // 1.1 and 1.2.
-            let group_blocks = DataBlock::group_by(&block, &cols)?;
-            // 1.3 Apply take blocks to aggregate function by group_key.
-            {
-                for (group_key, group_keys, take_block) in group_blocks {
-                    let rows = take_block.num_rows();
-
-                    let mut groups = self.groups.write();
-                    match groups.get_mut(&group_key) {
-                        // New group.
-                        None => {
-                            let mut aggr_funcs = vec![];
-                            for expr in &self.aggr_exprs {
-                                let mut func =
-                                    expr.to_aggregate_function(&schema_before_group_by)?;
-                                let name = expr.column_name();
-                                let args = expr.to_aggregate_function_names()?;
-                                let arg_columns = args
-                                    .iter()
-                                    .map(|arg| {
-                                        take_block.try_column_by_name(arg).map(|c| c.clone())
-                                    })
-                                    .collect::<Result<Vec<DataColumnarValue>>>()?;
-                                func.accumulate(&arg_columns, rows)?;
-                                aggr_funcs.push((func, name, args));
-                            }
-
-                            groups.insert(group_key.clone(), (aggr_funcs, group_keys));
-                        }
-                        // Accumulate result against the take block by indices.
-                        Some((aggr_funcs, _)) => {
-                            for func in aggr_funcs {
-                                let arg_columns = func
-                                    .2
-                                    .iter()
-                                    .map(|arg| {
-                                        take_block.try_column_by_name(arg).map(|c| c.clone())
-                                    })
-                                    .collect::<Result<Vec<DataColumnarValue>>>()?;
-
-                                func.0.accumulate(&arg_columns, rows)?
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        if self.groups.read().is_empty() {
-            return Ok(Box::pin(DataBlockStream::create(
-                DataSchemaRefExt::create(vec![]),
-                None,
-                vec![],
-            )));
-        }
-
-        let delta = start.elapsed();
-        tracing::debug!("Group by partial cost: {:?}", delta);
-
-        let groups = self.groups.read();
-
-        // Builders.
-        let mut builders: Vec<StringBuilder> = (0..1 + aggr_len)
-            .map(|_| StringBuilder::new(groups.len()))
-            .collect();
-
-        let mut group_key_builder = BinaryBuilder::new(groups.len());
-        for (key, (funcs, values)) in groups.iter() {
-            for (idx, func) in funcs.iter().enumerate() {
-                let states = DataValue::Struct(func.0.accumulate_result()?);
-                let ser = serde_json::to_string(&states)?;
-                builders[idx].append_value(ser.as_str())?;
-            }
-
-            // TODO: separate keys in each column
-            let key_ser = serde_json::to_string(&DataValue::Struct(values.clone()))?;
-            builders[aggr_len].append_value(key_ser.as_str())?;
-
-            group_key_builder.append_value(key)?;
+        let cols = self
+            .group_exprs
+            .iter()
+            .map(|x| x.column_name())
+            .collect::<Vec<_>>();
+        let method = DataBlock::choose_hash_method(&self.schema_before_group_by, &cols)?;
+        tracing::debug!("Group by partial hash method: {}", method.name());
+
+        match method {
+            HashMethodKind::Serializer(method) => self.execute_with_method(method).await,
+            HashMethodKind::KeysU8(method) => self.execute_with_method(method).await,
+            HashMethodKind::KeysU16(method) => self.execute_with_method(method).await,
+            HashMethodKind::KeysU32(method) => self.execute_with_method(method).await,
+            HashMethodKind::KeysU64(method) => self.execute_with_method(method).await,
+            HashMethodKind::KeysU128(method) => self.execute_with_method(method).await,
         }
-
-        let mut columns: Vec<DataArrayRef> = Vec::with_capacity(self.schema.fields().len());
-        for mut builder in builders {
-            columns.push(Arc::new(builder.finish()));
-        }
-        columns.push(Arc::new(group_key_builder.finish()));
-
-        let block = DataBlock::create_by_array(self.schema.clone(), columns);
-        Ok(Box::pin(DataBlockStream::create(
-            self.schema.clone(),
-            None,
-            vec![block],
-        )))
     }
 }
@@ -6,6 +6,7 @@
 async fn test_transform_partial_group_by() -> anyhow::Result<()> {
     use std::sync::Arc;
 
+    use common_datavalues::DataValue;
     use common_planners::*;
     use common_planners::{self};
     use futures::TryStreamExt;
@@ -47,18 +48,42 @@ async fn test_transform_partial_group_by() -> anyhow::Result<()> {
     assert_eq!(block.num_columns(), 4);
 
     // SELECT SUM(number), AVG(number), number ... GROUP BY number;
-    let expected = vec![
-        "+---------------------------+-----------------------------------------------------+---------------------------+------------------+",
-        "| sum(number)               | avg(number)                                         | _group_keys               | _group_by_key    |",
-        "+---------------------------+-----------------------------------------------------+---------------------------+------------------+",
-        "| {\"Struct\":[{\"UInt64\":0}]} | {\"Struct\":[{\"Struct\":[{\"UInt64\":0},{\"UInt64\":1}]}]} | {\"Struct\":[{\"UInt64\":0}]} | 0000000000000000 |",
-        "| {\"Struct\":[{\"UInt64\":1}]} | {\"Struct\":[{\"Struct\":[{\"UInt64\":1},{\"UInt64\":1}]}]} | {\"Struct\":[{\"UInt64\":1}]} | 0100000000000000 |",
-        "| {\"Struct\":[{\"UInt64\":2}]} | {\"Struct\":[{\"Struct\":[{\"UInt64\":2},{\"UInt64\":1}]}]} | {\"Struct\":[{\"UInt64\":2}]} | 0200000000000000 |",
-        "| {\"Struct\":[{\"UInt64\":3}]} | {\"Struct\":[{\"Struct\":[{\"UInt64\":3},{\"UInt64\":1}]}]} | {\"Struct\":[{\"UInt64\":3}]} | 0300000000000000 |",
-        "| {\"Struct\":[{\"UInt64\":4}]} | {\"Struct\":[{\"Struct\":[{\"UInt64\":4},{\"UInt64\":1}]}]} | {\"Struct\":[{\"UInt64\":4}]} | 0400000000000000 |",
-        "+---------------------------+-----------------------------------------------------+---------------------------+------------------+",
-    ];
-    common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+    let funcs = aggr_exprs
+        .iter()
+        .map(|expr| expr.to_aggregate_function(&source_schema))
+        .collect::<common_exception::Result<Vec<_>>>()?;
+    let binary = |column: usize, row: usize| -> anyhow::Result<Vec<u8>> {
+        match DataValue::try_from_column(block.column(column), row)? {
+            DataValue::Binary(Some(bytes)) => Ok(bytes),
+            other => anyhow::bail!("Unexpected binary: {:?}", other),
+        }
+    };
+
+    // Rows of [sum states, avg states, group keys] by the group key.
+    let mut rows = vec![];
+    for row in 0..block.num_rows() {
+        rows.push((binary(3, row)?, vec![
+            funcs[0].deserialize(&binary(0, row)?)?,
+            funcs[1].deserialize(&binary(1, row)?)?,
+            DataValue::deserialize_values(&binary(2, row)?)?,
+        ]));
+    }
+    rows.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let expected = (0..5u64)
+        .map(|number| {
+            vec![
+                vec![DataValue::UInt64(Some(number))],
+                vec![DataValue::Struct(vec![
+                    DataValue::UInt64(Some(number)),
+                    DataValue::UInt64(Some(1)),
+                ])],
+                vec![DataValue::UInt64(Some(number))],
+            ]
+        })
+        .collect::<Vec<_>>();
+    let actual = rows.into_iter().map(|(_, row)| row).collect::<Vec<_>>();
+    assert_eq!(actual, expected);
 
     Ok(())
 }
@@ -0,0 +1,560 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use common_aggregate_functions::AggregateFunction;
+use common_aggregate_functions::AggregateStates;
+use common_arrow::arrow::array::BinaryBuilder;
+use common_arrow::arrow::array::UInt64Array;
+use common_datablocks::DataBlock;
+use common_datablocks::HashMethod;
+use common_datavalues::DataArrayRef;
+use common_datavalues::DataColumnarValue;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataValue;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_streams::SendableDataBlockStream;
+use common_streams::SpillFile;
+use common_streams::SpillStream;
+use futures::future::BoxFuture;
+use futures::stream::StreamExt;
+
+use crate::sessions::MemoryReservation;
+use crate::sessions::MemoryTrackerRef;
+
+/// The number of the buckets of the two-level group by table, the groups are placed
+/// by the hash of the group key so that every bucket is flushed and merged on its own.
+pub const GROUP_BY_BUCKETS: usize = 16;
+
+/// The bucket of the group key. The hash (FNV-1a) is stable across the processes
+/// because the partial states may come from the other nodes.
+pub fn group_by_bucket(group_key: &[u8]) -> usize {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in group_key {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    (hash % GROUP_BY_BUCKETS as u64) as usize
+}
+
+/// The buckets of the rows of the partial states block, by the group key column.
+pub fn group_by_buckets(block: &DataBlock) -> Result<DataColumnarValue> {
+    let column = block.column(block.num_columns() - 1);
+    let mut buckets = Vec::with_capacity(block.num_rows());
+    for row in 0..block.num_rows() {
+        match DataValue::try_from_column(column, row)? {
+            DataValue::Binary(Some(group_key)) => buckets.push(group_by_bucket(&group_key) as u64),
+            _ => buckets.push(0),
+        }
+    }
+    Ok(DataColumnarValue::Array(Arc::new(UInt64Array::from(
+        buckets,
+    ))))
+}
+
+/// The approximate memory size of the key of a new group in the partial table.
+pub fn group_memory_size(group_key: &[u8], group_keys: &[DataValue]) -> usize {
+    group_key.len() + std::mem::size_of::<DataValue>() * group_keys.len()
+}
+
+/// The groups of the partial aggregation. The states of a function are allocated
+/// in its arena and indexed by the group, the blocks are accumulated into them in place.
+pub struct GroupStates<Key> {
+    // Table for <hash_key, group>
+    table: HashMap<Key, usize, ahash::RandomState>,
+    // The group key and the group by values of every group.
+    keys: Vec<(Vec<u8>, Vec<DataValue>)>,
+    // The states of every function, indexed by the group.
+    states: Vec<Box<dyn AggregateStates>>,
+    // The groups of every bucket.
+    buckets: Vec<Vec<usize>>,
+    bytes: usize,
+}
+
+impl<Key: Eq + Hash + Clone> GroupStates<Key> {
+    pub fn create(funcs: &[Box<dyn AggregateFunction>]) -> Self {
+        GroupStates {
+            table: HashMap::default(),
+            keys: vec![],
+            states: funcs.iter().map(|func| func.create_states()).collect(),
+            buckets: (0..GROUP_BY_BUCKETS).map(|_| vec![]).collect(),
+            bytes: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The approximate memory size of the groups, with their states.
+    pub fn memory_size(&self) -> usize {
+        self.bytes
+            + self
+                .states
+                .iter()
+                .map(|states| states.memory_size())
+                .sum::<usize>()
+    }
+
+    /// Accumulates the rows of the block into their groups, args are the argument columns
+    /// of every function.
+    pub fn accumulate<Method: HashMethod<HashKey = Key>>(
+        &mut self,
+        method: &Method,
+        args: &[Vec<String>],
+        group_columns: &[&DataColumnarValue],
+        block: &DataBlock,
+    ) -> Result<()> {
+        let rows = block.num_rows();
+        let keys = method.build_keys(group_columns, rows)?;
+
+        // The group of every row, which is the place of its states.
+        let mut places = Vec::with_capacity(rows);
+        for (row, key) in keys.into_iter().enumerate() {
+            let group = match self.table.get(&key) {
+                Some(group) => *group,
+                None => self.create_group(method, group_columns, key, row)?,
+            };
+            places.push(group);
+        }
+
+        for (idx, states) in self.states.iter_mut().enumerate() {
+            let arg_columns = args[idx]
+                .iter()
+                .map(|arg| block.try_column_by_name(arg).map(|c| c.clone()))
+                .collect::<Result<Vec<DataColumnarValue>>>()?;
+            states.accumulate(&arg_columns, &places)?;
+        }
+        Ok(())
+    }
+
+    fn create_group<Method: HashMethod<HashKey = Key>>(
+        &mut self,
+        method: &Method,
+        group_columns: &[&DataColumnarValue],
+        key: Key,
+        row: usize,
+    ) -> Result<usize> {
+        let group = self.keys.len();
+        let group_key = method.key_bytes(&key);
+        let group_keys = group_columns
+            .iter()
+            .map(|col| DataValue::try_from_column(col, row))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.bytes += group_memory_size(&group_key, &group_keys);
+        self.buckets[group_by_bucket(&group_key)].push(group);
+        for states in self.states.iter_mut() {
+            states.add_state();
+        }
+        self.keys.push((group_key, group_keys));
+        self.table.insert(key, group);
+        Ok(group)
+    }
+
+    /// The block of the partial states of the groups in the bucket:
+    /// the states of every function, the serialized group keys and the group key.
+    pub fn bucket_states_block(
+        &self,
+        schema: &DataSchemaRef,
+        bucket: usize,
+    ) -> Result<Option<DataBlock>> {
+        let groups = &self.buckets[bucket];
+        if groups.is_empty() {
+            return Ok(None);
+        }
+
+        let aggr_len = self.states.len();
+        let mut builders: Vec<BinaryBuilder> = (0..1 + aggr_len)
+            .map(|_| BinaryBuilder::new(groups.len()))
+            .collect();
+        let mut group_key_builder = BinaryBuilder::new(groups.len());
+        let mut bytes = vec![];
+        for group in groups {
+            for (idx, states) in self.states.iter().enumerate() {
+                bytes.clear();
+                states.serialize(*group, &mut bytes)?;
+                builders[idx].append_value(&bytes)?;
+            }
+
+            // TODO: separate keys in each column
+            let (key, values) = &self.keys[*group];
+            bytes.clear();
+            DataValue::serialize_values(values, &mut bytes)?;
+            builders[aggr_len].append_value(&bytes)?;
+
+            group_key_builder.append_value(key)?;
+        }
+
+        let mut columns: Vec<DataArrayRef> = Vec::with_capacity(schema.fields().len());
+        for mut builder in builders {
+            columns.push(Arc::new(builder.finish()));
+        }
+        columns.push(Arc::new(group_key_builder.finish()));
+        Ok(Some(DataBlock::create_by_array(schema.clone(), columns)))
+    }
+
+    /// The partial states blocks of all the buckets, None for the empty buckets.
+    pub fn states_blocks(&self, schema: &DataSchemaRef) -> Result<Vec<Option<DataBlock>>> {
+        (0..GROUP_BY_BUCKETS)
+            .map(|bucket| self.bucket_states_block(schema, bucket))
+            .collect()
+    }
+}
+
+/// The merged partial states of a bucket, the states of the groups are allocated in the
+/// arenas of the functions and indexed by the group.
+pub struct StatesBucket {
+    funcs: Vec<Box<dyn AggregateFunction>>,
+    states: Vec<Box<dyn AggregateStates>>,
+    // Table for <group_key, group>
+    groups: HashMap<Vec<u8>, usize, ahash::RandomState>,
+    // The group key and the serialized group by values of every group.
+    keys: Vec<(Vec<u8>, Vec<u8>)>,
+    bytes: usize,
+}
+
+impl StatesBucket {
+    pub fn create(funcs: Vec<Box<dyn AggregateFunction>>) -> Self {
+        let states = funcs.iter().map(|func| func.create_states()).collect();
+        StatesBucket {
+            funcs,
+            states,
+            groups: HashMap::default(),
+            keys: vec![],
+            bytes: 0,
+        }
+    }
+
+    /// The approximate memory size of the groups, with their states.
+    pub fn memory_size(&self) -> usize {
+        self.bytes
+            + self
+                .states
+                .iter()
+                .map(|states| states.memory_size())
+                .sum::<usize>()
+    }
+
+    /// Merges the rows of the partial states block into the groups.
+    pub fn merge_block(&mut self, block: &DataBlock) -> Result<()> {
+        let aggr_len = self.funcs.len();
+        for row in 0..block.num_rows() {
+            let group_key = match DataValue::try_from_column(block.column(1 + aggr_len), row)? {
+                DataValue::Binary(Some(group_key)) => group_key,
+                _ => continue,
+            };
+
+            let group = match self.groups.get(&group_key) {
+                Some(group) => *group,
+                None => {
+                    let keys = match DataValue::try_from_column(block.column(aggr_len), row)? {
+                        DataValue::Binary(Some(keys)) => keys,
+                        _ => vec![],
+                    };
+                    for states in self.states.iter_mut() {
+                        states.add_state();
+                    }
+                    self.bytes += 2 * group_key.len() + keys.len();
+                    self.keys.push((group_key.clone(), keys));
+                    self.groups.insert(group_key, self.keys.len() - 1);
+                    self.keys.len() - 1
+                }
+            };
+
+            for (i, func) in self.funcs.iter().enumerate() {
+                if let DataValue::Binary(Some(col)) =
+                    DataValue::try_from_column(block.column(i), row)?
+                {
+                    let states = func.deserialize(&col)?;
+                    self.states[i].merge(group, &states)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The block of the merged partial states, in the format of the partial transform.
+    pub fn states_block(&self, schema: &DataSchemaRef) -> Result<Option<DataBlock>> {
+        if self.keys.is_empty() {
+            return Ok(None);
+        }
+
+        let aggr_len = self.funcs.len();
+        let mut builders: Vec<BinaryBuilder> = (0..1 + aggr_len)
+            .map(|_| BinaryBuilder::new(self.keys.len()))
+            .collect();
+        let mut group_key_builder = BinaryBuilder::new(self.keys.len());
+        let mut bytes = vec![];
+        for (group, (key, keys)) in self.keys.iter().enumerate() {
+            for (idx, states) in self.states.iter().enumerate() {
+                bytes.clear();
+                states.serialize(group, &mut bytes)?;
+                builders[idx].append_value(&bytes)?;
+            }
+            builders[aggr_len].append_value(keys)?;
+            group_key_builder.append_value(key)?;
+        }
+
+        let mut columns: Vec<DataArrayRef> = Vec::with_capacity(schema.fields().len());
+        for mut builder in builders {
+            columns.push(Arc::new(builder.finish()));
+        }
+        columns.push(Arc::new(group_key_builder.finish()));
+        Ok(Some(DataBlock::create_by_array(schema.clone(), columns)))
+    }
+
+    /// The block of the final results: the results of every function and the group by values.
+    pub fn final_block(
+        &self,
+        schema: &DataSchemaRef,
+        group_expr_len: usize,
+    ) -> Result<Option<DataBlock>> {
+        if self.keys.is_empty() {
+            return Ok(None);
+        }
+
+        let aggr_len = self.funcs.len();
+        let mut aggr_values: Vec<Vec<DataValue>> = (0..aggr_len).map(|_| vec![]).collect();
+        let mut group_values: Vec<Vec<DataValue>> = (0..group_expr_len).map(|_| vec![]).collect();
+        for (group, (_, keys)) in self.keys.iter().enumerate() {
+            let values = DataValue::deserialize_values(keys)?;
+            for (i, value) in values.into_iter().enumerate() {
+                group_values[i].push(value);
+            }
+
+            for (i, states) in self.states.iter().enumerate() {
+                aggr_values[i].push(states.merge_result(group)?);
+            }
+        }
+
+        let mut columns: Vec<DataArrayRef> = Vec::with_capacity(aggr_len + group_expr_len);
+        for value in aggr_values.iter().chain(group_values.iter()) {
+            if !value.is_empty() {
+                columns.push(DataValue::try_into_data_array(value.as_slice())?);
+            }
+        }
+        Ok(Some(DataBlock::create_by_array(schema.clone(), columns)))
+    }
+}
+
+/// The buckets flushed to a spill file, the blocks are written in the order of the buckets.
+pub struct SpilledBuckets {
+    stream: SpillStream,
+    // The bucket of every block in the file.
+    buckets: VecDeque<usize>,
+}
+
+impl SpilledBuckets {
+    /// Flushes the non-empty buckets to a spill file.
+    pub fn try_create<Key: Eq + Hash + Clone>(
+        schema: &DataSchemaRef,
+        groups: &GroupStates<Key>,
+    ) -> Result<Self> {
+        let buckets = groups
+            .states_blocks(schema)?
+            .into_iter()
+            .map(|block| block.into_iter().collect())
+            .collect::<Vec<_>>();
+        Self::try_create_from_blocks(schema, &buckets)
+    }
+
+    /// Flushes the partial states blocks of every bucket to a spill file.
+    pub fn try_create_from_blocks(
+        schema: &DataSchemaRef,
+        buckets: &[Vec<DataBlock>],
+    ) -> Result<Self> {
+        let mut file = SpillFile::try_create(schema.clone())?;
+        let mut spilled = VecDeque::new();
+        for (bucket, blocks) in buckets.iter().enumerate() {
+            for block in blocks {
+                file.write(block)?;
+                spilled.push_back(bucket);
+            }
+        }
+
+        Ok(SpilledBuckets {
+            stream: file.into_stream()?,
+            buckets: spilled,
+        })
+    }
+
+    /// Reads the spilled blocks of the bucket, the buckets must be read in order.
+    pub async fn read_bucket(&mut self, bucket: usize) -> Result<Vec<DataBlock>> {
+        let mut blocks = vec![];
+        while self.buckets.front() == Some(&bucket) {
+            self.buckets.pop_front();
+            if let Some(block) = self.stream.next().await {
+                blocks.push(block?);
+            }
+        }
+        Ok(blocks)
+    }
+}
+
+/// Merges the spilled buckets and the buckets in memory one bucket at a time, so only
+/// the blocks of the buckets being merged are kept in memory. The merged bucket is
+/// returned as partial states, or as the final results once `with_final` is set.
+pub struct SpilledGroupByMerger {
+    schema: DataSchemaRef,
+    funcs: Vec<Box<dyn AggregateFunction>>,
+    spills: Vec<SpilledBuckets>,
+    // The partial states blocks of the buckets in memory.
+    buckets: Vec<Vec<DataBlock>>,
+    next: usize,
+    // The schema of the final results and the number of the group by expressions.
+    final_schema: Option<(DataSchemaRef, usize)>,
+    merge_threads: usize,
+    // The buckets in memory and the states of the buckets being merged are accounted to it.
+    memory_tracker: Option<MemoryTrackerRef>,
+    reservation: Option<MemoryReservation>,
+}
+
+impl SpilledGroupByMerger {
+    pub fn create(
+        schema: DataSchemaRef,
+        funcs: Vec<Box<dyn AggregateFunction>>,
+        spills: Vec<SpilledBuckets>,
+        buckets: Vec<Vec<DataBlock>>,
+    ) -> Self {
+        SpilledGroupByMerger {
+            schema,
+            funcs,
+            spills,
+            buckets,
+            next: 0,
+            final_schema: None,
+            merge_threads: 1,
+            memory_tracker: None,
+            reservation: None,
+        }
+    }
+
+    /// Returns the final results of the groups instead of their partial states.
+    pub fn with_final(mut self, schema: DataSchemaRef, group_expr_len: usize) -> Self {
+        self.final_schema = Some((schema, group_expr_len));
+        self
+    }
+
+    /// The number of the buckets merged at the same time.
+    pub fn with_merge_threads(mut self, merge_threads: usize) -> Self {
+        self.merge_threads = std::cmp::max(merge_threads, 1);
+        self
+    }
+
+    /// Reserves the bytes of the buckets in memory, they are released as the buckets are merged.
+    pub fn with_memory_tracker(mut self, memory_tracker: MemoryTrackerRef) -> Result<Self> {
+        let mut reservation = MemoryReservation::create(memory_tracker.clone());
+        reservation.resize(self.buckets_memory_size())?;
+        self.memory_tracker = Some(memory_tracker);
+        self.reservation = Some(reservation);
+        Ok(self)
+    }
+
+    fn buckets_memory_size(&self) -> usize {
+        self.buckets
+            .iter()
+            .flatten()
+            .map(|block| block.memory_size())
+            .sum()
+    }
+
+    /// The blocks of the next non-empty bucket, from the spill files and the memory.
+    async fn next_bucket(&mut self) -> Result<Option<Vec<DataBlock>>> {
+        while self.next < self.buckets.len() {
+            let bucket = self.next;
+            self.next += 1;
+
+            let mut blocks = vec![];
+            for spilled in self.spills.iter_mut() {
+                blocks.extend(spilled.read_bucket(bucket).await?);
+            }
+            blocks.append(&mut self.buckets[bucket]);
+            if let Some(reservation) = self.reservation.as_mut() {
+                // The blocks of the bucket are accounted by its merge.
+                reservation.resize(self.buckets_memory_size())?;
+            }
+            if !blocks.is_empty() {
+                return Ok(Some(blocks));
+            }
+        }
+        Ok(None)
+    }
+
+    fn merge_bucket(
+        funcs: Vec<Box<dyn AggregateFunction>>,
+        schema: DataSchemaRef,
+        final_schema: Option<(DataSchemaRef, usize)>,
+        memory_tracker: Option<MemoryTrackerRef>,
+        blocks: Vec<DataBlock>,
+    ) -> Result<Option<DataBlock>> {
+        // The blocks of the bucket and the merged states are accounted while merging.
+        let blocks_bytes = blocks
+            .iter()
+            .map(|block| block.memory_size())
+            .sum::<usize>();
+        let mut reservation = memory_tracker.map(MemoryReservation::create);
+        if let Some(reservation) = reservation.as_mut() {
+            reservation.resize(blocks_bytes)?;
+        }
+
+        let mut states = StatesBucket::create(funcs);
+        for block in &blocks {
+            states.merge_block(block)?;
+            if let Some(reservation) = reservation.as_mut() {
+                reservation.resize(blocks_bytes + states.memory_size())?;
+            }
+        }
+        drop(blocks);
+
+        match final_schema {
+            Some((schema, group_expr_len)) => states.final_block(&schema, group_expr_len),
+            None => states.states_block(&schema),
+        }
+    }
+
+    pub fn into_stream(self) -> SendableDataBlockStream {
+        let merge_threads = self.merge_threads;
+        let merges = futures::stream::unfold(self, |mut merger| async move {
+            let blocks = match merger.next_bucket().await {
+                Ok(Some(blocks)) => blocks,
+                Ok(None) => return None,
+                Err(error) => {
+                    // The rest of the buckets are not read after an error.
+                    merger.next = merger.buckets.len();
+                    let failed: BoxFuture<'static, Result<Option<DataBlock>>> =
+                        Box::pin(futures::future::ready(Err(error)));
+                    return Some((failed, merger));
+                }
+            };
+
+            let funcs = merger.funcs.clone();
+            let schema = merger.schema.clone();
+            let final_schema = merger.final_schema.clone();
+            let memory_tracker = merger.memory_tracker.clone();
+            let merge: BoxFuture<'static, Result<Option<DataBlock>>> = Box::pin(async move {
+                tokio::task::spawn_blocking(move || {
+                    Self::merge_bucket(funcs, schema, final_schema, memory_tracker, blocks)
+                })
+                .await
+                .map_err(|e| {
+                    ErrorCode::TokioError(format!("Cannot merge the group by bucket: {}", e))
+                })?
+            });
+            Some((merge, merger))
+        });
+
+        // The next buckets are read while the previous ones are merged.
+        Box::pin(
+            merges
+                .buffered(merge_threads)
+                .filter_map(|block| futures::future::ready(block.transpose())),
+        )
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[test]
+fn test_group_by_bucket() -> anyhow::Result<()> {
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::transforms::transform_group_by_two_level::*;
+
+    // The bucket is stable for the same key, and the keys are spread over the buckets.
+    let mut used = vec![false; GROUP_BY_BUCKETS];
+    for i in 0..1000u64 {
+        let key = i.to_le_bytes();
+        let bucket = group_by_bucket(&key);
+        assert!(bucket < GROUP_BY_BUCKETS);
+        assert_eq!(bucket, group_by_bucket(&key));
+        used[bucket] = true;
+    }
+    assert!(used.iter().all(|used| *used));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_group_by_external() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_planners::*;
+    use common_planners::{self};
+    use futures::TryStreamExt;
+
+    use crate::pipelines::processors::*;
+    use crate::pipelines::transforms::*;
+
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings()
+        .update_settings("max_block_size", "2".to_string())?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    // sum(number), avg(number)
+    let aggr_exprs = &[sum(col("number")), avg(col("number"))];
+    let group_exprs = &[col("number")];
+    let aggr_partial = PlanBuilder::create(test_source.number_schema_for_test()?)
+        .aggregate_partial(aggr_exprs, group_exprs)?
+        .build()?;
+    let aggr_final = PlanBuilder::create(test_source.number_schema_for_test()?)
+        .aggregate_final(
+            test_source.number_schema_for_test()?,
+            aggr_exprs,
+            group_exprs,
+        )?
+        .build()?;
+
+    // Every number is read twice, in the different blocks.
+    let mut pipeline = Pipeline::create(ctx.clone());
+    let source_schema = test_source.number_schema_for_test()?;
+    pipeline.add_source(Arc::new(test_source.number_source_transform_for_test(6)?))?;
+    pipeline.add_source(Arc::new(test_source.number_source_transform_for_test(6)?))?;
+    pipeline.merge_processor()?;
+
+    // The buckets are spilled after every block.
+    pipeline.add_simple_transform(|| {
+        Ok(Box::new(
+            GroupByPartialTransform::create(
+                aggr_partial.schema(),
+                source_schema.clone(),
+                aggr_exprs.to_vec(),
+                group_exprs.to_vec(),
+            )
+            .with_external_group_by(1),
+        ))
+    })?;
+    pipeline.add_simple_transform(|| {
+        Ok(Box::new(GroupByFinalTransform::create(
+            aggr_final.schema(),
+            source_schema.clone(),
+            aggr_exprs.to_vec(),
+            group_exprs.to_vec(),
+        )))
+    })?;
+
+    // Result.
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+
+    let expected = vec![
+        "+-------------+-------------+--------+",
+        "| sum(number) | avg(number) | number |",
+        "+-------------+-------------+--------+",
+        "| 0           | 0           | 0      |",
+        "| 10          | 5           | 5      |",
+        "| 2           | 1           | 1      |",
+        "| 4           | 2           | 2      |",
+        "| 6           | 3           | 3      |",
+        "| 8           | 4           | 4      |",
+        "+-------------+-------------+--------+",
+    ];
+    common_datablocks::assert_blocks_sorted_eq(expected, result.as_slice());
+
+    Ok(())
+}
@@ -8,6 +8,7 @@ use std::sync::Arc;
 use common_datavalues::DataSchemaRef;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_streams::AbortStream;
 use common_streams::SendableDataBlockStream;
 use common_tracing::tracing;
 
@@ -71,8 +72,13 @@ impl Processor for RemoteTransform {
 
         let timeout = self.ctx.get_settings().get_flight_client_timeout()?;
         let mut flight_client = fetch_node.get_flight_client().await?;
-        flight_client
+        let stream = flight_client
             .fetch_stream(self.fetch_name.clone(), self.schema.clone(), timeout)
-            .await
+            .await?;
+        // A stalled node must not hold the query past the max_execution_time.
+        Ok(Box::pin(
+            AbortStream::try_create(stream, self.ctx.abort_callback()?)?
+                .with_deadline(self.ctx.get_deadline()?),
+        ))
     }
 }
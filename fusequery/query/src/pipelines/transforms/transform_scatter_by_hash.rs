@@ -16,18 +16,20 @@ use common_planners::Expression;
 
 use crate::pipelines::transforms::ExpressionExecutor;
 
-pub struct FlightScatterByHash {
+/// Scatters the rows of the blocks to num outputs by the hash of the rows, the rows with the
+/// same hash go to the same output.
+pub struct ScatterByHash {
     scatter_expression_executor: Arc<ExpressionExecutor>,
     scatter_expression_name: String,
     scattered_size: usize,
 }
 
-impl FlightScatterByHash {
+impl ScatterByHash {
     pub fn try_create(
         schema: DataSchemaRef,
         action: Expression,
         num: usize,
-    ) -> Result<FlightScatterByHash> {
+    ) -> Result<ScatterByHash> {
         let indices_expression_action = Expression::ScalarFunction {
             op: String::from("modulo"),
             args: vec![
@@ -41,7 +43,7 @@ impl FlightScatterByHash {
 
         let output_name = indices_expression_action.column_name();
         let expression_executor = ExpressionExecutor::try_create(
-            "indices expression in FlightScatterByHash",
+            "indices expression in ScatterByHash",
             schema,
             DataSchemaRefExt::create(vec![DataField::new(&output_name, DataType::UInt64, false)]),
             vec![indices_expression_action],
@@ -49,7 +51,7 @@ impl FlightScatterByHash {
         )?;
         expression_executor.validate()?;
 
-        Ok(FlightScatterByHash {
+        Ok(ScatterByHash {
             scatter_expression_executor: Arc::new(expression_executor),
             scatter_expression_name: output_name,
             scattered_size: num,
@@ -8,21 +8,32 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use common_datablocks::DataBlock;
 use common_datavalues::DataSchemaRef;
+use common_exception::ErrorCode;
 use common_exception::Result;
 use common_planners::Expression;
 use common_streams::DataBlockStream;
 use common_streams::SendableDataBlockStream;
+use common_streams::SortMergeStream;
+use common_streams::SpillFile;
 use common_tracing::tracing;
 use futures::StreamExt;
 
 use crate::pipelines::processors::EmptyProcessor;
 use crate::pipelines::processors::Processor;
 use crate::pipelines::transforms::transform_sort_partial::get_sort_descriptions;
+use crate::sessions::MemoryReservation;
+use crate::sessions::MemoryTracker;
+use crate::sessions::MemoryTrackerRef;
 
 pub struct SortMergeTransform {
     schema: DataSchemaRef,
     exprs: Vec<Expression>,
     limit: Option<usize>,
+    // The sorted runs are spilled to the temp files past these bytes, 0 to disable.
+    max_bytes_before_external_sort: usize,
+    block_size: usize,
+    // The buffered blocks are accounted to the tracker of the query.
+    memory_tracker: MemoryTrackerRef,
     input: Arc<dyn Processor>,
 }
 
@@ -36,9 +47,50 @@ impl SortMergeTransform {
             schema,
             exprs,
             limit,
+            max_bytes_before_external_sort: 0,
+            block_size: 0,
+            memory_tracker: MemoryTracker::create("query", 0),
             input: Arc::new(EmptyProcessor::create()),
         })
     }
+
+    /// Spills the sorted runs to disk once the buffered blocks exceed max_bytes,
+    /// the runs are merged by a streaming k-way merge into blocks of block_size rows.
+    pub fn with_external_sort(mut self, max_bytes: usize, block_size: usize) -> Self {
+        self.max_bytes_before_external_sort = max_bytes;
+        self.block_size = block_size;
+        self
+    }
+
+    pub fn with_memory_tracker(mut self, memory_tracker: MemoryTrackerRef) -> Self {
+        self.memory_tracker = memory_tracker;
+        self
+    }
+
+    /// Writes the sorted run to a temp file on a blocking thread.
+    async fn spill(&self, run: DataBlock) -> Result<SendableDataBlockStream> {
+        let schema = self.schema.clone();
+        let block_size = std::cmp::max(self.block_size, 1);
+        let file = tokio::task::spawn_blocking(move || -> Result<SpillFile> {
+            let mut file = SpillFile::try_create(schema)?;
+            let mut offset = 0;
+            while offset < run.num_rows() {
+                let length = std::cmp::min(block_size, run.num_rows() - offset);
+                file.write(&run.slice(offset, length))?;
+                offset += length;
+            }
+            Ok(file)
+        })
+        .await
+        .map_err(|e| ErrorCode::TokioError(format!("Cannot spill the sorted run: {}", e)))??;
+
+        tracing::debug!(
+            "Spill the sorted run of {} rows, {} bytes",
+            file.num_rows(),
+            file.memory_size()
+        );
+        Ok(Box::pin(file.into_stream()?))
+    }
 }
 
 #[async_trait]
@@ -65,12 +117,53 @@ impl Processor for SortMergeTransform {
 
         let sort_columns_descriptions = get_sort_descriptions(&self.schema, &self.exprs)?;
         let mut blocks = vec![];
+        let mut bytes = 0;
+        let mut runs = vec![];
+        let mut reservation = MemoryReservation::create(self.memory_tracker.clone());
         let mut stream = self.input.execute().await?;
 
         while let Some(block) = stream.next().await {
-            blocks.push(block?);
+            let block = block?;
+            bytes += block.memory_size();
+            reservation.resize(bytes)?;
+            blocks.push(block);
+
+            if self.max_bytes_before_external_sort > 0
+                && bytes > self.max_bytes_before_external_sort
+            {
+                // The merged run is held next to the blocks until they are dropped.
+                reservation.resize(bytes * 2)?;
+                let run =
+                    DataBlock::merge_sort_blocks(&blocks, &sort_columns_descriptions, self.limit)?;
+                blocks.clear();
+                reservation.resize(run.memory_size())?;
+                runs.push(self.spill(run).await?);
+                bytes = 0;
+                reservation.resize(bytes)?;
+            }
+        }
+
+        if !runs.is_empty() {
+            if !blocks.is_empty() {
+                reservation.resize(bytes * 2)?;
+                let run =
+                    DataBlock::merge_sort_blocks(&blocks, &sort_columns_descriptions, self.limit)?;
+                runs.push(Box::pin(DataBlockStream::create(
+                    self.schema.clone(),
+                    None,
+                    vec![run],
+                )));
+            }
+            return Ok(Box::pin(SortMergeStream::try_create(
+                self.schema.clone(),
+                runs,
+                sort_columns_descriptions,
+                self.limit,
+                self.block_size,
+            )?));
         }
 
+        reservation.resize(bytes * 2)?;
         let results = match blocks.len() {
             0 => vec![],
             _ => vec![DataBlock::merge_sort_blocks(
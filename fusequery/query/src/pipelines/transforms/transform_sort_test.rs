@@ -78,3 +78,119 @@ async fn test_transform_sort() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_sort_external() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_planners::*;
+    use common_planners::{self};
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::processors::*;
+    use crate::pipelines::transforms::*;
+
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings()
+        .update_settings("max_block_size", "2".to_string())?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    // Pipeline.
+    let mut pipeline = Pipeline::create(ctx.clone());
+    let a = test_source.number_source_transform_for_test(8)?;
+    pipeline.add_source(Arc::new(a))?;
+
+    let sort_expression = &[sort("number", false, false)];
+    let plan = PlanBuilder::create(test_source.number_schema_for_test()?)
+        .sort(sort_expression)?
+        .build()?;
+
+    pipeline.add_simple_transform(|| {
+        Ok(Box::new(SortPartialTransform::try_create(
+            plan.schema().clone(),
+            sort_expression.to_vec(),
+            None,
+        )?))
+    })?;
+
+    // Every block is spilled as a sorted run, the runs are merged into blocks of 3 rows.
+    pipeline.merge_processor()?;
+    pipeline.add_simple_transform(|| {
+        Ok(Box::new(
+            SortMergeTransform::try_create(plan.schema().clone(), sort_expression.to_vec(), None)?
+                .with_external_sort(1, 3),
+        ))
+    })?;
+
+    // Result.
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await?;
+    assert_eq!(
+        vec![3, 3, 2],
+        result.iter().map(|b| b.num_rows()).collect::<Vec<_>>()
+    );
+
+    let expected = vec![
+        "+--------+",
+        "| number |",
+        "+--------+",
+        "| 7      |",
+        "| 6      |",
+        "| 5      |",
+        "| 4      |",
+        "| 3      |",
+        "| 2      |",
+        "| 1      |",
+        "| 0      |",
+        "+--------+",
+    ];
+    common_datablocks::assert_blocks_eq(expected, result.as_slice());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transform_sort_memory_limit() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_planners::*;
+    use common_planners::{self};
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::processors::*;
+    use crate::pipelines::transforms::*;
+
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings()
+        .update_settings("max_memory_usage", "1".to_string())?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    // Pipeline.
+    let mut pipeline = Pipeline::create(ctx.clone());
+    let a = test_source.number_source_transform_for_test(8)?;
+    pipeline.add_source(Arc::new(a))?;
+
+    let sort_expression = &[sort("number", false, false)];
+    let plan = PlanBuilder::create(test_source.number_schema_for_test()?)
+        .sort(sort_expression)?
+        .build()?;
+
+    // The buffered blocks exceed the max_memory_usage of the query.
+    let memory_tracker = ctx.try_get_memory_tracker()?;
+    pipeline.merge_processor()?;
+    pipeline.add_simple_transform(|| {
+        Ok(Box::new(
+            SortMergeTransform::try_create(plan.schema().clone(), sort_expression.to_vec(), None)?
+                .with_memory_tracker(memory_tracker.clone()),
+        ))
+    })?;
+
+    let stream = pipeline.execute().await?;
+    let result = stream.try_collect::<Vec<_>>().await;
+    assert_eq!(result.unwrap_err().code(), 42);
+    assert_eq!(memory_tracker.get_usage(), 0);
+
+    Ok(())
+}
@@ -7,6 +7,9 @@ use std::sync::Arc;
 
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_planners::AggregatorPartialPlan;
+use common_planners::ReadDataSourcePlan;
+use common_streams::AbortStream;
 use common_streams::SendableDataBlockStream;
 use common_tracing::tracing;
 
@@ -16,23 +19,29 @@ use crate::sessions::FuseQueryContextRef;
 
 pub struct SourceTransform {
     ctx: FuseQueryContextRef,
-    db: String,
-    table: String,
-    remote: bool,
+    source_plan: ReadDataSourcePlan,
+    // The partial aggregation computed by the table while reading.
+    aggregation: Option<AggregatorPartialPlan>,
 }
 
 impl SourceTransform {
-    pub fn try_create(
+    pub fn try_create(ctx: FuseQueryContextRef, source_plan: ReadDataSourcePlan) -> Result<Self> {
+        Ok(SourceTransform {
+            ctx,
+            source_plan,
+            aggregation: None,
+        })
+    }
+
+    pub fn try_create_partial_aggregated(
         ctx: FuseQueryContextRef,
-        db: &str,
-        table: &str,
-        remote: bool,
+        source_plan: ReadDataSourcePlan,
+        aggregation: AggregatorPartialPlan,
     ) -> Result<Self> {
         Ok(SourceTransform {
             ctx,
-            db: db.to_string(),
-            table: table.to_string(),
-            remote,
+            source_plan,
+            aggregation: Some(aggregation),
         })
     }
 }
@@ -40,7 +49,10 @@ impl SourceTransform {
 #[async_trait::async_trait]
 impl Processor for SourceTransform {
     fn name(&self) -> &str {
-        "SourceTransform"
+        match self.aggregation {
+            None => "SourceTransform",
+            Some(_) => "SourceTransform(AggregatorPartial)",
+        }
     }
 
     fn connect_to(&mut self, _: Arc<dyn Processor>) -> Result<()> {
@@ -58,21 +70,32 @@ impl Processor for SourceTransform {
     }
 
     async fn execute(&self) -> Result<SendableDataBlockStream> {
+        let db = self.source_plan.db.as_str();
+        let table = self.source_plan.table.as_str();
         tracing::debug!(
             "execute, table:{:#}.{:#}, is_remote:{:#}...",
-            self.db,
-            self.table,
-            self.remote
+            db,
+            table,
+            self.source_plan.remote
         );
 
-        let table = if self.remote {
-            self.ctx
-                .get_remote_table(self.db.as_str(), self.table.as_str())
-                .await?
+        let table = if self.source_plan.remote {
+            self.ctx.get_remote_table(db, table).await?
         } else {
-            self.ctx.get_table(self.db.as_str(), self.table.as_str())?
+            self.ctx.get_table(db, table)?
         };
 
-        table.read(self.ctx.clone()).await
+        let stream = match &self.aggregation {
+            None => table.read(self.ctx.clone(), &self.source_plan).await?,
+            Some(aggregation) => {
+                table
+                    .read_partial_aggregated(self.ctx.clone(), &self.source_plan, aggregation)
+                    .await?
+            }
+        };
+        Ok(Box::pin(AbortStream::try_create(
+            stream,
+            self.ctx.abort_callback()?,
+        )?))
     }
 }
@@ -41,3 +41,67 @@ async fn transform_source_test() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn transform_source_cancel_test() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use futures::StreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::processors::*;
+
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings()
+        .update_settings("max_block_size", "1".to_string())?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    let mut pipeline = Pipeline::create(ctx.clone());
+    let a = test_source.number_source_transform_for_test(8)?;
+    pipeline.add_source(Arc::new(a))?;
+
+    let mut stream = pipeline.execute().await?;
+    stream.next().await.unwrap()?;
+
+    // The source fails at its next block once the query is cancelled.
+    ctx.cancel();
+    let error = stream.next().await.unwrap().unwrap_err();
+    assert_eq!(error.code(), 43);
+    assert_eq!(error.message(), "Query 2021 was cancelled");
+
+    // The next query of the session starts again.
+    ctx.reset()?;
+    assert!(!ctx.is_cancelled());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn transform_source_timeout_test() -> anyhow::Result<()> {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::pipelines::processors::*;
+
+    let ctx = crate::tests::try_create_context()?;
+    ctx.get_settings()
+        .update_settings("max_execution_time", "1".to_string())?;
+    // The query runs past the max_execution_time.
+    let ctx = ctx.with_start_time(Instant::now() - Duration::from_millis(1100))?;
+    let test_source = crate::tests::NumberTestData::create(ctx.clone());
+
+    let mut pipeline = Pipeline::create(ctx.clone());
+    let a = test_source.number_source_transform_for_test(8)?;
+    pipeline.add_source(Arc::new(a))?;
+
+    let stream = pipeline.execute().await?;
+    let error = stream.try_collect::<Vec<_>>().await.unwrap_err();
+    assert_eq!(error.code(), 44);
+    assert!(ctx.is_cancelled());
+
+    Ok(())
+}
@@ -10,21 +10,30 @@ use std::time::Instant;
 
 use clickhouse_srv::connection::Connection;
 use clickhouse_srv::errors::ServerError;
+use clickhouse_srv::protocols::Packet;
 use clickhouse_srv::types::Block as ClickHouseBlock;
 use clickhouse_srv::*;
+use common_datablocks::DataBlock;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_infallible::Mutex;
+use common_planners::InsertIntoPlan;
+use common_planners::PlanNode;
+use common_streams::AbortStream;
 use log::error;
 use metrics::histogram;
 use tokio::net::TcpListener;
 use tokio::sync::mpsc;
+use tokio::sync::oneshot;
 use tokio::time;
 use tokio_stream::wrappers::IntervalStream;
+use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::StreamExt;
 
 use crate::clusters::ClusterRef;
 use crate::configs::Config;
 use crate::interpreters::InterpreterFactory;
+use crate::servers::clickhouse::ClickHouseInsertStream;
 use crate::servers::clickhouse::ClickHouseStream;
 use crate::sessions::FuseQueryContextRef;
 use crate::sessions::SessionManagerRef;
@@ -54,21 +63,15 @@ enum BlockItem {
     ProgressTicker,
 }
 
-#[async_trait::async_trait]
-impl ClickHouseSession for Session {
-    async fn execute_query(
+impl Session {
+    async fn execute_select(
         &self,
+        plan: PlanNode,
         ctx: &mut CHContext,
         connection: &mut Connection,
     ) -> clickhouse_srv::errors::Result<()> {
-        self.ctx.reset().map_err(to_clickhouse_err)?;
-        let start = Instant::now();
-
-        let interpreter = PlanParser::create(self.ctx.clone())
-            .build_from_sql(&ctx.state.query)
-            .and_then(|built_plan| InterpreterFactory::get(self.ctx.clone(), built_plan))
-            .map_err(to_clickhouse_err)?;
-
+        let interpreter =
+            InterpreterFactory::get(self.ctx.clone(), plan).map_err(to_clickhouse_err)?;
         let schema = interpreter.schema();
 
         let mut interval_stream = IntervalStream::new(time::interval(Duration::from_millis(30)));
@@ -106,22 +109,143 @@ impl ClickHouseSession for Session {
         });
 
         while let Some(item) = rx.recv().await {
-            match item {
-                BlockItem::Block(block) => {
-                    connection
-                        .write_block(block.map_err(to_clickhouse_err)?)
-                        .await?;
-                }
-
+            let written = match item {
+                BlockItem::Block(Ok(block)) => connection.write_block(&block).await,
+                BlockItem::Block(Err(error)) => Err(to_clickhouse_err(error)),
                 BlockItem::ProgressTicker => {
                     let progress = self.get_progress();
                     connection
                         .write_progress(progress, ctx.client_revision)
-                        .await?;
+                        .await
                 }
+            };
+
+            // The query failed or the client is gone, the rest of the query stops at its next block.
+            if let Err(cause) = written {
+                self.ctx.cancel();
+                return Err(cause);
             }
         }
 
+        Ok(())
+    }
+
+    /// The client sends the rows of an INSERT as native blocks after the query.
+    /// We reply with an empty block of the insert schema, then read the blocks until the
+    /// empty one which ends them. The insert is acknowledged only after it is written,
+    /// a failed insert is returned to the client as an exception.
+    async fn execute_insert(
+        &self,
+        plan: InsertIntoPlan,
+        ctx: &mut CHContext,
+        connection: &mut Connection,
+    ) -> clickhouse_srv::errors::Result<()> {
+        let schema = plan.schema();
+        let sample_block =
+            ClickHouseStream::convert_block(DataBlock::empty_with_schema(schema.clone()))
+                .map_err(to_clickhouse_err)?;
+
+        let (tx, rx) = mpsc::channel(4);
+        let error = Arc::new(Mutex::new(None));
+        let insert_stream =
+            ClickHouseInsertStream::create(ReceiverStream::new(rx), schema, error.clone());
+        // A cancelled insert fails at its next block instead of committing the rows before it.
+        let abort_callback = self.ctx.abort_callback().map_err(to_clickhouse_err)?;
+        let input_stream = AbortStream::try_create(Box::pin(insert_stream), abort_callback)
+            .map_err(to_clickhouse_err)?;
+        plan.set_input_stream(Box::pin(input_stream));
+
+        let interpreter = InterpreterFactory::get(self.ctx.clone(), PlanNode::InsertInto(plan))
+            .map_err(to_clickhouse_err)?;
+
+        connection.write_block(&sample_block).await?;
+
+        let (result_tx, result_rx) = oneshot::channel();
+        self.ctx.execute_task(async move {
+            let res = interpreter.execute().await.map(|_| ());
+            // The conversion error is reported with its own code.
+            let res = match error.lock().take() {
+                Some(e) => Err(e),
+                None => res,
+            };
+            let _ = result_tx.send(res);
+        });
+
+        let cause = loop {
+            match connection.read_packet(ctx).await {
+                Ok(Some(Packet::Data(block))) if block.is_empty() => break None,
+                // The insert stopped reading, the rest of the blocks are skipped.
+                Ok(Some(Packet::Data(block))) => {
+                    let _ = tx.send(Ok(block)).await;
+                }
+                Ok(Some(_)) => {
+                    break Some(ErrorCode::UnknownException(
+                        "Unexpected packet while receiving the insert data",
+                    ))
+                }
+                Ok(None) => {
+                    self.ctx.cancel();
+                    break Some(ErrorCode::AbortedQuery(
+                        "The client disconnected while sending the insert data",
+                    ));
+                }
+                Err(e) => {
+                    break Some(ErrorCode::UnknownException(format!(
+                        "Cannot read the insert data: {}",
+                        e
+                    )))
+                }
+            }
+        };
+
+        // The input ends with the error, the insert fails instead of committing the blocks
+        // received before it.
+        if let Some(cause) = &cause {
+            let input_error = ErrorCode::create(cause.code(), cause.message(), None);
+            let _ = tx.send(Err(input_error)).await;
+        }
+        drop(tx);
+
+        let res = match result_rx.await {
+            Ok(res) => res,
+            Err(e) => Err(ErrorCode::TokioError(format!(
+                "Cannot receive the insert result: {}",
+                e
+            ))),
+        };
+        match (res, cause) {
+            (Ok(_), Some(cause)) => Err(to_clickhouse_err(cause)),
+            (res, _) => res.map_err(to_clickhouse_err),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ClickHouseSession for Session {
+    async fn execute_query(
+        &self,
+        ctx: &mut CHContext,
+        connection: &mut Connection,
+    ) -> clickhouse_srv::errors::Result<()> {
+        self.ctx.reset().map_err(to_clickhouse_err)?;
+        let start = Instant::now();
+
+        let plan = PlanParser::create(self.ctx.clone())
+            .build_from_sql(&ctx.state.query)
+            .map_err(to_clickhouse_err)?;
+
+        let res = match plan {
+            // Insert without values in the query, the rows are sent by the client.
+            PlanNode::InsertInto(plan) if !plan.has_input_stream() => {
+                self.execute_insert(plan, ctx, connection).await
+            }
+            _ => self.execute_select(plan, ctx, connection).await,
+        };
+        self.ctx
+            .get_process_list()
+            .on_query_done(&self.ctx, &ctx.state.query);
+        res?;
+
         histogram!(
             super::clickhouse_metrics::METRIC_CLICKHOUSE_PROCESSOR_REQUEST_DURATION,
             start.elapsed()
@@ -208,6 +332,7 @@ impl ClickHouseHandler {
                 {
                     error!("Error: {:?}", e);
                 }
+                ctx.cancel();
                 session_mgr.try_remove_context(ctx).unwrap();
             });
         }
@@ -0,0 +1,239 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+
+use chrono::Date;
+use chrono::DateTime;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use clickhouse_srv::types::Block as ClickHouseBlock;
+use clickhouse_srv::types::SqlType;
+use common_arrow::arrow::array::*;
+use common_datablocks::DataBlock;
+use common_datavalues::data_array_cast;
+use common_datavalues::DataArrayRef;
+use common_datavalues::DataSchemaRef;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_infallible::Mutex;
+use futures::stream::Stream;
+use futures::StreamExt;
+use tokio_stream::wrappers::ReceiverStream;
+
+/// Converts the native blocks sent by a ClickHouse client after an INSERT query
+/// into DataBlocks of the insert schema.
+/// An error of the input (e.g. the client disconnected) or of a conversion fails the insert,
+/// the conversion error is also kept for the handler to report it with its own code.
+pub struct ClickHouseInsertStream {
+    input: ReceiverStream<Result<ClickHouseBlock>>,
+    schema: DataSchemaRef,
+    error: Arc<Mutex<Option<ErrorCode>>>,
+}
+
+impl ClickHouseInsertStream {
+    pub fn create(
+        input: ReceiverStream<Result<ClickHouseBlock>>,
+        schema: DataSchemaRef,
+        error: Arc<Mutex<Option<ErrorCode>>>,
+    ) -> Self {
+        ClickHouseInsertStream {
+            input,
+            schema,
+            error,
+        }
+    }
+}
+
+impl Stream for ClickHouseInsertStream {
+    type Item = Result<DataBlock>;
+
+    fn poll_next(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.error.lock().is_some() {
+            return Poll::Ready(None);
+        }
+
+        self.input.poll_next_unpin(ctx).map(|x| match x {
+            Some(Ok(block)) => match from_clickhouse_block(self.schema.clone(), &block) {
+                Ok(block) => Some(Ok(block)),
+                Err(e) => {
+                    let cause = ErrorCode::create(e.code(), e.message(), e.backtrace());
+                    *self.error.lock() = Some(e);
+                    Some(Err(cause))
+                }
+            },
+            Some(Err(e)) => Some(Err(e)),
+            None => None,
+        })
+    }
+}
+
+pub fn from_clickhouse_block(schema: DataSchemaRef, block: &ClickHouseBlock) -> Result<DataBlock> {
+    let mut arrays = Vec::with_capacity(schema.fields().len());
+    for field in schema.fields() {
+        let index = block
+            .columns()
+            .iter()
+            .position(|column| column.name() == field.name())
+            .ok_or_else(|| {
+                ErrorCode::DataStructMissMatch(format!(
+                    "Column {} is missing in the inserted block",
+                    field.name()
+                ))
+            })?;
+
+        let array = from_clickhouse_column(block, index)?;
+        if array.data_type() == field.data_type() {
+            arrays.push(array);
+        } else {
+            arrays.push(data_array_cast(&array, field.data_type())?);
+        }
+    }
+    Ok(DataBlock::create_by_array(schema, arrays))
+}
+
+macro_rules! from_primitive_column {
+    ($COLUMN: expr, $NATIVE: ty, $ARRAY: ty) => {{
+        let values = $COLUMN.iter::<$NATIVE>().map_err(to_error_code)?;
+        Arc::new(<$ARRAY>::from_iter_values(values.copied())) as DataArrayRef
+    }};
+}
+
+macro_rules! from_nullable_primitive_column {
+    ($COLUMN: expr, $NATIVE: ty, $ARRAY: ty) => {{
+        let values = $COLUMN.iter::<Option<$NATIVE>>().map_err(to_error_code)?;
+        Arc::new(values.map(|v| v.copied()).collect::<$ARRAY>()) as DataArrayRef
+    }};
+}
+
+macro_rules! from_array_column {
+    ($COLUMN: expr, $ROWS: expr, $NATIVE: ty, $BUILDER: ty) => {{
+        let values = $COLUMN.iter::<Vec<$NATIVE>>().map_err(to_error_code)?;
+        let mut builder = ListBuilder::new(<$BUILDER>::new($ROWS));
+        for array in values {
+            for value in array {
+                builder.values().append_value(*value)?;
+            }
+            builder.append(true)?;
+        }
+        Arc::new(builder.finish()) as DataArrayRef
+    }};
+}
+
+fn from_clickhouse_column(block: &ClickHouseBlock, index: usize) -> Result<DataArrayRef> {
+    let column = &block.columns()[index];
+    let rows = block.row_count();
+    Ok(match column.sql_type() {
+        SqlType::Int8 => from_primitive_column!(column, i8, Int8Array),
+        SqlType::Int16 => from_primitive_column!(column, i16, Int16Array),
+        SqlType::Int32 => from_primitive_column!(column, i32, Int32Array),
+        SqlType::Int64 => from_primitive_column!(column, i64, Int64Array),
+        SqlType::UInt8 => from_primitive_column!(column, u8, UInt8Array),
+        SqlType::UInt16 => from_primitive_column!(column, u16, UInt16Array),
+        SqlType::UInt32 => from_primitive_column!(column, u32, UInt32Array),
+        SqlType::UInt64 => from_primitive_column!(column, u64, UInt64Array),
+        SqlType::Float32 => from_primitive_column!(column, f32, Float32Array),
+        SqlType::Float64 => from_primitive_column!(column, f64, Float64Array),
+        SqlType::String => {
+            let values = column.iter::<&[u8]>().map_err(to_error_code)?;
+            let values = values
+                .map(|v| std::str::from_utf8(v).map(Some))
+                .collect::<std::result::Result<Vec<Option<&str>>, _>>()
+                .map_err(ErrorCode::from_std_error)?;
+            Arc::new(StringArray::from(values)) as DataArrayRef
+        }
+
+        SqlType::Nullable(SqlType::Int8) => from_nullable_primitive_column!(column, i8, Int8Array),
+        SqlType::Nullable(SqlType::Int16) => {
+            from_nullable_primitive_column!(column, i16, Int16Array)
+        }
+        SqlType::Nullable(SqlType::Int32) => {
+            from_nullable_primitive_column!(column, i32, Int32Array)
+        }
+        SqlType::Nullable(SqlType::Int64) => {
+            from_nullable_primitive_column!(column, i64, Int64Array)
+        }
+        SqlType::Nullable(SqlType::UInt8) => {
+            from_nullable_primitive_column!(column, u8, UInt8Array)
+        }
+        SqlType::Nullable(SqlType::UInt16) => {
+            from_nullable_primitive_column!(column, u16, UInt16Array)
+        }
+        SqlType::Nullable(SqlType::UInt32) => {
+            from_nullable_primitive_column!(column, u32, UInt32Array)
+        }
+        SqlType::Nullable(SqlType::UInt64) => {
+            from_nullable_primitive_column!(column, u64, UInt64Array)
+        }
+        SqlType::Nullable(SqlType::Float32) => {
+            from_nullable_primitive_column!(column, f32, Float32Array)
+        }
+        SqlType::Nullable(SqlType::Float64) => {
+            from_nullable_primitive_column!(column, f64, Float64Array)
+        }
+        SqlType::Nullable(SqlType::String) => {
+            let values = column.iter::<Option<&[u8]>>().map_err(to_error_code)?;
+            let values = values
+                .map(|v| v.map(std::str::from_utf8).transpose())
+                .collect::<std::result::Result<Vec<Option<&str>>, _>>()
+                .map_err(ErrorCode::from_std_error)?;
+            Arc::new(StringArray::from(values)) as DataArrayRef
+        }
+
+        SqlType::Date => {
+            let values = column.iter::<Date<Tz>>().map_err(to_error_code)?;
+            Arc::new(Date32Array::from_iter_values(
+                values.map(from_clickhouse_date),
+            )) as DataArrayRef
+        }
+        SqlType::Nullable(SqlType::Date) => {
+            let values = column.iter::<Option<Date<Tz>>>().map_err(to_error_code)?;
+            let values = values.map(|v| v.map(from_clickhouse_date));
+            Arc::new(values.collect::<Date32Array>()) as DataArrayRef
+        }
+        SqlType::DateTime(_) => {
+            let values = column.iter::<DateTime<Tz>>().map_err(to_error_code)?;
+            let values = values.map(|v| v.timestamp_millis());
+            Arc::new(Date64Array::from_iter_values(values)) as DataArrayRef
+        }
+        SqlType::Nullable(SqlType::DateTime(_)) => {
+            let values = column
+                .iter::<Option<DateTime<Tz>>>()
+                .map_err(to_error_code)?;
+            let values = values.map(|v| v.map(|v| v.timestamp_millis()));
+            Arc::new(values.collect::<Date64Array>()) as DataArrayRef
+        }
+
+        SqlType::Array(SqlType::Int8) => from_array_column!(column, rows, i8, Int8Builder),
+        SqlType::Array(SqlType::Int16) => from_array_column!(column, rows, i16, Int16Builder),
+        SqlType::Array(SqlType::Int32) => from_array_column!(column, rows, i32, Int32Builder),
+        SqlType::Array(SqlType::Int64) => from_array_column!(column, rows, i64, Int64Builder),
+        SqlType::Array(SqlType::UInt8) => from_array_column!(column, rows, u8, UInt8Builder),
+        SqlType::Array(SqlType::UInt16) => from_array_column!(column, rows, u16, UInt16Builder),
+        SqlType::Array(SqlType::UInt32) => from_array_column!(column, rows, u32, UInt32Builder),
+        SqlType::Array(SqlType::UInt64) => from_array_column!(column, rows, u64, UInt64Builder),
+        SqlType::Array(SqlType::Float32) => from_array_column!(column, rows, f32, Float32Builder),
+        SqlType::Array(SqlType::Float64) => from_array_column!(column, rows, f64, Float64Builder),
+
+        other => {
+            return Err(ErrorCode::BadDataValueType(format!(
+                "Unsupported ClickHouse column type for insert: {:?}",
+                other
+            )))
+        }
+    })
+}
+
+// Days since UNIX epoch.
+fn from_clickhouse_date(date: Date<Tz>) -> i32 {
+    let epoch = NaiveDate::from_ymd(1970, 1, 1);
+    date.naive_utc().signed_duration_since(epoch).num_days() as i32
+}
+
+fn to_error_code(error: clickhouse_srv::errors::Error) -> ErrorCode {
+    ErrorCode::BadDataValueType(format!("{}", error))
+}
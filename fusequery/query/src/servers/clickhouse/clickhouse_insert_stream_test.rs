@@ -0,0 +1,99 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[test]
+fn test_from_clickhouse_block() -> anyhow::Result<()> {
+    use clickhouse_srv::types::Block as ClickHouseBlock;
+    use common_datavalues::*;
+    use pretty_assertions::assert_eq;
+
+    use crate::servers::clickhouse::from_clickhouse_block;
+
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("a", DataType::UInt8, false),
+        DataField::new("b", DataType::Int64, true),
+        DataField::new("c", DataType::Utf8, true),
+    ]);
+
+    // Columns are matched by name and casted to the insert schema.
+    let block = ClickHouseBlock::new()
+        .column("c", vec![Some("x"), None, Some("z")])
+        .column("a", vec![1u8, 2, 3])
+        .column("b", vec![Some(1i32), None, Some(3i32)]);
+    let block = from_clickhouse_block(schema, &block)?;
+    assert_eq!(block.column(1).data_type(), DataType::Int64);
+
+    let expected = vec![
+        "+---+---+---+",
+        "| a | b | c |",
+        "+---+---+---+",
+        "| 1 | 1 | x |",
+        "| 2 |   |   |",
+        "| 3 | 3 | z |",
+        "+---+---+---+",
+    ];
+    common_datablocks::assert_blocks_eq(expected, &[block]);
+
+    // Missing column.
+    let schema = DataSchemaRefExt::create(vec![DataField::new("d", DataType::UInt8, false)]);
+    let block = ClickHouseBlock::new().column("a", vec![1u8, 2, 3]);
+    let actual = from_clickhouse_block(schema, &block);
+    assert!(actual.is_err());
+    if let Err(e) = actual {
+        assert_eq!(
+            "Code: 17, displayText = Column d is missing in the inserted block.",
+            e.to_string()
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_insert_stream_disconnected() -> common_exception::Result<()> {
+    use std::sync::Arc;
+
+    use clickhouse_srv::types::Block as ClickHouseBlock;
+    use common_datavalues::*;
+    use common_exception::ErrorCode;
+    use common_infallible::Mutex;
+    use common_streams::AbortStream;
+    use futures::StreamExt;
+    use tokio_stream::wrappers::ReceiverStream;
+
+    use crate::servers::clickhouse::ClickHouseInsertStream;
+
+    let ctx = crate::tests::try_create_context()?;
+    let schema = DataSchemaRefExt::create(vec![DataField::new("a", DataType::UInt8, false)]);
+
+    // The client disconnects after the first block, the handler ends the input with the error.
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let error = Arc::new(Mutex::new(None));
+    let insert_stream = ClickHouseInsertStream::create(ReceiverStream::new(rx), schema, error);
+    let mut stream = AbortStream::try_create(Box::pin(insert_stream), ctx.abort_callback()?)?;
+
+    let block = ClickHouseBlock::new().column("a", vec![1u8, 2, 3]);
+    tx.send(Ok(block)).await.unwrap();
+    let cause = ErrorCode::AbortedQuery("The client disconnected");
+    tx.send(Err(cause)).await.unwrap();
+    let block = stream.next().await.unwrap()?;
+    assert_eq!(block.num_rows(), 3);
+    let actual = stream.next().await.unwrap();
+    assert_eq!(
+        actual.unwrap_err().code(),
+        ErrorCode::AbortedQuery("").code()
+    );
+
+    // The cancelled insert fails at its next block.
+    let block = ClickHouseBlock::new().column("a", vec![4u8]);
+    tx.send(Ok(block)).await.unwrap();
+    ctx.cancel();
+    let actual = stream.next().await.unwrap();
+    assert_eq!(
+        actual.unwrap_err().code(),
+        ErrorCode::AbortedQuery("").code()
+    );
+
+    Ok(())
+}
@@ -6,14 +6,20 @@ use std::pin::Pin;
 use std::task::Context;
 use std::task::Poll;
 
-use anyhow::anyhow;
+use chrono::Date;
+use chrono::DateTime;
+use chrono::NaiveDate;
+use chrono::TimeZone;
+use chrono_tz::Tz;
 use clickhouse_srv::types::Block as ClickHouseBlock;
+use clickhouse_srv::types::Decimal;
 use common_arrow::arrow::array::*;
 use common_arrow::arrow::datatypes::*;
+use common_arrow::arrow::util::display::array_value_to_string;
 use common_datablocks::DataBlock;
+use common_datavalues::data_array_cast;
 use common_datavalues::DataArrayRef;
 use common_datavalues::DataSchemaRef;
-use common_exception::ErrorCode;
 use common_exception::Result;
 use common_streams::SendableDataBlockStream;
 use futures::stream::Stream;
@@ -34,85 +40,15 @@ impl ClickHouseStream {
         }
     }
 
-    pub fn convert_block(&self, block: DataBlock) -> Result<ClickHouseBlock> {
+    pub fn convert_block(block: DataBlock) -> Result<ClickHouseBlock> {
         let mut result = ClickHouseBlock::new();
         if block.num_columns() == 0 {
             return Ok(result);
         }
 
-        for i in 0..block.num_columns() {
+        for (i, field) in block.schema().fields().iter().enumerate() {
             let column = block.column(i).to_array()?;
-            let name = block.schema().field(i).name();
-
-            match column.data_type() {
-                DataType::Int8 => {
-                    let data = build_primitive_column::<Int8Type>(&column)?;
-                    result = result.column(name, data);
-                }
-                DataType::Int16 => {
-                    let data = build_primitive_column::<Int16Type>(&column)?;
-                    result = result.column(name, data);
-                }
-                DataType::Int32 => {
-                    let data = build_primitive_column::<Int32Type>(&column)?;
-                    result = result.column(name, data);
-                }
-                DataType::Int64 => {
-                    let data = build_primitive_column::<Int64Type>(&column)?;
-                    result = result.column(name, data);
-                }
-                DataType::UInt8 => {
-                    let data = build_primitive_column::<UInt8Type>(&column)?;
-                    result = result.column(name, data);
-                }
-                DataType::UInt16 => {
-                    let data = build_primitive_column::<UInt16Type>(&column)?;
-                    result = result.column(name, data);
-                }
-                DataType::UInt32 => {
-                    let data = build_primitive_column::<UInt32Type>(&column)?;
-                    result = result.column(name, data);
-                }
-                DataType::UInt64 => {
-                    let data = build_primitive_column::<UInt64Type>(&column)?;
-                    result = result.column(name, data);
-                }
-
-                DataType::Float32 => {
-                    let data = build_primitive_column::<Float32Type>(&column)?;
-                    result = result.column(name, data);
-                }
-                DataType::Float64 => {
-                    let data = build_primitive_column::<Float64Type>(&column)?;
-                    result = result.column(name, data);
-                }
-
-                DataType::Date32 => {
-                    let data = build_primitive_column::<Date32Type>(&column)?;
-                    result = result.column(name, data);
-                }
-                DataType::Date64 => {
-                    let data = build_primitive_column::<Date64Type>(&column)?;
-                    result = result.column(name, data);
-                }
-
-                DataType::Boolean => {
-                    let data = build_boolean_column(&column)?;
-                    result = result.column(name, data);
-                }
-
-                DataType::Utf8 => {
-                    let data = build_string_column(&column)?;
-                    result = result.column(name, data);
-                }
-
-                _ => {
-                    return Err(ErrorCode::from(anyhow!(
-                        "Unsupported column type:{:?}",
-                        column.data_type()
-                    )))
-                }
-            }
+            result = append_column(result, field.name(), &column, field.is_nullable())?;
         }
         Ok(result)
     }
@@ -127,73 +63,288 @@ impl Stream for ClickHouseStream {
         if self.block_index == 0 {
             self.block_index += 1;
             let block = DataBlock::empty_with_schema(self.schema.clone());
-            return Poll::Ready(Some(self.convert_block(block)));
+            return Poll::Ready(Some(Self::convert_block(block)));
         }
 
         self.input.poll_next_unpin(ctx).map(|x| match x {
-            Some(Ok(v)) => Some(self.convert_block(v)),
-            // Some(Err(e)) => Some(Err(e)),
-            _other => None,
+            Some(Ok(v)) => Some(Self::convert_block(v)),
+            Some(Err(e)) => Some(Err(e)),
+            None => None,
         })
     }
 }
 
-fn build_primitive_column<T>(values: &DataArrayRef) -> Result<Vec<Option<T::Native>>>
-where T: ArrowPrimitiveType {
-    let values = as_primitive_array::<T>(values);
+macro_rules! append_primitive_column {
+    ($BLOCK: expr, $NAME: expr, $COLUMN: expr, $NULLABLE: expr, $TYPE: ty) => {{
+        let values = as_primitive_array::<$TYPE>($COLUMN);
+        if $NULLABLE {
+            $BLOCK.column($NAME, values.iter().collect::<Vec<_>>())
+        } else {
+            $BLOCK.column($NAME, values.values().to_vec())
+        }
+    }};
+}
 
-    Ok(match values.null_count() {
-        //faster path
-        0 => (0..values.len())
-            .map(|i| Some(values.value(i)))
-            .collect::<Vec<Option<T::Native>>>(),
-        _ => (0..values.len())
+macro_rules! append_primitive_list_column {
+    ($BLOCK: expr, $NAME: expr, $LIST: expr, $TYPE: ty) => {{
+        let values = (0..$LIST.len())
             .map(|i| {
-                if values.is_null(i) {
-                    None
+                if $LIST.is_null(i) {
+                    vec![]
                 } else {
-                    Some(values.value(i))
+                    as_primitive_array::<$TYPE>(&$LIST.value(i))
+                        .values()
+                        .to_vec()
                 }
             })
-            .collect::<Vec<Option<T::Native>>>(),
-    })
+            .collect::<Vec<_>>();
+        $BLOCK.column($NAME, values)
+    }};
 }
 
-fn build_boolean_column(values: &DataArrayRef) -> Result<Vec<Option<u8>>> {
-    let values = as_boolean_array(values);
+/// Appends the arrow column to the ClickHouse block as its ClickHouse equivalent:
+/// numbers as themselves, Boolean as UInt8, Utf8/Binary as String, Date32 as Date,
+/// Date64/Timestamp as DateTime, Decimal as Decimal and List as Array.
+/// A nullable field is wrapped in Nullable, ClickHouse has no nullable arrays so null lists are empty.
+/// Types without a ClickHouse equivalent are sent as their String representation.
+fn append_column(
+    block: ClickHouseBlock,
+    name: &str,
+    column: &DataArrayRef,
+    nullable: bool,
+) -> Result<ClickHouseBlock> {
+    Ok(match column.data_type() {
+        DataType::Int8 => append_primitive_column!(block, name, column, nullable, Int8Type),
+        DataType::Int16 => append_primitive_column!(block, name, column, nullable, Int16Type),
+        DataType::Int32 => append_primitive_column!(block, name, column, nullable, Int32Type),
+        DataType::Int64 => append_primitive_column!(block, name, column, nullable, Int64Type),
+        DataType::UInt8 => append_primitive_column!(block, name, column, nullable, UInt8Type),
+        DataType::UInt16 => append_primitive_column!(block, name, column, nullable, UInt16Type),
+        DataType::UInt32 => append_primitive_column!(block, name, column, nullable, UInt32Type),
+        DataType::UInt64 => append_primitive_column!(block, name, column, nullable, UInt64Type),
+        DataType::Float32 => append_primitive_column!(block, name, column, nullable, Float32Type),
+        DataType::Float64 => append_primitive_column!(block, name, column, nullable, Float64Type),
+        DataType::Float16 => {
+            let column = data_array_cast(column, &DataType::Float32)?;
+            append_column(block, name, &column, nullable)?
+        }
 
-    Ok(match values.null_count() {
-        //faster path
-        0 => (0..values.len())
-            .map(|i| Some(values.value(i) as u8))
-            .collect::<Vec<Option<u8>>>(),
-        _ => (0..values.len())
-            .map(|i| {
-                if values.is_null(i) {
-                    None
-                } else {
-                    Some(values.value(i) as u8)
+        DataType::Boolean => {
+            let values = as_boolean_array(column);
+            if nullable {
+                block.column(name, build_boolean_column(values))
+            } else {
+                block.column(name, build_boolean_values(values))
+            }
+        }
+
+        DataType::Utf8 => {
+            let values = as_string_array(column);
+            if nullable {
+                block.column(name, values.iter().collect::<Vec<_>>())
+            } else {
+                block.column(name, build_string_values(values))
+            }
+        }
+        DataType::LargeUtf8 => {
+            let values = as_largestring_array(column);
+            if nullable {
+                block.column(name, values.iter().collect::<Vec<_>>())
+            } else {
+                let values = (0..values.len()).map(|i| values.value(i));
+                block.column(name, values.collect::<Vec<_>>())
+            }
+        }
+        DataType::Binary => {
+            let values = column
+                .as_any()
+                .downcast_ref::<BinaryArray>()
+                .expect("Unable to downcast to BinaryArray");
+            append_binary_column(block, name, values, nullable)
+        }
+        DataType::LargeBinary => {
+            let values = column
+                .as_any()
+                .downcast_ref::<LargeBinaryArray>()
+                .expect("Unable to downcast to LargeBinaryArray");
+            append_binary_column(block, name, values, nullable)
+        }
+
+        DataType::Date32 => {
+            let values = as_primitive_array::<Date32Type>(column);
+            if nullable {
+                let values = values.iter().map(|v| v.map(to_clickhouse_date));
+                block.column(name, values.collect::<Vec<_>>())
+            } else {
+                let values = values.values().iter().map(|v| to_clickhouse_date(*v));
+                block.column(name, values.collect::<Vec<_>>())
+            }
+        }
+        DataType::Date64 => {
+            let values = as_primitive_array::<Date64Type>(column);
+            append_date_time_column(block, name, values, nullable, 1)
+        }
+        DataType::Timestamp(unit, _) => {
+            // ClickHouse DateTime is in seconds, the values are kept as milliseconds until then.
+            let values = data_array_cast(column, &DataType::Int64)?;
+            let values = as_primitive_array::<Int64Type>(&values);
+            match unit {
+                TimeUnit::Second => append_date_time_column(block, name, values, nullable, 1000),
+                TimeUnit::Millisecond => append_date_time_column(block, name, values, nullable, 1),
+                TimeUnit::Microsecond => {
+                    let values = values.iter().map(|v| v.map(|v| v / 1_000));
+                    let values = values.collect::<Int64Array>();
+                    append_date_time_column(block, name, &values, nullable, 1)
                 }
-            })
-            .collect::<Vec<Option<u8>>>(),
-    })
-}
+                TimeUnit::Nanosecond => {
+                    let values = values.iter().map(|v| v.map(|v| v / 1_000_000));
+                    let values = values.collect::<Int64Array>();
+                    append_date_time_column(block, name, &values, nullable, 1)
+                }
+            }
+        }
 
-fn build_string_column(values: &DataArrayRef) -> Result<Vec<Option<&str>>> {
-    let values = as_string_array(values);
-    Ok(match values.null_count() {
-        //faster path
-        0 => (0..values.len())
-            .map(|i| Some(values.value(i)))
-            .collect::<Vec<Option<&str>>>(),
-        _ => (0..values.len())
-            .map(|i| {
-                if values.is_null(i) {
-                    None
-                } else {
-                    Some(values.value(i))
+        DataType::Decimal(precision, scale) if *precision <= 18 => {
+            let values = column
+                .as_any()
+                .downcast_ref::<DecimalArray>()
+                .expect("Unable to downcast to DecimalArray");
+            let scale = *scale as u8;
+            let value = |i: usize| Decimal::new(values.value(i) as i64, scale);
+            if nullable {
+                let values =
+                    (0..values.len()).map(|i| Some(value(i)).filter(|_| values.is_valid(i)));
+                block.column(name, values.collect::<Vec<_>>())
+            } else {
+                let values = (0..values.len()).map(value);
+                block.column(name, values.collect::<Vec<_>>())
+            }
+        }
+
+        DataType::Dictionary(_, value_type) => {
+            let column = data_array_cast(column, value_type)?;
+            append_column(block, name, &column, nullable)?
+        }
+
+        DataType::List(field) => {
+            let list = as_list_array(column);
+            match field.data_type() {
+                DataType::Int8 => append_primitive_list_column!(block, name, list, Int8Type),
+                DataType::Int16 => append_primitive_list_column!(block, name, list, Int16Type),
+                DataType::Int32 => append_primitive_list_column!(block, name, list, Int32Type),
+                DataType::Int64 => append_primitive_list_column!(block, name, list, Int64Type),
+                DataType::UInt8 => append_primitive_list_column!(block, name, list, UInt8Type),
+                DataType::UInt16 => append_primitive_list_column!(block, name, list, UInt16Type),
+                DataType::UInt32 => append_primitive_list_column!(block, name, list, UInt32Type),
+                DataType::UInt64 => append_primitive_list_column!(block, name, list, UInt64Type),
+                DataType::Float32 => append_primitive_list_column!(block, name, list, Float32Type),
+                DataType::Float64 => append_primitive_list_column!(block, name, list, Float64Type),
+                DataType::Utf8 => {
+                    let values = (0..list.len())
+                        .map(|i| {
+                            if list.is_null(i) {
+                                vec![]
+                            } else {
+                                let values = list.value(i);
+                                let values = as_string_array(&values);
+                                (0..values.len())
+                                    .map(|j| values.value(j).to_string())
+                                    .collect()
+                            }
+                        })
+                        .collect::<Vec<Vec<String>>>();
+                    block.column(name, values)
                 }
-            })
-            .collect::<Vec<Option<&str>>>(),
+                _ => append_string_representation(block, name, column, nullable)?,
+            }
+        }
+
+        DataType::Null => block.column(name, vec![None::<u8>; column.len()]),
+
+        _ => append_string_representation(block, name, column, nullable)?,
     })
 }
+
+// For arrow types without a ClickHouse equivalent, such as Struct, Time and Interval.
+fn append_string_representation(
+    block: ClickHouseBlock,
+    name: &str,
+    column: &DataArrayRef,
+    nullable: bool,
+) -> Result<ClickHouseBlock> {
+    let values = (0..column.len())
+        .map(|i| {
+            if column.is_null(i) {
+                Ok(None)
+            } else {
+                array_value_to_string(column, i).map(Some)
+            }
+        })
+        .collect::<std::result::Result<Vec<Option<String>>, _>>()?;
+
+    if nullable {
+        Ok(block.column(name, values))
+    } else {
+        let values = values.into_iter().map(|v| v.unwrap_or_default());
+        Ok(block.column(name, values.collect::<Vec<_>>()))
+    }
+}
+
+fn append_date_time_column(
+    block: ClickHouseBlock,
+    name: &str,
+    values: &PrimitiveArray<impl ArrowPrimitiveType<Native = i64>>,
+    nullable: bool,
+    millis_per_unit: i64,
+) -> ClickHouseBlock {
+    if nullable {
+        let values = values
+            .iter()
+            .map(|v| v.map(|v| to_clickhouse_date_time(v * millis_per_unit)));
+        block.column(name, values.collect::<Vec<_>>())
+    } else {
+        let values = values
+            .values()
+            .iter()
+            .map(|v| to_clickhouse_date_time(*v * millis_per_unit));
+        block.column(name, values.collect::<Vec<_>>())
+    }
+}
+
+fn append_binary_column<T: BinaryOffsetSizeTrait>(
+    block: ClickHouseBlock,
+    name: &str,
+    values: &GenericBinaryArray<T>,
+    nullable: bool,
+) -> ClickHouseBlock {
+    if nullable {
+        let values = values.iter().map(|v| v.map(|v| v.to_vec()));
+        block.column(name, values.collect::<Vec<_>>())
+    } else {
+        let values = (0..values.len()).map(|i| values.value(i));
+        block.column(name, values.collect::<Vec<_>>())
+    }
+}
+
+// Days since UNIX epoch.
+fn to_clickhouse_date(days: i32) -> Date<Tz> {
+    let date = NaiveDate::from_ymd(1970, 1, 1) + chrono::Duration::days(days as i64);
+    Tz::UTC.from_utc_date(&date)
+}
+
+// Milliseconds since UNIX epoch.
+fn to_clickhouse_date_time(millis: i64) -> DateTime<Tz> {
+    Tz::UTC.timestamp_millis(millis)
+}
+
+fn build_boolean_column(values: &BooleanArray) -> Vec<Option<u8>> {
+    values.iter().map(|v| v.map(|v| v as u8)).collect()
+}
+
+fn build_boolean_values(values: &BooleanArray) -> Vec<u8> {
+    (0..values.len()).map(|i| values.value(i) as u8).collect()
+}
+
+fn build_string_values(values: &StringArray) -> Vec<&str> {
+    (0..values.len()).map(|i| values.value(i)).collect()
+}
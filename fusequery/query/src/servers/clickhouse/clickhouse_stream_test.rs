@@ -0,0 +1,119 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[test]
+fn test_convert_block() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use chrono::Date;
+    use chrono::DateTime;
+    use chrono::TimeZone;
+    use chrono_tz::Tz;
+    use common_arrow::arrow::array::*;
+    use common_datablocks::DataBlock;
+    use common_datavalues::*;
+    use pretty_assertions::assert_eq;
+
+    use crate::servers::clickhouse::from_clickhouse_block;
+    use crate::servers::clickhouse::ClickHouseStream;
+
+    let mut list = ListBuilder::new(UInt32Builder::new(2));
+    list.values().append_value(1)?;
+    list.values().append_value(2)?;
+    list.append(true)?;
+    list.append(false)?;
+
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("i8", DataType::Int8, false),
+        DataField::new("u64", DataType::UInt64, true),
+        DataField::new("f64", DataType::Float64, false),
+        DataField::new("bool", DataType::Boolean, true),
+        DataField::new("utf8", DataType::Utf8, false),
+        DataField::new("binary", DataType::Binary, true),
+        DataField::new("date32", DataType::Date32, false),
+        DataField::new("date64", DataType::Date64, true),
+        DataField::new(
+            "list",
+            DataType::List(Box::new(DataField::new("item", DataType::UInt32, true))),
+            false,
+        ),
+        DataField::new("null", DataType::Null, true),
+    ]);
+    let block = DataBlock::create_by_array(schema.clone(), vec![
+        Arc::new(Int8Array::from(vec![1, -1])),
+        Arc::new(UInt64Array::from(vec![Some(1), None])),
+        Arc::new(Float64Array::from(vec![0.1, 0.2])),
+        Arc::new(BooleanArray::from(vec![Some(true), None])),
+        Arc::new(StringArray::from(vec!["a", "b"])),
+        Arc::new(BinaryArray::from(vec![Some(b"x".as_ref()), None])),
+        Arc::new(Date32Array::from(vec![0, 1])),
+        Arc::new(Date64Array::from(vec![Some(1000), None])),
+        Arc::new(list.finish()),
+        Arc::new(NullArray::new(2)),
+    ]);
+
+    let ch_block = ClickHouseStream::convert_block(block)?;
+    assert_eq!(ch_block.row_count(), 2);
+
+    let types = ch_block
+        .columns()
+        .iter()
+        .map(|column| column.sql_type().to_string().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(types, vec![
+        "Int8",
+        "Nullable(UInt64)",
+        "Float64",
+        "Nullable(UInt8)",
+        "String",
+        "Nullable(String)",
+        "Date",
+        "Nullable(DateTime)",
+        "Array(UInt32)",
+        "Nullable(UInt8)",
+    ]);
+
+    assert_eq!(ch_block.get::<i8, _>(1, "i8")?, -1);
+    assert_eq!(ch_block.get::<Option<u64>, _>(0, "u64")?, Some(1));
+    assert_eq!(ch_block.get::<Option<u64>, _>(1, "u64")?, None);
+    assert_eq!(ch_block.get::<f64, _>(1, "f64")?, 0.2);
+    assert_eq!(ch_block.get::<Option<u8>, _>(0, "bool")?, Some(1));
+    assert_eq!(ch_block.get::<Option<u8>, _>(1, "bool")?, None);
+    assert_eq!(ch_block.get::<&str, _>(1, "utf8")?, "b");
+    assert_eq!(
+        ch_block.get::<Option<&[u8]>, _>(0, "binary")?,
+        Some(b"x".as_ref())
+    );
+    assert_eq!(
+        ch_block.get::<Date<Tz>, _>(1, "date32")?,
+        Tz::UTC.ymd(1970, 1, 2)
+    );
+    assert_eq!(
+        ch_block.get::<Option<DateTime<Tz>>, _>(0, "date64")?,
+        Some(Tz::UTC.timestamp(1, 0))
+    );
+    assert_eq!(ch_block.get::<Vec<u32>, _>(0, "list")?, vec![1, 2]);
+    assert_eq!(ch_block.get::<Vec<u32>, _>(1, "list")?, Vec::<u32>::new());
+    assert_eq!(ch_block.get::<Option<u8>, _>(0, "null")?, None);
+
+    // Decoding the ClickHouse block gets back the same data.
+    let round_trip_schema = DataSchemaRefExt::create(vec![
+        schema.field(0).clone(),
+        schema.field(1).clone(),
+        schema.field(4).clone(),
+        schema.field(6).clone(),
+    ]);
+    let expected = vec![
+        "+----+-----+------+------------+",
+        "| i8 | u64 | utf8 | date32     |",
+        "+----+-----+------+------------+",
+        "| 1  | 1   | a    | 1970-01-01 |",
+        "| -1 |     | b    | 1970-01-02 |",
+        "+----+-----+------+------------+",
+    ];
+    let actual = from_clickhouse_block(round_trip_schema, &ch_block)?;
+    common_datablocks::assert_blocks_eq(expected, &[actual]);
+
+    Ok(())
+}
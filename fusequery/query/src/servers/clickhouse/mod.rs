@@ -2,9 +2,17 @@
 //
 // SPDX-License-Identifier: Apache-2.0.
 
+#[cfg(test)]
+mod clickhouse_insert_stream_test;
+#[cfg(test)]
+mod clickhouse_stream_test;
+
 mod clickhouse_handler;
+mod clickhouse_insert_stream;
 mod clickhouse_metrics;
 mod clickhouse_stream;
 
 pub use self::clickhouse_handler::ClickHouseHandler;
+pub use self::clickhouse_insert_stream::from_clickhouse_block;
+pub use self::clickhouse_insert_stream::ClickHouseInsertStream;
 pub use self::clickhouse_stream::ClickHouseStream;
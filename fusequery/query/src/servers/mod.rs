@@ -6,6 +6,8 @@
 
 mod clickhouse;
 mod mysql;
+mod postgres;
 
 pub use clickhouse::ClickHouseHandler;
 pub use mysql::MySQLHandler;
+pub use postgres::PostgresHandler;
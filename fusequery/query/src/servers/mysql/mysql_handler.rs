@@ -28,11 +28,24 @@ use crate::sql::PlanParser;
 
 struct Session {
     ctx: FuseQueryContextRef,
+    // A clone of the client socket, watched while a query runs.
+    client: Option<net::TcpStream>,
 }
 
 impl Session {
-    pub fn create(ctx: FuseQueryContextRef) -> Self {
-        Session { ctx }
+    pub fn create(ctx: FuseQueryContextRef, client: Option<net::TcpStream>) -> Self {
+        Session { ctx, client }
+    }
+}
+
+/// Resolves when the client closes the connection, the client sends nothing while its query runs.
+async fn client_disconnected(client: &tokio::net::TcpStream) {
+    let mut buf = [0u8; 1];
+    if let Ok(size) = client.peek(&mut buf).await {
+        if size > 0 {
+            // The client is still connected.
+            futures::future::pending::<()>().await;
+        }
     }
 }
 
@@ -74,13 +87,50 @@ impl<W: io::Write> MysqlShim<W> for Session {
         }
 
         type ResultSet = Result<Vec<DataBlock>>;
-        fn receive_data_set(runtime: Runtime, interpreter: InterpreterPtr) -> ResultSet {
+        fn receive_data_set(
+            runtime: Runtime,
+            interpreter: InterpreterPtr,
+            ctx: FuseQueryContextRef,
+            client: Option<&net::TcpStream>,
+        ) -> ResultSet {
             use futures::future::TryFutureExt;
-            runtime.block_on(
-                interpreter
-                    .execute()
-                    .and_then(|stream| stream.collect::<Result<Vec<DataBlock>>>()),
-            )
+            let query = interpreter
+                .execute()
+                .and_then(|stream| stream.collect::<Result<Vec<DataBlock>>>());
+
+            let watched = client.and_then(|client| client.try_clone().ok());
+            let data_set = runtime.block_on(async move {
+                let watched = watched.and_then(|watched| {
+                    watched.set_nonblocking(true).ok()?;
+                    tokio::net::TcpStream::from_std(watched).ok()
+                });
+
+                match watched {
+                    None => query.await,
+                    Some(watched) => {
+                        futures::pin_mut!(query);
+                        let data_set = tokio::select! {
+                            data_set = &mut query => Some(data_set),
+                            _ = client_disconnected(&watched) => None,
+                        };
+
+                        match data_set {
+                            Some(data_set) => data_set,
+                            None => {
+                                // The query stops at its next block.
+                                ctx.cancel();
+                                query.await
+                            }
+                        }
+                    }
+                }
+            });
+
+            // The socket is shared with the clone, the result set is written in blocking mode.
+            if let Some(client) = client {
+                client.set_nonblocking(false)?;
+            }
+            data_set
         }
 
         use crate::servers::mysql::endpoints::on_query_done as done;
@@ -89,9 +139,12 @@ impl<W: io::Write> MysqlShim<W> for Session {
             .and_then(|built_plan| InterpreterFactory::get(self.ctx.clone(), built_plan))
             .zip(build_runtime())
             // Execute query and get result
-            .and_then_tuple(receive_data_set)
+            .and_then_tuple(|runtime, interpreter| {
+                receive_data_set(runtime, interpreter, self.ctx.clone(), self.client.as_ref())
+            })
             // Push result set to client
             .and_match(done(writer));
+        self.ctx.get_process_list().on_query_done(&self.ctx, query);
 
         histogram!(
             super::mysql_metrics::METRIC_MYSQL_PROCESSOR_REQUEST_DURATION,
@@ -141,6 +194,7 @@ impl MySQLHandler {
 
         for stream in listener.incoming() {
             let stream = stream?;
+            let client = stream.try_clone().ok();
             let ctx = self
                 .session_manager
                 .try_create_context()?
@@ -150,7 +204,7 @@ impl MySQLHandler {
             let session_mgr = self.session_manager.clone();
             session_executor.execute(move || {
                 if let Err(error) =
-                    MysqlIntermediary::run_on_tcp(Session::create(ctx.clone()), stream)
+                    MysqlIntermediary::run_on_tcp(Session::create(ctx.clone(), client), stream)
                 {
                     log::error!(
                         "Unexpected error occurred during query execution: {:?}",
@@ -158,6 +212,7 @@ impl MySQLHandler {
                     );
                 }
 
+                ctx.cancel();
                 if let Err(error) = session_mgr.try_remove_context(ctx) {
                     log::error!("Cannot to destroy FuseQueryContext: {:?}", error);
                 }
@@ -0,0 +1,15 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[cfg(test)]
+mod postgres_handler_test;
+#[cfg(test)]
+mod postgres_types_test;
+
+pub use self::postgres_handler::PostgresHandler;
+
+mod postgres_handler;
+mod postgres_message;
+mod postgres_metrics;
+mod postgres_types;
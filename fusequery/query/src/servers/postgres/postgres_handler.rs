@@ -0,0 +1,620 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::collections::HashMap;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Instant;
+
+use common_datablocks::DataBlock;
+use common_datavalues::DataSchemaRef;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_planners::PlanNode;
+use common_streams::SendableDataBlockStream;
+use log::error;
+use metrics::histogram;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::BufReader;
+use tokio::net::TcpListener;
+use tokio_stream::StreamExt;
+
+use crate::clusters::ClusterRef;
+use crate::configs::Config;
+use crate::interpreters::InterpreterFactory;
+use crate::interpreters::InterpreterPtr;
+use crate::servers::postgres::postgres_message::*;
+use crate::servers::postgres::postgres_types::*;
+use crate::sessions::FuseQueryContextRef;
+use crate::sessions::SessionManagerRef;
+use crate::sql::PlanParser;
+
+// Buffered rows are sent to the client past this size.
+const FLUSH_THRESHOLD: usize = 64 * 1024;
+
+struct PreparedStatement {
+    query: String,
+    param_types: Vec<i32>,
+}
+
+struct Portal {
+    // None for an empty query.
+    interpreter: Option<InterpreterPtr>,
+    query: String,
+    command: &'static str,
+    inserted_rows: Arc<AtomicUsize>,
+    result_formats: Vec<i16>,
+    // Started by the first Execute, kept while the rows are fetched in batches.
+    result: Option<QueryResult>,
+}
+
+struct QueryResult {
+    stream: SendableDataBlockStream,
+    // The block an Execute with a row limit stopped in, and the next row of it.
+    pending: Option<(DataBlock, usize)>,
+    rows: usize,
+    // All the rows are written, or the query failed.
+    done: bool,
+}
+
+impl QueryResult {
+    fn create(stream: SendableDataBlockStream) -> Self {
+        QueryResult {
+            stream,
+            pending: None,
+            rows: 0,
+            done: false,
+        }
+    }
+
+    /// Writes at most `max_rows` rows (0 for all), returns false if some rows are left.
+    async fn write_rows<W: AsyncWrite + Unpin>(
+        &mut self,
+        formats: &[i16],
+        max_rows: usize,
+        writer: &mut MessageWriter<W>,
+    ) -> Result<bool> {
+        let mut written = 0;
+        loop {
+            let (block, offset) = match self.pending.take() {
+                Some(pending) => pending,
+                None => match self.stream.next().await {
+                    Some(block) => (block?, 0),
+                    None => return Ok(true),
+                },
+            };
+
+            let arrays = block
+                .columns()
+                .iter()
+                .map(|column| prepare_array(&column.to_array()?))
+                .collect::<Result<Vec<_>>>()?;
+            for row in offset..block.num_rows() {
+                if max_rows > 0 && written == max_rows {
+                    self.pending = Some((block, row));
+                    return Ok(false);
+                }
+
+                let values = arrays
+                    .iter()
+                    .enumerate()
+                    .map(|(i, array)| encode_value(array, row, result_format(formats, i)))
+                    .collect::<Result<Vec<_>>>()?;
+                writer.write_message(&BackendMessage::DataRow(values));
+                written += 1;
+                self.rows += 1;
+            }
+
+            if writer.buffered() > FLUSH_THRESHOLD {
+                writer.flush().await?;
+            }
+        }
+    }
+}
+
+pub struct Session {
+    ctx: FuseQueryContextRef,
+    statements: HashMap<String, PreparedStatement>,
+    portals: HashMap<String, Portal>,
+}
+
+impl Session {
+    pub fn create(ctx: FuseQueryContextRef) -> Self {
+        Session {
+            ctx,
+            statements: HashMap::new(),
+            portals: HashMap::new(),
+        }
+    }
+
+    pub async fn run<S: AsyncRead + AsyncWrite + Unpin>(mut self, stream: S) -> Result<()> {
+        let (reader, writer) = tokio::io::split(stream);
+        let mut reader = BufReader::new(reader);
+        let mut writer = MessageWriter::create(writer);
+        if !self.startup(&mut reader, &mut writer).await? {
+            return Ok(());
+        }
+
+        // After an error in the extended query flow, the messages are skipped until Sync.
+        let mut skip_until_sync = false;
+        while let Some(message) = read_message(&mut reader).await? {
+            match message {
+                FrontendMessage::Terminate => break,
+                FrontendMessage::Sync => {
+                    // Sync ends the implicit transaction, its portals are closed.
+                    self.portals.clear();
+                    skip_until_sync = false;
+                    writer.write_message(&BackendMessage::ReadyForQuery);
+                    writer.flush().await?;
+                }
+                FrontendMessage::Flush => writer.flush().await?,
+                FrontendMessage::Query(query) => {
+                    let start = Instant::now();
+                    if let Err(e) = self.simple_query(&query, &mut writer).await {
+                        error!("Postgres query error: {:?}", e);
+                        writer.write_message(&error_response(&e));
+                    }
+                    writer.write_message(&BackendMessage::ReadyForQuery);
+                    writer.flush().await?;
+                    histogram!(
+                        super::postgres_metrics::METRIC_POSTGRES_PROCESSOR_REQUEST_DURATION,
+                        start.elapsed()
+                    );
+                }
+                _ if skip_until_sync => {}
+                message => {
+                    if let Err(e) = self.extended_query(message, &mut writer).await {
+                        error!("Postgres extended query error: {:?}", e);
+                        writer.write_message(&error_response(&e));
+                        skip_until_sync = true;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns false if the connection must be closed.
+    async fn startup<R, W>(&self, reader: &mut R, writer: &mut MessageWriter<W>) -> Result<bool>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        loop {
+            match read_startup_message(reader).await? {
+                // No encryption, the client goes on in plain text or gives up.
+                StartupMessage::SslRequest | StartupMessage::GssEncRequest => {
+                    writer.write_bytes(b"N");
+                    writer.flush().await?;
+                }
+                // Running queries can't be cancelled yet.
+                StartupMessage::CancelRequest { .. } => return Ok(false),
+                StartupMessage::Startup { version, params } => {
+                    let res = if version != PROTOCOL_VERSION {
+                        Err(ErrorCode::UnImplement(format!(
+                            "Unsupported protocol version: {}.{}",
+                            version >> 16,
+                            version & 0xffff
+                        )))
+                    } else if let Some(database) = params.get("database") {
+                        self.ctx.set_current_database(database.clone())
+                    } else {
+                        Ok(())
+                    };
+
+                    if let Err(e) = res {
+                        writer.write_message(&BackendMessage::ErrorResponse {
+                            severity: "FATAL",
+                            code: sqlstate(&e),
+                            message: e.message(),
+                        });
+                        writer.flush().await?;
+                        return Ok(false);
+                    }
+
+                    writer.write_message(&BackendMessage::AuthenticationOk);
+                    let status = [
+                        ("server_version", "12.0"),
+                        ("server_encoding", "UTF8"),
+                        ("client_encoding", "UTF8"),
+                        ("DateStyle", "ISO, MDY"),
+                        ("TimeZone", "UTC"),
+                        ("integer_datetimes", "on"),
+                        ("standard_conforming_strings", "on"),
+                    ];
+                    for (name, value) in status.iter() {
+                        writer.write_message(&BackendMessage::ParameterStatus(
+                            name.to_string(),
+                            value.to_string(),
+                        ));
+                    }
+                    writer.write_message(&BackendMessage::BackendKeyData(
+                        std::process::id() as i32,
+                        rand::random(),
+                    ));
+                    writer.write_message(&BackendMessage::ReadyForQuery);
+                    writer.flush().await?;
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
+    async fn simple_query<W: AsyncWrite + Unpin>(
+        &mut self,
+        query: &str,
+        writer: &mut MessageWriter<W>,
+    ) -> Result<()> {
+        if is_empty_query(query) {
+            writer.write_message(&BackendMessage::EmptyQueryResponse);
+            return Ok(());
+        }
+
+        self.start_query()?;
+        let (interpreter, command, inserted_rows) = self.plan(query)?;
+        let schema = interpreter.schema();
+        if !schema.fields().is_empty() {
+            writer.write_message(&row_description(&schema, &[]));
+        }
+
+        let mut result = QueryResult::create(interpreter.execute().await?);
+        result.write_rows(&[], 0, writer).await?;
+        writer.write_message(&BackendMessage::CommandComplete(command_tag(
+            command,
+            result.rows,
+            &inserted_rows,
+        )));
+        self.ctx.get_process_list().on_query_done(&self.ctx, query);
+        Ok(())
+    }
+
+    async fn extended_query<W: AsyncWrite + Unpin>(
+        &mut self,
+        message: FrontendMessage,
+        writer: &mut MessageWriter<W>,
+    ) -> Result<()> {
+        match message {
+            FrontendMessage::Parse {
+                name,
+                query,
+                param_types,
+            } => {
+                self.statements
+                    .insert(name, PreparedStatement { query, param_types });
+                writer.write_message(&BackendMessage::ParseComplete);
+            }
+            FrontendMessage::Bind {
+                portal,
+                statement,
+                param_formats,
+                params,
+                result_formats,
+            } => {
+                let statement = self.get_statement(&statement)?;
+                let query = bind_parameters(
+                    &statement.query,
+                    &statement.param_types,
+                    &param_formats,
+                    &params,
+                )?;
+                let (interpreter, command, inserted_rows) = if is_empty_query(&query) {
+                    (None, "", Arc::new(AtomicUsize::new(0)))
+                } else {
+                    let (interpreter, command, inserted_rows) = self.plan(&query)?;
+                    (Some(interpreter), command, inserted_rows)
+                };
+                self.portals.insert(portal, Portal {
+                    interpreter,
+                    query,
+                    command,
+                    inserted_rows,
+                    result_formats,
+                    result: None,
+                });
+                writer.write_message(&BackendMessage::BindComplete);
+            }
+            FrontendMessage::Describe { kind: b'S', name } => {
+                let statement = self.get_statement(&name)?;
+                let count = count_parameters(&statement.query)?.max(statement.param_types.len());
+                let types = (0..count)
+                    .map(|i| match statement.param_types.get(i) {
+                        Some(oid) if *oid != 0 => *oid,
+                        _ => TEXT_OID,
+                    })
+                    .collect();
+                writer.write_message(&BackendMessage::ParameterDescription(types));
+
+                // The columns are known once the query is planned, with NULL for the parameters.
+                let query = bind_parameters(&statement.query, &[], &[], &vec![None; count])?;
+                if is_empty_query(&query) {
+                    writer.write_message(&BackendMessage::NoData);
+                } else {
+                    let (interpreter, _, _) = self.plan(&query)?;
+                    writer.write_message(&describe_result(&interpreter.schema(), &[]));
+                }
+            }
+            FrontendMessage::Describe { name, .. } => {
+                let portal = self.get_portal(&name)?;
+                match &portal.interpreter {
+                    Some(interpreter) => writer.write_message(&describe_result(
+                        &interpreter.schema(),
+                        &portal.result_formats,
+                    )),
+                    None => writer.write_message(&BackendMessage::NoData),
+                }
+            }
+            FrontendMessage::Execute { portal, max_rows } => {
+                self.execute_portal(&portal, max_rows, writer).await?
+            }
+            FrontendMessage::Close { kind, name } => {
+                if kind == b'S' {
+                    self.statements.remove(&name);
+                } else {
+                    self.portals.remove(&name);
+                }
+                writer.write_message(&BackendMessage::CloseComplete);
+            }
+            other => {
+                return Err(ErrorCode::LogicalError(format!(
+                    "Unexpected message in extended query: {:?}",
+                    other
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    async fn execute_portal<W: AsyncWrite + Unpin>(
+        &mut self,
+        name: &str,
+        max_rows: i32,
+        writer: &mut MessageWriter<W>,
+    ) -> Result<()> {
+        // The query of the portal starts with its first Execute.
+        if self.get_portal(name)?.result.is_none() {
+            self.start_query()?;
+        }
+
+        let portal = self
+            .portals
+            .get_mut(name)
+            .ok_or_else(|| ErrorCode::BadArguments(format!("Unknown portal: {:?}", name)))?;
+        let interpreter = match &portal.interpreter {
+            Some(interpreter) => interpreter.clone(),
+            None => {
+                writer.write_message(&BackendMessage::EmptyQueryResponse);
+                return Ok(());
+            }
+        };
+
+        if portal.result.is_none() {
+            portal.result = Some(QueryResult::create(interpreter.execute().await?));
+        }
+        if let Some(result) = portal.result.as_mut() {
+            let max_rows = max_rows.max(0) as usize;
+            if result.done {
+                // The portal is complete, executing it again returns no rows.
+                writer.write_message(&BackendMessage::CommandComplete(command_tag(
+                    portal.command,
+                    0,
+                    &AtomicUsize::new(0),
+                )));
+                return Ok(());
+            }
+
+            let res = result
+                .write_rows(&portal.result_formats, max_rows, writer)
+                .await;
+            result.done = !matches!(res, Ok(false));
+            if result.done {
+                self.ctx
+                    .get_process_list()
+                    .on_query_done(&self.ctx, &portal.query);
+            }
+            if res? {
+                writer.write_message(&BackendMessage::CommandComplete(command_tag(
+                    portal.command,
+                    result.rows,
+                    &portal.inserted_rows,
+                )));
+            } else {
+                writer.write_message(&BackendMessage::PortalSuspended);
+            }
+        }
+        Ok(())
+    }
+
+    // The context is shared by the queries of the session, it's reset for each query.
+    // The suspended portal still reads its rows with it, so the other queries wait for it
+    // to complete or to be closed.
+    fn start_query(&self) -> Result<()> {
+        if let Some(name) = self
+            .portals
+            .iter()
+            .find_map(|(name, portal)| match &portal.result {
+                Some(result) if !result.done => Some(name),
+                _ => None,
+            })
+        {
+            return Err(ErrorCode::LogicalError(format!(
+                "Cannot run a query while the portal {:?} is suspended",
+                name
+            )));
+        }
+        self.ctx.reset()
+    }
+
+    /// Plans the query, the rows appended by an INSERT are counted for its command tag.
+    fn plan(&self, query: &str) -> Result<(InterpreterPtr, &'static str, Arc<AtomicUsize>)> {
+        let plan = PlanParser::create(self.ctx.clone()).build_from_sql(query)?;
+        let command = plan_command(&plan);
+        let inserted_rows = Arc::new(AtomicUsize::new(0));
+        if let PlanNode::InsertInto(insert) = &plan {
+            let input_stream = insert.input_stream.lock().unwrap().take();
+            if let Some(input_stream) = input_stream {
+                let counter = inserted_rows.clone();
+                insert.set_input_stream(Box::pin(input_stream.map(move |block| {
+                    if let Ok(block) = &block {
+                        counter.fetch_add(block.num_rows(), Ordering::Relaxed);
+                    }
+                    block
+                })));
+            }
+        }
+        Ok((
+            InterpreterFactory::get(self.ctx.clone(), plan)?,
+            command,
+            inserted_rows,
+        ))
+    }
+
+    fn get_statement(&self, name: &str) -> Result<&PreparedStatement> {
+        self.statements.get(name).ok_or_else(|| {
+            ErrorCode::BadArguments(format!("Unknown prepared statement: {:?}", name))
+        })
+    }
+
+    fn get_portal(&self, name: &str) -> Result<&Portal> {
+        self.portals
+            .get(name)
+            .ok_or_else(|| ErrorCode::BadArguments(format!("Unknown portal: {:?}", name)))
+    }
+}
+
+fn is_empty_query(query: &str) -> bool {
+    query.trim().trim_matches(';').trim().is_empty()
+}
+
+// No format code means text, one applies to all the columns.
+fn result_format(formats: &[i16], column: usize) -> i16 {
+    match formats.len() {
+        0 => TEXT_FORMAT,
+        1 => formats[0],
+        _ => formats.get(column).copied().unwrap_or(TEXT_FORMAT),
+    }
+}
+
+fn row_description(schema: &DataSchemaRef, formats: &[i16]) -> BackendMessage {
+    let fields = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let (type_oid, type_size) = postgres_type(field.data_type());
+            FieldDescription {
+                name: field.name().clone(),
+                type_oid,
+                type_size,
+                format: result_format(formats, i),
+            }
+        })
+        .collect();
+    BackendMessage::RowDescription(fields)
+}
+
+fn describe_result(schema: &DataSchemaRef, formats: &[i16]) -> BackendMessage {
+    if schema.fields().is_empty() {
+        BackendMessage::NoData
+    } else {
+        row_description(schema, formats)
+    }
+}
+
+fn plan_command(plan: &PlanNode) -> &'static str {
+    match plan {
+        // ANALYZE TABLE returns the collected statistics as rows.
+        PlanNode::Select(_) | PlanNode::Explain(_) | PlanNode::AnalyzeTable(_) => "SELECT",
+        PlanNode::InsertInto(_) => "INSERT",
+        PlanNode::CreateDatabase(_) => "CREATE DATABASE",
+        PlanNode::DropDatabase(_) => "DROP DATABASE",
+        PlanNode::CreateTable(_) => "CREATE TABLE",
+        PlanNode::DropTable(_) => "DROP TABLE",
+        PlanNode::UseDatabase(_) => "USE",
+        PlanNode::SetVariable(_) => "SET",
+        _ => "OK",
+    }
+}
+
+fn command_tag(command: &str, rows: usize, inserted_rows: &AtomicUsize) -> String {
+    match command {
+        "SELECT" => format!("SELECT {}", rows),
+        "INSERT" => format!("INSERT 0 {}", inserted_rows.load(Ordering::Relaxed)),
+        _ => command.to_string(),
+    }
+}
+
+fn sqlstate(e: &ErrorCode) -> &'static str {
+    match e.code() {
+        // UnImplement
+        2 => "0A000",
+        // UnknownDatabase
+        3 => "3D000",
+        // UnknownSetting, UnknownVariable
+        4 | 20 => "42704",
+        // SyntaxException
+        5 => "42601",
+        // UnknownFunction
+        8 => "42883",
+        // UnknownTable
+        25 => "42P01",
+        _ => "XX000",
+    }
+}
+
+fn error_response(e: &ErrorCode) -> BackendMessage {
+    BackendMessage::ErrorResponse {
+        severity: "ERROR",
+        code: sqlstate(e),
+        message: e.message(),
+    }
+}
+
+pub struct PostgresHandler {
+    conf: Config,
+    cluster: ClusterRef,
+    session_manager: SessionManagerRef,
+}
+
+impl PostgresHandler {
+    pub fn create(conf: Config, cluster: ClusterRef, session_manager: SessionManagerRef) -> Self {
+        PostgresHandler {
+            conf,
+            cluster,
+            session_manager,
+        }
+    }
+
+    pub async fn start(&self) -> anyhow::Result<()> {
+        let listener = TcpListener::bind(format!(
+            "{}:{}",
+            self.conf.postgres_handler_host, self.conf.postgres_handler_port
+        ))
+        .await?;
+
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let ctx = self
+                .session_manager
+                .try_create_context()?
+                .with_cluster(self.cluster.clone())?;
+            ctx.set_max_threads(self.conf.num_cpus)?;
+
+            let session_mgr = self.session_manager.clone();
+            tokio::spawn(async move {
+                if let Err(e) = Session::create(ctx.clone()).run(stream).await {
+                    error!("Postgres session error: {:?}", e);
+                }
+                if let Err(e) = session_mgr.try_remove_context(ctx) {
+                    error!("Cannot to destroy FuseQueryContext: {:?}", e);
+                }
+            });
+        }
+    }
+
+    pub fn stop(&self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::DuplexStream;
+
+fn message(tag: u8, body: &[u8]) -> Vec<u8> {
+    let mut buf = vec![tag];
+    buf.extend_from_slice(&(body.len() as i32 + 4).to_be_bytes());
+    buf.extend_from_slice(body);
+    buf
+}
+
+fn cstring(v: &str) -> Vec<u8> {
+    let mut buf = v.as_bytes().to_vec();
+    buf.push(0);
+    buf
+}
+
+// Reads the backend messages up to ReadyForQuery.
+async fn read_messages(client: &mut DuplexStream) -> anyhow::Result<Vec<(char, Vec<u8>)>> {
+    let mut messages = vec![];
+    loop {
+        let tag = client.read_u8().await?;
+        let length = client.read_i32().await?;
+        let mut body = vec![0u8; length as usize - 4];
+        client.read_exact(&mut body).await?;
+        messages.push((tag as char, body));
+        if tag == b'Z' {
+            return Ok(messages);
+        }
+    }
+}
+
+fn tags(messages: &[(char, Vec<u8>)]) -> String {
+    messages.iter().map(|(tag, _)| *tag).collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_postgres_session() -> anyhow::Result<()> {
+    use pretty_assertions::assert_eq;
+
+    use crate::servers::postgres::postgres_handler::Session;
+    use crate::tests::try_create_context;
+
+    let (mut client, server) = tokio::io::duplex(64 * 1024);
+    let session = tokio::spawn(Session::create(try_create_context()?).run(server));
+
+    // SSL is refused, then the startup.
+    {
+        client
+            .write_all(&[0, 0, 0, 8, 0x04, 0xd2, 0x16, 0x2f])
+            .await?;
+        assert_eq!(b'N', client.read_u8().await?);
+
+        let mut body = 196608i32.to_be_bytes().to_vec();
+        body.extend(cstring("user"));
+        body.extend(cstring("root"));
+        body.extend(cstring("database"));
+        body.extend(cstring("system"));
+        body.push(0);
+        client
+            .write_all(&(body.len() as i32 + 4).to_be_bytes())
+            .await?;
+        client.write_all(&body).await?;
+
+        let messages = read_messages(&mut client).await?;
+        assert_eq!("RSSSSSSSKZ", tags(&messages));
+    }
+
+    // Simple query.
+    {
+        let query = "SELECT number FROM numbers(3) ORDER BY number";
+        client.write_all(&message(b'Q', &cstring(query))).await?;
+
+        let messages = read_messages(&mut client).await?;
+        assert_eq!("TDDDCZ", tags(&messages));
+        // One column of one value "2".
+        assert_eq!(vec![0, 1, 0, 0, 0, 1, b'2'], messages[3].1);
+        assert_eq!(cstring("SELECT 3"), messages[4].1);
+    }
+
+    // Error, the current database is system.
+    {
+        client
+            .write_all(&message(b'Q', &cstring("SELECT * FROM xx")))
+            .await?;
+
+        let messages = read_messages(&mut client).await?;
+        assert_eq!("EZ", tags(&messages));
+        let error = String::from_utf8_lossy(&messages[0].1).to_string();
+        assert!(error.contains("42P01"));
+        assert!(error.contains("Unknown table: 'xx'"));
+    }
+
+    // Extended query with a binary parameter, fetched one row at a time.
+    {
+        let mut parse = cstring("");
+        parse.extend(cstring(
+            "SELECT number FROM numbers(5) WHERE number > $1 ORDER BY number",
+        ));
+        parse.extend(&1i16.to_be_bytes());
+        parse.extend(&20i32.to_be_bytes());
+
+        let mut bind = cstring("");
+        bind.extend(cstring(""));
+        bind.extend(&[0, 1, 0, 1]);
+        bind.extend(&[0, 1, 0, 0, 0, 8]);
+        bind.extend(&2i64.to_be_bytes());
+        bind.extend(&[0, 1, 0, 1]);
+
+        let mut execute = cstring("");
+        execute.extend(&1i32.to_be_bytes());
+
+        let mut execute_all = cstring("");
+        execute_all.extend(&0i32.to_be_bytes());
+
+        let mut describe = vec![b'P'];
+        describe.extend(cstring(""));
+
+        for m in [
+            message(b'P', &parse),
+            message(b'B', &bind),
+            message(b'D', &describe),
+            message(b'E', &execute),
+            message(b'E', &execute_all),
+            message(b'S', &[]),
+        ]
+        .iter()
+        {
+            client.write_all(m).await?;
+        }
+
+        let messages = read_messages(&mut client).await?;
+        assert_eq!("12TDsDCZ", tags(&messages));
+        // The numbers are UInt64, sent as binary numeric: 3 then 4.
+        assert_eq!(
+            vec![0, 1, 0, 0, 0, 10, 0, 1, 0, 0, 0, 0, 0, 0, 0, 3],
+            messages[3].1
+        );
+        assert_eq!(
+            vec![0, 1, 0, 0, 0, 10, 0, 1, 0, 0, 0, 0, 0, 0, 0, 4],
+            messages[5].1
+        );
+        assert_eq!(cstring("SELECT 2"), messages[6].1);
+    }
+
+    // Errors of the extended query skip the messages until Sync.
+    {
+        let mut parse = cstring("");
+        parse.extend(cstring("SELECT * FROM xx"));
+        parse.extend(&0i16.to_be_bytes());
+
+        let mut bind = cstring("");
+        bind.extend(cstring(""));
+        bind.extend(&[0, 0, 0, 0, 0, 0]);
+
+        for m in [
+            message(b'P', &parse),
+            message(b'B', &bind),
+            message(b'E', &[0, 0, 0, 0, 0]),
+            message(b'S', &[]),
+        ]
+        .iter()
+        {
+            client.write_all(m).await?;
+        }
+
+        let messages = read_messages(&mut client).await?;
+        assert_eq!("1EZ", tags(&messages));
+    }
+
+    // The other queries wait for the suspended portal, Sync closes it.
+    {
+        let mut parse = cstring("s1");
+        parse.extend(cstring("SELECT number FROM numbers(3)"));
+        parse.extend(&0i16.to_be_bytes());
+
+        let mut bind = cstring("p1");
+        bind.extend(cstring("s1"));
+        bind.extend(&[0, 0, 0, 0, 0, 0]);
+
+        let mut bind_other = cstring("p2");
+        bind_other.extend(cstring("s1"));
+        bind_other.extend(&[0, 0, 0, 0, 0, 0]);
+
+        let mut execute = cstring("p1");
+        execute.extend(&1i32.to_be_bytes());
+
+        let mut execute_other = cstring("p2");
+        execute_other.extend(&0i32.to_be_bytes());
+
+        for m in [
+            message(b'P', &parse),
+            message(b'B', &bind),
+            message(b'B', &bind_other),
+            message(b'E', &execute),
+            message(b'E', &execute_other),
+            message(b'S', &[]),
+        ]
+        .iter()
+        {
+            client.write_all(m).await?;
+        }
+
+        let messages = read_messages(&mut client).await?;
+        assert_eq!("122DsEZ", tags(&messages));
+        let error = String::from_utf8_lossy(&messages[5].1).to_string();
+        assert!(
+            error.contains("the portal \"p1\" is suspended"),
+            "{}",
+            error
+        );
+
+        for m in [
+            message(b'B', &bind_other),
+            message(b'E', &execute_other),
+            message(b'S', &[]),
+        ]
+        .iter()
+        {
+            client.write_all(m).await?;
+        }
+
+        let messages = read_messages(&mut client).await?;
+        assert_eq!("2DDDCZ", tags(&messages));
+    }
+
+    client.write_all(&message(b'X', &[])).await?;
+    session.await??;
+
+    Ok(())
+}
@@ -0,0 +1,412 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::collections::HashMap;
+use std::io::ErrorKind;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+
+// Protocol 3.0, the only one spoken by the clients since PostgreSQL 7.4.
+pub const PROTOCOL_VERSION: i32 = 196608;
+const CANCEL_REQUEST_CODE: i32 = 80877102;
+const SSL_REQUEST_CODE: i32 = 80877103;
+const GSSENC_REQUEST_CODE: i32 = 80877104;
+
+// The protocol has no limit, a bigger message is most likely garbage.
+const MAX_MESSAGE_LENGTH: usize = 256 * 1024 * 1024;
+const MAX_STARTUP_MESSAGE_LENGTH: usize = 10 * 1024;
+
+/// The first message of a connection, it has no type byte.
+#[derive(Debug, PartialEq)]
+pub enum StartupMessage {
+    SslRequest,
+    GssEncRequest,
+    CancelRequest {
+        process_id: i32,
+        secret_key: i32,
+    },
+    Startup {
+        version: i32,
+        params: HashMap<String, String>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FrontendMessage {
+    Query(String),
+    Parse {
+        name: String,
+        query: String,
+        param_types: Vec<i32>,
+    },
+    Bind {
+        portal: String,
+        statement: String,
+        param_formats: Vec<i16>,
+        params: Vec<Option<Vec<u8>>>,
+        result_formats: Vec<i16>,
+    },
+    /// Describe a prepared statement (b'S') or a portal (b'P').
+    Describe {
+        kind: u8,
+        name: String,
+    },
+    Execute {
+        portal: String,
+        max_rows: i32,
+    },
+    /// Close a prepared statement (b'S') or a portal (b'P').
+    Close {
+        kind: u8,
+        name: String,
+    },
+    Sync,
+    Flush,
+    Terminate,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDescription {
+    pub name: String,
+    pub type_oid: i32,
+    pub type_size: i16,
+    /// 0 for text, 1 for binary.
+    pub format: i16,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BackendMessage {
+    AuthenticationOk,
+    ParameterStatus(String, String),
+    BackendKeyData(i32, i32),
+    /// We don't support transactions, the status is always idle.
+    ReadyForQuery,
+    RowDescription(Vec<FieldDescription>),
+    DataRow(Vec<Option<Vec<u8>>>),
+    CommandComplete(String),
+    EmptyQueryResponse,
+    ErrorResponse {
+        severity: &'static str,
+        code: &'static str,
+        message: String,
+    },
+    ParseComplete,
+    BindComplete,
+    CloseComplete,
+    NoData,
+    ParameterDescription(Vec<i32>),
+    PortalSuspended,
+}
+
+pub async fn read_startup_message<R: AsyncRead + Unpin>(reader: &mut R) -> Result<StartupMessage> {
+    let length = reader.read_i32().await? as usize;
+    if !(8..=MAX_STARTUP_MESSAGE_LENGTH).contains(&length) {
+        return Err(ErrorCode::BadBytes(format!(
+            "Invalid startup message length: {}",
+            length
+        )));
+    }
+
+    let mut body = vec![0u8; length - 4];
+    reader.read_exact(&mut body).await?;
+    decode_startup_message(&body)
+}
+
+pub fn decode_startup_message(body: &[u8]) -> Result<StartupMessage> {
+    let mut body = MessageBody::create(body);
+    match body.read_i32()? {
+        SSL_REQUEST_CODE => Ok(StartupMessage::SslRequest),
+        GSSENC_REQUEST_CODE => Ok(StartupMessage::GssEncRequest),
+        CANCEL_REQUEST_CODE => Ok(StartupMessage::CancelRequest {
+            process_id: body.read_i32()?,
+            secret_key: body.read_i32()?,
+        }),
+        version => {
+            let mut params = HashMap::new();
+            loop {
+                let name = body.read_cstring()?;
+                if name.is_empty() {
+                    break;
+                }
+                params.insert(name, body.read_cstring()?);
+            }
+            Ok(StartupMessage::Startup { version, params })
+        }
+    }
+}
+
+/// Reads the next message, None if the client closed the connection.
+pub async fn read_message<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Option<FrontendMessage>> {
+    let tag = match reader.read_u8().await {
+        Ok(tag) => tag,
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let length = reader.read_i32().await? as usize;
+    if !(4..=MAX_MESSAGE_LENGTH).contains(&length) {
+        return Err(ErrorCode::BadBytes(format!(
+            "Invalid message length: {}",
+            length
+        )));
+    }
+
+    let mut body = vec![0u8; length - 4];
+    reader.read_exact(&mut body).await?;
+    decode_message(tag, &body).map(Some)
+}
+
+pub fn decode_message(tag: u8, body: &[u8]) -> Result<FrontendMessage> {
+    let mut body = MessageBody::create(body);
+    match tag {
+        b'Q' => Ok(FrontendMessage::Query(body.read_cstring()?)),
+        b'P' => {
+            let name = body.read_cstring()?;
+            let query = body.read_cstring()?;
+            let param_types = (0..body.read_i16()?)
+                .map(|_| body.read_i32())
+                .collect::<Result<Vec<_>>>()?;
+            Ok(FrontendMessage::Parse {
+                name,
+                query,
+                param_types,
+            })
+        }
+        b'B' => {
+            let portal = body.read_cstring()?;
+            let statement = body.read_cstring()?;
+            let param_formats = (0..body.read_i16()?)
+                .map(|_| body.read_i16())
+                .collect::<Result<Vec<_>>>()?;
+            let params = (0..body.read_i16()?)
+                .map(|_| match body.read_i32()? {
+                    -1 => Ok(None),
+                    length => body.read_bytes(length as usize).map(|v| Some(v.to_vec())),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let result_formats = (0..body.read_i16()?)
+                .map(|_| body.read_i16())
+                .collect::<Result<Vec<_>>>()?;
+            Ok(FrontendMessage::Bind {
+                portal,
+                statement,
+                param_formats,
+                params,
+                result_formats,
+            })
+        }
+        b'D' => Ok(FrontendMessage::Describe {
+            kind: body.read_u8()?,
+            name: body.read_cstring()?,
+        }),
+        b'E' => Ok(FrontendMessage::Execute {
+            portal: body.read_cstring()?,
+            max_rows: body.read_i32()?,
+        }),
+        b'C' => Ok(FrontendMessage::Close {
+            kind: body.read_u8()?,
+            name: body.read_cstring()?,
+        }),
+        b'S' => Ok(FrontendMessage::Sync),
+        b'H' => Ok(FrontendMessage::Flush),
+        b'X' => Ok(FrontendMessage::Terminate),
+        other => Err(ErrorCode::BadBytes(format!(
+            "Unsupported message type: {}",
+            other as char
+        ))),
+    }
+}
+
+impl BackendMessage {
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        let tag = match self {
+            BackendMessage::AuthenticationOk => b'R',
+            BackendMessage::ParameterStatus(_, _) => b'S',
+            BackendMessage::BackendKeyData(_, _) => b'K',
+            BackendMessage::ReadyForQuery => b'Z',
+            BackendMessage::RowDescription(_) => b'T',
+            BackendMessage::DataRow(_) => b'D',
+            BackendMessage::CommandComplete(_) => b'C',
+            BackendMessage::EmptyQueryResponse => b'I',
+            BackendMessage::ErrorResponse { .. } => b'E',
+            BackendMessage::ParseComplete => b'1',
+            BackendMessage::BindComplete => b'2',
+            BackendMessage::CloseComplete => b'3',
+            BackendMessage::NoData => b'n',
+            BackendMessage::ParameterDescription(_) => b't',
+            BackendMessage::PortalSuspended => b's',
+        };
+        buf.push(tag);
+
+        // The length includes itself and is known after the body is written.
+        let start = buf.len();
+        buf.extend_from_slice(&[0; 4]);
+
+        match self {
+            BackendMessage::AuthenticationOk => put_i32(buf, 0),
+            BackendMessage::ParameterStatus(name, value) => {
+                put_cstring(buf, name);
+                put_cstring(buf, value);
+            }
+            BackendMessage::BackendKeyData(process_id, secret_key) => {
+                put_i32(buf, *process_id);
+                put_i32(buf, *secret_key);
+            }
+            BackendMessage::ReadyForQuery => buf.push(b'I'),
+            BackendMessage::RowDescription(fields) => {
+                put_i16(buf, fields.len() as i16);
+                for field in fields {
+                    put_cstring(buf, &field.name);
+                    // Table oid and column attribute number.
+                    put_i32(buf, 0);
+                    put_i16(buf, 0);
+                    put_i32(buf, field.type_oid);
+                    put_i16(buf, field.type_size);
+                    // Type modifier.
+                    put_i32(buf, -1);
+                    put_i16(buf, field.format);
+                }
+            }
+            BackendMessage::DataRow(values) => {
+                put_i16(buf, values.len() as i16);
+                for value in values {
+                    match value {
+                        Some(value) => {
+                            put_i32(buf, value.len() as i32);
+                            buf.extend_from_slice(value);
+                        }
+                        None => put_i32(buf, -1),
+                    }
+                }
+            }
+            BackendMessage::CommandComplete(tag) => put_cstring(buf, tag),
+            BackendMessage::ErrorResponse {
+                severity,
+                code,
+                message,
+            } => {
+                buf.push(b'S');
+                put_cstring(buf, severity);
+                buf.push(b'V');
+                put_cstring(buf, severity);
+                buf.push(b'C');
+                put_cstring(buf, code);
+                buf.push(b'M');
+                put_cstring(buf, message);
+                buf.push(0);
+            }
+            BackendMessage::ParameterDescription(types) => {
+                put_i16(buf, types.len() as i16);
+                for oid in types {
+                    put_i32(buf, *oid);
+                }
+            }
+            BackendMessage::EmptyQueryResponse
+            | BackendMessage::ParseComplete
+            | BackendMessage::BindComplete
+            | BackendMessage::CloseComplete
+            | BackendMessage::NoData
+            | BackendMessage::PortalSuspended => {}
+        }
+
+        let length = (buf.len() - start) as i32;
+        buf[start..start + 4].copy_from_slice(&length.to_be_bytes());
+    }
+}
+
+/// Buffers the backend messages until flush.
+pub struct MessageWriter<W> {
+    writer: W,
+    buf: Vec<u8>,
+}
+
+impl<W: AsyncWrite + Unpin> MessageWriter<W> {
+    pub fn create(writer: W) -> Self {
+        MessageWriter {
+            writer,
+            buf: Vec::with_capacity(8192),
+        }
+    }
+
+    pub fn write_message(&mut self, message: &BackendMessage) {
+        message.encode(&mut self.buf);
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    pub fn buffered(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub async fn flush(&mut self) -> Result<()> {
+        self.writer.write_all(&self.buf).await?;
+        self.writer.flush().await?;
+        self.buf.clear();
+        Ok(())
+    }
+}
+
+struct MessageBody<'a> {
+    body: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> MessageBody<'a> {
+    fn create(body: &'a [u8]) -> Self {
+        MessageBody { body, pos: 0 }
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8]> {
+        if self.body.len() - self.pos < length {
+            return Err(ErrorCode::BadBytes("Unexpected end of message"));
+        }
+        let bytes = &self.body[self.pos..self.pos + length];
+        self.pos += length;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_i16(&mut self) -> Result<i16> {
+        let bytes = self.read_bytes(2)?;
+        Ok(i16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_i32(&mut self) -> Result<i32> {
+        let bytes = self.read_bytes(4)?;
+        Ok(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_cstring(&mut self) -> Result<String> {
+        let rest = &self.body[self.pos..];
+        let end = rest
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or_else(|| ErrorCode::BadBytes("Unterminated string in message"))?;
+        self.pos += end + 1;
+        String::from_utf8(rest[..end].to_vec()).map_err(ErrorCode::from_std_error)
+    }
+}
+
+fn put_i16(buf: &mut Vec<u8>, v: i16) {
+    buf.extend_from_slice(&v.to_be_bytes());
+}
+
+fn put_i32(buf: &mut Vec<u8>, v: i32) {
+    buf.extend_from_slice(&v.to_be_bytes());
+}
+
+fn put_cstring(buf: &mut Vec<u8>, v: &str) {
+    buf.extend_from_slice(v.as_bytes());
+    buf.push(0);
+}
@@ -0,0 +1,5 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+pub static METRIC_POSTGRES_PROCESSOR_REQUEST_DURATION: &str = "postgres.process_request_duration";
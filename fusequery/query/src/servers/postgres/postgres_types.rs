@@ -0,0 +1,406 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::convert::TryInto;
+
+use chrono::NaiveDateTime;
+use common_arrow::arrow::array::*;
+use common_arrow::arrow::datatypes::*;
+use common_arrow::arrow::util::display::array_value_to_string;
+use common_datavalues::data_array_cast;
+use common_datavalues::DataArrayRef;
+use common_datavalues::DataType;
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+// Type oids of pg_type.
+pub const BOOL_OID: i32 = 16;
+pub const BYTEA_OID: i32 = 17;
+pub const INT8_OID: i32 = 20;
+pub const INT2_OID: i32 = 21;
+pub const INT4_OID: i32 = 23;
+pub const TEXT_OID: i32 = 25;
+pub const FLOAT4_OID: i32 = 700;
+pub const FLOAT8_OID: i32 = 701;
+pub const DATE_OID: i32 = 1082;
+pub const TIMESTAMP_OID: i32 = 1114;
+pub const NUMERIC_OID: i32 = 1700;
+
+pub const TEXT_FORMAT: i16 = 0;
+pub const BINARY_FORMAT: i16 = 1;
+
+// The binary date and timestamp values count from 2000-01-01.
+const POSTGRES_EPOCH_DAYS: i64 = 10957;
+const POSTGRES_EPOCH_MICROS: i64 = POSTGRES_EPOCH_DAYS * 86_400_000_000;
+
+/// The type oid and the type size (-1 for variable length) of an arrow type.
+/// Types without a Postgres counterpart are sent as text.
+pub fn postgres_type(data_type: &DataType) -> (i32, i16) {
+    match data_type {
+        DataType::Boolean => (BOOL_OID, 1),
+        DataType::Int8 | DataType::UInt8 | DataType::Int16 => (INT2_OID, 2),
+        DataType::UInt16 | DataType::Int32 => (INT4_OID, 4),
+        DataType::UInt32 | DataType::Int64 => (INT8_OID, 8),
+        DataType::UInt64 | DataType::Decimal(_, _) => (NUMERIC_OID, -1),
+        DataType::Float16 | DataType::Float32 => (FLOAT4_OID, 4),
+        DataType::Float64 => (FLOAT8_OID, 8),
+        DataType::Binary | DataType::LargeBinary => (BYTEA_OID, -1),
+        DataType::Date32 => (DATE_OID, 4),
+        DataType::Date64 | DataType::Timestamp(_, _) => (TIMESTAMP_OID, 8),
+        DataType::Dictionary(_, value_type) => postgres_type(value_type),
+        _ => (TEXT_OID, -1),
+    }
+}
+
+/// Casts the arrays which have no encoder of their own.
+pub fn prepare_array(array: &DataArrayRef) -> Result<DataArrayRef> {
+    match array.data_type() {
+        DataType::Float16 => data_array_cast(array, &DataType::Float32),
+        DataType::Dictionary(_, value_type) => data_array_cast(array, value_type),
+        _ => Ok(array.clone()),
+    }
+}
+
+/// Encodes a value in the text (0) or binary (1) format, None for NULL.
+pub fn encode_value(array: &DataArrayRef, row: usize, format: i16) -> Result<Option<Vec<u8>>> {
+    if array.is_null(row) {
+        return Ok(None);
+    }
+    if format == BINARY_FORMAT {
+        encode_binary(array, row).map(Some)
+    } else {
+        encode_text(array, row).map(|v| Some(v.into_bytes()))
+    }
+}
+
+fn encode_text(array: &DataArrayRef, row: usize) -> Result<String> {
+    Ok(match array.data_type() {
+        DataType::Boolean => {
+            let text = if as_boolean_array(array).value(row) {
+                "t"
+            } else {
+                "f"
+            };
+            text.to_string()
+        }
+        DataType::Float32 => {
+            let value = as_primitive_array::<Float32Type>(array).value(row);
+            float_text(array, row, value as f64)?
+        }
+        DataType::Float64 => {
+            let value = as_primitive_array::<Float64Type>(array).value(row);
+            float_text(array, row, value)?
+        }
+        DataType::Binary => hex_text(binary_value::<i32>(array, row)),
+        DataType::LargeBinary => hex_text(binary_value::<i64>(array, row)),
+        DataType::Date64 | DataType::Timestamp(_, _) => {
+            let micros = timestamp_micros(array, row);
+            let time = NaiveDateTime::from_timestamp(
+                micros.div_euclid(1_000_000),
+                (micros.rem_euclid(1_000_000) * 1000) as u32,
+            );
+            time.format("%Y-%m-%d %H:%M:%S%.f").to_string()
+        }
+        _ => array_value_to_string(array, row)?,
+    })
+}
+
+fn encode_binary(array: &DataArrayRef, row: usize) -> Result<Vec<u8>> {
+    Ok(match array.data_type() {
+        DataType::Boolean => vec![as_boolean_array(array).value(row) as u8],
+        DataType::Int8 => (as_primitive_array::<Int8Type>(array).value(row) as i16)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::UInt8 => (as_primitive_array::<UInt8Type>(array).value(row) as i16)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::Int16 => as_primitive_array::<Int16Type>(array)
+            .value(row)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::UInt16 => (as_primitive_array::<UInt16Type>(array).value(row) as i32)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::Int32 => as_primitive_array::<Int32Type>(array)
+            .value(row)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::UInt32 => (as_primitive_array::<UInt32Type>(array).value(row) as i64)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::Int64 => as_primitive_array::<Int64Type>(array)
+            .value(row)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::UInt64 | DataType::Decimal(_, _) => {
+            encode_numeric(&array_value_to_string(array, row)?)?
+        }
+        DataType::Float32 => as_primitive_array::<Float32Type>(array)
+            .value(row)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::Float64 => as_primitive_array::<Float64Type>(array)
+            .value(row)
+            .to_be_bytes()
+            .to_vec(),
+        DataType::Binary => binary_value::<i32>(array, row).to_vec(),
+        DataType::LargeBinary => binary_value::<i64>(array, row).to_vec(),
+        DataType::Date32 => {
+            let days = as_primitive_array::<Date32Type>(array).value(row) as i64;
+            ((days - POSTGRES_EPOCH_DAYS) as i32).to_be_bytes().to_vec()
+        }
+        DataType::Date64 | DataType::Timestamp(_, _) => (timestamp_micros(array, row)
+            - POSTGRES_EPOCH_MICROS)
+            .to_be_bytes()
+            .to_vec(),
+        // Sent as text, the binary format of text is the UTF-8 bytes.
+        _ => encode_text(array, row)?.into_bytes(),
+    })
+}
+
+// Microseconds since UNIX epoch.
+fn timestamp_micros(array: &DataArrayRef, row: usize) -> i64 {
+    match array.data_type() {
+        DataType::Date64 => as_primitive_array::<Date64Type>(array).value(row) * 1_000,
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            as_primitive_array::<TimestampSecondType>(array).value(row) * 1_000_000
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            as_primitive_array::<TimestampMillisecondType>(array).value(row) * 1_000
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            as_primitive_array::<TimestampMicrosecondType>(array).value(row)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            as_primitive_array::<TimestampNanosecondType>(array).value(row) / 1_000
+        }
+        _ => unreachable!("Not a timestamp array"),
+    }
+}
+
+// Postgres spells the special float values differently.
+fn float_text(array: &DataArrayRef, row: usize, value: f64) -> Result<String> {
+    if value.is_nan() {
+        Ok("NaN".to_string())
+    } else if value.is_infinite() && value > 0.0 {
+        Ok("Infinity".to_string())
+    } else if value.is_infinite() {
+        Ok("-Infinity".to_string())
+    } else {
+        Ok(array_value_to_string(array, row)?)
+    }
+}
+
+fn binary_value<T: BinaryOffsetSizeTrait>(array: &DataArrayRef, row: usize) -> &[u8] {
+    array
+        .as_any()
+        .downcast_ref::<GenericBinaryArray<T>>()
+        .expect("Unable to downcast to BinaryArray")
+        .value(row)
+}
+
+fn hex_text(value: &[u8]) -> String {
+    let mut text = String::with_capacity(2 + value.len() * 2);
+    text.push_str("\\x");
+    for b in value {
+        text.push_str(&format!("{:02x}", b));
+    }
+    text
+}
+
+/// Encodes a decimal string like `-12.50` in the binary numeric format:
+/// the digits are in base 10000, the weight is the exponent of the first one.
+// Splits a decimal string `[-]digits[.digits]` into its sign, integer and fraction digits.
+fn split_decimal(value: &str) -> Option<(bool, &str, &str)> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value),
+    };
+    let (integer, fraction) = match value.split_once('.') {
+        Some((integer, fraction)) => (integer, fraction),
+        None => (value, ""),
+    };
+    if (integer.is_empty() && fraction.is_empty())
+        || !integer
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    Some((negative, integer, fraction))
+}
+
+pub fn encode_numeric(value: &str) -> Result<Vec<u8>> {
+    let (negative, integer, fraction) = split_decimal(value)
+        .ok_or_else(|| ErrorCode::BadDataValueType(format!("Invalid numeric value: {}", value)))?;
+
+    let integer = integer.trim_start_matches('0');
+    let integer = format!("{}{}", "0".repeat((4 - integer.len() % 4) % 4), integer);
+    let fraction_padded = format!("{}{}", fraction, "0".repeat((4 - fraction.len() % 4) % 4));
+
+    let group = |s: &str| -> Vec<i16> {
+        s.as_bytes()
+            .chunks(4)
+            .map(|chunk| std::str::from_utf8(chunk).unwrap().parse().unwrap())
+            .collect()
+    };
+    let mut digits = group(&integer);
+    let mut weight = digits.len() as i16 - 1;
+    digits.extend(group(&fraction_padded));
+
+    let leading_zeros = digits.iter().take_while(|d| **d == 0).count();
+    digits.drain(..leading_zeros);
+    weight -= leading_zeros as i16;
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    if digits.is_empty() {
+        weight = 0;
+    }
+
+    let sign: u16 = if negative && !digits.is_empty() {
+        0x4000
+    } else {
+        0
+    };
+    let mut buf = Vec::with_capacity(8 + digits.len() * 2);
+    buf.extend_from_slice(&(digits.len() as i16).to_be_bytes());
+    buf.extend_from_slice(&weight.to_be_bytes());
+    buf.extend_from_slice(&sign.to_be_bytes());
+    buf.extend_from_slice(&(fraction.len() as i16).to_be_bytes());
+    for digit in digits {
+        buf.extend_from_slice(&digit.to_be_bytes());
+    }
+    Ok(buf)
+}
+
+/// The number of `$n` placeholders a query needs.
+pub fn count_parameters(query: &str) -> Result<usize> {
+    let mut count = 0;
+    replace_placeholders(query, |index| {
+        count = count.max(index);
+        Ok(String::new())
+    })?;
+    Ok(count)
+}
+
+/// Replaces the `$n` placeholders of a query by the literals of the bound parameters,
+/// the SQL parser has no placeholders.
+pub fn bind_parameters(
+    query: &str,
+    param_types: &[i32],
+    param_formats: &[i16],
+    params: &[Option<Vec<u8>>],
+) -> Result<String> {
+    replace_placeholders(query, |index| {
+        let value = params
+            .get(index - 1)
+            .ok_or_else(|| ErrorCode::BadArguments(format!("There is no parameter ${}", index)))?;
+        // No format code means text, one applies to all the parameters.
+        let format = match param_formats.len() {
+            0 => TEXT_FORMAT,
+            1 => param_formats[0],
+            _ => param_formats.get(index - 1).copied().unwrap_or(TEXT_FORMAT),
+        };
+        let oid = param_types.get(index - 1).copied().unwrap_or(0);
+        parameter_literal(value.as_deref(), oid, format)
+    })
+}
+
+fn replace_placeholders(query: &str, mut f: impl FnMut(usize) -> Result<String>) -> Result<String> {
+    let mut output = String::with_capacity(query.len());
+    let mut quote = None;
+    let mut chars = query.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (None, '\'') | (None, '"') | (None, '`') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '$') if chars.peek().map(|c| c.is_ascii_digit()).unwrap_or(false) => {
+                let mut index = 0usize;
+                while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                    index = index * 10 + digit as usize;
+                    chars.next();
+                }
+                if index == 0 {
+                    return Err(ErrorCode::BadArguments("Parameter index starts from $1"));
+                }
+                output.push_str(&f(index)?);
+                continue;
+            }
+            _ => {}
+        }
+        output.push(c);
+    }
+    Ok(output)
+}
+
+fn parameter_literal(value: Option<&[u8]>, oid: i32, format: i16) -> Result<String> {
+    let value = match value {
+        Some(value) => value,
+        None => return Ok("NULL".to_string()),
+    };
+    let text = if format == BINARY_FORMAT {
+        decode_binary_parameter(value, oid)?
+    } else {
+        String::from_utf8(value.to_vec()).map_err(ErrorCode::from_std_error)?
+    };
+
+    match oid {
+        // Numbers are parsed and inlined re-formatted, so they can't smuggle anything else.
+        // A negative number is parenthesized, `a-$1` must not become the comment `a--1`.
+        INT2_OID | INT4_OID | INT8_OID => match text.trim().parse::<i64>() {
+            Ok(value) if value < 0 => Ok(format!("({})", value)),
+            Ok(value) => Ok(value.to_string()),
+            Err(_) => Err(invalid_number(&text)),
+        },
+        // A decimal is inlined verbatim, parsing it as a float would round its digits.
+        NUMERIC_OID => match split_decimal(text.trim()) {
+            Some((true, _, _)) => Ok(format!("({})", text.trim())),
+            Some(_) => Ok(text.trim().to_string()),
+            None => Err(invalid_number(&text)),
+        },
+        FLOAT4_OID | FLOAT8_OID => match text.trim().parse::<f64>() {
+            Ok(value) if !value.is_finite() => Err(invalid_number(&text)),
+            Ok(value) if value < 0.0 => Ok(format!("({:?})", value)),
+            Ok(value) => Ok(format!("{:?}", value)),
+            Err(_) => Err(invalid_number(&text)),
+        },
+        BOOL_OID => match text.to_lowercase().as_str() {
+            "t" | "true" | "1" => Ok("true".to_string()),
+            "f" | "false" | "0" => Ok("false".to_string()),
+            _ => Err(ErrorCode::BadDataValueType(format!(
+                "Invalid boolean parameter: {}",
+                text
+            ))),
+        },
+        _ => Ok(format!("'{}'", text.replace('\'', "''"))),
+    }
+}
+
+fn invalid_number(text: &str) -> ErrorCode {
+    ErrorCode::BadDataValueType(format!("Invalid number parameter: {}", text))
+}
+
+fn decode_binary_parameter(value: &[u8], oid: i32) -> Result<String> {
+    let bad_length = || {
+        ErrorCode::BadBytes(format!(
+            "Invalid binary parameter length {} for type {}",
+            value.len(),
+            oid
+        ))
+    };
+    Ok(match oid {
+        BOOL_OID => match value {
+            [v] => (*v != 0).to_string(),
+            _ => return Err(bad_length()),
+        },
+        INT2_OID => i16::from_be_bytes(value.try_into().map_err(|_| bad_length())?).to_string(),
+        INT4_OID => i32::from_be_bytes(value.try_into().map_err(|_| bad_length())?).to_string(),
+        INT8_OID => i64::from_be_bytes(value.try_into().map_err(|_| bad_length())?).to_string(),
+        FLOAT4_OID => f32::from_be_bytes(value.try_into().map_err(|_| bad_length())?).to_string(),
+        FLOAT8_OID => f64::from_be_bytes(value.try_into().map_err(|_| bad_length())?).to_string(),
+        _ => String::from_utf8(value.to_vec()).map_err(ErrorCode::from_std_error)?,
+    })
+}
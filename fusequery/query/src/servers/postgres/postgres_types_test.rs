@@ -0,0 +1,199 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+#[test]
+fn test_encode_value() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_arrow::arrow::array::*;
+    use common_datavalues::*;
+    use pretty_assertions::assert_eq;
+
+    use crate::servers::postgres::postgres_types::*;
+
+    struct Test {
+        name: &'static str,
+        array: DataArrayRef,
+        oid: i32,
+        text: Vec<Option<&'static str>>,
+        binary: Vec<Option<Vec<u8>>>,
+    }
+
+    let tests = vec![
+        Test {
+            name: "bool",
+            array: Arc::new(BooleanArray::from(vec![Some(true), None])),
+            oid: BOOL_OID,
+            text: vec![Some("t"), None],
+            binary: vec![Some(vec![1]), None],
+        },
+        Test {
+            name: "uint8-as-int2",
+            array: Arc::new(UInt8Array::from(vec![255])),
+            oid: INT2_OID,
+            text: vec![Some("255")],
+            binary: vec![Some(vec![0, 255])],
+        },
+        Test {
+            name: "int64",
+            array: Arc::new(Int64Array::from(vec![-2])),
+            oid: INT8_OID,
+            text: vec![Some("-2")],
+            binary: vec![Some((-2i64).to_be_bytes().to_vec())],
+        },
+        Test {
+            name: "uint64-as-numeric",
+            array: Arc::new(UInt64Array::from(vec![10001])),
+            oid: NUMERIC_OID,
+            text: vec![Some("10001")],
+            binary: vec![Some(vec![0, 2, 0, 1, 0, 0, 0, 0, 0, 1, 0, 1])],
+        },
+        Test {
+            name: "float64",
+            array: Arc::new(Float64Array::from(vec![0.5, f64::INFINITY])),
+            oid: FLOAT8_OID,
+            text: vec![Some("0.5"), Some("Infinity")],
+            binary: vec![
+                Some(0.5f64.to_be_bytes().to_vec()),
+                Some(f64::INFINITY.to_be_bytes().to_vec()),
+            ],
+        },
+        Test {
+            name: "utf8",
+            array: Arc::new(StringArray::from(vec!["abc"])),
+            oid: TEXT_OID,
+            text: vec![Some("abc")],
+            binary: vec![Some(b"abc".to_vec())],
+        },
+        Test {
+            name: "binary",
+            array: Arc::new(BinaryArray::from(vec![b"\x01\xff".as_ref()])),
+            oid: BYTEA_OID,
+            text: vec![Some("\\x01ff")],
+            binary: vec![Some(vec![1, 255])],
+        },
+        Test {
+            name: "date32",
+            array: Arc::new(Date32Array::from(vec![10958])),
+            oid: DATE_OID,
+            text: vec![Some("2000-01-02")],
+            binary: vec![Some(1i32.to_be_bytes().to_vec())],
+        },
+        Test {
+            name: "date64",
+            array: Arc::new(Date64Array::from(vec![946684800500])),
+            oid: TIMESTAMP_OID,
+            text: vec![Some("2000-01-01 00:00:00.500")],
+            binary: vec![Some(500_000i64.to_be_bytes().to_vec())],
+        },
+    ];
+
+    for test in tests {
+        let array = prepare_array(&test.array)?;
+        assert_eq!(
+            test.oid,
+            postgres_type(array.data_type()).0,
+            "{}",
+            test.name
+        );
+        for row in 0..array.len() {
+            let text = encode_value(&array, row, TEXT_FORMAT)?;
+            let expect = test.text[row].map(|v| v.as_bytes().to_vec());
+            assert_eq!(expect, text, "{}", test.name);
+
+            let binary = encode_value(&array, row, BINARY_FORMAT)?;
+            assert_eq!(test.binary[row], binary, "{}", test.name);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_encode_numeric() -> anyhow::Result<()> {
+    use pretty_assertions::assert_eq;
+
+    use crate::servers::postgres::postgres_types::encode_numeric;
+
+    // ndigits, weight, sign, dscale, digits.
+    assert_eq!(encode_numeric("0")?, vec![0, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(encode_numeric("-12.50")?, vec![
+        0, 2, 0, 0, 0x40, 0, 0, 2, 0, 12, 19, 136
+    ]);
+    assert_eq!(encode_numeric("0.00005")?, vec![
+        0, 1, 255, 254, 0, 0, 0, 5, 19, 136
+    ]);
+    assert!(encode_numeric("1e5").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_bind_parameters() -> anyhow::Result<()> {
+    use pretty_assertions::assert_eq;
+
+    use crate::servers::postgres::postgres_types::*;
+
+    let query = "SELECT * FROM t WHERE a = $1 AND b = $2 AND c = '$3' AND d = $1";
+    assert_eq!(count_parameters(query)?, 2);
+
+    // Text parameters.
+    let params = vec![Some(b"1".to_vec()), Some(b"it's".to_vec())];
+    let actual = bind_parameters(query, &[INT8_OID], &[], &params)?;
+    assert_eq!(
+        actual,
+        "SELECT * FROM t WHERE a = 1 AND b = 'it''s' AND c = '$3' AND d = 1"
+    );
+
+    // Binary parameters, NULL.
+    let params = vec![Some(7i32.to_be_bytes().to_vec()), None];
+    let actual = bind_parameters(query, &[INT4_OID, TEXT_OID], &[1], &params)?;
+    assert_eq!(
+        actual,
+        "SELECT * FROM t WHERE a = 7 AND b = NULL AND c = '$3' AND d = 7"
+    );
+
+    // A number parameter can't carry anything else.
+    for value in ["1 OR 1=1", "1--", "1-1", "1e5", ""].iter() {
+        let params = vec![Some(value.as_bytes().to_vec()), None];
+        let actual = bind_parameters(query, &[INT8_OID], &[], &params);
+        assert!(actual.is_err(), "{}", value);
+    }
+    for value in ["1--", "1-1", "NaN", "inf"].iter() {
+        let params = vec![Some(value.as_bytes().to_vec()), None];
+        let actual = bind_parameters(query, &[FLOAT8_OID], &[], &params);
+        assert!(actual.is_err(), "{}", value);
+    }
+
+    // The numbers are re-formatted, the negative ones can't start a comment.
+    let query = "SELECT a-$1, b-$2";
+    let params = vec![Some(b"-1".to_vec()), Some(b" -2.5e0".to_vec())];
+    let actual = bind_parameters(query, &[INT8_OID, FLOAT8_OID], &[], &params)?;
+    assert_eq!(actual, "SELECT a-(-1), b-(-2.5)");
+
+    // The decimals are inlined verbatim, without the rounding of a float.
+    let params = vec![
+        Some(b"12345678901234567890.123456789".to_vec()),
+        Some(b"-0.10".to_vec()),
+    ];
+    let actual = bind_parameters(query, &[NUMERIC_OID, NUMERIC_OID], &[], &params)?;
+    assert_eq!(actual, "SELECT a-12345678901234567890.123456789, b-(-0.10)");
+    for value in ["1--", "1-1", "1e5", "NaN", "1.2.3", "-", ""].iter() {
+        let params = vec![Some(value.as_bytes().to_vec()), None];
+        let actual = bind_parameters(query, &[NUMERIC_OID], &[], &params);
+        assert!(actual.is_err(), "{}", value);
+    }
+
+    // Missing parameter.
+    let actual = bind_parameters(query, &[], &[], &[None]);
+    assert!(actual.is_err());
+    if let Err(e) = actual {
+        assert_eq!(
+            "Code: 6, displayText = There is no parameter $2.",
+            e.to_string()
+        );
+    }
+
+    Ok(())
+}
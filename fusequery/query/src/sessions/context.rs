@@ -4,7 +4,11 @@
 
 use std::collections::VecDeque;
 use std::future::Future;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 
 use common_exception::ErrorCode;
 use common_exception::Result;
@@ -16,6 +20,7 @@ use common_progress::Progress;
 use common_progress::ProgressCallback;
 use common_progress::ProgressValues;
 use common_runtime::Runtime;
+use common_streams::AbortCallback;
 use tokio::task::JoinHandle;
 use uuid::Uuid;
 
@@ -24,6 +29,14 @@ use crate::clusters::ClusterRef;
 use crate::datasources::DataSource;
 use crate::datasources::Table;
 use crate::datasources::TableFunction;
+use crate::pipelines::processors::PipelineExecutor;
+use crate::pipelines::processors::PipelineExecutorRef;
+use crate::sessions::MemoryTracker;
+use crate::sessions::MemoryTrackerRef;
+use crate::sessions::ProcessList;
+use crate::sessions::ProcessListRef;
+use crate::sessions::QueryCache;
+use crate::sessions::QueryCacheRef;
 use crate::sessions::Settings;
 
 #[derive(Clone)]
@@ -37,6 +50,12 @@ pub struct FuseQueryContext {
     current_database: Arc<RwLock<String>>,
     progress: Arc<Progress>,
     runtime: Arc<RwLock<Runtime>>,
+    query_cache: Arc<RwLock<QueryCacheRef>>,
+    process_list: Arc<RwLock<ProcessListRef>>,
+    memory_tracker: Arc<RwLock<MemoryTrackerRef>>,
+    pipeline_executor: Arc<RwLock<Option<PipelineExecutorRef>>>,
+    cancelled: Arc<AtomicBool>,
+    start_time: Arc<RwLock<Instant>>,
     version: String,
 }
 
@@ -45,6 +64,9 @@ pub type FuseQueryContextRef = Arc<FuseQueryContext>;
 impl FuseQueryContext {
     pub fn try_create() -> Result<FuseQueryContextRef> {
         let settings = Settings::try_create()?;
+        let process_list = ProcessList::create(0);
+        let memory_tracker =
+            MemoryTracker::create_child("query", 0, process_list.get_memory_tracker());
         let ctx = FuseQueryContext {
             uuid: Arc::new(RwLock::new(Uuid::new_v4().to_string())),
             settings: settings.clone(),
@@ -57,6 +79,14 @@ impl FuseQueryContext {
             runtime: Arc::new(RwLock::new(Runtime::with_worker_threads(
                 settings.get_max_threads()? as usize,
             )?)),
+            query_cache: Arc::new(RwLock::new(QueryCache::create(
+                process_list.get_memory_tracker(),
+            ))),
+            process_list: Arc::new(RwLock::new(process_list)),
+            memory_tracker: Arc::new(RwLock::new(memory_tracker)),
+            pipeline_executor: Arc::new(RwLock::new(None)),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            start_time: Arc::new(RwLock::new(Instant::now())),
             version: format!(
                 "FuseQuery v-{}",
                 *crate::configs::config::FUSE_COMMIT_VERSION
@@ -71,14 +101,49 @@ impl FuseQueryContext {
         Ok(Arc::new(self.clone()))
     }
 
+    pub fn with_query_cache(&self, query_cache: QueryCacheRef) -> Result<FuseQueryContextRef> {
+        *self.query_cache.write() = query_cache;
+        Ok(Arc::new(self.clone()))
+    }
+
+    /// Registers the context in the process list of the server,
+    /// the memory of the context is accounted to the server-wide tracker.
+    pub fn with_process_list(&self, process_list: ProcessListRef) -> Result<FuseQueryContextRef> {
+        *self.memory_tracker.write() =
+            MemoryTracker::create_child("query", 0, process_list.get_memory_tracker());
+        *self.process_list.write() = process_list.clone();
+
+        let ctx = Arc::new(self.clone());
+        process_list.insert(&ctx);
+        Ok(ctx)
+    }
+
+    /// Shares the pipeline executor of the server with the context.
+    pub fn with_pipeline_executor(
+        &self,
+        executor: PipelineExecutorRef,
+    ) -> Result<FuseQueryContextRef> {
+        *self.pipeline_executor.write() = Some(executor);
+        Ok(Arc::new(self.clone()))
+    }
+
     pub fn with_id(&self, uuid: &str) -> Result<FuseQueryContextRef> {
         *self.uuid.write() = uuid.to_string();
         Ok(Arc::new(self.clone()))
     }
 
+    /// Sets the start of the running query, the max_execution_time counts from it.
+    pub fn with_start_time(&self, start_time: Instant) -> Result<FuseQueryContextRef> {
+        *self.start_time.write() = start_time;
+        Ok(Arc::new(self.clone()))
+    }
+
     /// ctx.reset will reset the necessary variables in the session
     pub fn reset(&self) -> Result<()> {
         self.progress.reset();
+        self.memory_tracker.read().reset_peak();
+        self.cancelled.store(false, Ordering::Relaxed);
+        *self.start_time.write() = Instant::now();
         self.statistics.write().clear();
         self.partition_queue.write().clear();
         Ok(())
@@ -104,6 +169,45 @@ impl FuseQueryContext {
         }))
     }
 
+    /// Cancels the running query, the sources fail at their next block.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Fails if the query is cancelled or runs longer than the max_execution_time setting.
+    pub fn check_cancelled(&self) -> Result<()> {
+        check_cancelled(
+            &self.get_id(),
+            &self.cancelled,
+            &self.start_time,
+            &self.settings,
+        )
+    }
+
+    /// The callback checked by the sources before every block, like the progress callback.
+    pub fn abort_callback(&self) -> Result<AbortCallback> {
+        let id = self.get_id();
+        let cancelled = self.cancelled.clone();
+        let start_time = self.start_time.clone();
+        let settings = self.settings.clone();
+        Ok(Box::new(move || {
+            check_cancelled(&id, &cancelled, &start_time, &settings)
+        }))
+    }
+
+    /// The instant the running query times out, None without max_execution_time.
+    pub fn get_deadline(&self) -> Result<Option<Instant>> {
+        let max_execution_time = self.settings.get_max_execution_time()?;
+        Ok(match max_execution_time {
+            0 => None,
+            _ => Some(*self.start_time.read() + Duration::from_secs(max_execution_time)),
+        })
+    }
+
     pub fn get_progress_value(&self) -> ProgressValues {
         self.progress.as_ref().get_values()
     }
@@ -221,6 +325,35 @@ impl FuseQueryContext {
     pub fn get_settings(&self) -> Arc<Settings> {
         self.settings.clone()
     }
+
+    pub fn get_query_cache(&self) -> QueryCacheRef {
+        self.query_cache.read().clone()
+    }
+
+    pub fn get_process_list(&self) -> ProcessListRef {
+        self.process_list.read().clone()
+    }
+
+    /// The executor driving the processors of the query, a context without
+    /// the executor of the server uses the default executor of the process.
+    pub fn try_get_pipeline_executor(&self) -> Result<PipelineExecutorRef> {
+        let mut executor = self.pipeline_executor.write();
+        match &*executor {
+            Some(executor) => Ok(executor.clone()),
+            None => {
+                let default = PipelineExecutor::try_get_default()?;
+                *executor = Some(default.clone());
+                Ok(default)
+            }
+        }
+    }
+
+    /// The memory tracker of the query, limited by the max_memory_usage setting.
+    pub fn try_get_memory_tracker(&self) -> Result<MemoryTrackerRef> {
+        let tracker = self.memory_tracker.read().clone();
+        tracker.set_limit(self.settings.get_max_memory_usage()?);
+        Ok(tracker)
+    }
 }
 
 impl std::fmt::Debug for FuseQueryContext {
@@ -228,3 +361,30 @@ impl std::fmt::Debug for FuseQueryContext {
         write!(f, "{:?}", self.settings)
     }
 }
+
+fn check_cancelled(
+    id: &str,
+    cancelled: &AtomicBool,
+    start_time: &RwLock<Instant>,
+    settings: &Settings,
+) -> Result<()> {
+    if cancelled.load(Ordering::Relaxed) {
+        return Err(ErrorCode::AbortedQuery(format!(
+            "Query {} was cancelled",
+            id
+        )));
+    }
+
+    let max_execution_time = settings.get_max_execution_time()?;
+    let elapsed = start_time.read().elapsed();
+    if max_execution_time > 0 && elapsed >= Duration::from_secs(max_execution_time) {
+        // The other sources of the query stop at their next block.
+        cancelled.store(true, Ordering::Relaxed);
+        return Err(ErrorCode::Timeout(format!(
+            "Timeout exceeded: elapsed {:.3} seconds, maximum: {} seconds",
+            elapsed.as_secs_f64(),
+            max_execution_time
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,139 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+
+/// Tracks the bytes of the blocks and the states buffered by a query, or by the whole server
+/// as the parent of the query trackers. The limit 0 is unlimited.
+pub struct MemoryTracker {
+    name: &'static str,
+    limit: AtomicU64,
+    usage: AtomicU64,
+    peak: AtomicU64,
+    parent: Option<MemoryTrackerRef>,
+}
+
+pub type MemoryTrackerRef = Arc<MemoryTracker>;
+
+impl MemoryTracker {
+    pub fn create(name: &'static str, limit: u64) -> MemoryTrackerRef {
+        Arc::new(MemoryTracker {
+            name,
+            limit: AtomicU64::new(limit),
+            usage: AtomicU64::new(0),
+            peak: AtomicU64::new(0),
+            parent: None,
+        })
+    }
+
+    /// A tracker whose allocations are also accounted to the parent, and checked against its limit.
+    pub fn create_child(
+        name: &'static str,
+        limit: u64,
+        parent: MemoryTrackerRef,
+    ) -> MemoryTrackerRef {
+        Arc::new(MemoryTracker {
+            name,
+            limit: AtomicU64::new(limit),
+            usage: AtomicU64::new(0),
+            peak: AtomicU64::new(0),
+            parent: Some(parent),
+        })
+    }
+
+    pub fn set_limit(&self, limit: u64) {
+        self.limit.store(limit, Ordering::Relaxed);
+    }
+
+    pub fn get_limit(&self) -> u64 {
+        self.limit.load(Ordering::Relaxed)
+    }
+
+    pub fn get_usage(&self) -> u64 {
+        self.usage.load(Ordering::Relaxed)
+    }
+
+    pub fn get_peak(&self) -> u64 {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    /// The peak starts again from the current usage, at the start of a new query.
+    pub fn reset_peak(&self) {
+        self.peak.store(self.get_usage(), Ordering::Relaxed);
+    }
+
+    /// Accounts the bytes, nothing is accounted if this or any parent exceeds its limit.
+    pub fn alloc(&self, bytes: u64) -> Result<()> {
+        let usage = self.usage.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        let limit = self.get_limit();
+        if limit > 0 && usage > limit {
+            self.usage.fetch_sub(bytes, Ordering::Relaxed);
+            return Err(ErrorCode::MemoryLimitExceeded(format!(
+                "Memory limit (for {}) exceeded: would use {} bytes (attempt to allocate {} bytes), maximum: {} bytes",
+                self.name, usage, bytes, limit
+            )));
+        }
+
+        if let Some(parent) = &self.parent {
+            if let Err(cause) = parent.alloc(bytes) {
+                self.usage.fetch_sub(bytes, Ordering::Relaxed);
+                return Err(cause);
+            }
+        }
+
+        self.peak.fetch_max(usage, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn free(&self, bytes: u64) {
+        let _ = self
+            .usage
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |usage| {
+                Some(usage.saturating_sub(bytes))
+            });
+
+        if let Some(parent) = &self.parent {
+            parent.free(bytes);
+        }
+    }
+}
+
+/// The bytes reserved from a tracker by a transform, released when it is dropped.
+pub struct MemoryReservation {
+    tracker: MemoryTrackerRef,
+    bytes: u64,
+}
+
+impl MemoryReservation {
+    pub fn create(tracker: MemoryTrackerRef) -> Self {
+        MemoryReservation { tracker, bytes: 0 }
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Grows or shrinks the reservation to the bytes, it is unchanged if the limit is exceeded.
+    pub fn resize(&mut self, bytes: usize) -> Result<()> {
+        let bytes = bytes as u64;
+        if bytes > self.bytes {
+            self.tracker.alloc(bytes - self.bytes)?;
+        } else {
+            self.tracker.free(self.bytes - bytes);
+        }
+        self.bytes = bytes;
+        Ok(())
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.tracker.free(self.bytes);
+    }
+}
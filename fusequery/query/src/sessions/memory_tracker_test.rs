@@ -0,0 +1,87 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use common_exception::Result;
+use pretty_assertions::assert_eq;
+
+use crate::sessions::MemoryReservation;
+use crate::sessions::MemoryTracker;
+
+#[test]
+fn test_memory_tracker_limit() -> Result<()> {
+    let tracker = MemoryTracker::create("query", 100);
+    tracker.alloc(60)?;
+    tracker.alloc(40)?;
+    assert_eq!(tracker.get_usage(), 100);
+
+    let result = tracker.alloc(1);
+    let error = result.unwrap_err();
+    assert_eq!(error.code(), 42);
+    assert_eq!(
+        error.message(),
+        "Memory limit (for query) exceeded: would use 101 bytes (attempt to allocate 1 bytes), maximum: 100 bytes"
+    );
+    assert_eq!(tracker.get_usage(), 100);
+
+    tracker.free(70);
+    tracker.alloc(1)?;
+    assert_eq!(tracker.get_usage(), 31);
+    assert_eq!(tracker.get_peak(), 100);
+
+    tracker.reset_peak();
+    assert_eq!(tracker.get_peak(), 31);
+
+    // The limit 0 is unlimited.
+    tracker.set_limit(0);
+    tracker.alloc(u32::MAX as u64)?;
+
+    // Freeing more than the usage saturates.
+    tracker.free(u64::MAX);
+    assert_eq!(tracker.get_usage(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_memory_tracker_parent_limit() -> Result<()> {
+    let server = MemoryTracker::create("server", 100);
+    let query1 = MemoryTracker::create_child("query", 0, server.clone());
+    let query2 = MemoryTracker::create_child("query", 0, server.clone());
+
+    query1.alloc(80)?;
+    let error = query2.alloc(30).unwrap_err();
+    assert_eq!(error.code(), 42);
+    assert!(error.message().contains("(for server)"));
+    assert_eq!(query2.get_usage(), 0);
+    assert_eq!(server.get_usage(), 80);
+
+    query1.free(80);
+    query2.alloc(30)?;
+    assert_eq!(server.get_usage(), 30);
+    assert_eq!(server.get_peak(), 80);
+    Ok(())
+}
+
+#[test]
+fn test_memory_reservation() -> Result<()> {
+    let tracker = MemoryTracker::create("query", 100);
+    {
+        let mut reservation = MemoryReservation::create(tracker.clone());
+        reservation.resize(60)?;
+        assert_eq!(tracker.get_usage(), 60);
+
+        reservation.resize(90)?;
+        assert_eq!(tracker.get_usage(), 90);
+
+        // The reservation is unchanged past the limit.
+        assert!(reservation.resize(120).is_err());
+        assert_eq!(reservation.bytes(), 90);
+        assert_eq!(tracker.get_usage(), 90);
+
+        reservation.resize(10)?;
+        assert_eq!(tracker.get_usage(), 10);
+    }
+    assert_eq!(tracker.get_usage(), 0);
+    assert_eq!(tracker.get_peak(), 90);
+    Ok(())
+}
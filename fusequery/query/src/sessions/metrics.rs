@@ -4,3 +4,5 @@
 
 pub static METRIC_SESSION_CONNECT_NUMBERS: &str = "session.connect_numbers";
 pub static METRIC_SESSION_CLOSE_NUMBERS: &str = "session.close_numbers";
+pub static METRIC_QUERY_CACHE_HITS: &str = "session.query_cache_hits";
+pub static METRIC_QUERY_CACHE_MISSES: &str = "session.query_cache_misses";
@@ -2,17 +2,37 @@
 //
 // SPDX-License-Identifier: Apache-2.0.
 
+#[cfg(test)]
+mod memory_tracker_test;
+#[cfg(test)]
+mod query_cache_test;
+
 #[macro_use]
 mod macros;
 
 mod context;
+mod memory_tracker;
 mod metrics;
+mod process_list;
+mod query_cache;
 #[allow(clippy::module_inception)]
 mod sessions;
 mod settings;
 
 pub use context::FuseQueryContext;
 pub use context::FuseQueryContextRef;
+pub use memory_tracker::MemoryReservation;
+pub use memory_tracker::MemoryTracker;
+pub use memory_tracker::MemoryTrackerRef;
+pub use process_list::ProcessInfo;
+pub use process_list::ProcessList;
+pub use process_list::ProcessListRef;
+pub use query_cache::CachedTableVersion;
+pub use query_cache::QueryCache;
+pub use query_cache::QueryCacheInfo;
+pub use query_cache::QueryCacheKey;
+pub use query_cache::QueryCacheRef;
+pub use sessions::SessionGuard;
 pub use sessions::SessionManager;
 pub use sessions::SessionManagerRef;
 pub use settings::Settings;
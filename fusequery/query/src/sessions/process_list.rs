@@ -0,0 +1,94 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Weak;
+
+use common_exception::Result;
+use common_infallible::RwLock;
+
+use crate::sessions::FuseQueryContext;
+use crate::sessions::FuseQueryContextRef;
+use crate::sessions::MemoryTracker;
+use crate::sessions::MemoryTrackerRef;
+
+/// The state of a context in system.processes.
+#[derive(Clone, Debug)]
+pub struct ProcessInfo {
+    pub id: String,
+    pub database: String,
+    pub read_rows: usize,
+    pub read_bytes: usize,
+    pub memory_usage: u64,
+    pub peak_memory_usage: u64,
+}
+
+/// The contexts of the server with the server-wide memory tracker,
+/// the parent of the memory trackers of the contexts.
+pub struct ProcessList {
+    memory_tracker: MemoryTrackerRef,
+    contexts: RwLock<HashMap<String, Weak<FuseQueryContext>>>,
+}
+
+pub type ProcessListRef = Arc<ProcessList>;
+
+impl ProcessList {
+    pub fn create(max_server_memory_usage: u64) -> ProcessListRef {
+        Arc::new(ProcessList {
+            memory_tracker: MemoryTracker::create("server", max_server_memory_usage),
+            contexts: RwLock::new(HashMap::new()),
+        })
+    }
+
+    pub fn get_memory_tracker(&self) -> MemoryTrackerRef {
+        self.memory_tracker.clone()
+    }
+
+    pub fn insert(&self, ctx: &FuseQueryContextRef) {
+        self.contexts
+            .write()
+            .insert(ctx.get_id(), Arc::downgrade(ctx));
+    }
+
+    pub fn remove(&self, id: &str) {
+        self.contexts.write().remove(id);
+    }
+
+    /// Logs the query with the rows it read and the peak memory of its context, once it's done.
+    pub fn on_query_done(&self, ctx: &FuseQueryContext, query: &str) {
+        let progress = ctx.get_progress_value();
+        let peak_memory_usage = ctx
+            .try_get_memory_tracker()
+            .map(|tracker| tracker.get_peak())
+            .unwrap_or_default();
+        log::info!(
+            "Query done, id: {}, query: {}, read rows: {}, read bytes: {}, peak memory usage: {} bytes",
+            ctx.get_id(),
+            query,
+            progress.read_rows,
+            progress.read_bytes,
+            peak_memory_usage
+        );
+    }
+
+    pub fn get_infos(&self) -> Result<Vec<ProcessInfo>> {
+        let mut infos = vec![];
+        for ctx in self.contexts.read().values() {
+            if let Some(ctx) = ctx.upgrade() {
+                let progress = ctx.get_progress_value();
+                let tracker = ctx.try_get_memory_tracker()?;
+                infos.push(ProcessInfo {
+                    id: ctx.get_id(),
+                    database: ctx.get_current_database(),
+                    read_rows: progress.read_rows,
+                    read_bytes: progress.read_bytes,
+                    memory_usage: tracker.get_usage(),
+                    peak_memory_usage: tracker.get_peak(),
+                });
+            }
+        }
+        Ok(infos)
+    }
+}
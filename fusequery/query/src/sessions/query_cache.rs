@@ -0,0 +1,292 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use common_datablocks::DataBlock;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_infallible::Mutex;
+use common_infallible::RwLock;
+use common_planners::Expression;
+use common_planners::PlanNode;
+use metrics::counter;
+
+use crate::optimizers::is_deterministic;
+use crate::sessions::FuseQueryContextRef;
+use crate::sessions::MemoryReservation;
+use crate::sessions::MemoryTrackerRef;
+
+/// The version of a table read by a cached query.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedTableVersion {
+    pub db: String,
+    pub table: String,
+    // Changed by the inserts and the DDLs executed on this node.
+    pub generation: u64,
+    // The `ver` of the table in the store metadata, for the remote tables.
+    pub ver: Option<i64>,
+}
+
+/// The key of a cached query result: the optimized plan and the versions of the tables it reads.
+#[derive(Clone, Debug)]
+pub struct QueryCacheKey {
+    plan: String,
+    description: String,
+    tables: Vec<CachedTableVersion>,
+}
+
+impl QueryCacheKey {
+    /// The key of the plan, None if the plan reads the system tables or evaluates
+    /// a non-deterministic function, their results can't be cached.
+    pub async fn try_create(ctx: &FuseQueryContextRef, plan: &PlanNode) -> Result<Option<Self>> {
+        let mut sources = vec![];
+        let mut deterministic = true;
+        plan.walk_preorder(|node| -> Result<bool> {
+            let exprs: Vec<&Expression> = match node {
+                PlanNode::ReadSource(source) => {
+                    sources.push((source.db.clone(), source.table.clone(), source.remote));
+                    source.scan_plan.filters.iter().collect()
+                }
+                PlanNode::Expression(plan) => plan.exprs.iter().collect(),
+                PlanNode::Projection(plan) => plan.expr.iter().collect(),
+                PlanNode::Filter(plan) => vec![&plan.predicate],
+                PlanNode::Having(plan) => vec![&plan.predicate],
+                PlanNode::Sort(plan) => plan.order_by.iter().collect(),
+                PlanNode::LimitBy(plan) => plan.limit_by.iter().collect(),
+                PlanNode::AggregatorPartial(plan) => plan
+                    .aggr_expr
+                    .iter()
+                    .chain(plan.group_expr.iter())
+                    .collect(),
+                PlanNode::AggregatorFinal(plan) => plan
+                    .aggr_expr
+                    .iter()
+                    .chain(plan.group_expr.iter())
+                    .collect(),
+                _ => vec![],
+            };
+            for expr in exprs {
+                deterministic = deterministic && is_deterministic(expr)?;
+            }
+            Ok(true)
+        })?;
+        if !deterministic || sources.iter().any(|(db, _, _)| db == "system") {
+            return Ok(None);
+        }
+        sources.sort();
+        sources.dedup();
+
+        let cache = ctx.get_query_cache();
+        let datasource = ctx.get_datasource();
+        let mut tables = Vec::with_capacity(sources.len());
+        for (db, table, remote) in sources {
+            let ver = if remote {
+                Some(datasource.get_remote_table_version(&db, &table).await?)
+            } else {
+                None
+            };
+            tables.push(CachedTableVersion {
+                generation: cache.generation(&db, &table),
+                db,
+                table,
+                ver,
+            });
+        }
+
+        let key = serde_json::to_string(plan).map_err(|e| {
+            ErrorCode::LogicalError(format!(
+                "Cannot serialize the plan of the query cache: {}",
+                e
+            ))
+        })?;
+        Ok(Some(QueryCacheKey {
+            plan: key,
+            description: format!("{:?}", plan),
+            tables,
+        }))
+    }
+}
+
+struct QueryCacheEntry {
+    description: String,
+    tables: Vec<CachedTableVersion>,
+    blocks: Vec<DataBlock>,
+    bytes: usize,
+    created: Instant,
+    hits: u64,
+    misses: u64,
+}
+
+/// The information of a cached query result, for system.query_cache.
+#[derive(Clone, Debug)]
+pub struct QueryCacheInfo {
+    pub plan: String,
+    pub tables: Vec<String>,
+    pub rows: usize,
+    pub bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub age: Duration,
+}
+
+/// The results of the SELECT queries shared by all the sessions, keyed by the optimized plan.
+/// The results are valid until the TTL expires or any table they read is modified.
+pub struct QueryCache {
+    entries: RwLock<HashMap<String, QueryCacheEntry>>,
+    // The generations of the databases and the tables, keyed by "db" and "db.table".
+    generations: RwLock<HashMap<String, u64>>,
+    // The bytes of the cached blocks, reserved from the server memory tracker.
+    reservation: Mutex<MemoryReservation>,
+}
+
+pub type QueryCacheRef = Arc<QueryCache>;
+
+impl QueryCache {
+    pub fn create(memory_tracker: MemoryTrackerRef) -> QueryCacheRef {
+        Arc::new(QueryCache {
+            entries: RwLock::new(HashMap::new()),
+            generations: RwLock::new(HashMap::new()),
+            reservation: Mutex::new(MemoryReservation::create(memory_tracker)),
+        })
+    }
+
+    pub fn generation(&self, db: &str, table: &str) -> u64 {
+        let generations = self.generations.read();
+        let database = generations.get(db).cloned().unwrap_or(0);
+        let table = generations.get(&format!("{}.{}", db, table));
+        database + table.cloned().unwrap_or(0)
+    }
+
+    /// The cached blocks of the key, if they are not expired and the tables are not modified.
+    pub fn get(&self, key: &QueryCacheKey, ttl: Duration) -> Option<Vec<DataBlock>> {
+        let mut entries = self.entries.write();
+        let entry = match entries.get_mut(&key.plan) {
+            Some(entry) => entry,
+            None => {
+                counter!(super::metrics::METRIC_QUERY_CACHE_MISSES, 1);
+                return None;
+            }
+        };
+
+        if entry.tables != key.tables || entry.created.elapsed() > ttl {
+            entry.misses += 1;
+            counter!(super::metrics::METRIC_QUERY_CACHE_MISSES, 1);
+            return None;
+        }
+        entry.hits += 1;
+        counter!(super::metrics::METRIC_QUERY_CACHE_HITS, 1);
+        Some(entry.blocks.clone())
+    }
+
+    /// Caches the result of the key, the expired and the oldest entries are evicted
+    /// to keep the cache under max_bytes.
+    pub fn put(&self, key: QueryCacheKey, blocks: Vec<DataBlock>, ttl: Duration, max_bytes: usize) {
+        let bytes = blocks
+            .iter()
+            .map(|block| block.memory_size())
+            .sum::<usize>();
+        if bytes > max_bytes {
+            return;
+        }
+
+        // The tables are modified while the query is running, the result may be stale.
+        if key
+            .tables
+            .iter()
+            .any(|t| t.generation != self.generation(&t.db, &t.table))
+        {
+            return;
+        }
+
+        let mut entries = self.entries.write();
+        // The counters are kept for the plan, the first miss is the one creating the entry.
+        let (hits, misses) = match entries.remove(&key.plan) {
+            Some(entry) => (entry.hits, entry.misses),
+            None => (0, 1),
+        };
+        entries.retain(|_, entry| entry.created.elapsed() <= ttl);
+
+        let mut total_bytes = entries.values().map(|entry| entry.bytes).sum::<usize>();
+        while total_bytes + bytes > max_bytes {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.created)
+                .map(|(plan, _)| plan.clone());
+            match oldest.and_then(|plan| entries.remove(&plan)) {
+                Some(entry) => total_bytes -= entry.bytes,
+                None => break,
+            }
+        }
+
+        // The result is not cached if the server is out of memory.
+        if self.reservation.lock().resize(total_bytes + bytes).is_err() {
+            self.release(&entries);
+            return;
+        }
+
+        entries.insert(key.plan, QueryCacheEntry {
+            description: key.description,
+            tables: key.tables,
+            blocks,
+            bytes,
+            created: Instant::now(),
+            hits,
+            misses,
+        });
+    }
+
+    /// Drops the results reading the table, called by the inserts and the DDLs on it.
+    pub fn invalidate_table(&self, db: &str, table: &str) {
+        *self
+            .generations
+            .write()
+            .entry(format!("{}.{}", db, table))
+            .or_insert(0) += 1;
+        let mut entries = self.entries.write();
+        entries.retain(|_, entry| !entry.tables.iter().any(|t| t.db == db && t.table == table));
+        self.release(&entries);
+    }
+
+    /// Drops the results reading any table of the database.
+    pub fn invalidate_database(&self, db: &str) {
+        *self.generations.write().entry(db.to_string()).or_insert(0) += 1;
+        let mut entries = self.entries.write();
+        entries.retain(|_, entry| !entry.tables.iter().any(|t| t.db == db));
+        self.release(&entries);
+    }
+
+    // Shrinks the reservation to the bytes of the entries left.
+    fn release(&self, entries: &HashMap<String, QueryCacheEntry>) {
+        let bytes = entries.values().map(|entry| entry.bytes).sum::<usize>();
+        let mut reservation = self.reservation.lock();
+        if (bytes as u64) < reservation.bytes() {
+            // Shrinking never exceeds the limit.
+            let _ = reservation.resize(bytes);
+        }
+    }
+
+    pub fn get_infos(&self) -> Vec<QueryCacheInfo> {
+        self.entries
+            .read()
+            .values()
+            .map(|entry| QueryCacheInfo {
+                plan: entry.description.clone(),
+                tables: entry
+                    .tables
+                    .iter()
+                    .map(|t| format!("{}.{}", t.db, t.table))
+                    .collect(),
+                rows: entry.blocks.iter().map(|block| block.num_rows()).sum(),
+                bytes: entry.bytes,
+                hits: entry.hits,
+                misses: entry.misses,
+                age: entry.created.elapsed(),
+            })
+            .collect()
+    }
+}
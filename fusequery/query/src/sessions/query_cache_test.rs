@@ -0,0 +1,174 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_datablocks::DataBlock;
+use common_datavalues::DataField;
+use common_datavalues::DataSchemaRefExt;
+use common_datavalues::DataType;
+use common_datavalues::Int64Array;
+use common_planners::PlanNode;
+use futures::TryStreamExt;
+use pretty_assertions::assert_eq;
+
+use crate::interpreters::*;
+use crate::sessions::FuseQueryContextRef;
+use crate::sessions::MemoryTracker;
+use crate::sessions::QueryCache;
+use crate::sessions::QueryCacheKey;
+use crate::sql::PlanParser;
+
+async fn execute(ctx: FuseQueryContextRef, query: &str) -> anyhow::Result<Vec<DataBlock>> {
+    let plan = PlanParser::create(ctx.clone()).build_from_sql(query)?;
+    let executor = InterpreterFactory::get(ctx, plan)?;
+    let stream = executor.execute().await?;
+    Ok(stream.try_collect::<Vec<_>>().await?)
+}
+
+fn select_input(ctx: FuseQueryContextRef, query: &str) -> anyhow::Result<PlanNode> {
+    match PlanParser::create(ctx).build_from_sql(query)? {
+        PlanNode::Select(plan) => Ok(plan.input.as_ref().clone()),
+        _ => anyhow::bail!("expect select plan"),
+    }
+}
+
+fn result_block() -> DataBlock {
+    let schema = DataSchemaRefExt::create(vec![DataField::new("a", DataType::Int64, false)]);
+    DataBlock::create_by_array(schema, vec![Arc::new(Int64Array::from(vec![1, 2, 3]))])
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_query_cache() -> anyhow::Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    execute(
+        ctx.clone(),
+        "create table default.a(a bigint) Engine = Null",
+    )
+    .await?;
+
+    let cache = ctx.get_query_cache();
+    let ttl = Duration::from_secs(60);
+    let plan = select_input(ctx.clone(), "select a from default.a")?;
+    let key = QueryCacheKey::try_create(&ctx, &plan).await?.unwrap();
+
+    // Miss, then hit.
+    assert!(cache.get(&key, ttl).is_none());
+    cache.put(key.clone(), vec![result_block()], ttl, 1024 * 1024);
+    let blocks = cache.get(&key, ttl).unwrap();
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].num_rows(), 3);
+
+    let infos = cache.get_infos();
+    assert_eq!(infos.len(), 1);
+    assert_eq!(infos[0].tables, vec!["default.a"]);
+    assert_eq!(infos[0].rows, 3);
+    assert_eq!(infos[0].hits, 1);
+    assert_eq!(infos[0].misses, 1);
+
+    // The results larger than the limit are not cached.
+    let other = select_input(ctx.clone(), "select a from default.a where a > 1")?;
+    let other_key = QueryCacheKey::try_create(&ctx, &other).await?.unwrap();
+    cache.put(other_key, vec![result_block()], ttl, 1);
+    assert_eq!(cache.get_infos().len(), 1);
+
+    // Invalidated by the modification of the table.
+    cache.invalidate_table("default", "a");
+    assert!(cache.get_infos().is_empty());
+
+    // The result of the query running before the modification is stale.
+    cache.put(key.clone(), vec![result_block()], ttl, 1024 * 1024);
+    assert!(cache.get_infos().is_empty());
+    let key = QueryCacheKey::try_create(&ctx, &plan).await?.unwrap();
+    cache.put(key.clone(), vec![result_block()], ttl, 1024 * 1024);
+    assert!(cache.get(&key, ttl).is_some());
+
+    cache.invalidate_database("default");
+    assert!(cache.get(&key, ttl).is_none());
+
+    // The system tables are not cached.
+    let plan = select_input(ctx.clone(), "select * from system.settings")?;
+    assert!(QueryCacheKey::try_create(&ctx, &plan).await?.is_none());
+
+    // The non-deterministic functions are not cached, wherever they are in the plan.
+    for query in [
+        "select a, sleep(0) from default.a",
+        "select a from default.a where sleep(0) = 0",
+        "select max(a + sleep(0)) from default.a",
+        "select a from default.a order by a + sleep(0)",
+    ]
+    .iter()
+    {
+        let plan = select_input(ctx.clone(), query)?;
+        assert!(
+            QueryCacheKey::try_create(&ctx, &plan).await?.is_none(),
+            "{}",
+            query
+        );
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_query_cache_select() -> anyhow::Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    execute(
+        ctx.clone(),
+        "create table default.a(a bigint) Engine = Null",
+    )
+    .await?;
+
+    // Disabled by default.
+    execute(ctx.clone(), "select a from default.a").await?;
+    assert!(ctx.get_query_cache().get_infos().is_empty());
+
+    ctx.get_settings()
+        .update_settings("use_query_cache", "1".to_string())?;
+    execute(ctx.clone(), "select a from default.a").await?;
+    execute(ctx.clone(), "select a from default.a").await?;
+    let infos = ctx.get_query_cache().get_infos();
+    assert_eq!(infos.len(), 1);
+    assert_eq!(infos[0].hits, 1);
+    assert_eq!(infos[0].misses, 1);
+
+    // Invalidated by the DDL.
+    execute(ctx.clone(), "drop table default.a").await?;
+    assert!(ctx.get_query_cache().get_infos().is_empty());
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_query_cache_memory_tracker() -> anyhow::Result<()> {
+    let ctx = crate::tests::try_create_context()?;
+    execute(
+        ctx.clone(),
+        "create table default.a(a bigint) Engine = Null",
+    )
+    .await?;
+
+    let ttl = Duration::from_secs(60);
+    let plan = select_input(ctx.clone(), "select a from default.a")?;
+    let key = QueryCacheKey::try_create(&ctx, &plan).await?.unwrap();
+    let bytes = result_block().memory_size() as u64;
+
+    // The cached blocks are reserved from the server tracker.
+    let tracker = MemoryTracker::create("server", 0);
+    let cache = QueryCache::create(tracker.clone());
+    cache.put(key.clone(), vec![result_block()], ttl, 1024 * 1024);
+    assert_eq!(tracker.get_usage(), bytes);
+
+    // Released with the invalidated results.
+    cache.invalidate_table("default", "a");
+    assert_eq!(tracker.get_usage(), 0);
+
+    // Not cached when the server is out of memory.
+    let tracker = MemoryTracker::create("server", bytes - 1);
+    let cache = QueryCache::create(tracker.clone());
+    let key = QueryCacheKey::try_create(&ctx, &plan).await?.unwrap();
+    cache.put(key.clone(), vec![result_block()], ttl, 1024 * 1024);
+    assert!(cache.get_infos().is_empty());
+    assert_eq!(tracker.get_usage(), 0);
+    Ok(())
+}
@@ -3,7 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0.
 
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 
 use common_exception::ErrorCode;
 use common_exception::Result;
@@ -11,37 +14,162 @@ use common_infallible::RwLock;
 use common_planners::Partitions;
 use metrics::counter;
 
+use crate::configs::Config;
+use crate::pipelines::processors::PipelineExecutor;
+use crate::pipelines::processors::PipelineExecutorRef;
 use crate::sessions::FuseQueryContext;
 use crate::sessions::FuseQueryContextRef;
+use crate::sessions::ProcessList;
+use crate::sessions::ProcessListRef;
+use crate::sessions::QueryCache;
+use crate::sessions::QueryCacheRef;
 
 pub struct SessionManager {
     sessions: RwLock<HashMap<String, FuseQueryContextRef>>,
+    // The last use and the timeout of the sessions named by the clients, removed once idle longer.
+    expirations: RwLock<HashMap<String, (Instant, Duration)>>,
+    // The named sessions held by a running request, they don't expire while held.
+    locked: RwLock<HashSet<String>>,
+    // The query results cache shared by the sessions.
+    query_cache: QueryCacheRef,
+    // The sessions in system.processes, with the server-wide memory tracker.
+    process_list: ProcessListRef,
+    // The workers of the pipeline executor shared by the sessions, created with the first session.
+    executor_workers: usize,
+    pipeline_executor: RwLock<Option<PipelineExecutorRef>>,
 }
 
 pub type SessionManagerRef = Arc<SessionManager>;
 
 impl SessionManager {
     pub fn create() -> SessionManagerRef {
+        Self::create_with_limits(0, num_cpus::get())
+    }
+
+    pub fn from_conf(conf: Config) -> SessionManagerRef {
+        Self::create_with_limits(conf.max_server_memory_usage, conf.num_cpus as usize)
+    }
+
+    fn create_with_limits(
+        max_server_memory_usage: u64,
+        executor_workers: usize,
+    ) -> SessionManagerRef {
+        let process_list = ProcessList::create(max_server_memory_usage);
         Arc::new(SessionManager {
             sessions: RwLock::new(HashMap::new()),
+            expirations: RwLock::new(HashMap::new()),
+            locked: RwLock::new(HashSet::new()),
+            query_cache: QueryCache::create(process_list.get_memory_tracker()),
+            process_list,
+            executor_workers,
+            pipeline_executor: RwLock::new(None),
         })
     }
 
+    fn try_get_pipeline_executor(&self) -> Result<PipelineExecutorRef> {
+        let mut executor = self.pipeline_executor.write();
+        match &*executor {
+            Some(executor) => Ok(executor.clone()),
+            None => {
+                let created = PipelineExecutor::try_create(self.executor_workers)?;
+                *executor = Some(created.clone());
+                Ok(created)
+            }
+        }
+    }
+
     pub fn try_create_context(&self) -> Result<FuseQueryContextRef> {
         counter!(super::metrics::METRIC_SESSION_CONNECT_NUMBERS, 1);
 
-        let ctx = FuseQueryContext::try_create()?;
+        let ctx = FuseQueryContext::try_create()?
+            .with_query_cache(self.query_cache.clone())?
+            .with_pipeline_executor(self.try_get_pipeline_executor()?)?
+            .with_process_list(self.process_list.clone())?;
         self.sessions.write().insert(ctx.get_id(), ctx.clone());
         Ok(ctx)
     }
 
+    /// Create a context with the given id, used by the clients which name their sessions.
+    pub fn try_create_context_with_id(&self, id: &str) -> Result<FuseQueryContextRef> {
+        counter!(super::metrics::METRIC_SESSION_CONNECT_NUMBERS, 1);
+
+        self.remove_expired_contexts();
+
+        let ctx = FuseQueryContext::try_create()?
+            .with_query_cache(self.query_cache.clone())?
+            .with_id(id)?
+            .with_pipeline_executor(self.try_get_pipeline_executor()?)?
+            .with_process_list(self.process_list.clone())?;
+        self.sessions.write().insert(ctx.get_id(), ctx.clone());
+        Ok(ctx)
+    }
+
+    pub fn get_context(&self, id: &str) -> Option<FuseQueryContextRef> {
+        self.remove_expired_contexts();
+        self.sessions.read().get(id).cloned()
+    }
+
+    /// Marks the named session as used now, it is removed once idle for longer than the timeout.
+    pub fn refresh_context(&self, id: &str, timeout: Duration) {
+        if self.sessions.read().contains_key(id) {
+            self.expirations
+                .write()
+                .insert(id.to_string(), (Instant::now(), timeout));
+        }
+    }
+
+    /// Holds the named session for a request until the guard is dropped, a concurrent request
+    /// with the same session fails. The idle time of the session starts once it's released.
+    pub fn try_lock_session(
+        sessions: &SessionManagerRef,
+        id: &str,
+        timeout: Duration,
+    ) -> Result<SessionGuard> {
+        if !sessions.locked.write().insert(id.to_string()) {
+            return Err(ErrorCode::SessionIsLocked(format!(
+                "Session {} is locked by a concurrent client",
+                id
+            )));
+        }
+        Ok(SessionGuard {
+            sessions: sessions.clone(),
+            id: id.to_string(),
+            timeout,
+        })
+    }
+
     pub fn try_remove_context(&self, ctx: FuseQueryContextRef) -> Result<()> {
         counter!(super::metrics::METRIC_SESSION_CLOSE_NUMBERS, 1);
 
         self.sessions.write().remove(&*ctx.get_id());
+        self.expirations.write().remove(&*ctx.get_id());
+        self.process_list.remove(&*ctx.get_id());
         Ok(())
     }
 
+    // Removes the named sessions idle for longer than their timeout.
+    fn remove_expired_contexts(&self) {
+        let now = Instant::now();
+        let mut expired = vec![];
+        {
+            let locked = self.locked.read();
+            self.expirations.write().retain(|id, (last_used, timeout)| {
+                let alive = locked.contains(id) || now.duration_since(*last_used) < *timeout;
+                if !alive {
+                    expired.push(id.clone());
+                }
+                alive
+            });
+        }
+
+        for id in expired {
+            counter!(super::metrics::METRIC_SESSION_CLOSE_NUMBERS, 1);
+
+            self.sessions.write().remove(&id);
+            self.process_list.remove(&id);
+        }
+    }
+
     /// Fetch nums partitions from session manager by context id.
     pub fn try_fetch_partitions(&self, ctx_id: String, nums: usize) -> Result<Partitions> {
         let session_map = self.sessions.read();
@@ -51,3 +179,17 @@ impl SessionManager {
         ctx.try_get_partitions(nums)
     }
 }
+
+/// A named session held by a request, see `SessionManager::try_lock_session`.
+pub struct SessionGuard {
+    sessions: SessionManagerRef,
+    id: String,
+    timeout: Duration,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        self.sessions.locked.write().remove(&self.id);
+        self.sessions.refresh_context(&self.id, self.timeout);
+    }
+}
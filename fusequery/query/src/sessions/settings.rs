@@ -21,7 +21,21 @@ impl Settings {
         ("max_threads", u64, 16, "The maximum number of threads to execute the request. By default, it is determined automatically.".to_string()),
         ("flight_client_timeout", u64, 60, "Max duration the flight client request is allowed to take in seconds. By default, it is 60 seconds".to_string()),
         ("min_distributed_rows", u64, 100000000, "Minimum distributed read rows. In cluster mode, when read rows exceeds this value, the local table converted to distributed query.".to_string()),
-        ("min_distributed_bytes", u64, 500 * 1024 * 1024, "Minimum distributed read bytes. In cluster mode, when read bytes exceeds this value, the local table converted to distributed query.".to_string())
+        ("min_distributed_bytes", u64, 500 * 1024 * 1024, "Minimum distributed read bytes. In cluster mode, when read bytes exceeds this value, the local table converted to distributed query.".to_string()),
+        ("max_convergent_aggregate_groups", u64, 10000, "Maximum estimated groups of a distributed aggregation to merge the partial results on one node instead of shuffling them by the group by keys, the groups are estimated from the statistics of ANALYZE TABLE.".to_string()),
+        ("enable_constant_folding", u64, 1, "Enable the constant folding and expression simplification optimizer rule, 0 to disable.".to_string()),
+        ("enable_predicate_push_down", u64, 1, "Enable the predicate push down optimizer rule, 0 to disable.".to_string()),
+        ("enable_limit_push_down", u64, 1, "Enable the limit push down optimizer rule, 0 to disable.".to_string()),
+        ("enable_projection_push_down", u64, 1, "Enable the projection push down optimizer rule, 0 to disable.".to_string()),
+        ("enable_common_subexpression_elimination", u64, 1, "Enable the common subexpression elimination optimizer rule, 0 to disable.".to_string()),
+        ("enable_aggregation_push_down", u64, 1, "Enable pushing the partial aggregation down to the remote tables, the states are computed by the store next to the data, 0 to disable.".to_string()),
+        ("use_query_cache", u64, 0, "Use the query result cache for the SELECT queries, the results are shared by the sessions and invalidated by the inserts and the DDLs, 1 to enable.".to_string()),
+        ("query_cache_ttl_seconds", u64, 60, "Time to live of the query results in the cache in seconds.".to_string()),
+        ("query_cache_max_bytes", u64, 64 * 1024 * 1024, "Maximum bytes of the query results in the cache, the oldest results are evicted to make room for the new ones.".to_string()),
+        ("max_bytes_before_external_sort", u64, 0, "Maximum bytes of the blocks buffered by the sort, the sorted runs are spilled to the temp files and merged from disk past it, 0 to disable.".to_string()),
+        ("max_bytes_before_external_group_by", u64, 0, "Maximum bytes of the groups kept by the partial group by, the buckets are spilled to the temp files and merged bucket by bucket past it, 0 to disable.".to_string()),
+        ("max_memory_usage", u64, 0, "Maximum bytes of the blocks and the states buffered by a query, the query fails past it, 0 for unlimited.".to_string()),
+        ("max_execution_time", u64, 0, "Maximum seconds of the execution of a query, the query is cancelled past it, 0 for unlimited.".to_string())
     }
 
     pub fn try_create() -> Result<Arc<Settings>> {
@@ -35,6 +49,10 @@ impl Settings {
         Ok(settings)
     }
 
+    pub fn has_setting(&self, key: &str) -> bool {
+        self.inner.has_setting(key)
+    }
+
     pub fn iter(&self) -> SettingsIterator {
         SettingsIterator {
             settings: self.inner.get_settings(),
@@ -56,6 +74,12 @@ impl SettingsBase {
         }
     }
 
+    pub fn has_setting(&self, key: &str) -> bool {
+        self.settings
+            .read()
+            .contains_key(key.to_lowercase().as_str())
+    }
+
     // TODO, to use macro generate this codes
     #[allow(unused)]
     pub fn try_set_u64(&self, key: &'static str, val: u64, desc: String) -> Result<()> {
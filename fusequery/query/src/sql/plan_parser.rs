@@ -17,6 +17,7 @@ use common_datavalues::DataType;
 use common_datavalues::DataValue;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_planners::AnalyzeTablePlan;
 use common_planners::CreateDatabasePlan;
 use common_planners::CreateTablePlan;
 use common_planners::DropDatabasePlan;
@@ -57,9 +58,11 @@ use crate::sql::expr_common::unwrap_alias_exprs;
 use crate::sql::sql_statement::DfCreateTable;
 use crate::sql::sql_statement::DfDropDatabase;
 use crate::sql::sql_statement::DfUseDatabase;
+use crate::sql::DfAnalyzeTable;
 use crate::sql::DfCreateDatabase;
 use crate::sql::DfDropTable;
 use crate::sql::DfExplain;
+use crate::sql::DfInsertQuery;
 use crate::sql::DfParser;
 use crate::sql::DfStatement;
 use crate::sql::SQLCommon;
@@ -96,6 +99,8 @@ impl PlanParser {
             DfStatement::DropDatabase(v) => self.sql_drop_database_to_plan(&v),
             DfStatement::CreateTable(v) => self.sql_create_table_to_plan(&v),
             DfStatement::DropTable(v) => self.sql_drop_table_to_plan(&v),
+            DfStatement::AnalyzeTable(v) => self.sql_analyze_table_to_plan(&v),
+            DfStatement::InsertQuery(v) => self.sql_insert_to_plan(&v),
             DfStatement::UseDatabase(v) => self.sql_use_database_to_plan(&v),
 
             // TODO: support like and other filters in show queries
@@ -246,6 +251,83 @@ impl PlanParser {
         }))
     }
 
+    /// DfAnalyzeTable to plan.
+    #[tracing::instrument(level = "info", skip(self, analyze), fields(ctx.id = self.ctx.get_id().as_str()))]
+    pub fn sql_analyze_table_to_plan(&self, analyze: &DfAnalyzeTable) -> Result<PlanNode> {
+        let mut db = self.ctx.get_current_database();
+        if analyze.name.0.is_empty() {
+            return Result::Err(ErrorCode::SyntaxException("Analyze table name is empty"));
+        }
+        let mut table = analyze.name.0[0].value.clone();
+        if analyze.name.0.len() > 1 {
+            db = table;
+            table = analyze.name.0[1].value.clone();
+        }
+        Ok(PlanNode::AnalyzeTable(AnalyzeTablePlan { db, table }))
+    }
+
+    /// DfInsertQuery to plan.
+    #[tracing::instrument(level = "info", skip(self, insert), fields(ctx.id = self.ctx.get_id().as_str()))]
+    pub fn sql_insert_to_plan(&self, insert: &DfInsertQuery) -> Result<PlanNode> {
+        match (&insert.format, &insert.source) {
+            (None, Some(source)) => self.insert_to_plan(&insert.name, &insert.columns, source),
+            (Some(format), _) => self.insert_format_to_plan(&insert.name, &insert.columns, format),
+            (None, None) => Result::Err(ErrorCode::SyntaxException("Insert source is empty")),
+        }
+    }
+
+    /// The rows of `INSERT INTO t FORMAT xx` are not part of the query, the plan is created
+    /// with the table schema and the server handler attaches the input stream later.
+    fn insert_format_to_plan(
+        &self,
+        table_name: &ObjectName,
+        columns: &[Ident],
+        format: &str,
+    ) -> Result<PlanNode> {
+        match format.to_uppercase().as_str() {
+            "NATIVE" | "VALUES" => {}
+            _ => {
+                return Result::Err(ErrorCode::UnImplement(format!(
+                    "Unsupported insert format: {}",
+                    format
+                )))
+            }
+        }
+
+        let mut db_name = self.ctx.get_current_database();
+        if table_name.0.is_empty() {
+            return Result::Err(ErrorCode::SyntaxException("Insert table name is empty"));
+        }
+        let mut tbl_name = table_name.0[0].value.clone();
+        if table_name.0.len() > 1 {
+            db_name = tbl_name;
+            tbl_name = table_name.0[1].value.clone();
+        }
+
+        let table_schema = self.ctx.get_table(&db_name, &tbl_name)?.schema()?;
+        let schema = if columns.is_empty() {
+            table_schema
+        } else {
+            let fields = columns
+                .iter()
+                .map(|ident| {
+                    table_schema
+                        .index_of(&ident.value)
+                        .map(|index| table_schema.field(index).clone())
+                        .map_err(ErrorCode::from)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            DataSchemaRefExt::create(fields)
+        };
+
+        Ok(PlanNode::InsertInto(InsertIntoPlan {
+            db_name,
+            tbl_name,
+            schema,
+            input_stream: InsertIntoPlan::empty_stream(),
+        }))
+    }
+
     #[tracing::instrument(level = "info", skip(self, table_name, columns, source), fields(ctx.id = self.ctx.get_id().as_str()))]
     fn insert_to_plan(
         &self,
@@ -312,7 +394,7 @@ impl PlanParser {
                     DataBlock::create_by_array(schema.clone(), cols)
                 })
                 .collect();
-            let input_stream = futures::stream::iter(blocks);
+            let input_stream = futures::stream::iter(blocks.into_iter().map(Ok));
             let plan_node = InsertIntoPlan {
                 db_name,
                 tbl_name,
@@ -18,16 +18,19 @@ use sqlparser::ast::Value;
 use sqlparser::dialect::keywords::Keyword;
 use sqlparser::dialect::Dialect;
 use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::IsOptional;
 use sqlparser::parser::Parser;
 use sqlparser::parser::ParserError;
 use sqlparser::tokenizer::Token;
 use sqlparser::tokenizer::Tokenizer;
 
+use crate::sql::DfAnalyzeTable;
 use crate::sql::DfCreateDatabase;
 use crate::sql::DfCreateTable;
 use crate::sql::DfDropDatabase;
 use crate::sql::DfDropTable;
 use crate::sql::DfExplain;
+use crate::sql::DfInsertQuery;
 use crate::sql::DfShowDatabases;
 use crate::sql::DfShowSettings;
 use crate::sql::DfShowTables;
@@ -119,6 +122,10 @@ impl<'a> DfParser<'a> {
                         self.parser.next_token();
                         self.parse_explain()
                     }
+                    Keyword::INSERT => {
+                        self.parser.next_token();
+                        self.parse_insert()
+                    }
 
                     Keyword::SHOW => {
                         self.parser.next_token();
@@ -133,6 +140,11 @@ impl<'a> DfParser<'a> {
                             self.expected("tables or settings", self.parser.peek_token())
                         }
                     }
+                    // ANALYZE may or may not be a keyword of the native parser.
+                    _ if w.value.to_uppercase() == "ANALYZE" => {
+                        self.parser.next_token();
+                        self.parse_analyze()
+                    }
                     Keyword::NoKeyword => match w.value.to_uppercase().as_str() {
                         // Use database
                         "USE" => self.parse_use_database(),
@@ -165,6 +177,10 @@ impl<'a> DfParser<'a> {
                     self.parser.next_token();
                     ExplainType::Graph
                 }
+                "OPTIMIZER" => {
+                    self.parser.next_token();
+                    ExplainType::Optimizer
+                }
                 _ => ExplainType::Syntax,
             },
             _ => ExplainType::Syntax,
@@ -175,6 +191,53 @@ impl<'a> DfParser<'a> {
         Ok(DfStatement::Explain(explain_plan))
     }
 
+    /// Parse an SQL INSERT statement.
+    /// Besides `INSERT INTO t [(cols)] <query>`, this accepts the streaming forms
+    /// `INSERT INTO t [(cols)] FORMAT <name>` and a bare `INSERT INTO t [(cols)] VALUES`,
+    /// whose rows are sent by the client as data blocks after the query.
+    fn parse_insert(&mut self) -> Result<DfStatement, ParserError> {
+        // Parser is at the token immediately after INSERT
+        self.parser.expect_keyword(Keyword::INTO)?;
+        let name = self.parser.parse_object_name()?;
+        let columns = self
+            .parser
+            .parse_parenthesized_column_list(IsOptional::Optional)?;
+
+        let format = if self.consume_token("FORMAT") {
+            match self.parser.next_token() {
+                Token::Word(w) => Some(w.value),
+                unexpected => return self.expected("format name", unexpected),
+            }
+        } else if self.is_bare_values() {
+            self.parser.next_token();
+            Some("Values".to_string())
+        } else {
+            None
+        };
+
+        let source = match format {
+            Some(_) => None,
+            None => Some(Box::new(self.parser.parse_query()?)),
+        };
+
+        Ok(DfStatement::InsertQuery(DfInsertQuery {
+            name,
+            columns,
+            format,
+            source,
+        }))
+    }
+
+    // VALUES without any tuple after it, the rows follow the query.
+    fn is_bare_values(&self) -> bool {
+        match self.parser.peek_token() {
+            Token::Word(w) if w.keyword == Keyword::VALUES => {
+                matches!(self.parser.peek_nth_token(1), Token::EOF | Token::SemiColon)
+            }
+            _ => false,
+        }
+    }
+
     // This is a copy of the equivalent implementation in sqlparser.
     fn parse_columns(&mut self) -> Result<(Vec<ColumnDef>, Vec<TableConstraint>), ParserError> {
         let mut columns = vec![];
@@ -339,6 +402,16 @@ impl<'a> DfParser<'a> {
         Ok(DfStatement::DropTable(drop))
     }
 
+    /// Analyze table.
+    fn parse_analyze(&mut self) -> Result<DfStatement, ParserError> {
+        self.parser.expect_keyword(Keyword::TABLE)?;
+        let table_name = self.parser.parse_object_name()?;
+
+        Ok(DfStatement::AnalyzeTable(DfAnalyzeTable {
+            name: table_name,
+        }))
+    }
+
     // Parse 'use database' db name.
     fn parse_use_database(&mut self) -> Result<DfStatement, ParserError> {
         if !self.consume_token("USE") {
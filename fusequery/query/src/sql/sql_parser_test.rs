@@ -190,6 +190,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn analyze_table() -> Result<()> {
+        {
+            let sql = "ANALYZE TABLE t1";
+            let expected = DfStatement::AnalyzeTable(DfAnalyzeTable {
+                name: ObjectName(vec![Ident::new("t1")]),
+            });
+            expect_parse_ok(sql, expected)?;
+        }
+        {
+            let sql = "analyze table db1.t1";
+            let expected = DfStatement::AnalyzeTable(DfAnalyzeTable {
+                name: ObjectName(vec![Ident::new("db1"), Ident::new("t1")]),
+            });
+            expect_parse_ok(sql, expected)?;
+        }
+
+        expect_parse_error("ANALYZE t1", "Expected TABLE")?;
+
+        Ok(())
+    }
+
     #[test]
     fn show_queries() -> Result<()> {
         // positive case
@@ -216,4 +238,33 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn insert_format() -> Result<()> {
+        {
+            let sql = "INSERT INTO db1.t1(a, b) FORMAT Native";
+            let expected = DfStatement::InsertQuery(DfInsertQuery {
+                name: ObjectName(vec![Ident::new("db1"), Ident::new("t1")]),
+                columns: vec![Ident::new("a"), Ident::new("b")],
+                format: Some("Native".to_string()),
+                source: None,
+            });
+            expect_parse_ok(sql, expected)?;
+        }
+
+        {
+            let sql = "INSERT INTO t1 VALUES";
+            let expected = DfStatement::InsertQuery(DfInsertQuery {
+                name: ObjectName(vec![Ident::new("t1")]),
+                columns: vec![],
+                format: Some("Values".to_string()),
+                source: None,
+            });
+            expect_parse_ok(sql, expected)?;
+        }
+
+        expect_parse_error("INSERT INTO t1 FORMAT", "Expected format name")?;
+
+        Ok(())
+    }
 }
@@ -6,7 +6,9 @@ use common_planners::DatabaseEngineType;
 use common_planners::ExplainType;
 use common_planners::TableEngineType;
 use sqlparser::ast::ColumnDef;
+use sqlparser::ast::Ident;
 use sqlparser::ast::ObjectName;
+use sqlparser::ast::Query;
 use sqlparser::ast::SqlOption;
 use sqlparser::ast::Statement as SQLStatement;
 
@@ -41,6 +43,22 @@ pub struct DfDropTable {
     pub name: ObjectName,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct DfAnalyzeTable {
+    pub name: ObjectName,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DfInsertQuery {
+    /// Table name
+    pub name: ObjectName,
+    pub columns: Vec<Ident>,
+    /// Rows are sent by the client after the query, e.g. `INSERT INTO t FORMAT Native`.
+    /// A bare `INSERT INTO t VALUES` (as sent by clickhouse-client) is format `Values`.
+    pub format: Option<String>,
+    pub source: Option<Box<Query>>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct DfCreateDatabase {
     pub if_not_exists: bool,
@@ -77,6 +95,8 @@ pub enum DfStatement {
     ShowTables(DfShowTables),
     CreateTable(DfCreateTable),
     DropTable(DfDropTable),
+    AnalyzeTable(DfAnalyzeTable),
+    InsertQuery(DfInsertQuery),
 
     // Settings.
     ShowSettings(DfShowSettings),
@@ -123,6 +123,7 @@ async fn test_flight_create_get_table() -> anyhow::Result<()> {
             let got = client.get_table("db1".into(), "tb2".into()).await.unwrap();
             let want = GetTableActionResult {
                 table_id: 1,
+                ver: 1,
                 db: "db1".into(),
                 name: "tb2".into(),
                 schema: schema.clone(),
@@ -139,6 +140,7 @@ async fn test_flight_create_get_table() -> anyhow::Result<()> {
             let got = client.get_table("db1".into(), "tb2".into()).await.unwrap();
             let want = GetTableActionResult {
                 table_id: 1,
+                ver: 1,
                 db: "db1".into(),
                 name: "tb2".into(),
                 schema: schema.clone(),
@@ -164,6 +166,7 @@ async fn test_flight_create_get_table() -> anyhow::Result<()> {
             let got = client.get_table("db1".into(), "tb2".into()).await.unwrap();
             let want = GetTableActionResult {
                 table_id: 1,
+                ver: 1,
                 db: "db1".into(),
                 name: "tb2".into(),
                 schema: schema.clone(),
@@ -208,7 +211,7 @@ async fn test_do_append() -> anyhow::Result<()> {
     let block = DataBlock::create_by_array(schema.clone(), vec![col0, col1]);
     let batches = vec![block.clone(), block];
     let num_batch = batches.len();
-    let stream = futures::stream::iter(batches);
+    let stream = futures::stream::iter(batches.into_iter().map(Ok));
 
     let mut client = StoreClient::try_create(addr.as_str(), "root", "xxx").await?;
     {
@@ -247,6 +250,169 @@ async fn test_do_append() -> anyhow::Result<()> {
     });
     Ok(())
 }
+#[test(tokio::test)]
+async fn test_do_append_aborted() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_arrow::arrow::datatypes::DataType;
+    use common_datavalues::DataField;
+    use common_datavalues::DataSchema;
+    use common_datavalues::Int64Array;
+    use common_exception::ErrorCode;
+    use common_planners::CreateDatabasePlan;
+    use common_planners::CreateTablePlan;
+    use common_planners::DatabaseEngineType;
+    use common_planners::TableEngineType;
+
+    let addr = crate::tests::start_store_server().await?;
+
+    let schema = Arc::new(DataSchema::new(vec![DataField::new(
+        "col_i",
+        DataType::Int64,
+        false,
+    )]));
+    let db_name = "test_db";
+    let tbl_name = "test_tbl";
+
+    let col0: ArrayRef = Arc::new(Int64Array::from(vec![0, 1, 2]));
+    let block = DataBlock::create_by_array(schema.clone(), vec![col0]);
+    // The input of the insert fails after the first block, e.g. the client disconnects.
+    let stream = futures::stream::iter(vec![
+        Ok(block),
+        Err(ErrorCode::AbortedQuery("the client is disconnected")),
+    ]);
+
+    let mut client = StoreClient::try_create(addr.as_str(), "root", "xxx").await?;
+    {
+        let plan = CreateDatabasePlan {
+            if_not_exists: false,
+            db: db_name.to_string(),
+            engine: DatabaseEngineType::Local,
+            options: Default::default(),
+        };
+        client.create_database(plan.clone()).await?;
+        let plan = CreateTablePlan {
+            if_not_exists: false,
+            db: db_name.to_string(),
+            table: tbl_name.to_string(),
+            schema: schema.clone(),
+            options: Default::default(),
+            engine: TableEngineType::Parquet,
+        };
+        client.create_table(plan.clone()).await?;
+    }
+    let res = client
+        .append_data(
+            db_name.to_string(),
+            tbl_name.to_string(),
+            schema,
+            Box::pin(stream),
+        )
+        .await;
+    let err = res.unwrap_err().to_string();
+    assert!(err.contains("the client is disconnected"), "{}", err);
+
+    // The block before the error is not committed.
+    let plan = ScanPlan {
+        schema_name: tbl_name.to_string(),
+        ..ScanPlan::empty()
+    };
+    let res = client
+        .scan_partition(db_name.to_string(), tbl_name.to_string(), &plan)
+        .await?;
+    assert_eq!(res.map(|parts| parts.len()).unwrap_or(0), 0);
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_table_statistics() -> anyhow::Result<()> {
+    use std::sync::Arc;
+
+    use common_arrow::arrow::datatypes::DataType;
+    use common_datavalues::DataField;
+    use common_datavalues::DataSchema;
+    use common_datavalues::Int64Array;
+    use common_flights::StoreClient;
+    use common_planners::CreateDatabasePlan;
+    use common_planners::CreateTablePlan;
+    use common_planners::DatabaseEngineType;
+    use common_planners::TableEngineType;
+    use common_planners::TableStatistics;
+
+    let addr = crate::tests::start_store_server().await?;
+
+    let schema = Arc::new(DataSchema::new(vec![DataField::new(
+        "col_i",
+        DataType::Int64,
+        false,
+    )]));
+    let db_name = "test_db";
+    let tbl_name = "test_tbl";
+
+    let mut client = StoreClient::try_create(addr.as_str(), "root", "xxx").await?;
+    {
+        let plan = CreateDatabasePlan {
+            if_not_exists: false,
+            db: db_name.to_string(),
+            engine: DatabaseEngineType::Local,
+            options: Default::default(),
+        };
+        client.create_database(plan.clone()).await?;
+        let plan = CreateTablePlan {
+            if_not_exists: false,
+            db: db_name.to_string(),
+            table: tbl_name.to_string(),
+            schema: schema.clone(),
+            options: maplit::hashmap! {"opt‐1".into() => "val-1".into()},
+            engine: TableEngineType::Parquet,
+        };
+        client.create_table(plan.clone()).await?;
+    }
+
+    // The statistics are stored with the table meta.
+    let statistics = TableStatistics {
+        rows: 3,
+        bytes: 24,
+        columns: Default::default(),
+    };
+    client
+        .set_table_statistics(
+            db_name.to_string(),
+            tbl_name.to_string(),
+            Some(statistics.clone()),
+        )
+        .await?;
+    let got = client
+        .get_table_statistics(db_name.to_string(), tbl_name.to_string())
+        .await?;
+    assert_eq!(Some(statistics), got);
+
+    // And dropped by an append.
+    let col0: ArrayRef = Arc::new(Int64Array::from(vec![0, 1, 2]));
+    let block = DataBlock::create_by_array(schema.clone(), vec![col0]);
+    let stream = futures::stream::iter(vec![Ok(block)]);
+    client
+        .append_data(
+            db_name.to_string(),
+            tbl_name.to_string(),
+            schema,
+            Box::pin(stream),
+        )
+        .await?;
+    let got = client
+        .get_table_statistics(db_name.to_string(), tbl_name.to_string())
+        .await?;
+    assert_eq!(None, got);
+
+    // Unknown table.
+    let res = client
+        .get_table_statistics(db_name.to_string(), "unknown".to_string())
+        .await;
+    assert!(res.is_err());
+
+    Ok(())
+}
+
 #[test(tokio::test)]
 async fn test_scan_partition() -> anyhow::Result<()> {
     use std::sync::Arc;
@@ -257,6 +423,8 @@ async fn test_scan_partition() -> anyhow::Result<()> {
     use common_datavalues::Int64Array;
     use common_datavalues::StringArray;
     use common_flights::StoreClient;
+    use common_planners::col;
+    use common_planners::lit;
     use common_planners::CreateDatabasePlan;
     use common_planners::CreateTablePlan;
     use common_planners::DatabaseEngineType;
@@ -283,7 +451,7 @@ async fn test_scan_partition() -> anyhow::Result<()> {
     ]);
     let batches = vec![block.clone(), block];
     let num_batch = batches.len();
-    let stream = futures::stream::iter(batches);
+    let stream = futures::stream::iter(batches.into_iter().map(Ok));
 
     let mut client = StoreClient::try_create(addr.as_str(), "root", "xxx").await?;
     {
@@ -327,9 +495,30 @@ async fn test_scan_partition() -> anyhow::Result<()> {
     };
     let res = client
         .scan_partition(db_name.to_string(), tbl_name.to_string(), &plan)
-        .await;
-    // TODO d assertions, de-duplicated codes
-    println!("scan res is {:?}", res);
+        .await?;
+    // TODO de-duplicated codes
+    assert_eq!(res.map(|parts| parts.len()), Some(num_batch));
+
+    // The parts are skipped by the min/max of the columns.
+    let plan = ScanPlan {
+        schema_name: tbl_name.to_string(),
+        filters: vec![col("col_i").gt(lit(2i64))],
+        ..ScanPlan::empty()
+    };
+    let res = client
+        .scan_partition(db_name.to_string(), tbl_name.to_string(), &plan)
+        .await?;
+    assert_eq!(res.map(|parts| parts.len()), Some(0));
+
+    let plan = ScanPlan {
+        schema_name: tbl_name.to_string(),
+        filters: vec![col("col_s").eq(lit("str2"))],
+        ..ScanPlan::empty()
+    };
+    let res = client
+        .scan_partition(db_name.to_string(), tbl_name.to_string(), &plan)
+        .await?;
+    assert_eq!(res.map(|parts| parts.len()), Some(num_batch));
 
     Ok(())
 }
@@ -0,0 +1,149 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+//
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use common_aggregate_functions::AggregateFunction;
+use common_arrow::arrow::array::BinaryBuilder;
+use common_datablocks::DataBlock;
+use common_datavalues::DataArrayRef;
+use common_datavalues::DataColumnarValue;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataValue;
+use common_planners::AggregatorPartialPlan;
+use common_planners::Expression;
+
+// The aggregate functions with their arguments, the columns or the literals.
+type AggregateFunctions = Vec<(Box<dyn AggregateFunction>, Vec<Expression>)>;
+
+/// Computes the partial aggregation states of the blocks read from a part, in the same format
+/// as the partial aggregator transforms of the query, for the final aggregator to merge.
+pub(crate) struct PartialAggregator {
+    schema: DataSchemaRef,
+    input_schema: DataSchemaRef,
+    aggr_exprs: Vec<Expression>,
+    group_columns: Vec<String>,
+    // The states of the aggregation without group by.
+    funcs: AggregateFunctions,
+    // Table for <group_key, (functions, keys)>
+    groups: HashMap<Vec<u8>, (AggregateFunctions, Vec<DataValue>)>,
+}
+
+impl PartialAggregator {
+    pub fn try_create(plan: &AggregatorPartialPlan) -> Result<Self> {
+        let input_schema = plan.input.schema();
+        let funcs = if plan.group_expr.is_empty() {
+            Self::create_funcs(&plan.aggr_expr, &input_schema)?
+        } else {
+            vec![]
+        };
+
+        Ok(PartialAggregator {
+            schema: plan.schema(),
+            input_schema,
+            aggr_exprs: plan.aggr_expr.clone(),
+            group_columns: plan.group_expr.iter().map(|e| e.column_name()).collect(),
+            funcs,
+            groups: HashMap::new(),
+        })
+    }
+
+    pub fn append(&mut self, block: &DataBlock) -> Result<()> {
+        if self.group_columns.is_empty() {
+            return Self::accumulate(&mut self.funcs, block);
+        }
+
+        for (group_key, group_keys, take_block) in DataBlock::group_by(block, &self.group_columns)?
+        {
+            match self.groups.get_mut(&group_key) {
+                Some((funcs, _)) => Self::accumulate(funcs, &take_block)?,
+                None => {
+                    let mut funcs = Self::create_funcs(&self.aggr_exprs, &self.input_schema)?;
+                    Self::accumulate(&mut funcs, &take_block)?;
+                    self.groups.insert(group_key, (funcs, group_keys));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The block of the partial states, None if there is no group.
+    pub fn finalize(self) -> Result<Option<DataBlock>> {
+        let mut columns: Vec<DataArrayRef> = Vec::with_capacity(self.schema.fields().len());
+        if self.group_columns.is_empty() {
+            for (func, _) in &self.funcs {
+                let mut states = vec![];
+                func.serialize(&mut states)?;
+                let mut builder = BinaryBuilder::new(1);
+                builder.append_value(&states)?;
+                columns.push(Arc::new(builder.finish()));
+            }
+            return Ok(Some(DataBlock::create_by_array(self.schema, columns)));
+        }
+
+        if self.groups.is_empty() {
+            return Ok(None);
+        }
+
+        let aggr_len = self.aggr_exprs.len();
+        let mut builders: Vec<BinaryBuilder> = (0..1 + aggr_len)
+            .map(|_| BinaryBuilder::new(self.groups.len()))
+            .collect();
+        let mut group_key_builder = BinaryBuilder::new(self.groups.len());
+        let mut bytes = vec![];
+        for (key, (funcs, values)) in self.groups.iter() {
+            for (idx, (func, _)) in funcs.iter().enumerate() {
+                bytes.clear();
+                func.serialize(&mut bytes)?;
+                builders[idx].append_value(&bytes)?;
+            }
+            bytes.clear();
+            DataValue::serialize_values(values, &mut bytes)?;
+            builders[aggr_len].append_value(&bytes)?;
+            group_key_builder.append_value(key)?;
+        }
+
+        for mut builder in builders {
+            columns.push(Arc::new(builder.finish()));
+        }
+        columns.push(Arc::new(group_key_builder.finish()));
+        Ok(Some(DataBlock::create_by_array(self.schema, columns)))
+    }
+
+    fn create_funcs(
+        aggr_exprs: &[Expression],
+        input_schema: &DataSchemaRef,
+    ) -> Result<AggregateFunctions> {
+        let mut funcs = Vec::with_capacity(aggr_exprs.len());
+        for expr in aggr_exprs {
+            let func = expr.to_aggregate_function(input_schema)?;
+            let args = match expr {
+                Expression::AggregateFunction { args, .. } => args.clone(),
+                _ => anyhow::bail!("{:?} is not an aggregate function", expr),
+            };
+            funcs.push((func, args));
+        }
+        Ok(funcs)
+    }
+
+    fn accumulate(funcs: &mut AggregateFunctions, block: &DataBlock) -> Result<()> {
+        let rows = block.num_rows();
+        for (func, args) in funcs.iter_mut() {
+            let mut arg_columns: Vec<DataColumnarValue> = Vec::with_capacity(args.len());
+            for arg in args.iter() {
+                match arg {
+                    Expression::Literal(value) => {
+                        arg_columns.push(DataColumnarValue::Constant(value.clone(), rows))
+                    }
+                    _ => arg_columns.push(block.try_column_by_name(&arg.column_name())?.clone()),
+                }
+            }
+            func.accumulate(&arg_columns, rows)?;
+        }
+        Ok(())
+    }
+}
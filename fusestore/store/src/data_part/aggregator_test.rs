@@ -0,0 +1,135 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+//
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_arrow::arrow::datatypes::DataType;
+use common_datablocks::DataBlock;
+use common_datavalues::DataField;
+use common_datavalues::DataSchema;
+use common_datavalues::DataValue;
+use common_datavalues::Int64Array;
+use common_datavalues::StringArray;
+use common_planners::col;
+use common_planners::lit;
+use common_planners::sum;
+use common_planners::AggregatorPartialPlan;
+use common_planners::Expression;
+use common_planners::PlanBuilder;
+use common_planners::PlanNode;
+use common_planners::ReadDataSourcePlan;
+use pretty_assertions::assert_eq;
+
+use crate::data_part::aggregator::PartialAggregator;
+
+fn count_all() -> Expression {
+    Expression::AggregateFunction {
+        op: "count".to_string(),
+        distinct: false,
+        args: vec![lit(0i64)],
+    }
+}
+
+fn partial_plan(
+    aggr: &[Expression],
+    group: &[Expression],
+) -> anyhow::Result<AggregatorPartialPlan> {
+    let schema = Arc::new(DataSchema::new(vec![
+        DataField::new("k", DataType::Utf8, false),
+        DataField::new("v", DataType::Int64, false),
+    ]));
+    let source = PlanNode::ReadSource(ReadDataSourcePlan {
+        schema,
+        remote: true,
+        ..ReadDataSourcePlan::empty()
+    });
+    match PlanBuilder::from(&source)
+        .aggregate_partial(aggr, group)?
+        .build()?
+    {
+        PlanNode::AggregatorPartial(plan) => Ok(plan),
+        _ => anyhow::bail!("expect partial aggregator plan"),
+    }
+}
+
+fn blocks(plan: &AggregatorPartialPlan) -> Vec<DataBlock> {
+    let schema = plan.input.schema();
+    vec![
+        DataBlock::create_by_array(schema.clone(), vec![
+            Arc::new(StringArray::from(vec!["a", "b", "a"])),
+            Arc::new(Int64Array::from(vec![1, 2, 3])),
+        ]),
+        DataBlock::create_by_array(schema, vec![
+            Arc::new(StringArray::from(vec!["b", "c"])),
+            Arc::new(Int64Array::from(vec![4, 5])),
+        ]),
+    ]
+}
+
+// Merges the states of the partial block the same way as the final aggregator of the query.
+fn merge(
+    plan: &AggregatorPartialPlan,
+    block: &DataBlock,
+    row: usize,
+) -> anyhow::Result<Vec<String>> {
+    let mut results = vec![];
+    for (idx, expr) in plan.aggr_expr.iter().enumerate() {
+        let mut func = expr.to_aggregate_function(&plan.input.schema())?;
+        if let DataValue::Binary(Some(states)) = DataValue::try_from_column(block.column(idx), row)?
+        {
+            let states = func.deserialize(&states)?;
+            func.merge(&states)?;
+        }
+        results.push(func.merge_result()?.to_string());
+    }
+    Ok(results)
+}
+
+#[test]
+fn test_partial_aggregator() -> anyhow::Result<()> {
+    let plan = partial_plan(&[sum(col("v")), count_all()], &[])?;
+    let mut aggregator = PartialAggregator::try_create(&plan)?;
+    for block in blocks(&plan) {
+        aggregator.append(&block)?;
+    }
+
+    let block = aggregator.finalize()?.unwrap();
+    assert_eq!(block.schema(), &plan.schema);
+    assert_eq!(block.num_rows(), 1);
+    assert_eq!(merge(&plan, &block, 0)?, vec!["15", "5"]);
+    Ok(())
+}
+
+#[test]
+fn test_partial_aggregator_group_by() -> anyhow::Result<()> {
+    let plan = partial_plan(&[sum(col("v")), count_all()], &[col("k")])?;
+    let mut aggregator = PartialAggregator::try_create(&plan)?;
+    for block in blocks(&plan) {
+        aggregator.append(&block)?;
+    }
+
+    let block = aggregator.finalize()?.unwrap();
+    assert_eq!(block.schema(), &plan.schema);
+    assert_eq!(block.num_rows(), 3);
+
+    let mut groups = HashMap::new();
+    for row in 0..block.num_rows() {
+        if let DataValue::Binary(Some(keys)) = DataValue::try_from_column(block.column(2), row)? {
+            // The keys of the group by column k.
+            let keys = DataValue::deserialize_values(&keys)?;
+            groups.insert(format!("{:?}", keys), merge(&plan, &block, row)?);
+        }
+    }
+    let keys = |k: &str| format!("{:?}", vec![DataValue::Utf8(Some(k.to_string()))]);
+    assert_eq!(groups[&keys("a")], vec!["4", "2"]);
+    assert_eq!(groups[&keys("b")], vec!["6", "2"]);
+    assert_eq!(groups[&keys("c")], vec!["5", "1"]);
+
+    // No group without rows.
+    let aggregator = PartialAggregator::try_create(&plan)?;
+    assert!(aggregator.finalize()?.is_none());
+    Ok(())
+}
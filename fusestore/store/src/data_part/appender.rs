@@ -3,18 +3,26 @@
 // SPDX-License-Identifier: Apache-2.0.
 //
 
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::sync::Arc;
 
 use anyhow::Context;
 use anyhow::Result;
+use common_arrow::arrow::array::*;
+use common_arrow::arrow::compute;
+use common_arrow::arrow::datatypes::DataType;
+use common_arrow::arrow::datatypes::TimeUnit;
 use common_arrow::arrow::record_batch::RecordBatch;
 use common_arrow::arrow_flight::utils::flight_data_to_arrow_batch;
 use common_arrow::arrow_flight::FlightData;
 use common_arrow::parquet::arrow::ArrowWriter;
 use common_arrow::parquet::file::writer::InMemoryWriteableCursor;
 use common_datablocks::DataBlock;
+use common_datavalues::DataArrayRef;
 use common_datavalues::DataSchema;
+use common_datavalues::DataValue;
+use common_flights::ColumnMinMax;
 use futures::StreamExt;
 use uuid::Uuid;
 
@@ -50,9 +58,17 @@ impl Appender {
                     (block.num_rows(), block.num_columns(), block.memory_size());
                 let part_uuid = Uuid::new_v4().to_simple().to_string() + ".parquet";
                 let location = format!("{}/{}", path, part_uuid);
+                let column_stats = column_min_max(&block)?;
                 let buffer = write_in_memory(block)?;
 
-                result.append_part(&location, rows, cols, wire_bytes, buffer.len());
+                result.append_part(
+                    &location,
+                    rows,
+                    cols,
+                    wire_bytes,
+                    buffer.len(),
+                    column_stats,
+                );
 
                 self.fs.add(&location, &buffer).await?;
             }
@@ -63,6 +79,75 @@ impl Appender {
     }
 }
 
+/// The min and max non-null values of each column of the block, computed by the arrow
+/// aggregate kernels. The columns without non-null values or of the other types are skipped.
+pub(crate) fn column_min_max(block: &DataBlock) -> Result<HashMap<String, ColumnMinMax>> {
+    let mut column_stats = HashMap::new();
+    for field in block.schema().fields() {
+        let array = block.try_array_by_name(field.name())?;
+        if let Some((min, max)) = array_min_max(&array)? {
+            column_stats.insert(field.name().clone(), ColumnMinMax { min, max });
+        }
+    }
+    Ok(column_stats)
+}
+
+macro_rules! primitive_min_max {
+    ($ARRAY:expr, $ARRAYTYPE:ident, $SCALAR:ident) => {{
+        let array = $ARRAY
+            .as_any()
+            .downcast_ref::<$ARRAYTYPE>()
+            .context(concat!("failed to downcast to ", stringify!($ARRAYTYPE)))?;
+        compute::min(array)
+            .zip(compute::max(array))
+            .map(|(min, max)| (DataValue::$SCALAR(Some(min)), DataValue::$SCALAR(Some(max))))
+    }};
+}
+
+fn array_min_max(array: &DataArrayRef) -> Result<Option<(DataValue, DataValue)>> {
+    Ok(match array.data_type() {
+        DataType::Int8 => primitive_min_max!(array, Int8Array, Int8),
+        DataType::Int16 => primitive_min_max!(array, Int16Array, Int16),
+        DataType::Int32 => primitive_min_max!(array, Int32Array, Int32),
+        DataType::Int64 => primitive_min_max!(array, Int64Array, Int64),
+        DataType::UInt8 => primitive_min_max!(array, UInt8Array, UInt8),
+        DataType::UInt16 => primitive_min_max!(array, UInt16Array, UInt16),
+        DataType::UInt32 => primitive_min_max!(array, UInt32Array, UInt32),
+        DataType::UInt64 => primitive_min_max!(array, UInt64Array, UInt64),
+        DataType::Float32 => primitive_min_max!(array, Float32Array, Float32),
+        DataType::Float64 => primitive_min_max!(array, Float64Array, Float64),
+        DataType::Date32 => primitive_min_max!(array, Date32Array, Date32),
+        DataType::Date64 => primitive_min_max!(array, Date64Array, Date64),
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            primitive_min_max!(array, TimestampSecondArray, TimestampSecond)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            primitive_min_max!(array, TimestampMillisecondArray, TimestampMillisecond)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            primitive_min_max!(array, TimestampMicrosecondArray, TimestampMicrosecond)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            primitive_min_max!(array, TimestampNanosecondArray, TimestampNanosecond)
+        }
+        DataType::Utf8 => {
+            let array = array
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .context("failed to downcast to StringArray")?;
+            compute::min_string(array)
+                .zip(compute::max_string(array))
+                .map(|(min, max)| {
+                    (
+                        DataValue::Utf8(Some(min.to_string())),
+                        DataValue::Utf8(Some(max.to_string())),
+                    )
+                })
+        }
+        _ => None,
+    })
+}
+
 pub(crate) fn write_in_memory(block: DataBlock) -> Result<Vec<u8>> {
     let cursor = InMemoryWriteableCursor::default();
     {
@@ -21,6 +21,8 @@ mod test {
     use common_datablocks::DataBlock;
     use common_datavalues::DataField;
     use common_datavalues::DataSchema;
+    use common_datavalues::DataValue;
+    use common_datavalues::Date32Array;
     use common_datavalues::Int64Array;
     use common_datavalues::StringArray;
 
@@ -91,4 +93,31 @@ mod test {
         assert!(r.is_ok());
         Ok(())
     }
+
+    #[test]
+    fn test_column_min_max() -> anyhow::Result<()> {
+        let schema = Arc::new(DataSchema::new(vec![
+            DataField::new("col_i", DataType::Int64, true),
+            DataField::new("col_s", DataType::Utf8, false),
+            DataField::new("col_d", DataType::Date32, false),
+            DataField::new("col_n", DataType::Int64, true),
+        ]));
+
+        let col0 = Arc::new(Int64Array::from(vec![Some(3), None, Some(-1), Some(7)]));
+        let col1 = Arc::new(StringArray::from(vec!["b", "d", "a", "c"]));
+        let col2 = Arc::new(Date32Array::from(vec![18659, 18628, 18700, 18640]));
+        let col3 = Arc::new(Int64Array::from(vec![None, None, None, None]));
+        let block = DataBlock::create_by_array(schema, vec![col0, col1, col2, col3]);
+
+        // The columns without non-null values are skipped.
+        let stats = column_min_max(&block)?;
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats["col_i"].min, DataValue::Int64(Some(-1)));
+        assert_eq!(stats["col_i"].max, DataValue::Int64(Some(7)));
+        assert_eq!(stats["col_s"].min, DataValue::Utf8(Some("a".to_string())));
+        assert_eq!(stats["col_s"].max, DataValue::Utf8(Some("d".to_string())));
+        assert_eq!(stats["col_d"].min, DataValue::Date32(Some(18628)));
+        assert_eq!(stats["col_d"].max, DataValue::Date32(Some(18700)));
+        Ok(())
+    }
 }
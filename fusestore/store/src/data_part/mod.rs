@@ -3,7 +3,13 @@
 // SPDX-License-Identifier: Apache-2.0.
 //
 
+pub(crate) mod aggregator;
 pub(crate) mod appender;
+pub(crate) mod reader;
 
+#[cfg(test)]
+mod aggregator_test;
 #[cfg(test)]
 mod appender_test;
+#[cfg(test)]
+mod reader_test;
@@ -0,0 +1,271 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+//
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use anyhow::Result;
+use common_arrow::arrow;
+use common_arrow::arrow::datatypes::DataType;
+use common_arrow::arrow::record_batch::RecordBatch;
+use common_arrow::parquet::arrow::parquet_to_arrow_schema;
+use common_arrow::parquet::arrow::ArrowReader;
+use common_arrow::parquet::arrow::ParquetFileArrowReader;
+use common_arrow::parquet::file::metadata::RowGroupMetaData;
+use common_arrow::parquet::file::reader::FileReader;
+use common_arrow::parquet::file::reader::SerializedFileReader;
+use common_arrow::parquet::file::serialized_reader::SliceableCursor;
+use common_arrow::parquet::file::statistics::Statistics as ParquetStatistics;
+use common_datablocks::DataBlock;
+use common_datavalues::BooleanArray;
+use common_datavalues::DataColumnarValue;
+use common_datavalues::DataSchema;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataValue;
+use common_planners::ColumnRange;
+use common_planners::Expression;
+use common_planners::ExpressionAction;
+use common_planners::ExpressionChain;
+use common_planners::RewriteHelper;
+
+/// Reads the projected columns of a part, skips the row groups whose statistics can't match
+/// the push down filters and keeps only the rows matching them.
+pub(crate) struct PartReader {
+    schema: DataSchemaRef,
+    filters: Vec<Expression>,
+    batch_size: usize,
+}
+
+impl PartReader {
+    pub fn create(schema: DataSchemaRef, filters: Vec<Expression>, batch_size: usize) -> Self {
+        PartReader {
+            schema,
+            filters,
+            batch_size,
+        }
+    }
+
+    /// Reads the part file content, the blocks are passed to the sink one by one.
+    pub fn read<F>(&self, content: Vec<u8>, mut sink: F) -> Result<()>
+    where F: FnMut(DataBlock) -> Result<()> {
+        let mut file_reader = SerializedFileReader::new(SliceableCursor::new(content))?;
+        let file_schema = {
+            let file_metadata = file_reader.metadata().file_metadata();
+            parquet_to_arrow_schema(
+                file_metadata.schema_descr(),
+                file_metadata.key_value_metadata(),
+            )?
+        };
+
+        // The filters on the columns not in the part are left to the query node.
+        let mut filter_columns = vec![];
+        let mut predicate: Option<Expression> = None;
+        for filter in &self.filters {
+            let mut columns = vec![];
+            for column in RewriteHelper::expression_plan_columns(filter)? {
+                if let Expression::Column(name) = column {
+                    columns.push(name);
+                }
+            }
+            if columns
+                .iter()
+                .all(|name| file_schema.index_of(name).is_ok())
+            {
+                filter_columns.append(&mut columns);
+                predicate = Some(match predicate {
+                    None => filter.clone(),
+                    Some(predicate) => predicate.and(filter.clone()),
+                });
+            }
+        }
+
+        // The projected columns and the columns of the filters, in the order of the file.
+        let mut projection = vec![];
+        for field in self.schema.fields() {
+            projection.push(file_schema.index_of(field.name())?);
+        }
+        for name in &filter_columns {
+            projection.push(file_schema.index_of(name)?);
+        }
+        projection.sort_unstable();
+        projection.dedup();
+        let read_schema = Arc::new(DataSchema::new(
+            projection
+                .iter()
+                .map(|index| file_schema.field(*index).clone())
+                .collect(),
+        ));
+
+        // Skip the row groups whose min/max statistics can't match the push down filters.
+        // Only the signed integers, floats, dates and strings sort the same way in the statistics.
+        let ranges: Vec<_> = read_schema
+            .fields()
+            .iter()
+            .filter(|field| {
+                matches!(
+                    field.data_type(),
+                    DataType::Int8
+                        | DataType::Int16
+                        | DataType::Int32
+                        | DataType::Int64
+                        | DataType::Float32
+                        | DataType::Float64
+                        | DataType::Date32
+                        | DataType::Date64
+                        | DataType::Utf8
+                )
+            })
+            .map(|field| {
+                let range =
+                    ColumnRange::from_filters(&self.filters, field.name(), field.data_type());
+                (field.name().clone(), field.data_type().clone(), range)
+            })
+            .filter(|(_, _, range)| range.min.is_some() || range.max.is_some())
+            .collect();
+        if !ranges.is_empty() {
+            file_reader.filter_row_groups(&|row_group: &RowGroupMetaData, _: usize| {
+                ranges.iter().all(|(name, data_type, range)| {
+                    match column_min_max(row_group, name, data_type) {
+                        Some((min, max)) => range.may_overlap(&min, &max),
+                        None => !range.is_empty(),
+                    }
+                })
+            });
+        }
+
+        let filter = match predicate {
+            None => None,
+            Some(predicate) => {
+                let chain = ExpressionChain::try_create(read_schema.clone(), &[predicate.clone()])?;
+                Some((chain, predicate.column_name()))
+            }
+        };
+
+        let mut arrow_reader = ParquetFileArrowReader::new(Arc::new(file_reader));
+        let batch_reader =
+            arrow_reader.get_record_reader_by_columns(projection, self.batch_size)?;
+        for batch in batch_reader {
+            let mut block = DataBlock::try_from(batch?)?;
+            if let Some((chain, column_name)) = &filter {
+                block = filter_block(block, chain, column_name)?;
+                if block.num_rows() == 0 {
+                    continue;
+                }
+            }
+
+            let mut columns = Vec::with_capacity(self.schema.fields().len());
+            for field in self.schema.fields() {
+                columns.push(block.try_column_by_name(field.name())?.clone());
+            }
+            sink(DataBlock::create(self.schema.clone(), columns))?;
+        }
+        Ok(())
+    }
+}
+
+// Evaluates the predicate the same way as the expression executor of the query,
+// and keeps the rows it's true for.
+fn filter_block(block: DataBlock, chain: &ExpressionChain, column_name: &str) -> Result<DataBlock> {
+    let rows = block.num_rows();
+    let mut columns: HashMap<String, DataColumnarValue> = HashMap::new();
+    for field in block.schema().fields() {
+        columns.insert(
+            field.name().clone(),
+            block.try_column_by_name(field.name())?.clone(),
+        );
+    }
+
+    for action in chain.actions.iter() {
+        if columns.contains_key(action.column_name()) {
+            continue;
+        }
+
+        match action {
+            ExpressionAction::Constant(constant) => {
+                let column = DataColumnarValue::Constant(constant.value.clone(), rows);
+                columns.insert(constant.name.clone(), column);
+            }
+            ExpressionAction::Function(f) => {
+                let mut args = Vec::with_capacity(f.arg_names.len());
+                for arg in &f.arg_names {
+                    match columns.get(arg) {
+                        Some(column) => args.push(column.clone()),
+                        None => anyhow::bail!("argument {} of {} is not prepared", arg, f.name),
+                    }
+                }
+                let column = f.to_function()?.eval(&args, rows)?;
+                columns.insert(f.name.clone(), column);
+            }
+            ExpressionAction::Alias(alias) => {
+                if let Some(column) = columns.get(&alias.arg_name).cloned() {
+                    columns.insert(alias.name.clone(), column);
+                }
+            }
+            ExpressionAction::Input(_) => {}
+        }
+    }
+
+    let filter = match columns.get(column_name) {
+        Some(column) => column.to_array()?,
+        None => anyhow::bail!("filter column {} is not evaluated", column_name),
+    };
+    let filter = match filter.as_any().downcast_ref::<BooleanArray>() {
+        Some(filter) => filter,
+        None => anyhow::bail!("filter column {} is not boolean", column_name),
+    };
+
+    let batch = RecordBatch::try_from(block)?;
+    let batch = arrow::compute::filter_record_batch(&batch, filter)?;
+    Ok(DataBlock::try_from(batch)?)
+}
+
+// The min/max of the column chunk from the row group statistics.
+fn column_min_max(
+    row_group: &RowGroupMetaData,
+    name: &str,
+    data_type: &DataType,
+) -> Option<(DataValue, DataValue)> {
+    let column = row_group
+        .columns()
+        .iter()
+        .find(|column| column.column_descr().name() == name)?;
+    let statistics = column.statistics()?;
+    if !statistics.has_min_max_set() {
+        return None;
+    }
+
+    match (statistics, data_type) {
+        (ParquetStatistics::Int32(s), DataType::Date32) => Some((
+            DataValue::Date32(Some(*s.min())),
+            DataValue::Date32(Some(*s.max())),
+        )),
+        (ParquetStatistics::Int64(s), DataType::Date64) => Some((
+            DataValue::Date64(Some(*s.min())),
+            DataValue::Date64(Some(*s.max())),
+        )),
+        (ParquetStatistics::Int32(s), _) => Some((
+            DataValue::Int32(Some(*s.min())),
+            DataValue::Int32(Some(*s.max())),
+        )),
+        (ParquetStatistics::Int64(s), _) => Some((
+            DataValue::Int64(Some(*s.min())),
+            DataValue::Int64(Some(*s.max())),
+        )),
+        (ParquetStatistics::Float(s), _) => Some((
+            DataValue::Float32(Some(*s.min())),
+            DataValue::Float32(Some(*s.max())),
+        )),
+        (ParquetStatistics::Double(s), _) => Some((
+            DataValue::Float64(Some(*s.min())),
+            DataValue::Float64(Some(*s.max())),
+        )),
+        (ParquetStatistics::ByteArray(s), DataType::Utf8) => Some((
+            DataValue::Binary(Some(s.min().data().to_vec())),
+            DataValue::Binary(Some(s.max().data().to_vec())),
+        )),
+        _ => None,
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright 2020-2021 The Datafuse Authors.
+//
+// SPDX-License-Identifier: Apache-2.0.
+//
+
+use std::sync::Arc;
+
+use common_arrow::arrow::datatypes::DataType;
+use common_datablocks::DataBlock;
+use common_datavalues::DataField;
+use common_datavalues::DataSchema;
+use common_datavalues::DataValue;
+use common_datavalues::Int64Array;
+use common_datavalues::StringArray;
+use common_planners::col;
+use common_planners::lit;
+use common_planners::Expression;
+use pretty_assertions::assert_eq;
+
+use crate::data_part::appender::write_in_memory;
+use crate::data_part::reader::PartReader;
+
+fn read(schema: &Arc<DataSchema>, filters: Vec<Expression>) -> anyhow::Result<Vec<DataBlock>> {
+    let table_schema = Arc::new(DataSchema::new(vec![
+        DataField::new("id", DataType::Int64, false),
+        DataField::new("city", DataType::Utf8, false),
+        DataField::new("rank", DataType::Int64, false),
+    ]));
+    let block = DataBlock::create_by_array(table_schema, vec![
+        Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
+        Arc::new(StringArray::from(vec![
+            "Beijing", "Shanghai", "Shenzhen", "Beijing",
+        ])),
+        Arc::new(Int64Array::from(vec![100, 90, 80, 70])),
+    ]);
+    let content = write_in_memory(block)?;
+
+    let mut blocks = vec![];
+    let reader = PartReader::create(schema.clone(), filters, 2);
+    reader.read(content, |block| {
+        blocks.push(block);
+        Ok(())
+    })?;
+    Ok(blocks)
+}
+
+fn column_values(blocks: &[DataBlock], name: &str) -> anyhow::Result<Vec<String>> {
+    let mut values = vec![];
+    for block in blocks {
+        let array = block.try_array_by_name(name)?;
+        for row in 0..array.len() {
+            values.push(DataValue::try_from_array(&array, row)?.to_string());
+        }
+    }
+    Ok(values)
+}
+
+#[test]
+fn test_part_reader_projection() -> anyhow::Result<()> {
+    let schema = Arc::new(DataSchema::new(vec![
+        DataField::new("rank", DataType::Int64, false),
+        DataField::new("id", DataType::Int64, false),
+    ]));
+
+    let blocks = read(&schema, vec![])?;
+    assert_eq!(blocks.len(), 2);
+    for block in &blocks {
+        assert_eq!(block.schema(), &schema);
+    }
+    assert_eq!(column_values(&blocks, "id")?, vec!["1", "2", "3", "4"]);
+    assert_eq!(column_values(&blocks, "rank")?, vec![
+        "100", "90", "80", "70"
+    ]);
+    Ok(())
+}
+
+#[test]
+fn test_part_reader_filters() -> anyhow::Result<()> {
+    let schema = Arc::new(DataSchema::new(vec![DataField::new(
+        "id",
+        DataType::Int64,
+        false,
+    )]));
+
+    // The filter columns are not projected.
+    let blocks = read(&schema, vec![col("city").eq(lit("Beijing"))])?;
+    assert_eq!(column_values(&blocks, "id")?, vec!["1", "4"]);
+
+    let blocks = read(&schema, vec![
+        col("rank").lt(lit(95i64)),
+        col("city").not_eq(lit("Shenzhen")),
+    ])?;
+    assert_eq!(column_values(&blocks, "id")?, vec!["2", "4"]);
+
+    // The row group is skipped by the statistics.
+    let blocks = read(&schema, vec![col("rank").gt(lit(200i64))])?;
+    assert!(blocks.is_empty());
+
+    // The filters on the columns not in the part are not applied.
+    let blocks = read(&schema, vec![col("name").eq(lit("x"))])?;
+    assert_eq!(column_values(&blocks, "id")?, vec!["1", "2", "3", "4"]);
+    Ok(())
+}
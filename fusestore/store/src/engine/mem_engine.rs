@@ -9,6 +9,7 @@ use common_flights::AppendResult;
 use common_flights::DataPartInfo;
 use common_planners::Partition;
 use common_planners::Statistics;
+use common_planners::TableStatistics;
 use tonic::Status;
 
 use crate::protobuf::CmdCreateDatabase;
@@ -20,6 +21,8 @@ use crate::protobuf::Table;
 pub struct MemEngine {
     pub dbs: HashMap<String, Db>,
     pub tbl_parts: HashMap<String, HashMap<String, Vec<DataPartInfo>>>,
+    // The statistics collected by ANALYZE TABLE, dropped once the table is modified.
+    pub tbl_statistics: HashMap<String, HashMap<String, TableStatistics>>,
     pub next_id: i64,
     pub next_ver: i64,
 }
@@ -30,6 +33,7 @@ impl MemEngine {
         let e = MemEngine {
             dbs: HashMap::new(),
             tbl_parts: HashMap::new(),
+            tbl_statistics: HashMap::new(),
             next_id: 0,
             next_ver: 0,
         };
@@ -166,6 +170,38 @@ impl MemEngine {
         Ok(table.clone())
     }
 
+    pub fn get_table_statistics(
+        &mut self,
+        db_name: &str,
+        table_name: &str,
+    ) -> Result<Option<TableStatistics>, Status> {
+        self.get_table(db_name.to_string(), table_name.to_string())?;
+        Ok(self
+            .tbl_statistics
+            .get(db_name)
+            .and_then(|m| m.get(table_name))
+            .cloned())
+    }
+
+    pub fn set_table_statistics(
+        &mut self,
+        db_name: &str,
+        table_name: &str,
+        statistics: Option<TableStatistics>,
+    ) -> Result<(), Status> {
+        self.get_table(db_name.to_string(), table_name.to_string())?;
+        match statistics {
+            Some(statistics) => {
+                self.tbl_statistics
+                    .entry(db_name.to_string())
+                    .or_default()
+                    .insert(table_name.to_string(), statistics);
+            }
+            None => self.remove_table_statistics(db_name, table_name),
+        }
+        Ok(())
+    }
+
     pub fn get_data_parts(&self, db_name: &str, table_name: &str) -> Option<Vec<DataPartInfo>> {
         let parts = self.tbl_parts.get(db_name);
         parts.and_then(|m| m.get(table_name)).map(Clone::clone)
@@ -177,6 +213,17 @@ impl MemEngine {
         table_name: &str,
         append_res: &AppendResult,
     ) {
+        // Appending data is a modification of the table, the statistics are stale.
+        self.remove_table_statistics(db_name, table_name);
+        let ver = self.create_ver();
+        if let Some(db) = self.dbs.get_mut(db_name) {
+            if let Some(table_id) = db.table_name_to_id.get(table_name) {
+                if let Some(table) = db.tables.get_mut(table_id) {
+                    table.ver = ver;
+                }
+            }
+        }
+
         let part_info = || {
             append_res
                 .parts
@@ -192,6 +239,7 @@ impl MemEngine {
                             read_bytes: p.disk_bytes,
                             read_rows: p.rows,
                         },
+                        column_stats: p.column_stats.clone(),
                     }
                 })
                 .collect::<Vec<_>>()
@@ -215,10 +263,18 @@ impl MemEngine {
         self.tbl_parts
             .remove(db_name)
             .and_then(|mut t| t.remove(table_name));
+        self.remove_table_statistics(db_name, table_name);
     }
 
     pub fn remove_db_data_parts(&mut self, db_name: &str) {
         self.tbl_parts.remove(db_name);
+        self.tbl_statistics.remove(db_name);
+    }
+
+    pub fn remove_table_statistics(&mut self, db_name: &str, table_name: &str) {
+        if let Some(t) = self.tbl_statistics.get_mut(db_name) {
+            t.remove(table_name);
+        }
     }
     pub fn create_id(&mut self) -> i64 {
         let id = self.next_id;
@@ -4,6 +4,7 @@
 use std::collections::HashMap;
 
 use common_flights::status_err;
+use common_flights::AppendResult;
 use pretty_assertions::assert_eq;
 use tonic::Code;
 
@@ -272,3 +273,106 @@ fn test_mem_engine_drop_table() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_mem_engine_append_data_parts() -> anyhow::Result<()> {
+    let eng = MemEngine::create();
+
+    let mut eng = eng.lock().unwrap();
+
+    let cmd_db = CmdCreateDatabase {
+        db_name: "foo".into(),
+        db: Some(Db {
+            db_id: -1,
+            ver: -1,
+            table_name_to_id: HashMap::new(),
+            tables: HashMap::new(),
+        }),
+    };
+    let cmd_table = CmdCreateTable {
+        db_name: "foo".into(),
+        table_name: "t1".into(),
+        table: Some(Table {
+            table_id: -1,
+            ver: -1,
+            schema: vec![1, 2, 3],
+            options: maplit::hashmap! {"key".into() => "val".into()},
+            placement_policy: vec![1, 2, 3],
+        }),
+    };
+    eng.create_database(cmd_db, false)?;
+    eng.create_table(cmd_table, false)?;
+    assert_eq!(1, eng.get_table("foo".into(), "t1".into())?.ver);
+
+    // Every append changes the version of the table.
+    let mut append_res = AppendResult::default();
+    append_res.append_part("part-0", 1, 1, 8, 8, HashMap::new());
+    eng.append_data_parts("foo", "t1", &append_res);
+    assert_eq!(2, eng.get_table("foo".into(), "t1".into())?.ver);
+    eng.append_data_parts("foo", "t1", &append_res);
+    assert_eq!(3, eng.get_table("foo".into(), "t1".into())?.ver);
+    assert_eq!(Some(2), eng.get_data_parts("foo", "t1").map(|p| p.len()));
+
+    Ok(())
+}
+
+#[test]
+fn test_mem_engine_table_statistics() -> anyhow::Result<()> {
+    use common_planners::TableStatistics;
+
+    let eng = MemEngine::create();
+
+    let mut eng = eng.lock().unwrap();
+
+    let cmd_db = CmdCreateDatabase {
+        db_name: "foo".into(),
+        db: Some(Db {
+            db_id: -1,
+            ver: -1,
+            table_name_to_id: HashMap::new(),
+            tables: HashMap::new(),
+        }),
+    };
+    let cmd_table = CmdCreateTable {
+        db_name: "foo".into(),
+        table_name: "t1".into(),
+        table: Some(Table {
+            table_id: -1,
+            ver: -1,
+            schema: vec![1, 2, 3],
+            options: maplit::hashmap! {"key".into() => "val".into()},
+            placement_policy: vec![1, 2, 3],
+        }),
+    };
+    eng.create_database(cmd_db, false)?;
+    eng.create_table(cmd_table, false)?;
+
+    let statistics = TableStatistics {
+        rows: 1,
+        bytes: 8,
+        columns: HashMap::new(),
+    };
+    assert_eq!(None, eng.get_table_statistics("foo", "t1")?);
+    eng.set_table_statistics("foo", "t1", Some(statistics.clone()))?;
+    assert_eq!(
+        Some(statistics.clone()),
+        eng.get_table_statistics("foo", "t1")?
+    );
+
+    // Unknown table.
+    let r = eng.set_table_statistics("foo", "t2", Some(statistics.clone()));
+    assert_eq!(r.unwrap_err().code(), Code::NotFound);
+
+    // The statistics are stale once data is appended.
+    let mut append_res = AppendResult::default();
+    append_res.append_part("part-0", 1, 1, 8, 8, HashMap::new());
+    eng.append_data_parts("foo", "t1", &append_res);
+    assert_eq!(None, eng.get_table_statistics("foo", "t1")?);
+
+    // None drops them.
+    eng.set_table_statistics("foo", "t1", Some(statistics))?;
+    eng.set_table_statistics("foo", "t1", None)?;
+    assert_eq!(None, eng.get_table_statistics("foo", "t1")?);
+
+    Ok(())
+}
@@ -5,41 +5,52 @@
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::pin::Pin;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::sync::Mutex;
 
 use common_arrow::arrow::datatypes::Schema;
 use common_arrow::arrow::ipc::writer::IpcWriteOptions;
+use common_arrow::arrow::record_batch::RecordBatch;
 use common_arrow::arrow_flight;
 use common_arrow::arrow_flight::utils::flight_data_from_arrow_batch;
 use common_arrow::arrow_flight::FlightData;
-use common_arrow::parquet::arrow::ArrowReader;
-use common_arrow::parquet::arrow::ParquetFileArrowReader;
-use common_arrow::parquet::file::reader::SerializedFileReader;
-use common_arrow::parquet::file::serialized_reader::SliceableCursor;
+use common_datablocks::DataBlock;
 use common_flights::CreateDatabaseAction;
 use common_flights::CreateDatabaseActionResult;
 use common_flights::CreateTableAction;
 use common_flights::CreateTableActionResult;
+use common_flights::DataPartInfo;
 use common_flights::DropDatabaseAction;
 use common_flights::DropDatabaseActionResult;
 use common_flights::DropTableAction;
 use common_flights::DropTableActionResult;
 use common_flights::GetTableAction;
 use common_flights::GetTableActionResult;
+use common_flights::GetTableStatisticsAction;
+use common_flights::GetTableStatisticsActionResult;
 use common_flights::ReadAction;
 use common_flights::ScanPartitionAction;
+use common_flights::SetTableStatisticsAction;
+use common_flights::SetTableStatisticsActionResult;
 use common_flights::StoreDoAction;
 use common_flights::StoreDoActionResult;
+use common_planners::ColumnRange;
+use common_planners::Expression;
 use common_planners::PlanNode;
 use futures::Stream;
 use log::info;
+use tokio::sync::mpsc::Receiver;
 use tokio::sync::mpsc::Sender;
+use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::StreamExt;
 use tonic::Status;
 use tonic::Streaming;
 
+use crate::data_part::aggregator::PartialAggregator;
 use crate::data_part::appender::Appender;
+use crate::data_part::reader::PartReader;
 use crate::engine::MemEngine;
 use crate::fs::FileSystem;
 use crate::protobuf::CmdCreateDatabase;
@@ -93,6 +104,8 @@ impl ActionHandler {
             StoreDoAction::CreateTable(a) => self.create_table(a).await,
             StoreDoAction::DropTable(act) => self.drop_table(act).await,
             StoreDoAction::GetTable(a) => self.get_table(a).await,
+            StoreDoAction::GetTableStatistics(a) => self.get_table_statistics(a),
+            StoreDoAction::SetTableStatistics(a) => self.set_table_statistics(a),
             StoreDoAction::ScanPartition(act) => self.scan_partitions(&act),
         }
     }
@@ -176,6 +189,7 @@ impl ActionHandler {
 
         let rst = StoreDoActionResult::GetTable(GetTableActionResult {
             table_id: table.table_id,
+            ver: table.ver,
             db: db_name,
             name: table_name,
             schema: Arc::new(schema),
@@ -184,6 +198,28 @@ impl ActionHandler {
         Ok(rst)
     }
 
+    fn get_table_statistics(
+        &self,
+        act: GetTableStatisticsAction,
+    ) -> Result<StoreDoActionResult, Status> {
+        let mut meta = self.meta.lock().unwrap();
+        let statistics = meta.get_table_statistics(&act.db, &act.table)?;
+        Ok(StoreDoActionResult::GetTableStatistics(
+            GetTableStatisticsActionResult { statistics },
+        ))
+    }
+
+    fn set_table_statistics(
+        &self,
+        act: SetTableStatisticsAction,
+    ) -> Result<StoreDoActionResult, Status> {
+        let mut meta = self.meta.lock().unwrap();
+        meta.set_table_statistics(&act.db, &act.table, act.statistics)?;
+        Ok(StoreDoActionResult::SetTableStatistics(
+            SetTableStatisticsActionResult {},
+        ))
+    }
+
     async fn drop_db(&self, act: DropDatabaseAction) -> Result<StoreDoActionResult, Status> {
         let mut meta = self.meta.lock().unwrap();
         let _ = meta.drop_database(&act.plan.db, act.plan.if_exists)?;
@@ -213,15 +249,36 @@ impl ActionHandler {
             // table's current schema (or following the evolution rules of table schema)
         }
 
+        // The parts are committed only if the stream ends with the commit marker, a put that
+        // is cancelled or cut by an error leaves the table unchanged.
+        let completed = Arc::new(AtomicBool::new(false));
         let appender = Appender::new(self.fs.clone());
-        let parts = parts
-            .take_while(|item| item.is_ok())
-            .map(|item| item.unwrap());
+        let parts = {
+            let completed = completed.clone();
+            parts
+                .take_while(move |item| match item {
+                    Ok(flight_data) if common_flights::is_do_put_commit_marker(flight_data) => {
+                        completed.store(true, Ordering::Relaxed);
+                        false
+                    }
+                    Ok(_) => true,
+                    Err(_) => false,
+                })
+                .map(|item| item.unwrap())
+        };
 
         let res = appender
             .append_data(format!("{}/{}", &db_name, &table_name), Box::pin(parts))
             .await?;
 
+        if !completed.load(Ordering::Relaxed) {
+            anyhow::bail!(
+                "the put to {}.{} is not completed, the parts are not committed",
+                db_name,
+                table_name
+            );
+        }
+
         let mut meta = self.meta.lock().unwrap();
         meta.append_data_parts(&db_name, &table_name, &res);
         Ok(res)
@@ -236,57 +293,89 @@ impl ActionHandler {
         let tbl_name = splits[1];
 
         let meta = self.meta.lock().unwrap();
-        Ok(StoreDoActionResult::ScanPartition(
-            meta.get_data_parts(db_name, tbl_name),
-        ))
+        let parts = meta
+            .get_data_parts(db_name, tbl_name)
+            .map(|parts| prune_parts(parts, &cmd.scan_plan.filters));
+        Ok(StoreDoActionResult::ScanPartition(parts))
     }
 
     pub async fn read_partition(&self, action: ReadAction) -> anyhow::Result<DoGetStream> {
         log::info!("entering read");
         let part_file = action.partition.name;
 
-        let plan = if let PlanNode::ReadSource(read_source_plan) = action.push_down {
-            read_source_plan
-        } else {
-            anyhow::bail!("invalid PlanNode passed in")
+        // The partial aggregation is computed next to the data if it's pushed down.
+        let (plan, aggregation) = match action.push_down {
+            PlanNode::ReadSource(plan) => (plan, None),
+            PlanNode::AggregatorPartial(aggregation) => match aggregation.input.as_ref() {
+                PlanNode::ReadSource(plan) => (plan.clone(), Some(aggregation)),
+                _ => anyhow::bail!("invalid PlanNode passed in"),
+            },
+            _ => anyhow::bail!("invalid PlanNode passed in"),
         };
 
         let content = self.fs.read_all(&part_file).await?;
-        let cursor = SliceableCursor::new(content);
-
-        let file_reader = SerializedFileReader::new(cursor)?;
-        let mut arrow_reader = ParquetFileArrowReader::new(Arc::new(file_reader));
-
-        // before push_down is passed in, we returns all the columns
-        let schema = plan.schema;
-        let projection = (0..schema.fields().len()).collect::<Vec<_>>();
 
         // TODO config
         let batch_size = 2048;
+        let reader = PartReader::create(plan.schema, plan.scan_plan.filters.clone(), batch_size);
+
+        // `ParquetFileArrowReader` is neither Send nor Sync, the part is read in a blocking task
+        // and the batches are sent to the stream as soon as they are read.
+        let (tx, rx): (
+            Sender<Result<FlightData, tonic::Status>>,
+            Receiver<Result<FlightData, tonic::Status>>,
+        ) = tokio::sync::mpsc::channel(2);
+        tokio::task::spawn_blocking(move || {
+            let res = match aggregation {
+                None => reader.read(content, |block| send_block(&tx, block)),
+                Some(aggregation) => {
+                    PartialAggregator::try_create(&aggregation).and_then(|mut aggregator| {
+                        reader.read(content, |block| aggregator.append(&block))?;
+                        match aggregator.finalize()? {
+                            Some(block) => send_block(&tx, block),
+                            None => Ok(()),
+                        }
+                    })
+                }
+            };
+            if let Err(e) = res {
+                let _ = tx.blocking_send(Err(Status::internal(e.to_string())));
+            }
+        });
 
-        let batch_reader = arrow_reader.get_record_reader_by_columns(projection, batch_size)?;
-
-        // For simplicity, we do the conversion in-memory, to be optimized later
-        // TODO consider using `parquet_table` and `stream_parquet`
-        let write_opt = IpcWriteOptions::default();
-        let flights =
-            batch_reader
-                .into_iter()
-                .map(|batch| {
-                    batch.map(
-                    |b| flight_data_from_arrow_batch(&b, &write_opt).1, /*dictionary ignored*/
-                ).map_err(|arrow_err| Status::internal(arrow_err.to_string()))
-                })
-                .collect::<Vec<_>>();
-        let stream = futures::stream::iter(flights);
+        Ok(Box::pin(ReceiverStream::new(rx)))
+    }
+}
 
-        // This is not gonna work, cause `ParquetFileArrowReader` and `ParquetFileArrowReader` are neither Send nor Sync
-        //
-        // # let stream = futures::stream::iter(reader.into_iter());
-        // # let stream =
-        // #     stream.map(move |batch| flight_data_from_arrow_batch(&batch.unwrap(), &write_opt).1);
-        // # let stream = stream.map(|v| Ok(v));
+fn send_block(
+    tx: &Sender<Result<FlightData, tonic::Status>>,
+    block: DataBlock,
+) -> anyhow::Result<()> {
+    let batch = RecordBatch::try_from(block)?;
+    // The dictionary is ignored.
+    let flight_data = flight_data_from_arrow_batch(&batch, &IpcWriteOptions::default()).1;
+    tx.blocking_send(Ok(flight_data))
+        .map_err(|_| anyhow::anyhow!("the read stream is closed"))
+}
 
-        Ok(Box::pin(stream))
+/// Skips the parts whose column min/max can't match the push down filters.
+pub(crate) fn prune_parts(parts: Vec<DataPartInfo>, filters: &[Expression]) -> Vec<DataPartInfo> {
+    if filters.is_empty() {
+        return parts;
     }
+
+    let mut ranges: HashMap<String, ColumnRange> = HashMap::new();
+    parts
+        .into_iter()
+        .filter(|part| {
+            part.column_stats.iter().all(|(column, stats)| {
+                ranges
+                    .entry(column.clone())
+                    .or_insert_with(|| {
+                        ColumnRange::from_filters(filters, column, &stats.min.data_type())
+                    })
+                    .may_overlap(&stats.min, &stats.max)
+            })
+        })
+        .collect()
 }
@@ -7,6 +7,13 @@ use std::path::Path;
 use std::sync::Arc;
 
 use common_arrow::arrow_flight::FlightData;
+use common_datavalues::DataValue;
+use common_flights::ColumnMinMax;
+use common_flights::DataPartInfo;
+use common_planners::col;
+use common_planners::lit;
+use common_planners::Partition;
+use common_planners::Statistics;
 use common_tracing::tracing;
 use maplit::hashmap;
 use pretty_assertions::assert_eq;
@@ -15,6 +22,7 @@ use tokio::sync::mpsc::Receiver;
 use tokio::sync::mpsc::Sender;
 
 use crate::dfs::Dfs;
+use crate::executor::action_handler::prune_parts;
 use crate::executor::ActionHandler;
 use crate::fs::FileSystem;
 use crate::localfs::LocalFS;
@@ -52,6 +60,115 @@ async fn test_action_handler_do_pull_file() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_prune_parts() -> anyhow::Result<()> {
+    let part = |name: &str, min: &str, max: &str| DataPartInfo {
+        partition: Partition {
+            name: name.to_string(),
+            version: 0,
+        },
+        stats: Statistics::default(),
+        column_stats: hashmap! {
+            "ts".to_string() => ColumnMinMax {
+                min: DataValue::Utf8(Some(min.to_string())),
+                max: DataValue::Utf8(Some(max.to_string())),
+            },
+        },
+    };
+    let parts = vec![
+        part("p1", "2026-08-01", "2026-08-31"),
+        part("p2", "2026-09-01", "2026-09-30"),
+        part("p3", "2026-10-01", "2026-10-31"),
+    ];
+    let names = |parts: Vec<DataPartInfo>| {
+        parts
+            .into_iter()
+            .map(|part| part.partition.name)
+            .collect::<Vec<_>>()
+    };
+
+    // No filters.
+    assert_eq!(names(prune_parts(parts.clone(), &[])), vec![
+        "p1", "p2", "p3"
+    ]);
+
+    // The filters on the column.
+    let filters = vec![col("ts").gt_eq(lit("2026-10-01"))];
+    assert_eq!(names(prune_parts(parts.clone(), &filters)), vec!["p3"]);
+
+    let filters = vec![lit("2026-09-15").gt_eq(col("ts"))];
+    assert_eq!(names(prune_parts(parts.clone(), &filters)), vec![
+        "p1", "p2"
+    ]);
+
+    let filters = vec![col("ts")
+        .gt(lit("2026-08-15"))
+        .and(col("ts").lt(lit("2026-09-15")))];
+    assert_eq!(names(prune_parts(parts.clone(), &filters)), vec![
+        "p1", "p2"
+    ]);
+
+    // The filters can't match any value.
+    let filters = vec![
+        col("ts").gt(lit("2026-10-01")),
+        col("ts").lt(lit("2026-09-01")),
+    ];
+    assert_eq!(
+        names(prune_parts(parts.clone(), &filters)),
+        Vec::<String>::new()
+    );
+
+    // The filters on the other columns.
+    let filters = vec![col("id").eq(lit(1u64))];
+    assert_eq!(names(prune_parts(parts, &filters)), vec!["p1", "p2", "p3"]);
+    Ok(())
+}
+
+#[test]
+fn test_prune_parts_by_date() -> anyhow::Result<()> {
+    // The min/max of the date column are the days since the epoch.
+    let part = |name: &str, min: i32, max: i32| DataPartInfo {
+        partition: Partition {
+            name: name.to_string(),
+            version: 0,
+        },
+        stats: Statistics::default(),
+        column_stats: hashmap! {
+            "d".to_string() => ColumnMinMax {
+                min: DataValue::Date32(Some(min)),
+                max: DataValue::Date32(Some(max)),
+            },
+        },
+    };
+    let parts = vec![
+        part("p1", 18628, 18658), // 2021-01
+        part("p2", 18659, 18686), // 2021-02
+        part("p3", 18687, 18717), // 2021-03
+    ];
+    let names = |parts: Vec<DataPartInfo>| {
+        parts
+            .into_iter()
+            .map(|part| part.partition.name)
+            .collect::<Vec<_>>()
+    };
+
+    // The string literals are compared as dates.
+    let filters = vec![col("d").gt_eq(lit("2021-02-01"))];
+    assert_eq!(names(prune_parts(parts.clone(), &filters)), vec![
+        "p2", "p3"
+    ]);
+
+    let filters = vec![col("d")
+        .gt(lit("2021-01-15"))
+        .and(col("d").lt(lit("2021-01-31")))];
+    assert_eq!(names(prune_parts(parts.clone(), &filters)), vec!["p1"]);
+
+    // The strings which are not dates don't prune.
+    let filters = vec![col("d").gt_eq(lit("x"))];
+    assert_eq!(names(prune_parts(parts, &filters)), vec!["p1", "p2", "p3"]);
+    Ok(())
+}
+
 // Start an ActionHandler backed with a dfs.
 // And feed files into dfs.
 async fn bring_up_dfs_action_handler(
@@ -1,2 +1,3 @@
 set max_threads = 16;
 explain select sum(number+1)+2 as sumx from numbers_mt(80000) where (number+1)=4 limit 1;
+explain select number from numbers_mt(1000000000000) limit 5;